@@ -0,0 +1,85 @@
+-- 0001: wspólny schemat bazowy.
+-- Idempotentny, żeby istniejące bazy (tworzone wcześniej przez ensure_schema
+-- w poszczególnych komendach) dało się "zaadoptować" bez utraty danych.
+
+-- ---------- users ----------
+CREATE TABLE IF NOT EXISTS users (
+    id      BIGINT PRIMARY KEY,
+    balance BIGINT NOT NULL DEFAULT 0
+);
+
+ALTER TABLE users
+    ADD COLUMN IF NOT EXISTS last_work    TIMESTAMPTZ,
+    ADD COLUMN IF NOT EXISTS streak       INTEGER NOT NULL DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS last_streak  TIMESTAMPTZ,
+    ADD COLUMN IF NOT EXISTS last_slut    TIMESTAMPTZ,
+    ADD COLUMN IF NOT EXISTS flirt_rep    INTEGER NOT NULL DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS flirt_streak INTEGER NOT NULL DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS flirt_fails  INTEGER NOT NULL DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS last_crime   TIMESTAMPTZ,
+    ADD COLUMN IF NOT EXISTS last_rob     TIMESTAMPTZ,
+    ADD COLUMN IF NOT EXISTS last_daily   TIMESTAMPTZ,
+    ADD COLUMN IF NOT EXISTS created_at   TIMESTAMPTZ NOT NULL DEFAULT now(),
+    ADD COLUMN IF NOT EXISTS updated_at   TIMESTAMPTZ NOT NULL DEFAULT now();
+
+-- starsze instalacje miały last_daily jako TIMESTAMP bez strefy
+DO $$
+BEGIN
+    IF EXISTS (
+        SELECT 1 FROM information_schema.columns
+         WHERE table_name = 'users'
+           AND column_name = 'last_daily'
+           AND data_type = 'timestamp without time zone'
+    ) THEN
+        ALTER TABLE users
+            ALTER COLUMN last_daily TYPE TIMESTAMPTZ USING last_daily AT TIME ZONE 'UTC';
+    END IF;
+END
+$$;
+
+-- ---------- logs ----------
+CREATE TABLE IF NOT EXISTS logs (
+    id         BIGSERIAL PRIMARY KEY,
+    user_id    BIGINT NOT NULL,
+    action     TEXT   NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+ALTER TABLE logs
+    ADD COLUMN IF NOT EXISTS target_id   BIGINT,
+    ADD COLUMN IF NOT EXISTS amount      BIGINT,
+    ADD COLUMN IF NOT EXISTS description TEXT,
+    ADD COLUMN IF NOT EXISTS message     TEXT,
+    ADD COLUMN IF NOT EXISTS meta        JSONB;
+
+CREATE INDEX IF NOT EXISTS idx_logs_user_id ON logs(user_id);
+CREATE INDEX IF NOT EXISTS idx_logs_action  ON logs(action);
+
+-- ---------- crime: profile (HEAT / PP / skill) ----------
+CREATE TABLE IF NOT EXISTS profiles (
+    user_id     BIGINT PRIMARY KEY,
+    heat        INTEGER NOT NULL DEFAULT 0,
+    pp          INTEGER NOT NULL DEFAULT 0,
+    thief_skill INTEGER NOT NULL DEFAULT 0,
+    created_at  TIMESTAMPTZ NOT NULL DEFAULT now(),
+    updated_at  TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+-- ---------- crime: ostatnie ustawienia gry ----------
+CREATE TABLE IF NOT EXISTS crime_settings (
+    user_id    BIGINT PRIMARY KEY REFERENCES profiles(user_id) ON DELETE CASCADE,
+    mode       TEXT NULL,
+    risk       TEXT NULL,
+    loadout    TEXT[] NOT NULL DEFAULT ARRAY[]::TEXT[],
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+-- ---------- shop: subskrypcje rangi ----------
+CREATE TABLE IF NOT EXISTS role_subscriptions (
+    user_id    BIGINT NOT NULL,
+    role_id    BIGINT NOT NULL,
+    guild_id   BIGINT NOT NULL,
+    expires_at TIMESTAMPTZ NOT NULL,
+    active     BOOLEAN NOT NULL DEFAULT true,
+    PRIMARY KEY (user_id, role_id, guild_id)
+);
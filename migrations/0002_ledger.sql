@@ -0,0 +1,33 @@
+-- 0002: księga ruchów TK (double-entry: każdy wpis ma źródło i cel).
+-- Konta: "user:<id>" = portfel gracza (users.balance), "sys:<nazwa>" = konto systemowe
+-- (emisja/spalanie TK przez komendy, grzywny, sklep, administracja).
+
+CREATE TABLE IF NOT EXISTS ledger (
+    id                   BIGSERIAL PRIMARY KEY,
+    source               TEXT   NOT NULL,
+    sink                 TEXT   NOT NULL,
+    amount               BIGINT NOT NULL CHECK (amount > 0),
+    reason               TEXT   NOT NULL,
+    command              TEXT   NOT NULL,
+    interaction_id       BIGINT,
+    source_balance_after BIGINT,
+    sink_balance_after   BIGINT,
+    created_at           TIMESTAMPTZ NOT NULL DEFAULT now(),
+    CHECK (source <> sink)
+);
+
+CREATE INDEX IF NOT EXISTS idx_ledger_source      ON ledger(source, created_at);
+CREATE INDEX IF NOT EXISTS idx_ledger_sink        ON ledger(sink, created_at);
+CREATE INDEX IF NOT EXISTS idx_ledger_interaction ON ledger(interaction_id);
+
+-- bilans otwarcia: dotychczasowe salda jako jeden wpis na gracza,
+-- żeby suma wpisów zawsze odtwarzała users.balance
+INSERT INTO ledger (source, sink, amount, reason, command, sink_balance_after)
+SELECT 'sys:opening', 'user:' || id, balance, 'bilans otwarcia', 'migration', balance
+  FROM users
+ WHERE balance > 0;
+
+INSERT INTO ledger (source, sink, amount, reason, command, source_balance_after)
+SELECT 'user:' || id, 'sys:opening', -balance, 'bilans otwarcia (debet)', 'migration', balance
+  FROM users
+ WHERE balance < 0;
@@ -0,0 +1,46 @@
+-- 0003: ekonomie per serwer.
+-- Każda tabela ekonomii dostaje guild_id = id gildii, do której należy ekonomia.
+-- Istniejące dane trafiają do ekonomii 0 ("legacy") — serwer, który ma ją przejąć,
+-- wskazuje się przez LEGACY_GUILD_ID (link tworzony przy starcie bota).
+
+-- ---------- powiązania serwerów ze wspólną ekonomią ----------
+CREATE TABLE IF NOT EXISTS economy_links (
+    guild_id   BIGINT PRIMARY KEY,
+    economy_id BIGINT NOT NULL,
+    linked_by  BIGINT,
+    linked_at  TIMESTAMPTZ NOT NULL DEFAULT now(),
+    CHECK (guild_id <> economy_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_economy_links_economy ON economy_links(economy_id);
+
+-- ---------- users ----------
+ALTER TABLE users ADD COLUMN guild_id BIGINT NOT NULL DEFAULT 0;
+ALTER TABLE users DROP CONSTRAINT users_pkey;
+ALTER TABLE users ADD PRIMARY KEY (guild_id, id);
+ALTER TABLE users ALTER COLUMN guild_id DROP DEFAULT;
+
+-- ---------- crime: profile + ustawienia ----------
+ALTER TABLE crime_settings DROP CONSTRAINT IF EXISTS crime_settings_user_id_fkey;
+
+ALTER TABLE profiles ADD COLUMN guild_id BIGINT NOT NULL DEFAULT 0;
+ALTER TABLE profiles DROP CONSTRAINT profiles_pkey;
+ALTER TABLE profiles ADD PRIMARY KEY (guild_id, user_id);
+ALTER TABLE profiles ALTER COLUMN guild_id DROP DEFAULT;
+
+ALTER TABLE crime_settings ADD COLUMN guild_id BIGINT NOT NULL DEFAULT 0;
+ALTER TABLE crime_settings DROP CONSTRAINT crime_settings_pkey;
+ALTER TABLE crime_settings ADD PRIMARY KEY (guild_id, user_id);
+ALTER TABLE crime_settings ALTER COLUMN guild_id DROP DEFAULT;
+ALTER TABLE crime_settings
+    ADD CONSTRAINT crime_settings_profile_fkey
+    FOREIGN KEY (guild_id, user_id) REFERENCES profiles(guild_id, user_id) ON DELETE CASCADE;
+
+-- ---------- księga ----------
+ALTER TABLE ledger ADD COLUMN guild_id BIGINT NOT NULL DEFAULT 0;
+ALTER TABLE ledger ALTER COLUMN guild_id DROP DEFAULT;
+
+DROP INDEX IF EXISTS idx_ledger_source;
+DROP INDEX IF EXISTS idx_ledger_sink;
+CREATE INDEX idx_ledger_source ON ledger(guild_id, source, created_at);
+CREATE INDEX idx_ledger_sink   ON ledger(guild_id, sink, created_at);
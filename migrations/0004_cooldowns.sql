@@ -0,0 +1,37 @@
+-- 0004: wspólna tabela cooldownów zamiast kolumn last_* w users.
+-- Trzymamy moment gotowości (ready_at), więc modyfikatory (HEAT, subskrypcja)
+-- liczone są raz — przy starcie cooldownu.
+
+CREATE TABLE IF NOT EXISTS cooldowns (
+    guild_id   BIGINT NOT NULL,
+    user_id    BIGINT NOT NULL,
+    key        TEXT   NOT NULL,
+    ready_at   TIMESTAMPTZ NOT NULL,
+    started_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (guild_id, user_id, key)
+);
+
+CREATE INDEX IF NOT EXISTS idx_cooldowns_ready_at ON cooldowns(ready_at);
+
+-- przenosimy tylko trwające cooldowny (długości jak w kodzie w chwili migracji)
+INSERT INTO cooldowns (guild_id, user_id, key, ready_at, started_at)
+SELECT guild_id, id, 'work', last_work + interval '30 seconds', last_work
+  FROM users WHERE last_work + interval '30 seconds' > now();
+
+INSERT INTO cooldowns (guild_id, user_id, key, ready_at, started_at)
+SELECT guild_id, id, 'slut', last_slut + interval '30 seconds', last_slut
+  FROM users WHERE last_slut + interval '30 seconds' > now();
+
+INSERT INTO cooldowns (guild_id, user_id, key, ready_at, started_at)
+SELECT guild_id, id, 'rob', last_rob + interval '600 seconds', last_rob
+  FROM users WHERE last_rob + interval '600 seconds' > now();
+
+INSERT INTO cooldowns (guild_id, user_id, key, ready_at, started_at)
+SELECT guild_id, id, 'daily', last_daily + interval '24 hours', last_daily
+  FROM users WHERE last_daily + interval '24 hours' > now();
+
+ALTER TABLE users
+    DROP COLUMN last_work,
+    DROP COLUMN last_slut,
+    DROP COLUMN last_rob,
+    DROP COLUMN last_daily;
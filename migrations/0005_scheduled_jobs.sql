@@ -0,0 +1,16 @@
+-- 0005: stan zadań harmonogramu (ostatnie uruchomienie, wynik) + dzierżawa,
+-- żeby przy kilku procesach bota (dev/prod na jednej bazie) zadanie szło raz.
+
+CREATE TABLE IF NOT EXISTS scheduled_jobs (
+    name             TEXT PRIMARY KEY,
+    schedule         TEXT        NOT NULL,
+    next_run_at      TIMESTAMPTZ NOT NULL,
+    locked_by        TEXT,
+    locked_until     TIMESTAMPTZ,
+    last_started_at  TIMESTAMPTZ,
+    last_finished_at TIMESTAMPTZ,
+    last_ok          BOOLEAN,
+    last_output      TEXT,
+    run_count        BIGINT      NOT NULL DEFAULT 0,
+    fail_count       BIGINT      NOT NULL DEFAULT 0
+);
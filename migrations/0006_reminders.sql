@@ -0,0 +1,23 @@
+-- 0006: przypomnienia o końcu cooldownu (zamiast uśpionych tasków edytujących interakcję).
+-- Wiersz = oczekujące przypomnienie; po doręczeniu jest usuwany.
+
+CREATE TABLE IF NOT EXISTS reminder_prefs (
+    user_id    BIGINT PRIMARY KEY,
+    mode       TEXT NOT NULL DEFAULT 'dm' CHECK (mode IN ('dm', 'channel', 'off')),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE TABLE IF NOT EXISTS reminders (
+    guild_id   BIGINT NOT NULL,          -- prawdziwy serwer (nie ekonomia) — tu idzie ping
+    user_id    BIGINT NOT NULL,
+    key        TEXT   NOT NULL,          -- klucz cooldownu
+    channel_id BIGINT NOT NULL,
+    command    TEXT   NOT NULL,
+    due_at     TIMESTAMPTZ NOT NULL,
+    attempts   INT    NOT NULL DEFAULT 0,
+    last_error TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (guild_id, user_id, key)
+);
+
+CREATE INDEX IF NOT EXISTS idx_reminders_due_at ON reminders(due_at);
@@ -0,0 +1,14 @@
+-- 0007: sesje /crime w bazie zamiast w pamięci procesu.
+-- `state` to zserializowana SoloSession (JSON); `expires_at` przesuwa każda akcja gracza,
+-- a wygasłe sesje sprząta zadanie harmonogramu (z logiem porzuconego napadu).
+
+CREATE TABLE IF NOT EXISTS crime_sessions (
+    guild_id   BIGINT NOT NULL,            -- id ekonomii
+    user_id    BIGINT NOT NULL,
+    state      JSONB  NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    expires_at TIMESTAMPTZ NOT NULL,
+    PRIMARY KEY (guild_id, user_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_crime_sessions_expires_at ON crime_sessions(expires_at);
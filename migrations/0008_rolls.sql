@@ -0,0 +1,18 @@
+-- 0008: zapis losowań gry — ziarno RNG + wejście + wynik dla każdej interakcji.
+-- Z ziarna i wejścia da się odtworzyć wynik 1:1 (`/admcontrol rzut`), więc spór
+-- „bot oszukuje” rozstrzyga się powtórką, a nie zaufaniem.
+
+CREATE TABLE IF NOT EXISTS rolls (
+    id             BIGSERIAL PRIMARY KEY,
+    guild_id       BIGINT NOT NULL,          -- id ekonomii
+    user_id        BIGINT NOT NULL,
+    command        TEXT   NOT NULL,          -- rodzaj losowania, np. 'rob', 'crime.simon'
+    interaction_id BIGINT,
+    seed           BIGINT NOT NULL,          -- u64 zapisany bitowo jako i64
+    inputs         JSONB  NOT NULL,
+    outcome        JSONB  NOT NULL,
+    created_at     TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS idx_rolls_interaction ON rolls(interaction_id);
+CREATE INDEX IF NOT EXISTS idx_rolls_user ON rolls(guild_id, user_id, created_at DESC);
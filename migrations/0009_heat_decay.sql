@@ -0,0 +1,9 @@
+-- 0009: wygasanie HEAT z czasem.
+-- `heat_decayed_at` = moment, od którego liczymy kolejne godziny spadku; zadanie
+-- harmonogramu (`heat.decay`) zdejmuje pełne godziny i przesuwa znacznik o tyle samo,
+-- więc przestój bota nie gubi spadku. Przy wzroście HEAT od zera znacznik startuje od nowa.
+
+ALTER TABLE profiles
+    ADD COLUMN IF NOT EXISTS heat_decayed_at TIMESTAMPTZ NOT NULL DEFAULT now();
+
+CREATE INDEX IF NOT EXISTS idx_profiles_heat_positive ON profiles(heat_decayed_at) WHERE heat > 0;
@@ -0,0 +1,4 @@
+-- 0010: zapamiętana minigierka /crime (Simon albo QTE) obok trybu, ryzyka i ekwipunku.
+
+ALTER TABLE crime_settings
+    ADD COLUMN IF NOT EXISTS minigame TEXT NULL CHECK (minigame IN ('simon', 'qte'));
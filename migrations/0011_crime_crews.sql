@@ -0,0 +1,17 @@
+-- 0011: lobby napadów ekipowych (/crime ekipa).
+-- `state` to zserializowane CrewLobby (JSON: konfiguracja, członkowie z rolami, postęp QTE).
+-- Lobby znika po rozstrzygnięciu albo rozwiązaniu; porzucone sprząta zadanie harmonogramu.
+
+CREATE TABLE IF NOT EXISTS crime_crews (
+    id         BIGSERIAL PRIMARY KEY,
+    guild_id   BIGINT NOT NULL,            -- id ekonomii
+    channel_id BIGINT NOT NULL,
+    leader_id  BIGINT NOT NULL,
+    state      JSONB  NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    expires_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_crime_crews_guild ON crime_crews(guild_id);
+CREATE INDEX IF NOT EXISTS idx_crime_crews_expires_at ON crime_crews(expires_at);
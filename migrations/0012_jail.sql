@@ -0,0 +1,17 @@
+-- 0012: więzienie — wpadka przy wysokim HEAT (/crime, /rob) zamyka gracza do `release_at`.
+-- Jeden wiersz na gracza w ekonomii; kolejny wyrok przedłuża bieżący. Kaucja liczona
+-- z pozostałego czasu; ucieczka (minigierka) raz na wyrok — `escape_armed_ms` to start
+-- odliczania (znacznik Discorda), `escape_tried` blokuje drugą próbę.
+
+CREATE TABLE IF NOT EXISTS jail (
+    guild_id        BIGINT NOT NULL,       -- id ekonomii
+    user_id         BIGINT NOT NULL,
+    jailed_at       TIMESTAMPTZ NOT NULL,
+    release_at      TIMESTAMPTZ NOT NULL,
+    reason          TEXT   NOT NULL,
+    escape_armed_ms BIGINT NULL,
+    escape_tried    BOOLEAN NOT NULL DEFAULT FALSE,
+    PRIMARY KEY (guild_id, user_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_jail_release_at ON jail(release_at);
@@ -0,0 +1,12 @@
+-- 0013: ekwipunek /crime — przedmioty kupowane za TK zamiast darmowych progów PP.
+-- Jeden wiersz na (gracz, przedmiot). `charges`: przy trwałych to pozostała wytrzymałość
+-- (jeden egzemplarz, naprawialny), przy jednorazowych liczba sztuk. Zużyty do zera = wiersz znika.
+
+CREATE TABLE IF NOT EXISTS crime_inventory (
+    guild_id   BIGINT NOT NULL,            -- id ekonomii
+    user_id    BIGINT NOT NULL,
+    item       TEXT   NOT NULL,            -- klucz przedmiotu (jak w crime_settings.loadout)
+    charges    INTEGER NOT NULL CHECK (charges > 0),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (guild_id, user_id, item)
+);
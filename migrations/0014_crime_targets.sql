@@ -0,0 +1,4 @@
+-- 0014: zapamiętany cel napadu /crime (kiosk, jubiler, bank, skarbiec kasyna; NULL = ulica).
+
+ALTER TABLE crime_settings
+    ADD COLUMN IF NOT EXISTS target TEXT NULL CHECK (target IN ('kiosk', 'jubiler', 'bank', 'kasyno'));
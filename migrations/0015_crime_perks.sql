@@ -0,0 +1,13 @@
+-- 0015: drzewko złodzieja — umiejętności kupowane za PP.
+-- `profiles.pp` zostaje licznikiem zdobytych PP (od niego liczą się progi przedmiotów i celów);
+-- wolne PP = pp − suma `spent`. Reset drzewka usuwa wiersze gracza i oddaje całe `spent`.
+
+CREATE TABLE IF NOT EXISTS crime_perks (
+    guild_id   BIGINT  NOT NULL,            -- id ekonomii
+    user_id    BIGINT  NOT NULL,
+    perk       TEXT    NOT NULL,            -- klucz umiejętności (shadow_step, reflexes, …)
+    rank       INTEGER NOT NULL CHECK (rank > 0),
+    spent      INTEGER NOT NULL CHECK (spent >= 0),  -- PP wydane na wszystkie poziomy (po cenach z chwili zakupu)
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (guild_id, user_id, perk)
+);
@@ -0,0 +1,73 @@
+-- 0016: historia rozstrzygniętych napadów /crime — pod statystyki w `/crime profil`
+-- i podgląd admina (`/admcontrol napady`). Pełne wejście i wynik są dalej w `rolls`;
+-- tu leży płaska kopia, po której da się grupować bez rozbierania JSON-a.
+-- Napad ekipy zapisuje wiersz na członka (z jego udziałem w `amount`).
+
+CREATE TABLE IF NOT EXISTS crime_heists (
+    id             BIGSERIAL PRIMARY KEY,
+    guild_id       BIGINT  NOT NULL,            -- id ekonomii
+    user_id        BIGINT  NOT NULL,
+    crew           BOOLEAN NOT NULL DEFAULT false,
+    mode           TEXT    NOT NULL,            -- klucz trybu (standard, szybki, …)
+    risk           TEXT    NOT NULL,            -- low | medium | high | hardcore
+    target         TEXT    NULL,                -- kiosk | jubiler | bank | kasyno; NULL = ulica
+    items          TEXT[]  NOT NULL DEFAULT '{}',
+    minigame       TEXT    NOT NULL,            -- qte | simon
+    mg_result      TEXT    NOT NULL CHECK (mg_result IN ('not_played', 'success', 'partial', 'fail')),
+    mg_diff_ms     INTEGER NULL,                -- tylko dla 'partial'
+    seed           BIGINT  NOT NULL,            -- ziarno z `rolls` (u64 bitowo jako i64)
+    interaction_id BIGINT,
+    success        BOOLEAN NOT NULL,
+    ambushed       BOOLEAN NOT NULL DEFAULT false,
+    amount         BIGINT  NOT NULL,            -- zmiana salda (łup albo strata)
+    loot_drop      BIGINT  NOT NULL DEFAULT 0,  -- łup specjalny celu (wliczony w `amount`)
+    heat_delta     BIGINT  NOT NULL,
+    created_at     TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS idx_crime_heists_user ON crime_heists(guild_id, user_id, id DESC);
+
+-- Napady solo sprzed tej migracji odtwarzamy z `rolls`; historia ekip zaczyna się od teraz.
+INSERT INTO crime_heists (guild_id, user_id, crew, mode, risk, target, items, minigame, mg_result, mg_diff_ms,
+                          seed, interaction_id, success, ambushed, amount, loot_drop, heat_delta, created_at)
+SELECT r.guild_id,
+       r.user_id,
+       false,
+       COALESCE(lower(r.inputs->'cfg'->>'mode'), 'standard'),
+       COALESCE(lower(r.inputs->'cfg'->>'risk'), 'medium'),
+       CASE r.inputs->'cfg'->>'target'
+           WHEN 'Kiosk'       THEN 'kiosk'
+           WHEN 'Jeweller'    THEN 'jubiler'
+           WHEN 'Bank'        THEN 'bank'
+           WHEN 'CasinoVault' THEN 'kasyno'
+       END,
+       ARRAY(
+           SELECT CASE i
+                      WHEN 'HackerLaptop' THEN 'laptop'
+                      WHEN 'ProGloves'    THEN 'gloves'
+                      WHEN 'Toolkit'      THEN 'toolkit'
+                      WHEN 'Adrenaline'   THEN 'adrenaline'
+                      WHEN 'SmokeGrenade' THEN 'smoke'
+                      WHEN 'LockpickSet'  THEN 'lockpick'
+                  END
+             FROM jsonb_array_elements_text(COALESCE(r.inputs->'cfg'->'items', '[]'::jsonb)) AS i
+       ),
+       lower(COALESCE(r.inputs->'cfg'->>'minigame', 'qte')),
+       CASE
+           WHEN jsonb_typeof(r.inputs->'mg') = 'object' THEN 'partial'
+           WHEN r.inputs->>'mg' = 'Success'            THEN 'success'
+           WHEN r.inputs->>'mg' = 'Fail'               THEN 'fail'
+           ELSE 'not_played'
+       END,
+       (r.inputs->'mg'->>'Partial')::int,
+       r.seed,
+       r.interaction_id,
+       (r.outcome->1->>'success')::boolean,
+       COALESCE((r.outcome->1->>'ambushed')::boolean, false),
+       (r.outcome->1->>'amount_final')::bigint,
+       COALESCE((r.outcome->1->>'drop')::bigint, 0),
+       (r.outcome->1->>'heat_delta')::bigint,
+       r.created_at
+  FROM rolls r
+ WHERE r.command = 'crime'
+ ORDER BY r.id;
@@ -0,0 +1,7 @@
+-- 0017: konto bankowe obok portfela. `/rob` kradnie tylko z portfela (`balance`);
+-- `bank` zmienia się wyłącznie przez księgę (konto `bank:<id>`) i nie schodzi pod zero.
+-- Pojemność wynika z `bank_level` (kupowany za TK) i tabel balansu (`config::BankTables`).
+
+ALTER TABLE users
+    ADD COLUMN IF NOT EXISTS bank       BIGINT   NOT NULL DEFAULT 0 CHECK (bank >= 0),
+    ADD COLUMN IF NOT EXISTS bank_level SMALLINT NOT NULL DEFAULT 0 CHECK (bank_level >= 0);
@@ -0,0 +1,21 @@
+-- 0018: odsetki od banku i podatek od majątku (zadanie `wealth.daily`).
+-- `treasury` to skarbiec ekonomii — saldo zmienia tylko księga (konto `treasury`).
+-- `wealth_runs` pilnuje, żeby naliczenie w danej ekonomii poszło raz na dzień,
+-- nawet gdy zadanie wystartuje ponownie po awarii; zostaje też jako podsumowanie.
+
+CREATE TABLE IF NOT EXISTS treasury (
+    guild_id   BIGINT PRIMARY KEY,               -- id ekonomii
+    balance    BIGINT NOT NULL DEFAULT 0 CHECK (balance >= 0),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE TABLE IF NOT EXISTS wealth_runs (
+    guild_id    BIGINT  NOT NULL,                -- id ekonomii
+    day         DATE    NOT NULL,                -- dzień naliczenia (UTC)
+    interest    BIGINT  NOT NULL DEFAULT 0,      -- suma wypłaconych odsetek
+    interest_to INTEGER NOT NULL DEFAULT 0,      -- ilu graczy dostało odsetki
+    tax         BIGINT  NOT NULL DEFAULT 0,      -- suma pobranego podatku
+    taxed       INTEGER NOT NULL DEFAULT 0,      -- ilu graczy zapłaciło podatek
+    created_at  TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (guild_id, day)
+);
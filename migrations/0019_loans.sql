@@ -0,0 +1,28 @@
+-- 0019: pożyczki (`/pozyczka`) — od banku (`lender IS NULL`, konto `sys:loans`) albo od gracza.
+-- `owed` to ile zostało do spłaty (kapitał + odsetki, po zwłoce także kara); maleje z każdą
+-- spłatą i potrąceniem z /work, /slut, /daily. Po terminie zadanie `loans.default` przestawia
+-- pożyczkę na `defaulted` — dłużnik ma wtedy zablokowane /pay i zakupy, dopóki nie spłaci.
+-- Gracz ma naraz najwyżej jedną niespłaconą pożyczkę (indeks częściowy poniżej).
+
+CREATE TABLE IF NOT EXISTS loans (
+    id             BIGSERIAL PRIMARY KEY,
+    guild_id       BIGINT  NOT NULL,                 -- id ekonomii
+    borrower       BIGINT  NOT NULL,
+    lender         BIGINT,                           -- NULL = bank
+    principal      BIGINT  NOT NULL CHECK (principal > 0),
+    interest       BIGINT  NOT NULL CHECK (interest >= 0),
+    penalty        BIGINT  NOT NULL DEFAULT 0 CHECK (penalty >= 0),
+    owed           BIGINT  NOT NULL CHECK (owed >= 0),
+    status         TEXT    NOT NULL DEFAULT 'active'
+                   CHECK (status IN ('active', 'defaulted', 'repaid')),
+    due_at         TIMESTAMPTZ NOT NULL,
+    interaction_id BIGINT,
+    created_at     TIMESTAMPTZ NOT NULL DEFAULT now(),
+    closed_at      TIMESTAMPTZ
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS loans_open_borrower
+    ON loans (guild_id, borrower) WHERE status <> 'repaid';
+
+CREATE INDEX IF NOT EXISTS loans_due
+    ON loans (due_at) WHERE status = 'active';
@@ -0,0 +1,23 @@
+-- 0020: prośby o zapłatę (`/pay zadaj`). `requester` prosi `payer` o `amount` TK; płacący
+-- przyjmuje albo odrzuca przyciskiem. Zapłata idzie tą samą ścieżką co `/pay` (limit, opłata),
+-- a zmiana statusu z `pending` w tej samej transakcji pilnuje, żeby prośbę opłacić tylko raz.
+-- Wygasłe prośby przechodzą na `expired` przy pierwszym kliknięciu po terminie.
+
+CREATE TABLE IF NOT EXISTS pay_requests (
+    id             BIGSERIAL PRIMARY KEY,
+    guild_id       BIGINT  NOT NULL,                 -- id ekonomii
+    requester      BIGINT  NOT NULL,                 -- kto dostanie TK
+    payer          BIGINT  NOT NULL,                 -- kto ma zapłacić
+    amount         BIGINT  NOT NULL CHECK (amount > 0),
+    note           TEXT,
+    status         TEXT    NOT NULL DEFAULT 'pending'
+                   CHECK (status IN ('pending', 'paid', 'declined', 'cancelled', 'expired')),
+    expires_at     TIMESTAMPTZ NOT NULL,
+    interaction_id BIGINT,
+    created_at     TIMESTAMPTZ NOT NULL DEFAULT now(),
+    resolved_at    TIMESTAMPTZ,
+    CHECK (requester <> payer)
+);
+
+CREATE INDEX IF NOT EXISTS pay_requests_open
+    ON pay_requests (guild_id, requester) WHERE status = 'pending';
@@ -0,0 +1,16 @@
+-- 0021: oczekujące potwierdzenia dużych przelewów (`/pay wyslij` powyżej `confirm_above`).
+-- Wiersz powstaje razem z przyciskami, kluczem jest id interakcji `/pay`. Kliknięcie blokuje
+-- go `FOR UPDATE` i zmienia status w transakcji przelewu, więc drugi klik nie zapłaci drugi raz.
+
+CREATE TABLE IF NOT EXISTS pay_confirmations (
+    interaction_id BIGINT  PRIMARY KEY,              -- interakcja `/pay`, która pyta o potwierdzenie
+    guild_id       BIGINT  NOT NULL,                 -- id ekonomii
+    sender         BIGINT  NOT NULL,
+    target         BIGINT  NOT NULL,
+    amount         BIGINT  NOT NULL CHECK (amount > 0),
+    status         TEXT    NOT NULL DEFAULT 'pending'
+                   CHECK (status IN ('pending', 'sent', 'cancelled', 'expired')),
+    created_at     TIMESTAMPTZ NOT NULL DEFAULT now(),
+    resolved_at    TIMESTAMPTZ,
+    CHECK (sender <> target)
+);
@@ -0,0 +1,20 @@
+-- 0022: oferty odsprzedaży przedmiotów (`/ekwipunek sprzedaj`). `seller` proponuje `buyer`
+-- przedmiot za `price` TK (0 = prezent); kupujący przyjmuje albo odrzuca przyciskiem.
+-- Kliknięcie blokuje wiersz `FOR UPDATE` i zmienia status w transakcji wymiany, więc drugi
+-- klik nie przekaże przedmiotu ani nie pobierze ceny drugi raz.
+
+CREATE TABLE IF NOT EXISTS item_offers (
+    id             BIGSERIAL PRIMARY KEY,
+    guild_id       BIGINT  NOT NULL,                 -- id ekonomii
+    seller         BIGINT  NOT NULL,
+    buyer          BIGINT  NOT NULL,
+    item           TEXT    NOT NULL,                 -- klucz przedmiotu (jak w crime_inventory.item)
+    price          BIGINT  NOT NULL CHECK (price >= 0),
+    status         TEXT    NOT NULL DEFAULT 'pending'
+                   CHECK (status IN ('pending', 'accepted', 'rejected', 'cancelled', 'expired')),
+    expires_at     TIMESTAMPTZ NOT NULL,
+    interaction_id BIGINT,
+    created_at     TIMESTAMPTZ NOT NULL DEFAULT now(),
+    resolved_at    TIMESTAMPTZ,
+    CHECK (seller <> buyer)
+);
@@ -0,0 +1,22 @@
+-- 0023: oferty pożyczek od graczy (`/pozyczka pozycz`). `lender` proponuje `borrower`
+-- `amount` TK z odsetkami `interest` na `days` dni; pożyczający przyjmuje albo odrzuca
+-- przyciskiem. Przyjęcie wypłaca kapitał i zmienia status w jednej transakcji, więc starej
+-- oferty nie da się przyjąć drugi raz (np. po spłacie pierwszej pożyczki).
+
+CREATE TABLE IF NOT EXISTS loan_offers (
+    id             BIGSERIAL PRIMARY KEY,
+    guild_id       BIGINT  NOT NULL,                 -- id ekonomii
+    lender         BIGINT  NOT NULL,
+    borrower       BIGINT  NOT NULL,
+    amount         BIGINT  NOT NULL CHECK (amount > 0),
+    interest       BIGINT  NOT NULL CHECK (interest >= 0),
+    days           INTEGER NOT NULL CHECK (days > 0),
+    status         TEXT    NOT NULL DEFAULT 'pending'
+                   CHECK (status IN ('pending', 'accepted', 'rejected', 'cancelled', 'expired')),
+    expires_at     TIMESTAMPTZ NOT NULL,
+    interaction_id BIGINT,
+    loan_id        BIGINT  REFERENCES loans (id),    -- pożyczka powstała z przyjęcia
+    created_at     TIMESTAMPTZ NOT NULL DEFAULT now(),
+    resolved_at    TIMESTAMPTZ,
+    CHECK (lender <> borrower)
+);
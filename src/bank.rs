@@ -0,0 +1,169 @@
+//! Konto bankowe — TK poza zasięgiem `/rob`.
+//!
+//! Portfel (`users.balance`) to to, z czego płacą komendy i kradnie `/rob`; bank (`users.bank`)
+//! tylko przechowuje. Wpłata i wypłata to przelew w księdze między `user:<id>` a `bank:<id>`.
+//! Pojemność rośnie z poziomem konta kupowanym za TK z portfela (`config::BankTables`),
+//! opłata idzie do `sys:bank`.
+
+use anyhow::Result;
+use sqlx::{PgConnection, PgPool};
+
+use crate::economy::Economy;
+use crate::engine::config;
+use crate::ledger::{self, Account, Entry, Overdraft};
+
+/// Konto systemowe, do którego idą opłaty za poziomy konta.
+const BANK_ACCOUNT: &str = "bank";
+
+/// Portfel, bank i poziom konta gracza.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Vault {
+    pub wallet: i64,
+    pub bank: i64,
+    pub level: u8,
+}
+
+impl Vault {
+    pub fn capacity(&self) -> i64 {
+        capacity(self.level)
+    }
+
+    /// Ile jeszcze zmieści się w banku.
+    pub fn room(&self) -> i64 {
+        (self.capacity() - self.bank).max(0)
+    }
+}
+
+/// Pojemność konta na poziomie `level` (poziomy ponad `max_level` się nie liczą).
+pub fn capacity(level: u8) -> i64 {
+    let b = &config::get().bank;
+    b.base_capacity + b.capacity_per_level * i64::from(level.min(b.max_level))
+}
+
+/// Cena kolejnego poziomu (None = konto ma już maksymalny).
+pub fn upgrade_cost(level: u8) -> Option<i64> {
+    let b = &config::get().bank;
+    (level < b.max_level).then(|| b.upgrade_cost + b.upgrade_cost_step * i64::from(level))
+}
+
+/// Stan konta; `lock` = blokada wiersza do końca transakcji.
+async fn read(conn: &mut PgConnection, eco: Economy, user: u64, lock: bool) -> Result<Vault> {
+    ledger::ensure_user(conn, eco, user).await?;
+    let sql = if lock {
+        "SELECT balance, bank, bank_level FROM users WHERE guild_id = $1 AND id = $2 FOR UPDATE"
+    } else {
+        "SELECT balance, bank, bank_level FROM users WHERE guild_id = $1 AND id = $2"
+    };
+    let (wallet, bank, level): (i64, i64, i16) =
+        sqlx::query_as(sql).bind(eco.id()).bind(user as i64).fetch_one(&mut *conn).await?;
+    Ok(Vault { wallet, bank, level: level.clamp(0, u8::MAX as i16) as u8 })
+}
+
+pub async fn load(db: &PgPool, eco: Economy, user: u64) -> Result<Vault> {
+    let mut conn = db.acquire().await?;
+    read(&mut conn, eco, user, false).await
+}
+
+pub enum Deposit {
+    /// Pusty portfel.
+    Nothing,
+    TooPoor { wallet: i64 },
+    NoRoom { room: i64, capacity: i64 },
+    Done { amount: i64, vault: Vault },
+}
+
+/// Wpłata z portfela; `amount = None` wpłaca tyle, ile się zmieści.
+pub async fn deposit(db: &PgPool, eco: Economy, user: u64, amount: Option<i64>, interaction_id: Option<u64>) -> Result<Deposit> {
+    let mut tx = db.begin().await?;
+    let v = read(&mut tx, eco, user, true).await?;
+    if v.wallet <= 0 {
+        return Ok(Deposit::Nothing);
+    }
+    let amount = match amount {
+        Some(a) if a > v.wallet => return Ok(Deposit::TooPoor { wallet: v.wallet }),
+        Some(a) if a > v.room() => return Ok(Deposit::NoRoom { room: v.room(), capacity: v.capacity() }),
+        Some(a) => a,
+        None if v.room() == 0 => return Ok(Deposit::NoRoom { room: 0, capacity: v.capacity() }),
+        None => v.wallet.min(v.room()),
+    };
+
+    let entry = Entry { reason: "wpłata do banku", command: "bank", interaction_id };
+    let posted =
+        ledger::transfer(&mut tx, eco, Account::User(user), Account::Bank(user), amount, &entry, Overdraft::Forbid).await?;
+    tx.commit().await?;
+
+    let vault = Vault {
+        wallet: posted.source_balance.unwrap_or_default(),
+        bank: posted.sink_balance.unwrap_or_default(),
+        level: v.level,
+    };
+    Ok(Deposit::Done { amount, vault })
+}
+
+pub enum Withdraw {
+    /// Pusty bank.
+    Nothing,
+    TooLittle { bank: i64 },
+    Done { amount: i64, vault: Vault },
+}
+
+/// Wypłata do portfela; `amount = None` wypłaca wszystko.
+pub async fn withdraw(db: &PgPool, eco: Economy, user: u64, amount: Option<i64>, interaction_id: Option<u64>) -> Result<Withdraw> {
+    let mut tx = db.begin().await?;
+    let v = read(&mut tx, eco, user, true).await?;
+    if v.bank <= 0 {
+        return Ok(Withdraw::Nothing);
+    }
+    let amount = match amount {
+        Some(a) if a > v.bank => return Ok(Withdraw::TooLittle { bank: v.bank }),
+        Some(a) => a,
+        None => v.bank,
+    };
+
+    let entry = Entry { reason: "wypłata z banku", command: "bank", interaction_id };
+    let posted =
+        ledger::transfer(&mut tx, eco, Account::Bank(user), Account::User(user), amount, &entry, Overdraft::Forbid).await?;
+    tx.commit().await?;
+
+    let vault = Vault {
+        wallet: posted.sink_balance.unwrap_or_default(),
+        bank: posted.source_balance.unwrap_or_default(),
+        level: v.level,
+    };
+    Ok(Withdraw::Done { amount, vault })
+}
+
+pub enum Upgrade {
+    Maxed,
+    TooPoor { wallet: i64, cost: i64 },
+    Done { cost: i64, vault: Vault },
+}
+
+/// Kolejny poziom konta, płatny z portfela.
+pub async fn upgrade(db: &PgPool, eco: Economy, user: u64, interaction_id: Option<u64>) -> Result<Upgrade> {
+    let mut tx = db.begin().await?;
+    let v = read(&mut tx, eco, user, true).await?;
+    let Some(cost) = upgrade_cost(v.level) else {
+        return Ok(Upgrade::Maxed);
+    };
+
+    let entry = Entry { reason: "poziom konta bankowego", command: "bank", interaction_id };
+    let wallet = match ledger::settle(&mut tx, eco, user, BANK_ACCOUNT, -cost, &entry, Overdraft::Forbid).await {
+        Ok(b) => b,
+        Err(e) => {
+            return match ledger::insufficient(&e) {
+                Some(wallet) => Ok(Upgrade::TooPoor { wallet, cost }),
+                None => Err(e),
+            }
+        }
+    };
+
+    sqlx::query("UPDATE users SET bank_level = bank_level + 1, updated_at = now() WHERE guild_id = $1 AND id = $2")
+        .bind(eco.id())
+        .bind(user as i64)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+
+    Ok(Upgrade::Done { cost, vault: Vault { wallet, level: v.level + 1, ..v } })
+}
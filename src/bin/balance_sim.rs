@@ -0,0 +1,92 @@
+//! Symulator balansu: `cargo run --release --bin balance-sim -- [opcje] > wyniki.csv`
+//!
+//!   --runs N           graczy na scenariusz (domyślnie 100)
+//!   --steps N          akcji na gracza (domyślnie 200)
+//!   --seed N           ziarno (domyślnie 1)
+//!   --loadouts single|all   przedmioty: pojedynczo albo wszystkie zestawy do 3
+//!   --targets          policz też każdy cel napadu (scenariusz `cel/tryb/ryzyko/…`)
+//!   --play-secs N      czas gry na akcję, doliczany do cooldownu (domyślnie 20)
+//!   --only crime,work,slut  tylko wybrane rodzaje
+//!   --format csv|json  (domyślnie csv)
+//!   --out PLIK         zamiast stdout
+//!   --dump-balance     wypisz domyślne tabele balansu (JSON) i zakończ
+//!
+//! Tabele balansu (w tym cooldowny, spadek HEAT i progi więzienia) bierze z `BALANCE_CONFIG_PATH`
+//! jak bot.
+
+use std::time::Instant;
+
+use anyhow::{bail, Context as _, Result};
+use dotenvy::from_filename;
+use tigrus_bot::engine::config;
+use tigrus_bot::sim::{self, Loadouts, SimConfig};
+
+fn main() -> Result<()> {
+    from_filename(".env.dev").ok();
+
+    let mut cfg = SimConfig::default();
+    let mut json = false;
+    let mut out: Option<String> = None;
+
+    let mut args = std::env::args().skip(1);
+    while let Some(flag) = args.next() {
+        let mut value = || args.next().with_context(|| format!("{flag}: brak wartości"));
+        match flag.as_str() {
+            "--runs" => cfg.runs = value()?.parse()?,
+            "--steps" => cfg.steps = value()?.parse()?,
+            "--seed" => cfg.seed = value()?.parse()?,
+            "--play-secs" => cfg.play_secs = value()?.parse()?,
+            "--targets" => cfg.targets = true,
+            "--loadouts" => {
+                cfg.loadouts = match value()?.as_str() {
+                    "single" => Loadouts::Single,
+                    "all" => Loadouts::All,
+                    other => bail!("--loadouts: nieznane `{other}` (single|all)"),
+                }
+            }
+            "--only" => {
+                let kinds = value()?;
+                let has = |k: &str| kinds.split(',').any(|x| x.trim() == k);
+                cfg.crime = has("crime");
+                cfg.work = has("work");
+                cfg.slut = has("slut");
+            }
+            "--format" => {
+                json = match value()?.as_str() {
+                    "csv" => false,
+                    "json" => true,
+                    other => bail!("--format: nieznane `{other}` (csv|json)"),
+                }
+            }
+            "--out" => out = Some(value()?),
+            "--dump-balance" => {
+                println!("{}", config::defaults_json());
+                return Ok(());
+            }
+            other => bail!("Nieznana opcja `{other}` (zob. nagłówek src/bin/balance_sim.rs)"),
+        }
+    }
+
+    config::init()?;
+    let balance = config::current();
+    if let (Some(path), Some(sum)) = (&balance.source, &balance.checksum) {
+        eprintln!("⚖️ Balans z {} (v{}, {sum})", path.display(), balance.cfg.version);
+    }
+
+    let started = Instant::now();
+    let rows = sim::run(&cfg);
+    let samples: u64 = rows.iter().map(|r| r.samples).sum();
+    eprintln!(
+        "🎲 {} scenariuszy, {samples} akcji w {:.1}s (seed {})",
+        rows.len(),
+        started.elapsed().as_secs_f64(),
+        cfg.seed
+    );
+
+    let report = if json { sim::to_json(&rows)? } else { sim::to_csv(&rows) };
+    match out {
+        Some(path) => std::fs::write(&path, report).with_context(|| format!("Nie zapisano {path}"))?,
+        None => print!("{report}"),
+    }
+    Ok(())
+}
@@ -0,0 +1,70 @@
+//! Źródło czasu dla logiki gry (cooldowny, terminy).
+//!
+//! Produkcja używa [`SystemClock`]; testy/symulacje instalują [`ManualClock`]
+//! przez [`install`] i przesuwają czas ręcznie zamiast czekać.
+
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, Duration, Utc};
+use once_cell::sync::OnceCell;
+
+pub trait Clock: Send + Sync {
+    fn now(&self) -> DateTime<Utc>;
+}
+
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// Zegar stojący w miejscu, dopóki ktoś go nie przestawi.
+pub struct ManualClock {
+    now: Mutex<DateTime<Utc>>,
+}
+
+impl ManualClock {
+    pub fn new(at: DateTime<Utc>) -> Self {
+        Self { now: Mutex::new(at) }
+    }
+
+    pub fn set(&self, at: DateTime<Utc>) {
+        *self.now.lock().unwrap_or_else(|e| e.into_inner()) = at;
+    }
+
+    pub fn advance(&self, by: Duration) {
+        let mut now = self.now.lock().unwrap_or_else(|e| e.into_inner());
+        *now += by;
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> DateTime<Utc> {
+        *self.now.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+static CLOCK: OnceCell<Arc<dyn Clock>> = OnceCell::new();
+
+/// Podmienia zegar globalny — tylko przed pierwszym użyciem `now()`. `false` = już ustawiony.
+pub fn install(clock: Arc<dyn Clock>) -> bool {
+    CLOCK.set(clock).is_ok()
+}
+
+pub fn now() -> DateTime<Utc> {
+    CLOCK.get_or_init(|| Arc::new(SystemClock)).now()
+}
+
+/// Wspólny [`ManualClock`] testów, zainstalowany jako zegar globalny (instalacja działa raz na proces).
+#[cfg(test)]
+pub(crate) fn test_clock() -> Arc<ManualClock> {
+    static TEST: OnceCell<Arc<ManualClock>> = OnceCell::new();
+    TEST.get_or_init(|| {
+        let clock = Arc::new(ManualClock::new(Utc::now()));
+        assert!(install(clock.clone()), "zegar globalny użyty przed test_clock()");
+        clock
+    })
+    .clone()
+}
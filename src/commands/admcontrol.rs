@@ -5,10 +5,19 @@ use serenity::all::*;
 use serenity::all::CommandOptionType;
 use serenity::builder::{CreateCommand, CreateCommandOption};
 use serenity::all::{CommandDataOption, CommandDataOptionValue, CommandInteraction, User};
-use sqlx::{PgPool, Row};
+use serenity::async_trait;
+use sqlx::PgPool;
 use std::collections::HashSet;
 
+use crate::cooldowns;
+use crate::economy::{self, Economy};
+use crate::engine::config;
+use crate::ledger::{self, Entry, Overdraft};
+use crate::reminders;
+use crate::rng::{self, RollRecord};
 use crate::utils::log_action;
+use crate::commands::crime_stats;
+use crate::commands::registry::BotCommand;
 
 // =====================
 // Stałe i cache
@@ -119,6 +128,75 @@ pub fn register(cmd: &mut CreateCommand) -> &mut CreateCommand {
                     )
                     .required(true),
                 ),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "linkeconomy",
+                "Dołącz ten serwer do ekonomii innego serwera (wspólne saldo, cooldowny, profile)",
+            )
+            .add_sub_option(
+                CreateCommandOption::new(
+                    CommandOptionType::String,
+                    "serwer",
+                    "ID serwera, którego ekonomię ma przejąć ten serwer",
+                )
+                .required(true),
+            ),
+        )
+        .add_option(CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "unlinkeconomy",
+            "Odłącz ten serwer od wspólnej ekonomii (wraca do własnej)",
+        ))
+        .add_option(CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "economyinfo",
+            "Pokaż, z której ekonomii korzysta ten serwer",
+        ))
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "rzut",
+                "Pokaż zapisane losowanie (ziarno, wejście, wynik) i odtwórz je",
+            )
+            .add_sub_option(CreateCommandOption::new(
+                CommandOptionType::String,
+                "interakcja",
+                "ID interakcji (np. z logu ledgera)",
+            ))
+            .add_sub_option(CreateCommandOption::new(
+                CommandOptionType::User,
+                "gracz",
+                "Ostatnie losowania gracza",
+            )),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "napady",
+                "Statystyki i historia napadów /crime wybranego gracza",
+            )
+            .add_sub_option(
+                CreateCommandOption::new(CommandOptionType::User, "gracz", "Gracz")
+                    .required(true),
+            )
+            .add_sub_option(
+                CreateCommandOption::new(CommandOptionType::Integer, "strona", "Strona historii (od 1)")
+                    .min_int_value(1),
+            ),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "balans",
+                "Pokaż wersję tabel balansu albo przeładuj je z pliku (bez restartu)",
+            )
+            .add_sub_option(CreateCommandOption::new(
+                CommandOptionType::Boolean,
+                "przeladuj",
+                "Wczytaj ponownie plik z BALANCE_CONFIG_PATH",
+            )),
         );
     cmd
 }
@@ -181,11 +259,18 @@ pub async fn run(ctx: &Context, cmd: &CommandInteraction, db: &PgPool) -> Result
             }
 
             let uid = i64::try_from(user.id.get()).context("ID użytkownika nie mieści się w i64")?;
+            let eco = economy::of(db, cmd.guild_id).await?;
 
+            let reason = format!("{} (admin {})", sub.name, cmd.user.id.get());
+            let entry = Entry {
+                reason: &reason,
+                command: "admcontrol",
+                interaction_id: Some(cmd.id.get()),
+            };
             let final_balance = match sub.name.as_str() {
-                "addmoney" => modify_balance(db, uid, amount).await?,
-                "removemoney" => modify_balance(db, uid, -amount).await?,
-                "setmoney" => set_balance(db, uid, amount).await?,
+                "addmoney" => modify_balance(db, eco, uid, amount, &entry).await?,
+                "removemoney" => modify_balance(db, eco, uid, -amount, &entry).await?,
+                "setmoney" => set_balance(db, eco, uid, amount, &entry).await?,
                 _ => unreachable!(),
             };
 
@@ -242,14 +327,15 @@ pub async fn run(ctx: &Context, cmd: &CommandInteraction, db: &PgPool) -> Result
                 .ok_or_else(|| anyhow!("Nie podano gracza"))?;
             let uid = i64::try_from(user.id.get()).context("ID użytkownika nie mieści się w i64")?;
 
-            reset_cooldowns(db, uid).await?;
+            let eco = economy::of(db, cmd.guild_id).await?;
+            let cleared = reset_cooldowns(db, eco, uid).await?;
             let _ = log_action(
                 db,
                 cmd.user.id.get(),
                 "resetcooldowns",
                 Some(user.id.get()),
                 None,
-                None,
+                Some(&cleared),
             )
             .await;
 
@@ -259,16 +345,50 @@ pub async fn run(ctx: &Context, cmd: &CommandInteraction, db: &PgPool) -> Result
                 "resetcooldowns".to_string(),
                 Some(&user),
                 None,
-                Some("✅ Zresetowano cooldowny".to_string()),
+                Some(format!("✅ Zresetowano cooldowny: {cleared}")),
             );
             edit_response(
                 ctx,
                 cmd,
-                &format!("✅ Zresetowano cooldowny dla <@{}>.", user.id.get()),
+                &format!("✅ Zresetowano cooldowny dla <@{}>: {cleared}.", user.id.get()),
             )
             .await?;
         }
 
+        "rzut" => {
+            let msg = lookup_rolls(cmd, sub, db).await?;
+            spawn_log(ctx.clone(), cmd.clone(), "rzut".to_string(), None, None, Some(truncate(&msg, 200)));
+            edit_response(ctx, cmd, &msg).await?;
+        }
+
+        "napady" => {
+            let user = parse_user(sub, "gracz", cmd)
+                .ok_or_else(|| anyhow!("Nie podano gracza"))?;
+            let page = parse_integer(sub, "strona").unwrap_or(1).saturating_sub(1);
+            let eco = economy::of(db, cmd.guild_id).await?;
+            let (embeds, rows) =
+                crime_stats::view(db, eco, user.id.get(), u32::try_from(page).unwrap_or(0)).await?;
+            spawn_log(ctx.clone(), cmd.clone(), "napady".to_string(), Some(&user), None, None);
+            cmd.edit_response(
+                &ctx.http,
+                EditInteractionResponse::new().embeds(embeds).components(rows),
+            )
+            .await?;
+        }
+
+        "balans" => {
+            let msg = balance_tables(sub);
+            let _ = log_action(db, cmd.user.id.get(), "balans", None, None, Some(&msg)).await;
+            spawn_log(ctx.clone(), cmd.clone(), "balans".to_string(), None, None, Some(truncate(&msg, 200)));
+            edit_response(ctx, cmd, &msg).await?;
+        }
+
+        "linkeconomy" | "unlinkeconomy" | "economyinfo" => {
+            let msg = manage_economy(ctx, cmd, sub, db).await?;
+            spawn_log(ctx.clone(), cmd.clone(), sub.name.clone(), None, None, Some(msg.clone()));
+            edit_response(ctx, cmd, &msg).await?;
+        }
+
         _ => {
             spawn_log(
                 ctx.clone(),
@@ -291,10 +411,13 @@ pub async fn run(ctx: &Context, cmd: &CommandInteraction, db: &PgPool) -> Result
 
 #[inline]
 fn is_authorized(cmd: &CommandInteraction) -> bool {
+    is_authorized_member(cmd.member.as_deref())
+}
+
+/// To samo sprawdzenie dla przycisków innych komend (np. historia napadów gracza).
+pub(crate) fn is_authorized_member(member: Option<&Member>) -> bool {
     // Admin permisje zawsze przepuszczamy
-    if cmd
-        .member
-        .as_ref()
+    if member
         .and_then(|m| m.permissions)
         .map(|p| p.administrator())
         .unwrap_or(false)
@@ -308,7 +431,7 @@ fn is_authorized(cmd: &CommandInteraction) -> bool {
         return false;
     }
     // członek musi mieć co najmniej jedną z ról
-    match &cmd.member {
+    match member {
         Some(member) => member.roles.iter().any(|rid| allowed.contains(rid)),
         None => false,
     }
@@ -358,6 +481,22 @@ pub fn parse_integer(sub: &CommandDataOption, name: &str) -> Option<i64> {
     })
 }
 
+fn parse_string(sub: &CommandDataOption, name: &str) -> Option<String> {
+    let items = sub_items(sub)?;
+    items.iter().find_map(|o| match &o.value {
+        CommandDataOptionValue::String(s) if o.name == name => Some(s.clone()),
+        _ => None,
+    })
+}
+
+fn parse_bool(sub: &CommandDataOption, name: &str) -> Option<bool> {
+    let items = sub_items(sub)?;
+    items.iter().find_map(|o| match o.value {
+        CommandDataOptionValue::Boolean(b) if o.name == name => Some(b),
+        _ => None,
+    })
+}
+
 fn parse_user_amount(sub: &CommandDataOption, cmd: &CommandInteraction) -> Result<(User, i64)> {
     let user = parse_user(sub, "gracz", cmd).ok_or_else(|| anyhow!("Nie podano gracza"))?;
     let amount = parse_integer(sub, "kwota").ok_or_else(|| anyhow!("Nie podano kwoty"))?;
@@ -369,59 +508,233 @@ fn parse_user_amount(sub: &CommandDataOption, cmd: &CommandInteraction) -> Resul
 // =====================
 
 /// Modyfikuje saldo o `change` (może być ujemne). Nie pozwala spaść poniżej 0.
-async fn modify_balance(db: &PgPool, user_id: i64, change: i64) -> Result<i64> {
-    let row = sqlx::query(
-        r#"
-        INSERT INTO users (id, balance)
-        VALUES ($1, GREATEST(0, $2))
-        ON CONFLICT (id) DO UPDATE
-        SET balance = GREATEST(0, users.balance + $2)
-        RETURNING balance
-        "#,
-    )
-    .bind(user_id)
-    .bind(change)
-    .fetch_one(db)
-    .await?;
+async fn modify_balance(db: &PgPool, eco: Economy, user_id: i64, change: i64, entry: &Entry<'_>) -> Result<i64> {
+    let mut tx = db.begin().await?;
+    let current = lock_balance(&mut tx, eco, user_id).await?;
+    // zdejmujemy najwyżej tyle, ile jest — reszta przepada
+    let delta = change.max(-current.max(0));
+    let bal = ledger::settle(&mut tx, eco, user_id as u64, "admin", delta, entry, Overdraft::Forbid).await?;
+    tx.commit().await?;
+    Ok(bal)
+}
 
-    Ok(row.get::<i64, _>("balance"))
+/// Ustawia saldo dokładnie na `new_balance` (przycina do ≥ 0) — w księdze jako różnica.
+async fn set_balance(db: &PgPool, eco: Economy, user_id: i64, new_balance: i64, entry: &Entry<'_>) -> Result<i64> {
+    let mut tx = db.begin().await?;
+    let current = lock_balance(&mut tx, eco, user_id).await?;
+    let delta = new_balance.max(0) - current;
+    let bal = ledger::settle(&mut tx, eco, user_id as u64, "admin", delta, entry, Overdraft::Allow).await?;
+    tx.commit().await?;
+    Ok(bal)
 }
 
-/// Ustawia saldo dokładnie na `new_balance` (przycina do ≥ 0).
-async fn set_balance(db: &PgPool, user_id: i64, new_balance: i64) -> Result<i64> {
-    let nb = new_balance.max(0);
-    let row = sqlx::query(
-        r#"
-        INSERT INTO users (id, balance)
-        VALUES ($1, $2)
-        ON CONFLICT (id) DO UPDATE SET balance = EXCLUDED.balance
-        RETURNING balance
-        "#,
-    )
-    .bind(user_id)
-    .bind(nb)
-    .fetch_one(db)
-    .await?;
+async fn lock_balance(conn: &mut sqlx::PgConnection, eco: Economy, user_id: i64) -> Result<i64> {
+    ledger::ensure_user(conn, eco, user_id as u64).await?;
+    let bal: i64 = sqlx::query_scalar("SELECT balance FROM users WHERE guild_id = $1 AND id = $2 FOR UPDATE")
+        .bind(eco.id())
+        .bind(user_id)
+        .fetch_one(&mut *conn)
+        .await?;
+    Ok(bal)
+}
+
+/// Kasuje aktywne cooldowny gracza w ekonomii.
+/// Zwraca opis tego, co zostało zdjęte (np. "/work (zostało 12s), /rob (zostało 540s)").
+async fn reset_cooldowns(db: &PgPool, eco: Economy, user_id: i64) -> Result<String> {
+    let cleared = cooldowns::reset(db, eco, user_id as u64).await?;
+    if cleared.is_empty() {
+        return Ok("brak aktywnych".to_string());
+    }
+    let keys: Vec<String> = cleared.keys().cloned().collect();
+    reminders::cancel(db, user_id as u64, &keys).await?;
+
+    let names = crate::commands::registry().cooldowns();
+    let mut parts: Vec<String> = cleared
+        .iter()
+        .map(|(key, left)| {
+            let label = names
+                .iter()
+                .find(|(_, meta)| meta.key == key)
+                .map(|(name, _)| format!("/{name}"))
+                .unwrap_or_else(|| key.clone());
+            format!("{label} (zostało {left}s)")
+        })
+        .collect();
+    parts.sort();
+    Ok(parts.join(", "))
+}
+
+// =====================
+// Losowania (spory o wynik)
+// =====================
+
+/// Ile losowań gracza pokazujemy naraz (limit wiadomości Discorda).
+const ROLLS_SHOWN: i64 = 5;
+
+/// Zapisane losowania (po interakcji albo ostatnie gracza) + powtórka z ziarna.
+async fn lookup_rolls(cmd: &CommandInteraction, sub: &CommandDataOption, db: &PgPool) -> Result<String> {
+    let eco = economy::of(db, cmd.guild_id).await?;
+
+    let records = if let Some(raw) = parse_string(sub, "interakcja") {
+        let Ok(id) = raw.trim().parse::<u64>() else {
+            return Ok("❌ Podaj poprawne ID interakcji.".to_string());
+        };
+        let mut recs = rng::by_interaction(db, id).await?;
+        // tylko losowania z ekonomii tego serwera
+        recs.retain(|r| r.guild_id == eco.id());
+        recs
+    } else if let Some(user) = parse_user(sub, "gracz", cmd) {
+        rng::recent(db, eco, user.id.get(), ROLLS_SHOWN).await?
+    } else {
+        return Ok("❌ Podaj ID interakcji albo gracza.".to_string());
+    };
 
-    Ok(row.get::<i64, _>("balance"))
+    if records.is_empty() {
+        return Ok("ℹ️ Brak zapisanych losowań.".to_string());
+    }
+
+    let blocks: Vec<String> = records.iter().take(ROLLS_SHOWN as usize).map(describe_roll).collect();
+    Ok(blocks.join("\n\n"))
 }
 
-async fn reset_cooldowns(db: &PgPool, user_id: i64) -> Result<()> {
-    sqlx::query(
-        r#"
-        UPDATE users
-        SET last_work  = NULL,
-            last_daily = NULL,
-            last_slut  = NULL,
-            last_crime = NULL,
-            last_rob   = NULL
-        WHERE id = $1
-        "#,
+fn describe_roll(r: &RollRecord) -> String {
+    let verdict = match crate::commands::registry()
+        .by_roll(&r.command)
+        .and_then(|c| c.replay(&r.command, r.seed(), &r.inputs))
+    {
+        None => "⚠️ brak powtórki dla tego rodzaju".to_string(),
+        Some(Err(e)) => format!("⚠️ powtórka nieudana: {e}"),
+        Some(Ok(replayed)) => {
+            let stored: serde_json::Value = serde_json::from_str(&r.outcome).unwrap_or_default();
+            if stored == replayed {
+                "✅ zgodna z zapisem".to_string()
+            } else {
+                format!("❌ ROZBIEŻNA: `{}`", truncate(&replayed.to_string(), 300))
+            }
+        }
+    };
+
+    format!(
+        "**#{}** `{}` • <@{}> • <t:{}:f>\ninterakcja `{}` • ziarno `{:#018x}`\nwejście: `{}`\nwynik: `{}`\npowtórka: {verdict}",
+        r.id,
+        r.command,
+        r.user_id,
+        r.created_at.timestamp(),
+        r.interaction_id.map(|i| i.to_string()).unwrap_or_else(|| "—".into()),
+        r.seed(),
+        truncate(&r.inputs, 300),
+        truncate(&r.outcome, 300),
     )
-    .bind(user_id)
-    .execute(db)
-    .await?;
-    Ok(())
+}
+
+fn truncate(s: &str, max: usize) -> String {
+    if s.chars().count() <= max {
+        s.to_string()
+    } else {
+        format!("{}…", s.chars().take(max).collect::<String>())
+    }
+}
+
+// =====================
+// Tabele balansu
+// =====================
+
+/// Stan tabel balansu; z `przeladuj` najpierw wczytuje plik ponownie (zły plik = zostaje stary).
+fn balance_tables(sub: &CommandDataOption) -> String {
+    let mut head = String::new();
+    if parse_bool(sub, "przeladuj").unwrap_or(false) {
+        match config::reload() {
+            Ok((_, changed)) if changed.is_empty() => head = "🔄 Przeładowano — bez zmian w tabelach.\n".into(),
+            Ok((_, changed)) => head = format!("🔄 Przeładowano — zmienione sekcje: {}.\n", changed.join(", ")),
+            Err(e) => return format!("❌ Nie przeładowano (zostają obecne tabele): {e:#}"),
+        }
+    }
+
+    let now = config::current();
+    let source = match (&now.source, &now.checksum) {
+        (Some(path), Some(sum)) => format!("plik `{}` • sha `{sum}`", path.display()),
+        _ => "wartości domyślne (brak `BALANCE_CONFIG_PATH`)".to_string(),
+    };
+    format!("{head}⚖️ Balans v{} • {source}", now.cfg.version)
+}
+
+// =====================
+// Wspólne ekonomie
+// =====================
+
+/// linkeconomy / unlinkeconomy / economyinfo — zwraca treść odpowiedzi.
+async fn manage_economy(ctx: &Context, cmd: &CommandInteraction, sub: &CommandDataOption, db: &PgPool) -> Result<String> {
+    let guild = cmd.guild_id.ok_or_else(|| anyhow!("Brak serwera"))?;
+
+    match sub.name.as_str() {
+        "linkeconomy" => {
+            let Some(target) = parse_string(sub, "serwer")
+                .and_then(|s| s.trim().parse::<u64>().ok())
+                .filter(|&id| id != 0)
+                .map(GuildId::new)
+            else {
+                return Ok("❌ Podaj poprawne ID serwera.".to_string());
+            };
+
+            // zgoda drugiej strony: wołający musi być adminem także na serwerze docelowym
+            if !is_admin_in(ctx, target, cmd.user.id).await {
+                return Ok(format!(
+                    "❌ Bot nie jest na serwerze `{}` albo nie masz tam uprawnień administratora.",
+                    target.get()
+                ));
+            }
+
+            match economy::link(db, guild, target, cmd.user.id.get()).await {
+                Ok(eco) => Ok(format!(
+                    "✅ Ten serwer korzysta teraz z ekonomii `{}`. Dotychczasowe salda tego serwera zostają w bazie, ale nie są już widoczne.",
+                    eco.0
+                )),
+                Err(e) => Ok(format!("❌ {e}")),
+            }
+        }
+        "unlinkeconomy" => {
+            if economy::unlink(db, guild).await? {
+                Ok("✅ Serwer wrócił do własnej ekonomii.".to_string())
+            } else {
+                Ok("ℹ️ Ten serwer nie był podpięty pod cudzą ekonomię.".to_string())
+            }
+        }
+        _ => {
+            let eco = economy::resolve(db, guild).await?;
+            let mut shared = economy::linked_guilds(db, eco).await?;
+            if eco.0 != economy::LEGACY_ECONOMY {
+                shared.push(eco.0);
+            }
+            shared.retain(|&g| g != guild.get());
+            let others = if shared.is_empty() {
+                "brak".to_string()
+            } else {
+                shared.iter().map(|g| format!("`{g}`")).collect::<Vec<_>>().join(", ")
+            };
+            let mut conn = db.acquire().await?;
+            let treasury = ledger::treasury_of(&mut conn, eco).await?;
+            Ok(format!("🏦 Ekonomia: `{}`\nWspółdzielona z: {others}\n🏛️ Skarbiec: **{treasury} TK**", eco.0))
+        }
+    }
+}
+
+/// Właściciel albo rola z Administratorem na wskazanym serwerze (przez HTTP — cache może nie mieć gildii).
+async fn is_admin_in(ctx: &Context, guild: GuildId, user: UserId) -> bool {
+    let Ok(g) = guild.to_partial_guild(&ctx.http).await else {
+        return false;
+    };
+    if g.owner_id == user {
+        return true;
+    }
+    let Ok(member) = guild.member(&ctx.http, user).await else {
+        return false;
+    };
+    member
+        .roles
+        .iter()
+        .chain(std::iter::once(&RoleId::new(guild.get())))
+        .filter_map(|r| g.roles.get(r))
+        .any(|r| r.permissions.administrator())
 }
 
 // =====================
@@ -468,7 +781,6 @@ fn spawn_log(
         tokio::spawn({
     // sklonuj to, co potrzeba do taska
     let http = ctx.http.clone();
-    let ch = ch;
     let action_owned = action_owned.clone();
     let invoker = invoker.clone();
     let target_mention = target_mention.clone();
@@ -476,7 +788,6 @@ fn spawn_log(
     let guild_s = guild_s.clone();
     let channel_s = channel_s.clone();
     let result_owned = result_owned.clone();
-    let now_unix = now_unix;
 
     async move {
         // zbuduj embed krokami (Serenity 0.12 konsumuje self)
@@ -508,3 +819,30 @@ fn spawn_log(
 });
     }
 }
+
+// =======================
+// 🧭 Rejestr komend
+// =======================
+
+pub struct AdmControlCommand;
+
+#[async_trait]
+impl BotCommand for AdmControlCommand {
+    fn name(&self) -> &'static str {
+        "admcontrol"
+    }
+
+    fn economy(&self) -> bool {
+        true
+    }
+
+    fn register(&self) -> CreateCommand {
+        let mut c = CreateCommand::new("admcontrol");
+        register(&mut c);
+        c
+    }
+
+    async fn run(&self, ctx: &Context, cmd: &CommandInteraction, db: &PgPool) -> Result<()> {
+        run(ctx, cmd, db).await
+    }
+}
@@ -2,12 +2,17 @@ use anyhow::Result;
 use serenity::all::*;
 use serenity::all::CommandOptionType;
 use serenity::builder::{CreateCommand, CreateCommandOption, CreateEmbed, CreateEmbedAuthor};
-use sqlx::{PgPool, Row};
+use serenity::async_trait;
+use sqlx::PgPool;
+
+use crate::bank;
+use crate::commands::registry::BotCommand;
+use crate::economy;
 use num_format::{Locale, ToFormattedString};
 
 pub fn register(cmd: &mut CreateCommand) -> &mut CreateCommand {
     *cmd = CreateCommand::new("balance")
-        .description("Sprawdź saldo swoje lub innego gracza 💰")
+        .description("Sprawdź portfel i bank — swój lub innego gracza 💰")
         .add_option(
             CreateCommandOption::new(
                 CommandOptionType::User,
@@ -20,7 +25,7 @@ pub fn register(cmd: &mut CreateCommand) -> &mut CreateCommand {
 }
 
 pub async fn run(ctx: &Context, cmd: &CommandInteraction, db: &PgPool) -> Result<()> {
-    let (user, user_id) = match cmd.data.options.get(0) {
+    let (user, user_id) = match cmd.data.options.first() {
         Some(opt) => match &opt.value {
             CommandDataOptionValue::User(uid) => {
                 if let Some(u) = cmd.data.resolved.users.get(uid).cloned() {
@@ -34,19 +39,24 @@ pub async fn run(ctx: &Context, cmd: &CommandInteraction, db: &PgPool) -> Result
         None => (cmd.user.clone(), cmd.user.id.get()),
     };
 
-    let balance: i64 = sqlx::query("SELECT balance FROM users WHERE id = $1")
-        .bind(user_id as i64)
-        .fetch_optional(db)
-        .await?
-        .and_then(|row| row.try_get("balance").ok())
-        .unwrap_or(0);
+    let eco = economy::of(db, cmd.guild_id).await?;
+    let (wallet, bank, level): (i64, i64, i16) =
+        sqlx::query_as("SELECT balance, bank, bank_level FROM users WHERE guild_id = $1 AND id = $2")
+            .bind(eco.id())
+            .bind(user_id as i64)
+            .fetch_optional(db)
+            .await?
+            .unwrap_or_default();
+    let capacity = bank::capacity(level.clamp(0, u8::MAX as i16) as u8);
 
     // Formatowanie z separatorami tysięcy (np. 1 234 567)
-    let balance_str = balance.to_formatted_string(&Locale::pl);
+    let fmt = |v: i64| v.to_formatted_string(&Locale::pl);
 
     let embed = CreateEmbed::new()
         .title("💰 Saldo konta")
-        .description(format!("{} posiada **{} TK**", user.mention(), balance_str))
+        .description(format!("{} posiada łącznie **{} TK**", user.mention(), fmt(wallet + bank)))
+        .field("👛 Portfel", format!("{} TK", fmt(wallet)), true)
+        .field("🏦 Bank", format!("{} / {} TK", fmt(bank), fmt(capacity)), true)
         .color(0x00BFFF)
         .author(
             CreateEmbedAuthor::new(&user.name)
@@ -65,3 +75,30 @@ pub async fn run(ctx: &Context, cmd: &CommandInteraction, db: &PgPool) -> Result
 
     Ok(())
 }
+
+// =======================
+// 🧭 Rejestr komend
+// =======================
+
+pub struct BalanceCommand;
+
+#[async_trait]
+impl BotCommand for BalanceCommand {
+    fn name(&self) -> &'static str {
+        "balance"
+    }
+
+    fn economy(&self) -> bool {
+        true
+    }
+
+    fn register(&self) -> CreateCommand {
+        let mut c = CreateCommand::new("balance");
+        register(&mut c);
+        c
+    }
+
+    async fn run(&self, ctx: &Context, cmd: &CommandInteraction, db: &PgPool) -> Result<()> {
+        run(ctx, cmd, db).await
+    }
+}
@@ -0,0 +1,203 @@
+//! commands/bank.rs — `/bank`: wpłaty i wypłaty między portfelem a kontem bankowym.
+//!
+//! `/rob` sięga tylko do portfela, więc bank to sposób na trzymanie oszczędności.
+//! Konto ma pojemność zależną od poziomu, a kolejne poziomy kupuje się za TK (`/bank ulepsz`).
+
+use anyhow::Result;
+use serenity::all::{
+    CommandDataOptionValue, CommandInteraction, CommandOptionType, Context, CreateCommand, CreateCommandOption,
+    CreateEmbed, CreateInteractionResponse, CreateInteractionResponseMessage,
+};
+use serenity::async_trait;
+use sqlx::PgPool;
+
+use crate::bank::{self, Deposit, Upgrade, Vault, Withdraw};
+use crate::clock;
+use crate::commands::registry::BotCommand;
+use crate::economy;
+use crate::engine::config;
+use crate::utils::log_action;
+use crate::wealth;
+
+// =======================
+// 🔧 Rejestracja
+// =======================
+
+fn amount_option(description: &str) -> CreateCommandOption {
+    CreateCommandOption::new(CommandOptionType::Integer, "kwota", description).min_int_value(1)
+}
+
+pub fn register(cmd: &mut CreateCommand) -> &mut CreateCommand {
+    *cmd = CreateCommand::new("bank")
+        .description("Konto bankowe — TK, których nikt nie ukradnie 🏦")
+        .add_option(CreateCommandOption::new(CommandOptionType::SubCommand, "stan", "Portfel, bank i pojemność konta"))
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::SubCommand, "wplac", "Wpłać TK z portfela do banku")
+                .add_sub_option(amount_option("Ile wpłacić (puste = tyle, ile się zmieści)")),
+        )
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::SubCommand, "wyplac", "Wypłać TK z banku do portfela")
+                .add_sub_option(amount_option("Ile wypłacić (puste = wszystko)")),
+        )
+        .add_option(CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "ulepsz",
+            "Kup kolejny poziom konta (większa pojemność)",
+        ));
+    cmd
+}
+
+// =======================
+// ⌨️ Slash
+// =======================
+
+pub async fn run(ctx: &Context, cmd: &CommandInteraction, db: &PgPool) -> Result<()> {
+    let eco = economy::of(db, cmd.guild_id).await?;
+    let user = cmd.user.id.get();
+    let iid = Some(cmd.id.get());
+
+    let Some(sub) = cmd.data.options.first() else {
+        return show(ctx, cmd, bank::load(db, eco, user).await?, None).await;
+    };
+    let amount = match &sub.value {
+        CommandDataOptionValue::SubCommand(opts) => opts.iter().find_map(|o| match o.value {
+            CommandDataOptionValue::Integer(n) if o.name == "kwota" => Some(n),
+            _ => None,
+        }),
+        _ => None,
+    };
+    if amount.is_some_and(|a| a <= 0) {
+        return show(ctx, cmd, bank::load(db, eco, user).await?, Some("❌ Kwota musi być większa niż 0.".into())).await;
+    }
+
+    let (vault, note) = match sub.name.as_str() {
+        "wplac" => match bank::deposit(db, eco, user, amount, iid).await? {
+            Deposit::Nothing => (None, "👛 Portfel jest pusty — nie ma czego wpłacać.".to_string()),
+            Deposit::TooPoor { wallet } => (None, format!("💸 W portfelu masz tylko **{wallet} TK**.")),
+            Deposit::NoRoom { room: 0, capacity } => (
+                None,
+                format!("🏦 Konto jest pełne (**{capacity} TK**) — powiększysz je przez `/bank ulepsz`."),
+            ),
+            Deposit::NoRoom { room, capacity } => {
+                (None, format!("🏦 Zmieścisz jeszcze **{room} TK** (pojemność **{capacity} TK**)."))
+            }
+            Deposit::Done { amount, vault } => {
+                let _ = log_action(db, user, "bank_deposit", None, Some(amount), Some(&format!("Wpłata {amount} TK"))).await;
+                (Some(vault), format!("📥 Wpłacono **{amount} TK** do banku."))
+            }
+        },
+        "wyplac" => match bank::withdraw(db, eco, user, amount, iid).await? {
+            Withdraw::Nothing => (None, "🏦 Bank jest pusty.".to_string()),
+            Withdraw::TooLittle { bank } => (None, format!("🏦 W banku masz tylko **{bank} TK**.")),
+            Withdraw::Done { amount, vault } => {
+                let _ = log_action(db, user, "bank_withdraw", None, Some(amount), Some(&format!("Wypłata {amount} TK"))).await;
+                (Some(vault), format!("📤 Wypłacono **{amount} TK** do portfela."))
+            }
+        },
+        "ulepsz" => match bank::upgrade(db, eco, user, iid).await? {
+            Upgrade::Maxed => (None, "✨ Konto ma już maksymalny poziom.".to_string()),
+            Upgrade::TooPoor { wallet, cost } => {
+                (None, format!("💸 Kolejny poziom kosztuje **{cost} TK**, a w portfelu masz **{wallet} TK**."))
+            }
+            Upgrade::Done { cost, vault } => {
+                let desc = format!("Poziom konta {} za {cost} TK", vault.level);
+                let _ = log_action(db, user, "bank_upgrade", None, Some(-cost), Some(&desc)).await;
+                (
+                    Some(vault),
+                    format!("🏗️ Konto na poziomie **{}** — pojemność **{} TK**.", vault.level, vault.capacity()),
+                )
+            }
+        },
+        _ => (None, String::new()),
+    };
+
+    let vault = match vault {
+        Some(v) => v,
+        None => bank::load(db, eco, user).await?,
+    };
+    show(ctx, cmd, vault, (!note.is_empty()).then_some(note)).await
+}
+
+async fn show(ctx: &Context, cmd: &CommandInteraction, vault: Vault, note: Option<String>) -> Result<()> {
+    let mut msg = CreateInteractionResponseMessage::new().ephemeral(true).embed(render(&vault));
+    if let Some(n) = note {
+        msg = msg.content(n);
+    }
+    cmd.create_response(&ctx.http, CreateInteractionResponse::Message(msg)).await?;
+    Ok(())
+}
+
+// =======================
+// 🧱 Widok
+// =======================
+
+fn render(v: &Vault) -> CreateEmbed {
+    let next = match bank::upgrade_cost(v.level) {
+        Some(cost) => format!("{cost} TK → pojemność {} TK", bank::capacity(v.level + 1)),
+        None => "maksymalny poziom".to_string(),
+    };
+    let b = &config::get().bank;
+    CreateEmbed::new()
+        .title("🏦 Bank")
+        .description("Portfel płaci za wszystko i można go okraść (`/rob`). Bank przechowuje i nalicza odsetki — tam `/rob` nie sięgnie.")
+        .field("👛 Portfel", format!("{} TK", v.wallet), true)
+        .field("🏦 Bank", format!("{} / {} TK", v.bank, v.capacity()), true)
+        .field("Razem", format!("{} TK", v.wallet + v.bank), true)
+        .field(
+            format!("Poziom konta {}/{}", v.level, b.max_level),
+            format!("Następny: {next}"),
+            false,
+        )
+        .field(
+            "📈 Odsetki",
+            format!(
+                "{:.2}% dziennie od salda do {} TK — najbliższe ok. **{} TK**",
+                b.interest_rate * 100.0,
+                b.interest_cap,
+                wealth::interest(v.bank)
+            ),
+            false,
+        )
+        .field("🧾 Podatek od majątku (portfel + bank)", tax_brackets(), false)
+        .color(0x0ea5e9)
+        .timestamp(clock::now())
+}
+
+fn tax_brackets() -> String {
+    let brackets = &config::get().tax.brackets;
+    if brackets.is_empty() {
+        return "brak".to_string();
+    }
+    brackets
+        .iter()
+        .map(|b| format!("ponad {} TK: {:.2}% dziennie", b.from, b.rate * 100.0))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// =======================
+// 🧭 Rejestr komend
+// =======================
+
+pub struct BankCommand;
+
+#[async_trait]
+impl BotCommand for BankCommand {
+    fn name(&self) -> &'static str {
+        "bank"
+    }
+
+    fn economy(&self) -> bool {
+        true
+    }
+
+    fn register(&self) -> CreateCommand {
+        let mut c = CreateCommand::new("bank");
+        register(&mut c);
+        c
+    }
+
+    async fn run(&self, ctx: &Context, cmd: &CommandInteraction, db: &PgPool) -> Result<()> {
+        run(ctx, cmd, db).await
+    }
+}
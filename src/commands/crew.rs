@@ -0,0 +1,758 @@
+//! commands/crew.rs — napady ekipowe `/crime ekipa`: lobby na kanale, role, QTE każdego członka.
+//!
+//! Lider otwiera lobby (publiczna wiadomość), 2–5 graczy dołącza w rolach, po starcie każdy
+//! gra własne QTE na tej samej wiadomości, a lider rozstrzyga napad. Stan lobby żyje
+//! w `crime_crews` (JSON) — każda akcja blokuje wiersz, więc równoległe kliknięcia idą po kolei.
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serenity::all::{
+    ButtonStyle, CommandDataOption, CommandDataOptionValue, CommandInteraction, CommandOptionType,
+    ComponentInteraction, Context, CreateActionRow, CreateButton, CreateCommandOption, CreateEmbed,
+    CreateInteractionResponse, CreateInteractionResponseMessage, InteractionResponseFlags,
+};
+use sqlx::{PgConnection, PgExecutor, PgPool};
+
+use super::crime::{
+    bar10, emoji_for_mode, emoji_for_risk, from_key_mode, from_key_risk, heist_cooldown, mode_label, qte_spec,
+    repo, reply_ephemeral, saved_loadout, snowflake_ms, COOLDOWN_KEY, SESSION_TTL,
+};
+use crate::clock;
+use crate::cooldowns;
+use crate::economy::{self, Economy};
+use crate::inventory;
+use crate::jail;
+use crate::engine::{
+    crew::{CrewHeistConfig, CrewRole, LootSplit, MAX_CREW, MIN_CREW},
+    minigames,
+    repo::{CrewMember, CrewResolution},
+    types::*,
+};
+use crate::reminders;
+use crate::utils::log_action;
+
+/// Ilu graczy może zająć tę samą rolę.
+const MAX_PER_ROLE: usize = 2;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrewLobby {
+    pub leader_id: u64,
+    pub cfg: CrewHeistConfig,
+    pub phase: CrewPhase,
+    /// `members[0]` to zawsze lider.
+    pub members: Vec<CrewSlot>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CrewPhase {
+    /// Zbieranie ekipy.
+    Open,
+    /// Każdy gra swoje QTE; lider rozstrzyga.
+    Playing,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrewSlot {
+    pub user_id: u64,
+    pub role: CrewRole,
+    /// Snapshot ekwipunku przy starcie (z zapisanych ustawień /crime).
+    #[serde(default)]
+    pub items: Vec<ItemKey>,
+    #[serde(default)]
+    pub qte: Option<QteSpec>,
+    #[serde(default)]
+    pub armed_ms: Option<i64>,
+    #[serde(default)]
+    pub elapsed_ms: Option<i64>,
+    #[serde(default)]
+    pub result: Option<MinigameResult>,
+}
+
+impl CrewSlot {
+    fn new(user_id: u64, role: CrewRole) -> Self {
+        Self { user_id, role, items: Vec::new(), qte: None, armed_ms: None, elapsed_ms: None, result: None }
+    }
+}
+
+impl CrewLobby {
+    fn slot(&self, user: u64) -> Option<usize> {
+        self.members.iter().position(|m| m.user_id == user)
+    }
+
+    fn role_taken(&self, role: CrewRole) -> usize {
+        self.members.iter().filter(|m| m.role == role).count()
+    }
+
+    fn solo_cfg(&self, items: &[ItemKey]) -> SoloHeistConfig {
+        SoloHeistConfig {
+            mode: Some(self.cfg.mode),
+            risk: Some(self.cfg.risk),
+            minigame: MinigameKind::Qte,
+            items: items.to_vec(),
+            ..SoloHeistConfig::default()
+        }
+    }
+}
+
+// =================== Rejestracja ===================
+
+/// Podkomenda `/crime ekipa` (dopinana w `crime::register`).
+pub fn subcommand() -> CreateCommandOption {
+    let role = CrewRole::ALL.into_iter().fold(
+        CreateCommandOption::new(CommandOptionType::String, "rola", "Twoja rola w ekipie").required(true),
+        |o, r| o.add_string_choice(r.label(), r.key()),
+    );
+    CreateCommandOption::new(CommandOptionType::SubCommand, "ekipa", "Zbierz ekipę (2–5 osób) na wspólny napad")
+        .add_sub_option(role)
+        .add_sub_option(
+            CreateCommandOption::new(CommandOptionType::String, "tryb", "Tryb napadu (domyślnie Standard)")
+                .add_string_choice("Standard", "standard")
+                .add_string_choice("Szybki", "szybki")
+                .add_string_choice("Ostrożny", "ostrozny")
+                .add_string_choice("Shadow", "shadow")
+                .add_string_choice("Hardcore", "hardcore")
+                .add_string_choice("Ryzykowny", "ryzykowny")
+                .add_string_choice("Planowany", "planowany")
+                .add_string_choice("Szalony", "szalony"),
+        )
+        .add_sub_option(
+            CreateCommandOption::new(CommandOptionType::String, "ryzyko", "Poziom ryzyka (domyślnie Medium)")
+                .add_string_choice("Low", "low")
+                .add_string_choice("Medium", "medium")
+                .add_string_choice("High", "high")
+                .add_string_choice("Hardcore", "hardcore"),
+        )
+        .add_sub_option(
+            CreateCommandOption::new(CommandOptionType::String, "podzial", "Podział łupu (domyślnie po równo)")
+                .add_string_choice("Po równo", "rowno")
+                .add_string_choice("Wg ról", "role")
+                .add_string_choice("Działka lidera", "lider"),
+        )
+        .add_sub_option(
+            CreateCommandOption::new(CommandOptionType::Integer, "lider_procent", "Działka lidera w % (przy podziale „lider”)")
+                .min_int_value(10)
+                .max_int_value(60),
+        )
+}
+
+// =================== Slash ===================
+
+pub async fn open_lobby(ctx: &Context, cmd: &CommandInteraction, db: &PgPool) -> Result<()> {
+    let eco = economy::of(db, cmd.guild_id).await?;
+    let user = cmd.user.id.get();
+
+    let opts: &[CommandDataOption] = match cmd.data.options.first().map(|o| &o.value) {
+        Some(CommandDataOptionValue::SubCommand(opts)) => opts,
+        _ => &[],
+    };
+    let str_opt = |name: &str| {
+        opts.iter().find_map(|o| match &o.value {
+            CommandDataOptionValue::String(s) if o.name == name => Some(s.as_str()),
+            _ => None,
+        })
+    };
+    let Some(role) = str_opt("rola").and_then(CrewRole::parse) else {
+        return respond_ephemeral(ctx, cmd, "❌ Wybierz rolę w ekipie.").await;
+    };
+    let leader_pct = opts
+        .iter()
+        .find_map(|o| match o.value {
+            CommandDataOptionValue::Integer(n) if o.name == "lider_procent" => Some(n),
+            _ => None,
+        })
+        .unwrap_or(30)
+        .clamp(10, 60) as u8;
+    let split = match str_opt("podzial") {
+        Some("role") => LootSplit::ByRole,
+        Some("lider") => LootSplit::LeaderCut(leader_pct),
+        _ => LootSplit::Equal,
+    };
+    let cfg = CrewHeistConfig {
+        mode: str_opt("tryb").map(from_key_mode).unwrap_or(CrimeMode::Standard),
+        risk: str_opt("ryzyko").map(from_key_risk).unwrap_or(Risk::Medium),
+        split,
+    };
+
+    let mut tx = db.begin().await?;
+    if let Some(msg) = busy_reason(&mut tx, eco, user).await? {
+        drop(tx);
+        return respond_ephemeral(ctx, cmd, msg).await;
+    }
+
+    let lobby = CrewLobby { leader_id: user, cfg, phase: CrewPhase::Open, members: vec![CrewSlot::new(user, role)] };
+    let id = insert_lobby(&mut tx, eco, cmd.channel_id.get(), &lobby).await?;
+    tx.commit().await?;
+
+    let (embed, rows) = render_lobby(id, &lobby);
+    cmd.create_response(
+        &ctx.http,
+        CreateInteractionResponse::Message(CreateInteractionResponseMessage::new().add_embed(embed).components(rows)),
+    )
+    .await?;
+    Ok(())
+}
+
+// =================== Komponenty ===================
+
+pub async fn handle_component(ctx: &Context, mci: &ComponentInteraction, db: &PgPool) -> Result<()> {
+    // crime:crew:{id}:{action}[:payload]
+    let parts: Vec<&str> = mci.data.custom_id.split(':').collect();
+    let Some(id) = parts.get(2).and_then(|s| s.parse::<i64>().ok()) else {
+        return Ok(());
+    };
+    let action = parts.get(3).copied().unwrap_or_default();
+    let payload = parts.get(4).copied();
+
+    let eco = economy::of(db, mci.guild_id).await?;
+    let user = mci.user.id.get();
+
+    let mut tx = db.begin().await?;
+    let Some(mut lobby) = load_lobby(&mut tx, eco, id).await? else {
+        drop(tx);
+        return reply_ephemeral(ctx, mci, "⌛ Tej ekipy już nie ma. Zbierz nową: `/crime ekipa`.").await;
+    };
+    let is_leader = user == lobby.leader_id;
+
+    match action {
+        "join" => {
+            let Some(role) = payload.and_then(CrewRole::parse) else { return Ok(()) };
+            if lobby.phase != CrewPhase::Open {
+                drop(tx);
+                return reply_ephemeral(ctx, mci, "⛔ Ekipa już ruszyła na robotę.").await;
+            }
+            let taken = lobby.role_taken(role);
+            match lobby.slot(user) {
+                // zmiana roli w tej samej ekipie
+                Some(i) => {
+                    if lobby.members[i].role != role && taken >= MAX_PER_ROLE {
+                        drop(tx);
+                        return reply_ephemeral(ctx, mci, "⛔ Ta rola jest już obsadzona.").await;
+                    }
+                    lobby.members[i].role = role;
+                }
+                None => {
+                    if lobby.members.len() >= MAX_CREW {
+                        drop(tx);
+                        return reply_ephemeral(ctx, mci, format!("⛔ Ekipa jest pełna ({MAX_CREW} osób).")).await;
+                    }
+                    if taken >= MAX_PER_ROLE {
+                        drop(tx);
+                        return reply_ephemeral(ctx, mci, "⛔ Ta rola jest już obsadzona.").await;
+                    }
+                    if let Some(msg) = busy_reason(&mut tx, eco, user).await? {
+                        drop(tx);
+                        return reply_ephemeral(ctx, mci, msg).await;
+                    }
+                    lobby.members.push(CrewSlot::new(user, role));
+                }
+            }
+        }
+
+        "leave" => {
+            if lobby.phase != CrewPhase::Open {
+                drop(tx);
+                return reply_ephemeral(ctx, mci, "⛔ Z roboty w trakcie się nie wychodzi.").await;
+            }
+            if is_leader {
+                drop(tx);
+                return reply_ephemeral(ctx, mci, "ℹ️ Lider nie wychodzi — może rozwiązać ekipę.").await;
+            }
+            let Some(i) = lobby.slot(user) else {
+                drop(tx);
+                return reply_ephemeral(ctx, mci, "ℹ️ Nie jesteś w tej ekipie.").await;
+            };
+            lobby.members.remove(i);
+        }
+
+        "disband" => {
+            if !is_leader {
+                drop(tx);
+                return reply_ephemeral(ctx, mci, "⛔ Tylko lider może rozwiązać ekipę.").await;
+            }
+            if lobby.phase != CrewPhase::Open {
+                drop(tx);
+                return reply_ephemeral(ctx, mci, "⛔ Robota już trwa — dokończcie i rozstrzygnijcie.").await;
+            }
+            delete_lobby(&mut tx, id).await?;
+            tx.commit().await?;
+            mci.create_response(
+                &ctx.http,
+                CreateInteractionResponse::UpdateMessage(
+                    CreateInteractionResponseMessage::new()
+                        .embed(CreateEmbed::new().title("🕶️ Ekipa rozwiązana").color(0x95a5a6))
+                        .components(vec![]),
+                ),
+            )
+            .await?;
+            return Ok(());
+        }
+
+        "start" => {
+            if !is_leader {
+                drop(tx);
+                return reply_ephemeral(ctx, mci, "⛔ Tylko lider daje sygnał do startu.").await;
+            }
+            if lobby.phase != CrewPhase::Open {
+                drop(tx);
+                return reply_ephemeral(ctx, mci, "ℹ️ Ekipa już gra.").await;
+            }
+            if lobby.members.len() < MIN_CREW {
+                drop(tx);
+                return reply_ephemeral(ctx, mci, format!("⛔ Potrzeba co najmniej {MIN_CREW} osób.")).await;
+            }
+            for m in &lobby.members {
+                if let Some(stay) = jail::current(&mut *tx, eco, m.user_id).await? {
+                    drop(tx);
+                    return reply_ephemeral(
+                        ctx,
+                        mci,
+                        format!("🔒 <@{}> siedzi w więzieniu do <t:{}:R>.", m.user_id, stay.release_at.timestamp()),
+                    )
+                    .await;
+                }
+                if let Some(left) = cooldowns::remaining(&mut *tx, eco, m.user_id, COOLDOWN_KEY).await? {
+                    drop(tx);
+                    let at = clock::now() + chrono::Duration::seconds(left);
+                    return reply_ephemeral(
+                        ctx,
+                        mci,
+                        format!("⏳ <@{}> musi jeszcze odczekać — do <t:{}:R>.", m.user_id, at.timestamp()),
+                    )
+                    .await;
+                }
+            }
+
+            // ekwipunek i okno QTE każdego członka (jego przedmioty, po jednym użyciu, + jego HEAT)
+            let repo = repo(db, eco);
+            for i in 0..lobby.members.len() {
+                let uid = lobby.members[i].user_id;
+                let items = saved_loadout(&mut tx, &repo, eco, uid).await?;
+                let items = inventory::spend(&mut tx, eco, uid, &items).await?;
+                let heat = repo.get_or_create_in(&mut tx, uid).await?.heat;
+                let spec = qte_spec(&lobby.solo_cfg(&items), heat);
+                let m = &mut lobby.members[i];
+                m.items = items;
+                m.qte = Some(spec);
+            }
+            lobby.phase = CrewPhase::Playing;
+        }
+
+        // start odliczania: zapamiętujemy znacznik, panelu nie przerysowujemy
+        "arm" => {
+            if let Some(i) = lobby.slot(user) {
+                let m = &mut lobby.members[i];
+                if lobby.phase == CrewPhase::Playing && m.armed_ms.is_none() && m.result.is_none() {
+                    m.armed_ms = Some(snowflake_ms(mci.id.get()));
+                    save_lobby(&mut tx, id, &lobby).await?;
+                    tx.commit().await?;
+                    mci.create_response(&ctx.http, CreateInteractionResponse::Acknowledge).await?;
+                    return Ok(());
+                }
+            }
+            drop(tx);
+            mci.create_response(&ctx.http, CreateInteractionResponse::Acknowledge).await?;
+            return Ok(());
+        }
+
+        "hit" => {
+            let Some(i) = lobby.slot(user) else {
+                drop(tx);
+                return reply_ephemeral(ctx, mci, "ℹ️ Nie jesteś w tej ekipie.").await;
+            };
+            let m = &mut lobby.members[i];
+            if lobby.phase != CrewPhase::Playing || m.result.is_some() {
+                drop(tx);
+                return reply_ephemeral(ctx, mci, "ℹ️ Swoją część już zagrałeś.").await;
+            }
+            let spec = m.qte.clone().unwrap_or_else(|| minigames::qte_spec_for(lobby.cfg.risk, 0));
+            m.result = Some(match m.armed_ms {
+                Some(armed) => {
+                    let elapsed = snowflake_ms(mci.id.get()) - armed;
+                    m.elapsed_ms = Some(elapsed);
+                    minigames::score_qte(elapsed.clamp(0, i32::MAX as i64) as i32, &spec)
+                }
+                // falstart
+                None => MinigameResult::Fail,
+            });
+        }
+
+        "resolve" => {
+            if !is_leader {
+                drop(tx);
+                return reply_ephemeral(ctx, mci, "⛔ Tylko lider rozstrzyga napad.").await;
+            }
+            if lobby.phase != CrewPhase::Playing {
+                drop(tx);
+                return reply_ephemeral(ctx, mci, "ℹ️ Najpierw start roboty.").await;
+            }
+            let guild = mci.guild_id.ok_or_else(|| anyhow!("/crime poza serwerem"))?;
+
+            // kto nie zagrał — wchodzi jako NotPlayed
+            let crew: Vec<CrewMember> = lobby
+                .members
+                .iter()
+                .map(|m| CrewMember {
+                    user_id: m.user_id,
+                    role: m.role,
+                    items: m.items.clone(),
+                    mg: m.result.unwrap_or(MinigameResult::NotPlayed),
+                })
+                .collect();
+
+            let res = repo(db, eco).resolve_crew(&mut tx, &crew, &lobby.cfg, Some(mci.id.get())).await?;
+            let mut ready = Vec::with_capacity(crew.len());
+            let mut jailed = Vec::with_capacity(crew.len());
+            for (i, m) in lobby.members.iter().enumerate() {
+                let meta = heist_cooldown(&lobby.solo_cfg(&m.items), res.before[i].heat);
+                ready.push(cooldowns::start(&mut tx, eco, guild, m.user_id, meta).await?);
+
+                // wpadka ekipy — każdy odpowiada za swój HEAT
+                let sentence = (!res.outcome.success)
+                    .then(|| jail::sentence_mins(lobby.cfg.risk, res.after[i].heat, res.outcome.ambushed))
+                    .flatten();
+                jailed.push(match sentence {
+                    Some(mins) => Some(jail::imprison(&mut tx, eco, m.user_id, mins, "wpadka ekipy (/crime ekipa)").await?),
+                    None => None,
+                });
+            }
+            delete_lobby(&mut tx, id).await?;
+            tx.commit().await?;
+
+            for (i, m) in lobby.members.iter().enumerate() {
+                if let Err(e) =
+                    reminders::schedule(db, guild, mci.channel_id, m.user_id, COOLDOWN_KEY, "/crime ekipa", ready[i]).await
+                {
+                    eprintln!("❌ /crime ekipa: nie zapisano przypomnienia: {e:?}");
+                }
+                let desc = format!(
+                    "Napad ekipy #{id} ({}): {} • {} TK • HEAT +{}",
+                    m.role.key(),
+                    if res.outcome.success { "sukces" } else { "porażka" },
+                    res.outcome.shares[i],
+                    res.outcome.heat_deltas[i],
+                );
+                let _ = log_action(db, m.user_id, "crime_crew", None, None, Some(&desc)).await;
+            }
+
+            let (embed, rows) = render_report(&lobby, &res, &ready, &jailed);
+            mci.create_response(
+                &ctx.http,
+                CreateInteractionResponse::UpdateMessage(
+                    CreateInteractionResponseMessage::new().embed(embed).components(rows),
+                ),
+            )
+            .await?;
+            return Ok(());
+        }
+
+        _ => {}
+    }
+
+    save_lobby(&mut tx, id, &lobby).await?;
+    tx.commit().await?;
+
+    let (embed, rows) = render_lobby(id, &lobby);
+    mci.create_response(
+        &ctx.http,
+        CreateInteractionResponse::UpdateMessage(CreateInteractionResponseMessage::new().embed(embed).components(rows)),
+    )
+    .await?;
+    Ok(())
+}
+
+/// Czemu gracz nie może wejść do ekipy (inna ekipa, cooldown) — `None` = może.
+async fn busy_reason(conn: &mut PgConnection, eco: Economy, user: u64) -> Result<Option<String>> {
+    if lobby_of(&mut *conn, eco, user).await?.is_some() {
+        return Ok(Some("⛔ Jesteś już w innej ekipie.".into()));
+    }
+    if let Some(left) = cooldowns::remaining(&mut *conn, eco, user, COOLDOWN_KEY).await? {
+        let at = clock::now() + chrono::Duration::seconds(left);
+        return Ok(Some(format!("⏳ Za gorąco na kolejny skok — spróbuj <t:{}:R>.", at.timestamp())));
+    }
+    Ok(None)
+}
+
+async fn respond_ephemeral(ctx: &Context, cmd: &CommandInteraction, content: impl Into<String>) -> Result<()> {
+    cmd.create_response(
+        &ctx.http,
+        CreateInteractionResponse::Message(
+            CreateInteractionResponseMessage::new()
+                .flags(InteractionResponseFlags::EPHEMERAL)
+                .content(content),
+        ),
+    )
+    .await?;
+    Ok(())
+}
+
+// =================== Render ===================
+
+fn split_label(s: LootSplit) -> String {
+    match s {
+        LootSplit::Equal => "po równo".into(),
+        LootSplit::ByRole => "wg ról".into(),
+        LootSplit::LeaderCut(p) => format!("lider bierze {p}% z góry"),
+    }
+}
+
+fn qte_status(m: &CrewSlot) -> String {
+    match (m.result, m.elapsed_ms) {
+        (Some(MinigameResult::Success), Some(ms)) => format!("✅ {ms} ms"),
+        (Some(MinigameResult::Partial(_)), Some(ms)) => format!("🟡 {ms} ms"),
+        (Some(_), Some(ms)) => format!("❌ {ms} ms"),
+        (Some(_), None) => "❌ falstart".into(),
+        (None, _) if m.armed_ms.is_some() => "⏱️ odlicza…".into(),
+        (None, _) => "🕹️ czeka".into(),
+    }
+}
+
+fn render_lobby(id: i64, lobby: &CrewLobby) -> (CreateEmbed, Vec<CreateActionRow>) {
+    let cfg = &lobby.cfg;
+    let playing = lobby.phase == CrewPhase::Playing;
+
+    let members = lobby
+        .members
+        .iter()
+        .map(|m| {
+            let crown = if m.user_id == lobby.leader_id { " 👑" } else { "" };
+            if playing {
+                let target = m.qte.as_ref().map_or(0.0, |q| q.target_ms as f32 / 1000.0);
+                let window = m.qte.as_ref().map_or(0, |q| q.window_ms);
+                format!("{} <@{}>{crown} — cel **{target:.1}s** ±{window} ms • {}", m.role.label(), m.user_id, qte_status(m))
+            } else {
+                format!("{} <@{}>{crown}", m.role.label(), m.user_id)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let missing: Vec<&str> = CrewRole::ALL
+        .into_iter()
+        .filter(|r| lobby.role_taken(*r) == 0)
+        .map(|r| r.label())
+        .collect();
+
+    let howto = if playing {
+        "Każdy gra swoje QTE: **▶️ Start odliczania**, odlicz swój cel i **🎯 TERAZ!**\n\
+         Haker i włamywacz podbijają szansę, kierowca zmniejsza straty, czujka pilnuje zasadzki i HEAT.\n\
+         Lider rozstrzyga, gdy wszyscy skończą (kto nie zagrał, ciągnie ekipę w dół)."
+            .to_string()
+    } else {
+        format!(
+            "Dołącz przyciskiem swojej roli ({MIN_CREW}–{MAX_CREW} osób, max {MAX_PER_ROLE} na rolę).\n\
+             Do napadu idą przedmioty z twoich ustawień `/crime start`."
+        )
+    };
+
+    let mut e = CreateEmbed::new()
+        .title(format!("🕶️ Ekipa #{id} — {}", if playing { "robota trwa" } else { "zbiórka" }))
+        .color(if playing { 0xf39c12 } else { 0x5865f2 })
+        .description(howto)
+        .field("Tryb", format!("`{}` {}", mode_label(cfg.mode), emoji_for_mode(cfg.mode)), true)
+        .field("Ryzyko", format!("`{:?}` {}", cfg.risk, emoji_for_risk(cfg.risk)), true)
+        .field("Podział", split_label(cfg.split), true)
+        .field(format!("Skład ({}/{MAX_CREW})", lobby.members.len()), members, false);
+    if !missing.is_empty() {
+        e = e.field("Brakuje", missing.join(", "), false);
+    }
+
+    let rows = if playing {
+        vec![CreateActionRow::Buttons(vec![
+            CreateButton::new(format!("crime:crew:{id}:arm"))
+                .label("▶️ Start odliczania")
+                .style(ButtonStyle::Secondary),
+            CreateButton::new(format!("crime:crew:{id}:hit"))
+                .label("🎯 TERAZ!")
+                .style(ButtonStyle::Danger),
+            CreateButton::new(format!("crime:crew:{id}:resolve"))
+                .label("✅ Rozstrzygnij (lider)")
+                .style(ButtonStyle::Primary),
+        ])]
+    } else {
+        let roles = CrewRole::ALL
+            .into_iter()
+            .map(|r| {
+                CreateButton::new(format!("crime:crew:{id}:join:{}", r.key()))
+                    .label(r.label())
+                    .style(ButtonStyle::Secondary)
+                    .disabled(lobby.role_taken(r) >= MAX_PER_ROLE)
+            })
+            .collect();
+        vec![
+            CreateActionRow::Buttons(roles),
+            CreateActionRow::Buttons(vec![
+                CreateButton::new(format!("crime:crew:{id}:start"))
+                    .label("🚀 Ruszamy (lider)")
+                    .style(ButtonStyle::Success)
+                    .disabled(lobby.members.len() < MIN_CREW),
+                CreateButton::new(format!("crime:crew:{id}:leave"))
+                    .label("🚪 Wychodzę")
+                    .style(ButtonStyle::Secondary),
+                CreateButton::new(format!("crime:crew:{id}:disband"))
+                    .label("✖️ Rozwiąż (lider)")
+                    .style(ButtonStyle::Danger),
+            ]),
+        ]
+    };
+
+    (e, rows)
+}
+
+fn render_report(
+    lobby: &CrewLobby,
+    res: &CrewResolution,
+    ready: &[DateTime<Utc>],
+    jailed: &[Option<DateTime<Utc>>],
+) -> (CreateEmbed, Vec<CreateActionRow>) {
+    let o = &res.outcome;
+    let (title, color) = match (o.success, o.ambushed) {
+        (true, _) => ("💰 Ekipa zgarnęła łup!", 0x2ecc71),
+        (false, true) => ("🚨 Zasadzka! Ekipa wpadła", 0xc0392b),
+        (false, false) => ("🚓 Napad ekipy nieudany", 0xe74c3c),
+    };
+
+    let lines = lobby
+        .members
+        .iter()
+        .enumerate()
+        .map(|(i, m)| {
+            let heat_after = res.after[i].heat.max(0) as u32;
+            let next = match jailed[i] {
+                Some(t) => format!("🔒 więzienie do <t:{}:R>", t.timestamp()),
+                None => format!("następny skok <t:{}:R>", ready[i].timestamp()),
+            };
+            format!(
+                "{} <@{}> — {} • **{:+} TK** • HEAT {} → {} {}\n    {next}",
+                m.role.label(),
+                m.user_id,
+                qte_status(m),
+                o.shares[i],
+                res.before[i].heat,
+                res.after[i].heat,
+                bar10(heat_after.min(100)),
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let e = CreateEmbed::new()
+        .title(title)
+        .color(color)
+        .field("Tryb", format!("`{}` {}", mode_label(lobby.cfg.mode), emoji_for_mode(lobby.cfg.mode)), true)
+        .field("Ryzyko", format!("`{:?}` {}", lobby.cfg.risk, emoji_for_risk(lobby.cfg.risk)), true)
+        .field("Łup", if o.success { format!("**{} TK** ({})", o.loot, split_label(lobby.cfg.split)) } else { "—".into() }, true)
+        .field("Ekipa", lines, false);
+
+    (e, vec![])
+}
+
+// =================== DB ===================
+
+async fn insert_lobby(conn: &mut PgConnection, eco: Economy, channel_id: u64, lobby: &CrewLobby) -> Result<i64> {
+    let now = clock::now();
+    let id = sqlx::query_scalar(
+        r#"INSERT INTO crime_crews (guild_id, channel_id, leader_id, state, created_at, updated_at, expires_at)
+           VALUES ($1, $2, $3, $4::jsonb, $5, $5, $6)
+           RETURNING id"#,
+    )
+    .bind(eco.id())
+    .bind(channel_id as i64)
+    .bind(lobby.leader_id as i64)
+    .bind(serde_json::to_string(lobby)?)
+    .bind(now)
+    .bind(now + *SESSION_TTL)
+    .fetch_one(&mut *conn)
+    .await?;
+    Ok(id)
+}
+
+/// Aktywne lobby; w transakcji — z blokadą wiersza.
+async fn load_lobby(conn: &mut PgConnection, eco: Economy, id: i64) -> Result<Option<CrewLobby>> {
+    let raw: Option<String> = sqlx::query_scalar(
+        r#"SELECT state::text FROM crime_crews
+           WHERE id = $1 AND guild_id = $2 AND expires_at > $3
+           FOR UPDATE"#,
+    )
+    .bind(id)
+    .bind(eco.id())
+    .bind(clock::now())
+    .fetch_optional(&mut *conn)
+    .await?;
+
+    match raw {
+        Some(json) => match serde_json::from_str(&json) {
+            Ok(l) => Ok(Some(l)),
+            Err(e) => {
+                eprintln!("⚠️ /crime ekipa: nieczytelne lobby #{id}: {e}");
+                Ok(None)
+            }
+        },
+        None => Ok(None),
+    }
+}
+
+/// Zapisuje lobby i przesuwa jego wygaśnięcie.
+async fn save_lobby(conn: &mut PgConnection, id: i64, lobby: &CrewLobby) -> Result<()> {
+    let now = clock::now();
+    sqlx::query(r#"UPDATE crime_crews SET state = $2::jsonb, updated_at = $3, expires_at = $4 WHERE id = $1"#)
+        .bind(id)
+        .bind(serde_json::to_string(lobby)?)
+        .bind(now)
+        .bind(now + *SESSION_TTL)
+        .execute(&mut *conn)
+        .await?;
+    Ok(())
+}
+
+async fn delete_lobby(conn: &mut PgConnection, id: i64) -> Result<()> {
+    sqlx::query("DELETE FROM crime_crews WHERE id = $1").bind(id).execute(&mut *conn).await?;
+    Ok(())
+}
+
+/// Aktywne lobby, w którym gracz już jest (gracz = najwyżej jedna ekipa naraz).
+async fn lobby_of(db: impl PgExecutor<'_>, eco: Economy, user: u64) -> Result<Option<i64>> {
+    let probe = serde_json::json!([{ "user_id": user }]).to_string();
+    let id = sqlx::query_scalar(
+        r#"SELECT id FROM crime_crews
+           WHERE guild_id = $1 AND expires_at > $2 AND state->'members' @> $3::jsonb
+           LIMIT 1"#,
+    )
+    .bind(eco.id())
+    .bind(clock::now())
+    .bind(probe)
+    .fetch_optional(db)
+    .await?;
+    Ok(id)
+}
+
+/// Usuwa wygasłe lobby; ekipa porzucona w trakcie roboty trafia do logów lidera.
+/// Zwraca (wygasłe, porzucone napady).
+pub(crate) async fn expire_lobbies(db: &PgPool) -> Result<(usize, usize)> {
+    let rows: Vec<(i64, i64, String)> = sqlx::query_as(
+        r#"DELETE FROM crime_crews WHERE expires_at <= $1 RETURNING id, leader_id, state::text"#,
+    )
+    .bind(clock::now())
+    .fetch_all(db)
+    .await?;
+
+    let expired = rows.len();
+    let mut abandoned = 0;
+    for (id, leader_id, json) in rows {
+        let Ok(lobby) = serde_json::from_str::<CrewLobby>(&json) else { continue };
+        if lobby.phase != CrewPhase::Playing {
+            continue;
+        }
+        abandoned += 1;
+        let played = lobby.members.iter().filter(|m| m.result.is_some()).count();
+        let desc = format!(
+            "Porzucony napad ekipy #{id} (lobby wygasło): {} / {:?} • QTE {played}/{}",
+            mode_label(lobby.cfg.mode),
+            lobby.cfg.risk,
+            lobby.members.len(),
+        );
+        let _ = log_action(db, leader_id as u64, "crime_crew_abandon", None, None, Some(&desc)).await;
+    }
+
+    Ok((expired, abandoned))
+}
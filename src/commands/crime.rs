@@ -1,73 +1,72 @@
-//! commands/crime.rs — SOLO (Simon) + trwałe profile/ustawienia w Postgres (Serenity 0.12.4)
+//! commands/crime.rs — SOLO (Simon / QTE) + trwałe profile/ustawienia w Postgres (Serenity 0.12.4)
 
-use std::{
-    collections::HashSet,
-    sync::Arc,
-    time::{Duration, Instant},
-};
+use std::collections::HashSet;
 
-use anyhow::Result;
-use dashmap::DashMap;
-use once_cell::sync::OnceCell;
-use tokio::sync::Mutex;
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Duration, Utc};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
 
 use serenity::all::{
     ButtonStyle, CommandInteraction, CommandOptionType, ComponentInteraction, ComponentInteractionDataKind,
     Context, CreateActionRow, CreateButton, CreateCommand, CreateCommandOption, CreateEmbed,
     CreateInteractionResponse, CreateInteractionResponseMessage, CreateSelectMenu, CreateSelectMenuKind,
-    CreateSelectMenuOption, InteractionResponseFlags, ModalInteraction, UserId,
+    CreateSelectMenuOption, GuildId, InteractionResponseFlags, ModalInteraction, UserId,
 };
-use sqlx::PgPool;
+use serenity::async_trait;
+use sqlx::{PgConnection, PgPool};
 
 use crate::engine::{
-    core::resolve_solo,
+    balance,
+    config,
+    core::roll_solo,
+    crew::roll_crew,
     items,
-    minigames,
-    repo::{MemorySoloRepo, SoloRepo},
+    minigames, perks,
+    repo::{PgSoloRepo, SoloRepo},
+    targets,
     types::*,
 };
-
-// =================== Service & Sessions ===================
-
-static SERVICE: OnceCell<Arc<CrimeService>> = OnceCell::new();
-
-fn service() -> Arc<CrimeService> {
-    SERVICE.get_or_init(|| Arc::new(CrimeService::new_in_memory())).clone()
+use crate::clock;
+use crate::commands::{crew, crime_stats, skill_tree};
+use crate::commands::registry::{BotCommand, CooldownMeta};
+use crate::cooldowns;
+use crate::economy::{self, Economy};
+use crate::inventory::{self, charges_label, Owned};
+use crate::jail;
+use crate::reminders;
+use crate::rng::{self, Roll};
+use crate::skill_tree as tree;
+use crate::utils::log_action;
+
+// =================== Repo & Sessions ===================
+
+/// Profile (HEAT/PP/umiejętność + saldo) — jedyne źródło to Postgres.
+pub(super) fn repo(db: &PgPool, eco: Economy) -> PgSoloRepo {
+    PgSoloRepo::new(db.clone(), eco)
 }
 
-pub struct CrimeService {
-    pub repo: Arc<MemorySoloRepo>,           // HEAT/PP/skill in-memory (mirror DB)
-    pub sessions: DashMap<u64, SoloSession>, // per user_id
-    pub create_lock: Mutex<()>,
+/// Po ilu minutach bez akcji sesja napadu wygasa (ENV `CRIME_SESSION_TTL_MINS`).
+pub(super) static SESSION_TTL: Lazy<Duration> = Lazy::new(|| {
+    let mins = std::env::var("CRIME_SESSION_TTL_MINS")
+        .ok()
+        .and_then(|s| s.parse::<i64>().ok())
+        .filter(|&m| m > 0)
+        .unwrap_or(15);
+    Duration::minutes(mins)
+});
+
+pub(crate) const COOLDOWN_KEY: &str = "crime";
+
+/// Odstęp między napadami (`cooldowns.crime_secs`); HEAT sprzed napadu dokłada `extra_cooldown_secs` (zob. `heist_cooldown`).
+pub(crate) fn cooldown_meta() -> CooldownMeta {
+    CooldownMeta { key: COOLDOWN_KEY, secs: config::get().cooldowns.crime_secs, heat_penalty: false }
 }
-impl CrimeService {
-    pub fn new_in_memory() -> Self {
-        Self {
-            repo: Arc::new(MemorySoloRepo::new()),
-            sessions: DashMap::new(),
-            create_lock: Mutex::new(()),
-        }
-    }
 
-    pub async fn get_or_create_session(
-        &self,
-        user: UserId,
-    ) -> dashmap::mapref::one::RefMut<'_, u64, SoloSession> {
-        if let Some(e) = self.sessions.get_mut(&user.get()) {
-            return e;
-        }
-        let _g = self.create_lock.lock().await;
-        if let Some(e) = self.sessions.get_mut(&user.get()) {
-            return e;
-        }
-        self.sessions
-            .entry(user.get())
-            .or_insert_with(|| SoloSession::new(user.get()))
-    }
-}
-
-#[derive(Debug, Clone)]
+/// Stan napadu gracza — trzymany w `crime_sessions` (JSON), nie w pamięci procesu.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SoloSession {
+    #[allow(dead_code)]
     pub user_id: u64,
     pub state: SoloState,
     pub base_cfg: SoloHeistConfig, // snapshot do resolve
@@ -82,21 +81,29 @@ impl SoloSession {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum SoloState {
     Config(SoloHeistConfig),
+    /// QTE: czas mierzony ze znaczników Discorda w ID interakcji (start → „TERAZ!”),
+    /// więc opóźnienie bota i bazy nie wpływa na wynik.
+    InQte {
+        spec: QteSpec,
+        armed_ms: Option<i64>,
+        elapsed_ms: Option<i64>,
+        result: Option<MinigameResult>,
+    },
     InSimon {
         spec: SimonSpec,
         seq: Vec<char>,
         cursor: usize,
         result: Option<MinigameResult>,
-        reveal_until: Option<Instant>,
+        reveal_until: Option<DateTime<Utc>>,
         reveals_left: u8,
     },
     Resolved(ResolvedView),
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ResolvedView {
     pub outcome: HeistOutcome,
     pub cfg: SoloHeistConfig,
@@ -104,13 +111,22 @@ pub struct ResolvedView {
     pub before: PlayerProfile, // balance = z DB „przed”
     pub after: PlayerProfile,  // balance = z DB „po”
     pub newly_unlocked: Vec<ItemKey>,
+    /// Cele, które ten napad odblokował (PP).
+    #[serde(default)]
+    pub newly_targets: Vec<TargetKey>,
+    /// Kiedy minie cooldown po tym napadzie.
+    #[serde(default)]
+    pub ready_at: Option<DateTime<Utc>>,
+    /// Wpadka przy wysokim HEAT — do kiedy gracz siedzi.
+    #[serde(default)]
+    pub jailed_until: Option<DateTime<Utc>>,
 }
 
 // =================== Publiczny interfejs ===================
 
 pub fn register() -> CreateCommand {
     CreateCommand::new("crime")
-        .description("Napad SOLO (Simon) z przedmiotami")
+        .description("Napad SOLO (Simon albo QTE) albo z ekipą, z przedmiotami")
         .add_option(CreateCommandOption::new(
             CommandOptionType::SubCommand,
             "start",
@@ -119,35 +135,72 @@ pub fn register() -> CreateCommand {
         .add_option(CreateCommandOption::new(
             CommandOptionType::SubCommand,
             "profil",
-            "Pokaż swój profil i odblokowane przedmioty",
+            "Pokaż swój profil, ekwipunek, statystyki i historię napadów",
         ))
+        .add_option(CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "wznow",
+            "Przywróć panel trwającego napadu",
+        ))
+        .add_option(crew::subcommand())
+        .add_option(skill_tree::subcommand())
 }
 
 pub async fn run(ctx: &Context, cmd: &CommandInteraction, db: &PgPool) -> Result<()> {
     let sub = cmd
         .data
         .options
-        .get(0)
+        .first()
         .map(|o| o.name.as_str())
         .unwrap_or("start");
-    let _ = ensure_schema_all(db).await;
-    let svc = service();
-
     match sub {
-        "profil" => show_profile(ctx, cmd, &svc, db).await,
-        _ => start_solo(ctx, cmd, &svc, db).await, // <- przekazujemy db
+        "profil" => show_profile(ctx, cmd, db).await,
+        "wznow" => resume_solo(ctx, cmd, db).await,
+        "ekipa" => crew::open_lobby(ctx, cmd, db).await,
+        "drzewko" => skill_tree::open(ctx, cmd, db).await,
+        _ => start_solo(ctx, cmd, db).await,
     }
 }
 
 pub async fn handle_component(ctx: &Context, mci: &ComponentInteraction, db: &PgPool) -> Result<()> {
+    if mci.data.custom_id.starts_with("crime:crew:") {
+        return crew::handle_component(ctx, mci, db).await;
+    }
+    if mci.data.custom_id.starts_with("crime:tree:") {
+        return skill_tree::handle_component(ctx, mci, db).await;
+    }
+    if mci.data.custom_id.starts_with("crime:hist:") {
+        return crime_stats::handle_component(ctx, mci, db).await;
+    }
     if !mci.data.custom_id.starts_with("crime:solo:") {
         return Ok(());
     }
-    let svc = service();
+    let eco = economy::of(db, mci.guild_id).await?;
+    let repo = repo(db, eco);
 
     let user = mci.user.id;
-    let mut entry = svc.get_or_create_session(user).await;
-    let session = entry.value_mut();
+
+    // wiersz sesji blokujemy do końca akcji — równoległe kliknięcia idą po kolei
+    let mut tx = db.begin().await?;
+    let Some(mut loaded) = load_session(&mut tx, eco, user.get()).await? else {
+        drop(tx);
+        return mci
+            .create_response(
+                &ctx.http,
+                CreateInteractionResponse::Message(
+                    CreateInteractionResponseMessage::new()
+                        .flags(InteractionResponseFlags::EPHEMERAL)
+                        .content("⌛ Ta sesja napadu wygasła. Zacznij od nowa: `/crime start`."),
+                ),
+            )
+            .await
+            .map_err(Into::into);
+    };
+    let session = &mut loaded;
+    // (serwer, termin) przypomnienia o końcu cooldownu — zapisywane po commicie
+    let mut remind: Option<(GuildId, DateTime<Utc>)> = None;
+    // wpis do logu akcji — też dopiero po commicie
+    let mut laid_low: Option<String> = None;
 
     // crime:solo:{action}[:payload]
     let parts: Vec<&str> = mci.data.custom_id.split(':').collect();
@@ -165,7 +218,38 @@ pub async fn handle_component(ctx: &Context, mci: &ComponentInteraction, db: &Pg
                 }
             }
             if let Some(cfg) = to_save {
-                save_settings_db(db, user.get(), &cfg).await.ok();
+                save_settings_db(&mut tx, &repo, eco, user.get(), &cfg).await?;
+            }
+        }
+        "target" => {
+            let mut to_save: Option<SoloHeistConfig> = None;
+            if let SoloState::Config(cfg) = &mut session.state {
+                let target = payload.and_then(from_key_target);
+                if let Some(t) = target {
+                    let pp = repo.get_or_create_in(&mut tx, user.get()).await?.pp;
+                    if pp < targets::required_pp(t) {
+                        drop(tx);
+                        let msg = format!("🔒 **{}** odblokujesz od **{} PP**.", targets::target_name(t), targets::required_pp(t));
+                        return reply_ephemeral(ctx, mci, msg).await;
+                    }
+                }
+                cfg.target = target;
+                to_save = Some(cfg.clone());
+            }
+            if let Some(cfg) = to_save {
+                save_settings_db(&mut tx, &repo, eco, user.get(), &cfg).await?;
+            }
+        }
+        "minigame" => {
+            let mut to_save: Option<SoloHeistConfig> = None;
+            if let SoloState::Config(cfg) = &mut session.state {
+                if let Some(k) = payload.and_then(from_key_minigame) {
+                    cfg.minigame = k;
+                    to_save = Some(cfg.clone());
+                }
+            }
+            if let Some(cfg) = to_save {
+                save_settings_db(&mut tx, &repo, eco, user.get(), &cfg).await?;
             }
         }
         "risk" => {
@@ -177,7 +261,7 @@ pub async fn handle_component(ctx: &Context, mci: &ComponentInteraction, db: &Pg
                 }
             }
             if let Some(cfg) = to_save {
-                save_settings_db(db, user.get(), &cfg).await.ok();
+                save_settings_db(&mut tx, &repo, eco, user.get(), &cfg).await?;
             }
         }
 
@@ -186,7 +270,7 @@ pub async fn handle_component(ctx: &Context, mci: &ComponentInteraction, db: &Pg
             if let SoloState::InSimon { seq, result, reveal_until, reveals_left, .. } = &mut session.state {
                 if result.is_none() && *reveals_left > 0 {
                     let total_ms = 800u64 * seq.len() as u64;
-                    *reveal_until = Some(Instant::now() + Duration::from_millis(total_ms));
+                    *reveal_until = Some(clock::now() + Duration::milliseconds(total_ms as i64));
                     *reveals_left -= 1;
                 }
             }
@@ -196,9 +280,8 @@ pub async fn handle_component(ctx: &Context, mci: &ComponentInteraction, db: &Pg
         "itemselect" => {
             let mut to_save: Option<SoloHeistConfig> = None;
             if let SoloState::Config(cfg) = &mut session.state {
-                let profile = svc.repo.get_or_create(user.get());
-                let avail: std::collections::HashSet<_> =
-                    items::available_items(profile.pp).into_iter().collect();
+                // wybrać można tylko to, co jest w ekwipunku (`/ekwipunek kup`)
+                let avail: HashSet<_> = inventory::owned_keys(&mut *tx, eco, user.get()).await?.into_iter().collect();
 
                 if let ComponentInteractionDataKind::StringSelect { values } = &mci.data.kind {
                     let mut picked = Vec::new();
@@ -215,7 +298,7 @@ pub async fn handle_component(ctx: &Context, mci: &ComponentInteraction, db: &Pg
                 }
             }
             if let Some(cfg) = to_save {
-                save_settings_db(db, user.get(), &cfg).await.ok();
+                save_settings_db(&mut tx, &repo, eco, user.get(), &cfg).await?;
             }
         }
 
@@ -226,8 +309,9 @@ pub async fn handle_component(ctx: &Context, mci: &ComponentInteraction, db: &Pg
 
             if let SoloState::InSimon { seq, reveal_until, reveals_left, .. } = &mut session.state {
                 if let Some(t) = *reveal_until {
-                    if Instant::now() < t {
-                        let left = t.saturating_duration_since(Instant::now()).as_millis();
+                    let now = clock::now();
+                    if now < t {
+                        let left = (t - now).num_milliseconds();
                         return mci.create_response(
                             &ctx.http,
                             CreateInteractionResponse::Message(
@@ -251,7 +335,7 @@ pub async fn handle_component(ctx: &Context, mci: &ComponentInteraction, db: &Pg
 
                 let ms = simon_preview_ms(risk_for_preview, seq.len(), 1.0);
                 *reveals_left -= 1;
-                *reveal_until = Some(Instant::now() + Duration::from_millis(ms));
+                *reveal_until = Some(clock::now() + Duration::milliseconds(ms as i64));
             }
         }
 
@@ -271,28 +355,85 @@ pub async fn handle_component(ctx: &Context, mci: &ComponentInteraction, db: &Pg
                 }
             }
             if let Some(cfg) = to_save {
-                save_settings_db(db, user.get(), &cfg).await.ok();
+                save_settings_db(&mut tx, &repo, eco, user.get(), &cfg).await?;
             }
         }
 
         "start" => {
+            if let Some(left) = cooldowns::remaining(&mut *tx, eco, user.get(), COOLDOWN_KEY).await? {
+                drop(tx);
+                let at = clock::now() + Duration::seconds(left);
+                return reply_ephemeral(ctx, mci, format!("⏳ Za gorąco na kolejny skok — spróbuj <t:{}:R>.", at.timestamp())).await;
+            }
+            // każdy napad zużywa po jednym użyciu przedmiotów z zestawu — brak = nie startujemy
             if let SoloState::Config(cfg0) = &session.state {
                 if cfg0.mode.is_some() && cfg0.risk.is_some() {
-                    // wymuszamy Simon i zapisujemy snapshot:
-                    let mut cfg = cfg0.clone();
-                    cfg.minigame = MinigameKind::Simon;
+                    if let Some(t) = cfg0.target {
+                        if repo.get_or_create_in(&mut tx, user.get()).await?.pp < targets::required_pp(t) {
+                            drop(tx);
+                            let msg = format!("🔒 **{}** wymaga **{} PP** — wybierz inny cel.", targets::target_name(t), targets::required_pp(t));
+                            return reply_ephemeral(ctx, mci, msg).await;
+                        }
+                    }
+                    let used = inventory::spend(&mut tx, eco, user.get(), &cfg0.items).await?;
+                    if used.len() < cfg0.items.len() {
+                        drop(tx);
+                        let missing = cfg0
+                            .items
+                            .iter()
+                            .filter(|k| !used.contains(k))
+                            .map(|k| items::item_name(*k))
+                            .collect::<Vec<_>>()
+                            .join(", ");
+                        return reply_ephemeral(ctx, mci, format!("🎒 Brakuje: **{missing}** — kup w `/ekwipunek` albo zmień zestaw.")).await;
+                    }
+                }
+            }
+            // umiejętności z drzewka wchodzą do snapshotu tak jak przedmioty
+            let perk_ranks = tree::ranks(&mut *tx, eco, user.get()).await?;
+            if let SoloState::Config(cfg0) = &session.state {
+                if cfg0.mode.is_some() && cfg0.risk.is_some() && cfg0.minigame == MinigameKind::Qte {
+                    // snapshot konfiguracji do resolve
+                    let cfg = SoloHeistConfig { perks: perk_ranks, ..cfg0.clone() };
                     session.base_cfg = cfg.clone();
 
-                    let effects = items::aggregate(&cfg.items);
+                    let heat = repo.get_or_create_in(&mut tx, user.get()).await?.heat;
+                    session.state = SoloState::InQte {
+                        spec: qte_spec(&cfg, heat),
+                        armed_ms: None,
+                        elapsed_ms: None,
+                        result: None,
+                    };
+                    save_settings_db(&mut tx, &repo, eco, user.get(), &cfg).await?;
+                } else if cfg0.mode.is_some() && cfg0.risk.is_some() {
+                    // snapshot konfiguracji do resolve
+                    let cfg = SoloHeistConfig { perks: perk_ranks, ..cfg0.clone() };
+                    session.base_cfg = cfg.clone();
 
-                    let spec = minigames::simon_spec_for(cfg.risk.unwrap(), effects.simon_seq_delta);
-                    let seq  = minigames::gen_simon_seq(&spec);
+                    let effects = items::aggregate(&cfg.items);
+                    let heat = repo.get_or_create_in(&mut tx, user.get()).await?.heat;
+                    let heat_fx = balance::heat_effects(cfg.mode.unwrap(), cfg.risk.unwrap(), heat.max(0) as u32);
+
+                    let spec = minigames::simon_spec_for(
+                        cfg.risk.unwrap(),
+                        effects.simon_seq_delta + heat_fx.simon_seq_delta,
+                    );
+                    let (seed, mut rng) = rng::seeded();
+                    let seq = minigames::gen_simon_seq(&spec, &mut rng);
+                    rng::record(
+                        &mut *tx,
+                        eco,
+                        &Roll { command: "crime.simon", user: user.get(), interaction_id: Some(mci.id.get()), seed },
+                        &spec,
+                        &seq,
+                    )
+                    .await?;
 
                     let reveals_left = match cfg.risk.unwrap_or(Risk::Medium) {
                         Risk::Low => 2,
                         Risk::Medium => 1,
                         Risk::High | Risk::Hardcore => 0,
-                    };
+                    } + perks::effects(&cfg.perks).simon_reveals;
 
                     let ms = simon_preview_ms(cfg.risk.unwrap(), seq.len(), effects.simon_time_mult);
 
@@ -301,12 +442,40 @@ pub async fn handle_component(ctx: &Context, mci: &ComponentInteraction, db: &Pg
                         seq,
                         cursor: 0,
                         result: None,
-                        reveal_until: Some(Instant::now() + Duration::from_millis(ms)),
+                        reveal_until: Some(clock::now() + Duration::milliseconds(ms as i64)),
                         reveals_left,
                     };
 
                     // zapisz aktualne ustawienia do DB (dla pewności)
-                    save_settings_db(db, user.get(), &cfg).await.ok();
+                    save_settings_db(&mut tx, &repo, eco, user.get(), &cfg).await?;
+                }
+            }
+        }
+
+        // QTE — start odliczania: zapamiętujemy znacznik i nie przerysowujemy panelu,
+        // żeby „TERAZ!” nie mrugnęło graczowi pod palcem
+        "qte_arm" => {
+            if let SoloState::InQte { armed_ms: armed @ None, result: None, .. } = &mut session.state {
+                *armed = Some(snowflake_ms(mci.id.get()));
+                save_session(&mut tx, eco, user.get(), session).await?;
+                tx.commit().await?;
+            } else {
+                drop(tx);
+            }
+            mci.create_response(&ctx.http, CreateInteractionResponse::Acknowledge).await?;
+            return Ok(());
+        }
+
+        // QTE — „TERAZ!”; bez startu = falstart
+        "qte_hit" => {
+            if let SoloState::InQte { spec, armed_ms, elapsed_ms, result: result @ None } = &mut session.state {
+                match *armed_ms {
+                    Some(armed) => {
+                        let elapsed = snowflake_ms(mci.id.get()) - armed;
+                        *elapsed_ms = Some(elapsed);
+                        *result = Some(minigames::score_qte(elapsed.clamp(0, i32::MAX as i64) as i32, spec));
+                    }
+                    None => *result = Some(MinigameResult::Fail),
                 }
             }
         }
@@ -316,84 +485,96 @@ pub async fn handle_component(ctx: &Context, mci: &ComponentInteraction, db: &Pg
             if let (Some(k), SoloState::InSimon { seq, cursor, result, reveal_until, .. }) =
                 (payload, &mut session.state)
             {
-                if let Some(t) = *reveal_until {
-                    if Instant::now() < t {
-                        return Ok(());
-                    } else {
-                        *reveal_until = None;
-                    }
-                }
-                if result.is_some() { return Ok(()); }
+                // w trakcie podglądu klawisze są ignorowane (panel tylko się odświeża)
+                let revealing = reveal_until.is_some_and(|t| clock::now() < t);
+                if !revealing && result.is_none() {
+                    *reveal_until = None;
 
-                if *cursor >= seq.len() {
-                    *result = Some(MinigameResult::Success);
-                    return Ok(());
-                }
-
-                let got = k.chars().next().map(|c| c.to_ascii_uppercase()).unwrap_or('?');
-                let expected = seq[*cursor];
-
-                if minigames::check_simon_step(expected, got) {
-                    *cursor += 1;
                     if *cursor >= seq.len() {
                         *result = Some(MinigameResult::Success);
+                    } else {
+                        let got = k.chars().next().map(|c| c.to_ascii_uppercase()).unwrap_or('?');
+                        let expected = seq[*cursor];
+
+                        if minigames::check_simon_step(expected, got) {
+                            *cursor += 1;
+                            if *cursor >= seq.len() {
+                                *result = Some(MinigameResult::Success);
+                            }
+                        } else {
+                            *result = Some(MinigameResult::Fail);
+                        }
                     }
-                } else {
-                    *result = Some(MinigameResult::Fail);
                 }
             }
         }
 
         "resolve" => {
-            // 1) wejście do resolvera
+            // 1) wejście do resolvera — tylko po zakończonej minigierce (stary panel nie rozstrzyga drugi raz)
             let cfg = extract_cfg(session);
-            let mg_res = match &session.state {
-                SoloState::InSimon { result, .. } => result.unwrap_or(MinigameResult::NotPlayed),
-                SoloState::Config(_) => MinigameResult::NotPlayed,
-                SoloState::Resolved(v) => v.mg,
+            let mg_res = match session.state {
+                SoloState::InSimon { result: Some(r), .. } | SoloState::InQte { result: Some(r), .. } => r,
+                _ => {
+                    drop(tx);
+                    return reply_ephemeral(ctx, mci, "ℹ️ Ten napad nie czeka na rozstrzygnięcie.").await;
+                }
             };
+            let guild = mci.guild_id.ok_or_else(|| anyhow!("/crime poza serwerem"))?;
 
-            // 2) profil „pamięciowy” (HEAT/PP/skill)
-            let before_mem = svc.repo.get_or_create(user.get());
-
-            // 3) BALANCE z DB — stan „przed”
-            let db_before = fetch_balance(db, user.get()).await.unwrap_or(0);
-
-            // 4) rozstrzygnięcie (amount_final = delta TK)
-            let (after_mem, outcome) = resolve_solo(before_mem.clone(), &cfg, mg_res);
+            // 2) rozstrzygnięcie: profil + saldo + cooldown + odsiadka + sesja w jednej transakcji
+            let res = repo.resolve(&mut tx, user.get(), &cfg, mg_res, Some(mci.id.get())).await?;
+            let ready_at = cooldowns::start(&mut tx, eco, guild, user.get(), heist_cooldown(&cfg, res.before.heat)).await?;
+            remind = Some((guild, ready_at));
+            let jailed_until = jail_after_failure(&mut tx, eco, user.get(), &cfg, &res.after, &res.outcome).await?;
 
-            // 5) BALANCE z DB — atomowo dodaj delta TK i zwróć stan „po”
-            let db_after = add_balance(db, user.get(), outcome.amount_final)
-                .await
-                .unwrap_or(db_before);
-
-            // 6) nowo odblokowane itemy (pochodne od PP)
-            let before_av = items::available_items(before_mem.pp);
-            let after_av = items::available_items(after_mem.pp);
+            // 3) nowo odblokowane w sklepie itemy (pochodne od PP)
+            let before_av = items::available_items(res.before.pp);
+            let after_av = items::available_items(res.after.pp);
             let newly_unlocked: Vec<ItemKey> =
                 after_av.into_iter().filter(|i| !before_av.contains(i)).collect();
-
-            // 7) zapisz profil pamięciowy i do DB (balance z DB)
-            let mut after_mem_fixed = after_mem.clone();
-            let mut before_mem_fixed = before_mem.clone();
-            before_mem_fixed.balance = db_before;
-            after_mem_fixed.balance = db_after;
-
-            // persist w DB
-            save_profile_db(db, user.get(), &after_mem_fixed).await.ok();
-            // mirror in-memory
-            svc.repo.save(&after_mem_fixed);
+            let before_targets = targets::available_targets(res.before.pp);
+            let newly_targets: Vec<TargetKey> = targets::available_targets(res.after.pp)
+                .into_iter()
+                .filter(|t| !before_targets.contains(t))
+                .collect();
 
             session.state = SoloState::Resolved(ResolvedView {
-                outcome,
+                outcome: res.outcome,
                 cfg,
                 mg: mg_res,
-                before: before_mem_fixed,
-                after: after_mem_fixed,
+                before: res.before,
+                after: res.after,
                 newly_unlocked,
+                newly_targets,
+                ready_at: Some(ready_at),
+                jailed_until,
             });
         }
 
+        "laylow" => {
+            if !matches!(session.state, SoloState::Config(_)) {
+                drop(tx);
+                return reply_ephemeral(ctx, mci, "⛔ Przyczaić się można tylko przed napadem.").await;
+            }
+            if let Some(left) = cooldowns::remaining(&mut *tx, eco, user.get(), COOLDOWN_KEY).await? {
+                drop(tx);
+                let at = clock::now() + Duration::seconds(left);
+                return reply_ephemeral(ctx, mci, format!("🕶️ I tak siedzisz cicho — do <t:{}:R>.", at.timestamp())).await;
+            }
+            let guild = mci.guild_id.ok_or_else(|| anyhow!("/crime poza serwerem"))?;
+
+            let (before, after) = repo.lay_low(&mut tx, user.get(), config::get().heat.lay_low_heat).await?;
+            if before <= 0 {
+                drop(tx);
+                return reply_ephemeral(ctx, mci, "🧊 Nie masz HEAT — nikt cię nie szuka.").await;
+            }
+            let lay_low = CooldownMeta { secs: config::get().heat.lay_low_mins * 60, ..cooldown_meta() };
+            let ready_at = cooldowns::start(&mut tx, eco, guild, user.get(), lay_low).await?;
+            remind = Some((guild, ready_at));
+
+            laid_low = Some(format!("HEAT {before} → {after}, napady wstrzymane do {ready_at}"));
+        }
+
         "reset" => {
             // reset tylko w Config lub po rozstrzygnięciu
             if matches!(&session.state, SoloState::Config(_) | SoloState::Resolved(_)) {
@@ -418,8 +599,20 @@ pub async fn handle_component(ctx: &Context, mci: &ComponentInteraction, db: &Pg
         _ => {}
     }
 
+    save_session(&mut tx, eco, user.get(), session).await?;
+    tx.commit().await?;
+
+    if let Some(desc) = laid_low {
+        let _ = log_action(db, user.get(), "crime_laylow", None, None, Some(&desc)).await;
+    }
+    if let Some((guild, ready_at)) = remind {
+        if let Err(e) = reminders::schedule(db, guild, mci.channel_id, user.get(), COOLDOWN_KEY, "/crime start", ready_at).await {
+            eprintln!("❌ /crime: nie zapisano przypomnienia: {e:?}");
+        }
+    }
+
     // Render (UpdateMessage)
-    let (embed, rows) = render_session(&service(), mci.user.id, &session).await;
+    let (embed, rows) = render_session(db, eco, &repo, mci.user.id, session).await;
     mci.create_response(
         &ctx.http,
         CreateInteractionResponse::UpdateMessage(
@@ -433,41 +626,116 @@ pub async fn handle_component(ctx: &Context, mci: &ComponentInteraction, db: &Pg
     Ok(())
 }
 
+pub(crate) async fn reply_ephemeral(ctx: &Context, mci: &ComponentInteraction, content: impl Into<String>) -> Result<()> {
+    mci.create_response(
+        &ctx.http,
+        CreateInteractionResponse::Message(
+            CreateInteractionResponseMessage::new()
+                .flags(InteractionResponseFlags::EPHEMERAL)
+                .content(content),
+        ),
+    )
+    .await?;
+    Ok(())
+}
+
+/// Okno QTE: bazowe z ryzyka + „grace” przedmiotów i Refleksu, mnożniki przedmiotów i HEAT,
+/// plus stały zapas na sieć (`minigames.qte_network_grace_ms` — nie mierzony per gracz).
+pub(super) fn qte_spec(cfg: &SoloHeistConfig, heat: i64) -> QteSpec {
+    let risk = cfg.risk.unwrap_or(Risk::Medium);
+    let effects = items::aggregate(&cfg.items);
+    let fx = balance::heat_effects(cfg.mode.unwrap_or(CrimeMode::Standard), risk, heat.max(0) as u32);
+
+    let grace = effects.qte_grace_ms + perks::effects(&cfg.perks).qte_grace_ms;
+    let mut spec = minigames::qte_spec_for(risk, grace);
+    spec.window_ms = ((spec.window_ms as f32) * effects.qte_window_mult * fx.qte_window_mult).round() as i32
+        + config::get().minigames.qte_network_grace_ms;
+    spec
+}
+
+/// Znacznik czasu Discorda (ms) zaszyty w ID interakcji.
+pub(crate) fn snowflake_ms(id: u64) -> i64 {
+    ((id >> 22) + 1_420_070_400_000) as i64
+}
+
+/// Cooldown po napadzie: baza + dodatek z HEAT sprzed napadu (ważony trybem i ryzykiem).
+pub(super) fn heist_cooldown(cfg: &SoloHeistConfig, heat: i64) -> CooldownMeta {
+    let fx = balance::heat_effects(
+        cfg.mode.unwrap_or(CrimeMode::Standard),
+        cfg.risk.unwrap_or(Risk::Medium),
+        heat.max(0) as u32,
+    );
+    CooldownMeta { secs: cooldown_meta().secs + fx.extra_cooldown_secs as i64, ..cooldown_meta() }
+}
+
+/// Wyrok po nieudanym napadzie (HEAT po napadzie + ryzyko, zasadzka zawsze). `None` = na wolności.
+pub(super) async fn jail_after_failure(
+    conn: &mut PgConnection,
+    eco: Economy,
+    user: u64,
+    cfg: &SoloHeistConfig,
+    after: &PlayerProfile,
+    outcome: &HeistOutcome,
+) -> Result<Option<DateTime<Utc>>> {
+    if outcome.success {
+        return Ok(None);
+    }
+    let Some(mins) = jail::sentence_mins(cfg.risk.unwrap_or(Risk::Medium), after.heat, outcome.ambushed) else {
+        return Ok(None);
+    };
+    let reason = if outcome.ambushed { "zasadzka policji (/crime)" } else { "wpadka na napadzie (/crime)" };
+    Ok(Some(jail::imprison(conn, eco, user, mins, reason).await?))
+}
+
 pub async fn handle_modal(_ctx: &Context, _modal: &ModalInteraction, _db: &PgPool) -> Result<()> {
     Ok(())
 }
 
 // =================== Slash flows ===================
 
-async fn start_solo(ctx: &Context, cmd: &CommandInteraction, svc: &CrimeService, db: &PgPool) -> Result<()> {
-    // 1) wczytaj profil z DB do pamięci (mirror)
-    let mut p = load_profile_db(db, cmd.user.id.get()).await.unwrap_or_default();
-    // dołóż realny balance z DB
-    if let Ok(bal) = fetch_balance(db, cmd.user.id.get()).await {
-        p.balance = bal;
+async fn start_solo(ctx: &Context, cmd: &CommandInteraction, db: &PgPool) -> Result<()> {
+    let eco = economy::of(db, cmd.guild_id).await?;
+    let repo = repo(db, eco);
+
+    // 1) trwająca minigierka blokuje nowy start — inaczej przegrywany napad dałoby się
+    //    powtórzyć bez straty, HEAT i cooldownu (sesję trzymamy pod blokadą do zapisu nowej)
+    let mut tx = db.begin().await?;
+    if let Some(current) = load_session(&mut tx, eco, cmd.user.id.get()).await? {
+        if matches!(current.state, SoloState::InSimon { .. } | SoloState::InQte { .. }) {
+            drop(tx);
+            cmd.create_response(
+                &ctx.http,
+                CreateInteractionResponse::Message(
+                    CreateInteractionResponseMessage::new()
+                        .flags(InteractionResponseFlags::EPHEMERAL)
+                        .content("⛔ Masz trwający napad — dokończ go i rozstrzygnij: `/crime wznow`."),
+                ),
+            )
+            .await?;
+            return Ok(());
+        }
     }
-    svc.repo.save(&p);
 
     // 2) nowa sesja
-    {
-        let mut entry = svc.get_or_create_session(cmd.user.id).await;
-        *entry = SoloSession::new(cmd.user.id.get());
-    }
+    let mut session = SoloSession::new(cmd.user.id.get());
 
-    // 3) wczytaj ostatnie ustawienia i ustaw w sesji
-    if let Ok(Some(s)) = load_settings_db(db, cmd.user.id.get()).await {
-        let mut entry = svc.get_or_create_session(cmd.user.id).await;
-        if let SoloState::Config(cfg) = &mut entry.state {
+    // 3) wczytaj ostatnie ustawienia i ustaw w sesji (zestaw przycięty do posiadanych przedmiotów)
+    if let Some(s) = load_settings_db(&mut tx, &repo, eco, cmd.user.id.get()).await? {
+        let owned = inventory::owned_keys(&mut *tx, eco, cmd.user.id.get()).await?;
+        if let SoloState::Config(cfg) = &mut session.state {
             cfg.mode = s.mode;
             cfg.risk = s.risk;
-            cfg.items = s.items;
-            cfg.minigame = MinigameKind::Simon; // zawsze Simon
+            cfg.items = s.items.into_iter().filter(|k| owned.contains(k)).collect();
+            cfg.target = s.target;
+            cfg.minigame = s.minigame.unwrap_or(MinigameKind::Simon);
         }
     }
 
-    let entry = svc.get_or_create_session(cmd.user.id).await;
-    let session = entry.value();
-    let (embed, rows) = render_session(svc, cmd.user.id, session).await;
+    // 4) zapis (nadpisuje poprzednią sesję gracza w tej ekonomii — konfigurację albo wygasłą)
+    save_session(&mut tx, eco, cmd.user.id.get(), &session).await?;
+    tx.commit().await?;
+
+    let (embed, rows) = render_session(db, eco, &repo, cmd.user.id, &session).await;
 
     cmd.create_response(
         &ctx.http,
@@ -483,37 +751,42 @@ async fn start_solo(ctx: &Context, cmd: &CommandInteraction, svc: &CrimeService,
     Ok(())
 }
 
-async fn show_profile(ctx: &Context, cmd: &CommandInteraction, svc: &CrimeService, db: &PgPool) -> Result<()> {
-    // balance z DB
-    let bal = fetch_balance(db, cmd.user.id.get()).await.unwrap_or(0);
-    // profil z DB (jeśli brak, domyślny)
-    let mut p = load_profile_db(db, cmd.user.id.get()).await.unwrap_or_default();
-    p.balance = bal;
-    // mirror in-memory (żeby embed gry był spójny)
-    svc.repo.save(&p);
+async fn resume_solo(ctx: &Context, cmd: &CommandInteraction, db: &PgPool) -> Result<()> {
+    let eco = economy::of(db, cmd.guild_id).await?;
 
-    let available = items::available_items(p.pp);
-    let names: Vec<&'static str> = available.iter().map(|k| items::item_name(*k)).collect();
+    let mut conn = db.acquire().await?;
+    let session = load_session(&mut conn, eco, cmd.user.id.get()).await?;
+    drop(conn);
 
-    let embed = CreateEmbed::new()
-        .title(format!("🧾 Profil — {}", cmd.user.name))
-        .field("Saldo (TK)", format!("{}", bal), true)
-        .field("HEAT", format!("{}", p.heat), true)
-        .field("Umiejętność", format!("{}/50", p.thief_skill), true)
-        .field("PP", format!("{}", p.pp), true)
-        .field(
-            "Odblokowane przedmioty",
-            if names.is_empty() { "—".into() } else { names.join(", ") },
-            false,
-        )
-        .color(0x95a5a6);
+    let response = match session {
+        Some(s) => {
+            let (embed, rows) = render_session(db, eco, &repo(db, eco), cmd.user.id, &s).await;
+            CreateInteractionResponseMessage::new().add_embed(embed).components(rows)
+        }
+        None => CreateInteractionResponseMessage::new()
+            .content("🕳️ Nie masz trwającego napadu. Zacznij nowy: `/crime start`."),
+    };
+
+    cmd.create_response(
+        &ctx.http,
+        CreateInteractionResponse::Message(response.flags(InteractionResponseFlags::EPHEMERAL)),
+    )
+    .await?;
+
+    Ok(())
+}
+
+async fn show_profile(ctx: &Context, cmd: &CommandInteraction, db: &PgPool) -> Result<()> {
+    let eco = economy::of(db, cmd.guild_id).await?;
+    let (embeds, rows) = crime_stats::view(db, eco, cmd.user.id.get(), 0).await?;
 
     cmd.create_response(
         &ctx.http,
         CreateInteractionResponse::Message(
             CreateInteractionResponseMessage::new()
                 .flags(InteractionResponseFlags::EPHEMERAL)
-                .add_embed(embed),
+                .embeds(embeds)
+                .components(rows),
         ),
     )
     .await?;
@@ -521,28 +794,80 @@ async fn show_profile(ctx: &Context, cmd: &CommandInteraction, svc: &CrimeServic
     Ok(())
 }
 
+/// Karta profilu złodzieja (saldo, HEAT, PP, drzewko, cele, ekwipunek) — pierwsza z embedów `/crime profil`.
+pub(super) async fn profile_embed(db: &PgPool, eco: Economy, user: u64) -> Result<CreateEmbed> {
+    // profil z saldem (tworzy, jeśli brak)
+    let p = repo(db, eco).get_or_create(user).await?;
+    let bal = p.balance;
+
+    let tree = tree::load(db, eco, user).await?;
+    let learned: Vec<String> = tree::ordered(&tree.ranks)
+        .into_iter()
+        .map(|(k, rank)| format!("{} {rank}", perks::perk_name(k)))
+        .collect();
+    let owned = inventory::list(db, eco, user).await?;
+    let bag: Vec<String> = owned
+        .iter()
+        .map(|o| format!("{} {} ({})", emoji_for_item(o.item), items::item_name(o.item), charges_label(o.item, o.charges)))
+        .collect();
+    let shop: Vec<&'static str> = items::available_items(p.pp)
+        .into_iter()
+        .filter(|k| !owned.iter().any(|o| o.item == *k))
+        .map(items::item_name)
+        .collect();
+
+    Ok(CreateEmbed::new()
+        .title("🧾 Profil złodzieja")
+        .description(format!("<@{user}>"))
+        .field("Saldo (TK)", format!("{}", bal), true)
+        .field("HEAT", format!("{}", p.heat), true)
+        .field("Umiejętność", format!("{}/50", p.thief_skill), true)
+        .field("PP", format!("{}", p.pp), true)
+        .field(
+            "🌳 Drzewko (`/crime drzewko`)",
+            format!("Wolne PP: **{}**\n{}", tree.free(), if learned.is_empty() { "—".into() } else { learned.join(", ") }),
+            false,
+        )
+        .field("🎯 Cele", target_list(p.pp), false)
+        .field("🎒 Ekwipunek", if bag.is_empty() { "—".into() } else { bag.join("\n") }, false)
+        .field(
+            "🛒 Do kupienia (`/ekwipunek kup`)",
+            if shop.is_empty() { "—".into() } else { shop.join(", ") },
+            false,
+        )
+        .color(0x95a5a6))
+}
+
 // =================== Render ===================
 
 async fn render_session(
-    svc: &CrimeService,
+    db: &PgPool,
+    eco: Economy,
+    repo: &dyn SoloRepo,
     user: UserId,
     s: &SoloSession,
 ) -> (CreateEmbed, Vec<CreateActionRow>) {
     match &s.state {
-        SoloState::Config(cfg) => render_config(svc, user, cfg).await,
+        SoloState::Config(cfg) => {
+            // PP odblokowuje przedmioty w sklepie, ekwipunek mówi, co można zabrać;
+            // błąd odczytu = pusty ekwipunek (i zerowy HEAT)
+            let (pp, heat) = repo.get_or_create(user.get()).await.map(|p| (p.pp, p.heat)).unwrap_or((0, 0));
+            let owned = inventory::list(db, eco, user.get()).await.unwrap_or_default();
+            // podgląd minigierki z umiejętnościami (do napadu trafiają przy starcie)
+            let perk_ranks = tree::ranks(db, eco, user.get()).await.unwrap_or_default();
+            render_config(pp, heat, &owned, &SoloHeistConfig { perks: perk_ranks, ..cfg.clone() })
+        }
         SoloState::InSimon { spec, seq, cursor, result, reveal_until, reveals_left } => {
             render_simon(spec, seq, *cursor, *result, *reveal_until, *reveals_left)
         }
+        SoloState::InQte { spec, armed_ms, elapsed_ms, result } => {
+            render_qte(spec, armed_ms.is_some(), *elapsed_ms, *result)
+        }
         SoloState::Resolved(view) => render_outcome(view),
     }
 }
 
-async fn render_config(
-    svc: &CrimeService,
-    user: UserId,
-    cfg: &SoloHeistConfig,
-) -> (CreateEmbed, Vec<CreateActionRow>) {
-    let p = svc.repo.get_or_create(user.get());
+fn render_config(pp: u32, heat: i64, owned: &[Owned], cfg: &SoloHeistConfig) -> (CreateEmbed, Vec<CreateActionRow>) {
     let chosen: HashSet<ItemKey> = cfg.items.iter().copied().collect();
 
     // KROKI kreatora
@@ -554,24 +879,43 @@ async fn render_config(
     // Chipy/preset
     let mode_chip = cfg.mode.map(|m| format!("`{}` {}", mode_label(m), emoji_for_mode(m))).unwrap_or("`—`".into());
     let risk_chip = cfg.risk.map(|r| format!("`{:?}` {}", r, emoji_for_risk(r))).unwrap_or("`—`".into());
-    let mg_chip   = format!("`Simon` {}", emoji_for_minigame(MinigameKind::Simon));
+    let mg_chip   = format!("`{}` {}", minigame_label(cfg.minigame), emoji_for_minigame(cfg.minigame));
+    let target_chip = format!("`{}` {}", target_label(cfg.target), emoji_for_target(cfg.target));
     let bag_bar   = bag_bar3(cfg.items.len() as u32, 3);
 
     // Prognoza & preview (jeśli mamy m+r)
+    let heat_u = heat.max(0) as u32;
     let mut forecast = "—".to_string();
     let mut mg_preview = "—".to_string();
+    let mut heat_line = "Wybierz tryb i ryzyko, żeby zobaczyć wpływ HEAT.".to_string();
     if let (Some(m), Some(r)) = (cfg.mode, cfg.risk) {
-        let (min_r, max_r) = crate::engine::balance::reward_range(m, r);
-        let base_chance = crate::engine::balance::base_chance(m, r) * 100.0;
-
-        let eff = items::aggregate(&cfg.items);
-        let spec = minigames::simon_spec_for(r, eff.simon_seq_delta);
-        mg_preview = format!("🧠 Simon • Długość **{}** • Alfabet **{}**", spec.length, spec.alphabet.len());
+        let fx = balance::heat_effects(m, r, heat_u);
+        let (min_r, max_r) = targets::reward_range(m, r, cfg.target);
+        let base_chance = (targets::base_chance(m, r, cfg.target, cfg.minigame) * fx.chance_mult).clamp(1.0, 99.0);
+
+        mg_preview = match cfg.minigame {
+            MinigameKind::Simon => {
+                let eff = items::aggregate(&cfg.items);
+                let spec = minigames::simon_spec_for(r, eff.simon_seq_delta + fx.simon_seq_delta);
+                format!("🧠 Simon • Długość **{}** • Alfabet **{}**", spec.length, spec.alphabet.len())
+            }
+            MinigameKind::Qte => {
+                let spec = qte_spec(cfg, heat);
+                format!("🎯 QTE • Cel **{:.1}s** • Okno **±{} ms**", spec.target_ms as f32 / 1000.0, spec.window_ms)
+            }
+        };
 
         forecast = format!(
             "Szansa bazowa: **{:.0}%**\nWidełki łupu: **{}–{}**",
-            base_chance, min_r, max_r
+            base_chance,
+            ((min_r as f32) * fx.reward_mult).round() as i64,
+            ((max_r as f32) * fx.reward_mult).round() as i64,
         );
+        let wear = items::wear_cost(&cfg.items);
+        if wear > 0 {
+            forecast.push_str(&format!("\nZużycie sprzętu: **~{wear} TK**"));
+        }
+        heat_line = balance::format_heat_summary(fx);
     }
 
     // Wybrane itemy (z krótkim opisem)
@@ -588,7 +932,7 @@ async fn render_config(
     let description = format!(
         "**Kreator napadu (SOLO)**\n\
          {step_mode}  {step_risk}  {step_eq}  {step_go}\n\n\
-         **Preset**  {mode_chip} • {risk_chip} • {mg_chip}\n\
+         **Preset**  {target_chip} • {mode_chip} • {risk_chip} • {mg_chip}\n\
          **Pojemność**  {bag_bar}"
     );
 
@@ -598,12 +942,16 @@ async fn render_config(
         .color(0x3b82f6)
         .field("🔮 Prognoza", forecast, true)
         .field("🕹️ Minigra (podgląd)", mg_preview, true)
+        .field(format!("{} Cel — {}", emoji_for_target(cfg.target), target_label(cfg.target)), target_summary(cfg.target), false)
+        .field("🔥 HEAT", format!("**{}** {}\n{heat_line}", heat_u.min(100), bar10(heat_u.min(100))), false)
         .field("🎒 Ekwipunek (max 3)", items_str, false);
 
-    let mut rows: Vec<CreateActionRow> = Vec::new();
-    rows.push(row_modes_cfg(cfg));
-    rows.push(row_risks_cfg(cfg));
-    rows.push(row_select_items(p.pp, &chosen));
+    let mut rows: Vec<CreateActionRow> = vec![
+        row_modes_cfg(cfg),
+        row_risks_cfg(cfg),
+        row_targets_cfg(pp, cfg),
+        row_select_items(pp, owned, &chosen),
+    ];
 
     // Start / Reset
     let can_start = cfg.mode.is_some() && cfg.risk.is_some();
@@ -611,12 +959,20 @@ async fn render_config(
         .label("🚀 Start napadu")
         .style(ButtonStyle::Success);
     if !can_start { start = start.disabled(true); }
-    rows.push(CreateActionRow::Buttons(vec![
+    // limit 5 wierszy — przełącznik minigierki dzieli wiersz ze startem
+    let heat_cfg = &config::get().heat;
+    let mut buttons = vec![
         start,
         CreateButton::new("crime:solo:reset")
             .label("♻️ Reset")
             .style(ButtonStyle::Secondary),
-    ]));
+        CreateButton::new("crime:solo:laylow")
+            .label(format!("🕶️ Przyczaj się (−{} HEAT, {} min)", heat_cfg.lay_low_heat, heat_cfg.lay_low_mins))
+            .style(ButtonStyle::Secondary)
+            .disabled(heat <= 0),
+    ];
+    buttons.extend(minigame_buttons(cfg));
+    rows.push(CreateActionRow::Buttons(buttons));
 
     (e, rows)
 }
@@ -626,12 +982,12 @@ fn render_simon(
     seq: &[char],
     cursor: usize,
     result: Option<MinigameResult>,
-    reveal_until: Option<Instant>,
+    reveal_until: Option<DateTime<Utc>>,
     reveals_left: u8,
 ) -> (CreateEmbed, Vec<CreateActionRow>) {
     let total = seq.len();
     let hit = cursor.min(total);
-    let now = Instant::now();
+    let now = clock::now();
     let reveal_active = reveal_until.map(|t| now < t).unwrap_or(false);
 
     let status_chip = if reveal_active {
@@ -707,6 +1063,68 @@ fn render_simon(
     (e, rows)
 }
 
+fn render_qte(
+    spec: &QteSpec,
+    armed: bool,
+    elapsed_ms: Option<i64>,
+    result: Option<MinigameResult>,
+) -> (CreateEmbed, Vec<CreateActionRow>) {
+    let target_s = spec.target_ms as f32 / 1000.0;
+
+    let status = match (result, elapsed_ms) {
+        (Some(MinigameResult::Success), Some(ms)) => format!("✅ `IDEALNIE` — **{ms} ms**"),
+        (Some(MinigameResult::Partial(diff)), Some(ms)) => format!("🟡 `BLISKO` — **{ms} ms** (o {diff} ms od celu)"),
+        (Some(_), Some(ms)) => format!("❌ `PUDŁO` — **{ms} ms**"),
+        (Some(_), None) => "❌ `FALSTART` — najpierw start odliczania".to_string(),
+        (None, _) if armed => "⏱️ `ODLICZASZ` — kliknij TERAZ!".to_string(),
+        (None, _) => "🕹️ `GOTOWY`".to_string(),
+    };
+
+    let hud = format!(
+        "`Cel:` **{target_s:.1}s**   •   `Okno:` **±{} ms** (częściowo do ±{} ms)\n{status}",
+        spec.window_ms,
+        spec.window_ms * 2,
+    );
+
+    let (title, color) = match result {
+        Some(MinigameResult::Success)    => ("🎯 QTE — WYGRANA!", 0x2ecc71),
+        Some(MinigameResult::Partial(_)) => ("🎯 QTE — prawie!", 0xf1c40f),
+        Some(_)                          => ("🎯 QTE — Porażka", 0xe74c3c),
+        None                             => ("🎯 QTE — wyczucie czasu", 0xf39c12),
+    };
+
+    let e = CreateEmbed::new()
+        .title(title)
+        .color(color)
+        .description(format!(
+            "1. Kliknij **▶️ Start odliczania**.\n\
+             2. Odlicz w myślach **{target_s:.1}s** i kliknij **🎯 TERAZ!**\n\
+             Czas mierzy Discord (znaczniki obu kliknięć), nie bot."
+        ))
+        .field("HUD", hud, false);
+
+    let rows = vec![CreateActionRow::Buttons(vec![
+        CreateButton::new("crime:solo:qte_arm")
+            .label("▶️ Start odliczania")
+            .style(ButtonStyle::Secondary)
+            .disabled(armed || result.is_some()),
+        CreateButton::new("crime:solo:qte_hit")
+            .label("🎯 TERAZ!")
+            .style(ButtonStyle::Danger)
+            .disabled(result.is_some()),
+        CreateButton::new("crime:solo:resolve")
+            .label("✅ Rozstrzygnij napad")
+            .style(ButtonStyle::Primary)
+            .disabled(result.is_none()),
+        CreateButton::new("crime:solo:reset")
+            .label("↩️ Konfiguracja")
+            .style(ButtonStyle::Secondary)
+            .disabled(true),
+    ])];
+
+    (e, rows)
+}
+
 // ===== Pomocnicze dla Simon / UI =====
 
 fn keyboard_rows_from_chars(chars: &[char], disabled: bool) -> Vec<CreateActionRow> {
@@ -761,19 +1179,21 @@ fn render_outcome(v: &ResolvedView) -> (CreateEmbed, Vec<CreateActionRow>) {
             .join("\n")
     };
 
-    let newly = if v.newly_unlocked.is_empty() {
+    let newly = if v.newly_unlocked.is_empty() && v.newly_targets.is_empty() {
         "—".into()
     } else {
         v.newly_unlocked
             .iter()
             .map(|k| format!("🎁 {}", items::item_name(*k)))
+            .chain(v.newly_targets.iter().map(|t| format!("{} Cel: {}", emoji_for_target(Some(*t)), targets::target_name(*t))))
             .collect::<Vec<_>>()
             .join("\n")
     };
 
     let mode_chip  = v.cfg.mode.map(|m| format!("`{}` {}", mode_label(m), emoji_for_mode(m))).unwrap_or("`—`".into());
     let risk_chip  = v.cfg.risk.map(|r| format!("`{:?}` {}", r, emoji_for_risk(r))).unwrap_or("`—`".into());
-    let mg_chip    = format!("`Simon` {}", emoji_for_minigame(MinigameKind::Simon));
+    let mg_chip    = format!("`{}` {}", minigame_label(v.cfg.minigame), emoji_for_minigame(v.cfg.minigame));
+    let target_chip = format!("`{}` {}", target_label(v.cfg.target), emoji_for_target(v.cfg.target));
 
     let heat_gauge_before = bar10(heat_before.min(100));
     let heat_gauge_after  = bar10(heat_after.min(100));
@@ -786,12 +1206,27 @@ fn render_outcome(v: &ResolvedView) -> (CreateEmbed, Vec<CreateActionRow>) {
         _ => "💤 —",
     };
 
-    let title = if success { "🏆 SUKCES — Raport z napadu" } else { "💥 PORAŻKA — Raport z napadu" };
+    let title = if success {
+        "🏆 SUKCES — Raport z napadu"
+    } else if v.outcome.ambushed {
+        "🚨 ZASADZKA — Raport z napadu"
+    } else {
+        "💥 PORAŻKA — Raport z napadu"
+    };
     let color = if success { 0x2ecc71 } else { 0xe74c3c };
 
+    let next_heist = match (v.jailed_until, v.ready_at) {
+        (Some(t), _) => format!("\n**🔒 Więzienie** do <t:{0}:T> (<t:{0}:R>) — kaucja albo ucieczka: `/wiezienie`", t.timestamp()),
+        (None, Some(t)) => format!("\n**Następny skok**  <t:{}:R>", t.timestamp()),
+        (None, None) => String::new(),
+    };
+    let special = match v.cfg.target {
+        Some(t) if v.outcome.drop > 0 => format!("\n**Łup specjalny**  ✨ {} +{} TK", targets::drop_name(t), v.outcome.drop),
+        _ => String::new(),
+    };
     let summary = format!(
-        "**Konfiguracja**  {mode_chip} • {risk_chip} • {mg_chip}\n\
-         **Wynik**         {perf_medal}\n\
+        "**Konfiguracja**  {target_chip} • {mode_chip} • {risk_chip} • {mg_chip}\n\
+         **Wynik**         {perf_medal}{special}{next_heist}\n\
          **Przedmioty**\n{used_items}",
     );
 
@@ -852,96 +1287,90 @@ fn render_outcome(v: &ResolvedView) -> (CreateEmbed, Vec<CreateActionRow>) {
     (e, rows)
 }
 
-// =================== DB helpers (saldo + profil + ustawienia) ===================
+// =================== DB helpers (sesje + ustawienia) ===================
 
-async fn ensure_row_users(db: &PgPool, user_id: u64) -> Result<()> {
-    sqlx::query(r#"INSERT INTO users (id, balance) VALUES ($1, 0) ON CONFLICT (id) DO NOTHING"#)
-        .bind(user_id as i64)
-        .execute(db)
-        .await?;
-    Ok(())
-}
+// ---- Sesje napadu ----
 
-/// Pobierz saldo z DB (tworzy wiersz jeśli brak)
-async fn fetch_balance(db: &PgPool, user_id: u64) -> Result<i64> {
-    ensure_row_users(db, user_id).await?;
-    let bal = sqlx::query_scalar::<_, i64>(r#"SELECT balance FROM users WHERE id = $1"#)
-        .bind(user_id as i64)
-        .fetch_one(db)
-        .await?;
-    Ok(bal)
-}
-
-/// Dodaj delta do salda w DB. Zwraca saldo „po”.
-async fn add_balance(db: &PgPool, user_id: u64, delta: i64) -> Result<i64> {
-    ensure_row_users(db, user_id).await?;
-    let new_bal = sqlx::query_scalar::<_, i64>(
-        r#"UPDATE users SET balance = balance + $1 WHERE id = $2 RETURNING balance"#,
+/// Aktywna (niewygasła) sesja gracza; w transakcji — z blokadą wiersza.
+async fn load_session(conn: &mut PgConnection, eco: Economy, user_id: u64) -> Result<Option<SoloSession>> {
+    let raw: Option<String> = sqlx::query_scalar(
+        r#"SELECT state::text FROM crime_sessions
+           WHERE guild_id = $1 AND user_id = $2 AND expires_at > $3
+           FOR UPDATE"#,
     )
-    .bind(delta)
+    .bind(eco.id())
     .bind(user_id as i64)
-    .fetch_one(db)
+    .bind(clock::now())
+    .fetch_optional(&mut *conn)
     .await?;
-    Ok(new_bal)
-}
-
-// ---- Profile (HEAT/PP/skill) ----
 
-#[derive(Debug, Clone, Default)]
-struct DbProfile {
-    heat: i32,
-    pp: i32,
-    thief_skill: i32,
+    match raw {
+        Some(json) => match serde_json::from_str(&json) {
+            Ok(s) => Ok(Some(s)),
+            Err(e) => {
+                // format sesji zmienił się między wersjami — traktujemy jak brak sesji
+                eprintln!("⚠️ /crime: nieczytelna sesja {user_id}: {e}");
+                Ok(None)
+            }
+        },
+        None => Ok(None),
+    }
 }
 
-async fn ensure_row_profiles(db: &PgPool, user_id: u64) -> Result<()> {
+/// Zapisuje sesję i przesuwa jej wygaśnięcie.
+async fn save_session(conn: &mut PgConnection, eco: Economy, user_id: u64, s: &SoloSession) -> Result<()> {
+    let now = clock::now();
     sqlx::query(
-        r#"INSERT INTO profiles (user_id, heat, pp, thief_skill)
-           VALUES ($1, 0, 0, 0)
-           ON CONFLICT (user_id) DO NOTHING"#,
+        r#"INSERT INTO crime_sessions (guild_id, user_id, state, updated_at, expires_at)
+           VALUES ($1, $2, $3::jsonb, $4, $5)
+           ON CONFLICT (guild_id, user_id) DO UPDATE
+           SET state = EXCLUDED.state,
+               updated_at = EXCLUDED.updated_at,
+               expires_at = EXCLUDED.expires_at"#,
     )
+    .bind(eco.id())
     .bind(user_id as i64)
-    .execute(db)
+    .bind(serde_json::to_string(s)?)
+    .bind(now)
+    .bind(now + *SESSION_TTL)
+    .execute(&mut *conn)
     .await?;
     Ok(())
 }
 
-async fn load_profile_db(db: &PgPool, user_id: u64) -> Result<PlayerProfile> {
-    ensure_row_profiles(db, user_id).await?;
-    let rec = sqlx::query_as::<_, (i32, i32, i32)>(
-        r#"SELECT heat, pp, thief_skill FROM profiles WHERE user_id = $1"#,
+/// Usuwa wygasłe sesje; przerwany w trakcie napad trafia do logów jako porzucony.
+/// Woła to zadanie harmonogramu (`jobs::crime`). Zwraca (wygasłe, porzucone napady).
+pub(crate) async fn expire_sessions(db: &PgPool) -> Result<(usize, usize)> {
+    let rows: Vec<(i64, String)> = sqlx::query_as(
+        r#"DELETE FROM crime_sessions WHERE expires_at <= $1 RETURNING user_id, state::text"#,
     )
-    .bind(user_id as i64)
-    .fetch_one(db)
+    .bind(clock::now())
+    .fetch_all(db)
     .await?;
 
-    // balance dociągamy osobno, tutaj 0 (uzupełniany przy użyciu)
-    Ok(PlayerProfile {
-        user_id,
-        balance: 0,
-        heat: rec.0 as i64,
-        pp: rec.1 as u32,
-        thief_skill: rec.2 as u32,
-    })
-}
+    let expired = rows.len();
+    let mut abandoned = 0;
+    for (user_id, json) in rows {
+        let Ok(session) = serde_json::from_str::<SoloSession>(&json) else { continue };
+        let progress = match &session.state {
+            SoloState::InSimon { cursor, seq, .. } => format!("Simon {}/{}", (*cursor).min(seq.len()), seq.len()),
+            SoloState::InQte { armed_ms, .. } => {
+                format!("QTE {}", if armed_ms.is_some() { "w trakcie odliczania" } else { "nierozpoczęte" })
+            }
+            _ => continue,
+        };
+
+        abandoned += 1;
+        let cfg = &session.base_cfg;
+        let desc = format!(
+            "Porzucony napad (sesja wygasła): {} / {} • {progress}",
+            cfg.mode.map(mode_label).unwrap_or("—"),
+            cfg.risk.map(risk_to_str).unwrap_or("—"),
+        );
+        let _ = log_action(db, user_id as u64, "crime_abandon", None, None, Some(&desc)).await;
+    }
 
-async fn save_profile_db(db: &PgPool, user_id: u64, p: &PlayerProfile) -> Result<()> {
-    sqlx::query(
-        r#"INSERT INTO profiles (user_id, heat, pp, thief_skill)
-           VALUES ($1, $2, $3, $4)
-           ON CONFLICT (user_id) DO UPDATE
-           SET heat = EXCLUDED.heat,
-               pp = EXCLUDED.pp,
-               thief_skill = EXCLUDED.thief_skill,
-               updated_at = now()"#,
-    )
-    .bind(user_id as i64)
-    .bind(p.heat)
-    .bind(p.pp as i32)
-    .bind(p.thief_skill as i32)
-    .execute(db)
-    .await?;
-    Ok(())
+    Ok((expired, abandoned))
 }
 
 // ---- Ustawienia (mode/risk/items) ----
@@ -951,21 +1380,26 @@ struct DbSettings {
     mode: Option<CrimeMode>,
     risk: Option<Risk>,
     items: Vec<ItemKey>,
+    minigame: Option<MinigameKind>,
+    target: Option<TargetKey>,
 }
 
-async fn ensure_row_settings(db: &PgPool, user_id: u64) -> Result<()> {
+async fn ensure_row_settings(conn: &mut PgConnection, repo: &PgSoloRepo, eco: Economy, user_id: u64) -> Result<()> {
+    // FK do profiles — profil musi istnieć pierwszy
+    repo.get_or_create_in(conn, user_id).await?;
     sqlx::query(
-        r#"INSERT INTO crime_settings (user_id, mode, risk, loadout)
-           VALUES ($1, NULL, NULL, ARRAY[]::TEXT[])
-           ON CONFLICT (user_id) DO NOTHING"#,
+        r#"INSERT INTO crime_settings (guild_id, user_id, mode, risk, loadout)
+           VALUES ($1, $2, NULL, NULL, ARRAY[]::TEXT[])
+           ON CONFLICT (guild_id, user_id) DO NOTHING"#,
     )
+    .bind(eco.id())
     .bind(user_id as i64)
-    .execute(db)
+    .execute(&mut *conn)
     .await?;
     Ok(())
 }
 
-fn mode_to_str(m: CrimeMode) -> &'static str {
+pub(crate) fn mode_to_str(m: CrimeMode) -> &'static str {
     match m {
         CrimeMode::Standard  => "standard",
         CrimeMode::Szybki    => "szybki",
@@ -977,7 +1411,7 @@ fn mode_to_str(m: CrimeMode) -> &'static str {
         CrimeMode::Szalony   => "szalony",
     }
 }
-fn risk_to_str(r: Risk) -> &'static str {
+pub(crate) fn risk_to_str(r: Risk) -> &'static str {
     match r {
         Risk::Low => "low",
         Risk::Medium => "medium",
@@ -986,18 +1420,22 @@ fn risk_to_str(r: Risk) -> &'static str {
     }
 }
 
-async fn load_settings_db(db: &PgPool, user_id: u64) -> Result<Option<DbSettings>> {
-    ensure_row_settings(db, user_id).await?;
-    let row = sqlx::query_as::<_, (Option<String>, Option<String>, Option<Vec<String>>)>(
-        r#"SELECT mode, risk, loadout FROM crime_settings WHERE user_id = $1"#,
+async fn load_settings_db(conn: &mut PgConnection, repo: &PgSoloRepo, eco: Economy, user_id: u64) -> Result<Option<DbSettings>> {
+    ensure_row_settings(conn, repo, eco, user_id).await?;
+    #[allow(clippy::type_complexity)]
+    let row = sqlx::query_as::<_, (Option<String>, Option<String>, Option<Vec<String>>, Option<String>, Option<String>)>(
+        r#"SELECT mode, risk, loadout, minigame, target FROM crime_settings WHERE guild_id = $1 AND user_id = $2"#,
     )
+    .bind(eco.id())
     .bind(user_id as i64)
-    .fetch_optional(db)
+    .fetch_optional(&mut *conn)
     .await?;
 
-    if let Some((mode_s, risk_s, loadout_s)) = row {
+    if let Some((mode_s, risk_s, loadout_s, minigame_s, target_s)) = row {
         let mode = mode_s.as_deref().map(from_key_mode);
         let risk = risk_s.as_deref().map(from_key_risk);
+        let minigame = minigame_s.as_deref().and_then(from_key_minigame);
+        let target = target_s.as_deref().and_then(from_key_target);
 
         let items = loadout_s
             .unwrap_or_default()
@@ -1005,39 +1443,51 @@ async fn load_settings_db(db: &PgPool, user_id: u64) -> Result<Option<DbSettings
             .filter_map(|s| from_key_item(&s))
             .collect::<Vec<_>>();
 
-        Ok(Some(DbSettings { mode, risk, items }))
+        Ok(Some(DbSettings { mode, risk, items, minigame, target }))
     } else {
         Ok(None)
     }
 }
 
-async fn save_settings_db(db: &PgPool, user_id: u64, cfg: &SoloHeistConfig) -> Result<()> {
-    ensure_row_settings(db, user_id).await?;
+/// Zapamiętany zestaw gracza, przycięty do tego, co ma w ekwipunku (np. do napadu ekipy).
+pub(super) async fn saved_loadout(conn: &mut PgConnection, repo: &PgSoloRepo, eco: Economy, user_id: u64) -> Result<Vec<ItemKey>> {
+    let avail = inventory::owned_keys(&mut *conn, eco, user_id).await?;
+    let saved = load_settings_db(conn, repo, eco, user_id).await?.map(|s| s.items).unwrap_or_default();
+    Ok(saved.into_iter().filter(|k| avail.contains(k)).take(3).collect())
+}
+
+async fn save_settings_db(conn: &mut PgConnection, repo: &PgSoloRepo, eco: Economy, user_id: u64, cfg: &SoloHeistConfig) -> Result<()> {
+    ensure_row_settings(conn, repo, eco, user_id).await?;
     let mode_str: Option<&str> = cfg.mode.map(mode_to_str);
     let risk_str: Option<&str> = cfg.risk.map(risk_to_str);
     let loadout: Vec<&'static str> = cfg.items.iter().map(|k| key_item(*k)).collect();
 
     sqlx::query(
-        r#"INSERT INTO crime_settings (user_id, mode, risk, loadout, updated_at)
-           VALUES ($1, $2, $3, $4, now())
-           ON CONFLICT (user_id) DO UPDATE
+        r#"INSERT INTO crime_settings (guild_id, user_id, mode, risk, loadout, minigame, target, updated_at)
+           VALUES ($1, $2, $3, $4, $5, $6, $7, now())
+           ON CONFLICT (guild_id, user_id) DO UPDATE
            SET mode = EXCLUDED.mode,
                risk = EXCLUDED.risk,
                loadout = EXCLUDED.loadout,
+               minigame = EXCLUDED.minigame,
+               target = EXCLUDED.target,
                updated_at = now()"#,
     )
+    .bind(eco.id())
     .bind(user_id as i64)
     .bind(mode_str)
     .bind(risk_str)
     .bind(loadout)
-    .execute(db)
+    .bind(minigame_to_str(cfg.minigame))
+    .bind(cfg.target.map(key_target))
+    .execute(&mut *conn)
     .await?;
     Ok(())
 }
 
 // =================== Helpers UI / keys ===================
 
-fn mode_label(m: CrimeMode) -> &'static str {
+pub(super) fn mode_label(m: CrimeMode) -> &'static str {
     match m {
         CrimeMode::Standard => "Standard",
         CrimeMode::Szybki => "Szybki",
@@ -1062,21 +1512,24 @@ fn item_short_desc(k: ItemKey) -> &'static str {
     }
 }
 
-fn row_select_items(pp: u32, chosen: &HashSet<ItemKey>) -> CreateActionRow {
+fn row_select_items(pp: u32, owned: &[Owned], chosen: &HashSet<ItemKey>) -> CreateActionRow {
     let options = items::ITEM_META
         .iter()
-        .map(|(k, meta)| {
-            let unlocked = pp >= meta.required_pp;
+        .map(|(k, _)| {
+            let required_pp = items::required_pp(*k);
+            let have = owned.iter().find(|o| o.item == *k);
             let value = key_item(*k);
-            let label = if unlocked {
-                format!("{}", items::item_name(*k))
-            } else {
-                format!("🔒 {} (PP:{})", items::item_name(*k), meta.required_pp)
+            let label = match have {
+                Some(o) => format!("{} ({})", items::item_name(*k), charges_label(*k, o.charges)),
+                None if pp >= required_pp => format!("🛒 {}", items::item_name(*k)),
+                None => format!("🔒 {} (PP:{})", items::item_name(*k), required_pp),
             };
-            let desc = if unlocked {
-                item_short_desc(*k).to_string()
-            } else {
-                format!("Wymaga PP:{} • {}", meta.required_pp, item_short_desc(*k))
+            let desc = match have {
+                Some(_) => item_short_desc(*k).to_string(),
+                None if pp >= required_pp => {
+                    format!("Nie masz — {} TK w /ekwipunek • {}", config::get().items.get(*k).price, item_short_desc(*k))
+                }
+                None => format!("Wymaga PP:{} • {}", required_pp, item_short_desc(*k)),
             };
 
             let mut o = CreateSelectMenuOption::new(label, value).description(desc);
@@ -1118,6 +1571,72 @@ fn row_modes_cfg(cfg: &SoloHeistConfig) -> CreateActionRow {
     ])
 }
 
+/// Cele z progami PP — odblokowane i kolejne do zdobycia.
+fn target_list(pp: u32) -> String {
+    targets::TARGET_META
+        .iter()
+        .map(|(k, meta)| {
+            let required = targets::required_pp(*k);
+            if pp >= required {
+                format!("{} {}", emoji_for_target(Some(*k)), meta.name)
+            } else {
+                format!("🔒 {} (PP:{required})", meta.name)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" • ")
+}
+
+/// Modyfikatory celu w jednej linijce (do panelu konfiguracji).
+fn target_summary(target: Option<TargetKey>) -> String {
+    let Some(k) = target else {
+        return "Zwykły skok — tylko tryb i ryzyko.".to_string();
+    };
+    let t = targets::table(k);
+    format!(
+        "Szansa **{:+.0} pp** • Łup **×{:.2}** • HEAT **×{:.2}**\n\
+         Pod {} **{}** (+{:.0} pp) • {} **{:.0}%** na {}–{} TK",
+        t.chance_delta,
+        t.reward_mult,
+        t.heat_mult,
+        emoji_for_minigame(t.preferred_minigame),
+        minigame_label(t.preferred_minigame),
+        t.preferred_bonus,
+        targets::drop_name(k),
+        t.drop_chance * 100.0,
+        t.drop[0],
+        t.drop[1],
+    )
+}
+
+fn minigame_buttons(cfg: &SoloHeistConfig) -> [CreateButton; 2] {
+    let btn = |label: &str, kind: MinigameKind| {
+        CreateButton::new(format!("crime:solo:minigame:{}", minigame_to_str(kind)))
+            .label(label)
+            .style(if cfg.minigame == kind { ButtonStyle::Success } else { ButtonStyle::Secondary })
+    };
+    [btn("🧠 Simon", MinigameKind::Simon), btn("🎯 QTE", MinigameKind::Qte)]
+}
+
+fn row_targets_cfg(pp: u32, cfg: &SoloHeistConfig) -> CreateActionRow {
+    let btn = |target: Option<TargetKey>| {
+        let required = target.map(targets::required_pp).unwrap_or(0);
+        let key = target.map(key_target).unwrap_or("ulica");
+        let label = if pp >= required {
+            format!("{} {}", emoji_for_target(target), target_label(target))
+        } else {
+            format!("🔒 {} (PP:{required})", target_label(target))
+        };
+        CreateButton::new(format!("crime:solo:target:{key}"))
+            .label(label)
+            .style(if cfg.target == target { ButtonStyle::Success } else { ButtonStyle::Secondary })
+            .disabled(pp < required)
+    };
+    let mut buttons = vec![btn(None)];
+    buttons.extend(targets::TARGET_META.iter().map(|(k, _)| btn(Some(*k))));
+    CreateActionRow::Buttons(buttons)
+}
+
 fn row_risks_cfg(cfg: &SoloHeistConfig) -> CreateActionRow {
     let cur = cfg.risk.unwrap_or(Risk::Medium);
     let btn = |label: &str, key: &str, is_cur: bool| {
@@ -1137,7 +1656,7 @@ fn row_risks_cfg(cfg: &SoloHeistConfig) -> CreateActionRow {
     ])
 }
 
-fn from_key_mode(k: &str) -> CrimeMode {
+pub(super) fn from_key_mode(k: &str) -> CrimeMode {
     match k {
         "standard" => CrimeMode::Standard,
         "szybki" => CrimeMode::Szybki,
@@ -1150,7 +1669,7 @@ fn from_key_mode(k: &str) -> CrimeMode {
         _ => CrimeMode::Standard,
     }
 }
-fn from_key_risk(k: &str) -> Risk {
+pub(super) fn from_key_risk(k: &str) -> Risk {
     match k {
         "low" => Risk::Low,
         "medium" => Risk::Medium,
@@ -1159,7 +1678,20 @@ fn from_key_risk(k: &str) -> Risk {
         _ => Risk::Medium,
     }
 }
-fn from_key_item(k: &str) -> Option<ItemKey> {
+pub(crate) fn minigame_to_str(k: MinigameKind) -> &'static str {
+    match k {
+        MinigameKind::Simon => "simon",
+        MinigameKind::Qte => "qte",
+    }
+}
+pub(super) fn from_key_minigame(k: &str) -> Option<MinigameKind> {
+    match k {
+        "simon" => Some(MinigameKind::Simon),
+        "qte" => Some(MinigameKind::Qte),
+        _ => None,
+    }
+}
+pub(crate) fn from_key_item(k: &str) -> Option<ItemKey> {
     Some(match k {
         "laptop" => ItemKey::HackerLaptop,
         "gloves" => ItemKey::ProGloves,
@@ -1170,7 +1702,7 @@ fn from_key_item(k: &str) -> Option<ItemKey> {
         _ => return None,
     })
 }
-fn key_item(k: ItemKey) -> &'static str {
+pub(crate) fn key_item(k: ItemKey) -> &'static str {
     match k {
         ItemKey::HackerLaptop  => "laptop",
         ItemKey::ProGloves     => "gloves",
@@ -1181,6 +1713,24 @@ fn key_item(k: ItemKey) -> &'static str {
     }
 }
 
+pub(crate) fn from_key_target(k: &str) -> Option<TargetKey> {
+    Some(match k {
+        "kiosk" => TargetKey::Kiosk,
+        "jubiler" => TargetKey::Jeweller,
+        "bank" => TargetKey::Bank,
+        "kasyno" => TargetKey::CasinoVault,
+        _ => return None,
+    })
+}
+pub(crate) fn key_target(k: TargetKey) -> &'static str {
+    match k {
+        TargetKey::Kiosk       => "kiosk",
+        TargetKey::Jeweller    => "jubiler",
+        TargetKey::Bank        => "bank",
+        TargetKey::CasinoVault => "kasyno",
+    }
+}
+
 fn extract_cfg(s: &SoloSession) -> SoloHeistConfig {
     match &s.state {
         SoloState::Config(cfg) => cfg.clone(),
@@ -1188,9 +1738,9 @@ fn extract_cfg(s: &SoloSession) -> SoloHeistConfig {
     }
 }
 
-fn bar10(value_0_100: u32) -> String {
+pub(super) fn bar10(value_0_100: u32) -> String {
     let width = 10u32;
-    let filled = ((value_0_100.min(100) as u32 * width) + 99) / 100;
+    let filled = (value_0_100.min(100) * width).div_ceil(100);
     let mut s = String::with_capacity(10);
     for i in 0..width {
         if i < filled { s.push('▰'); } else { s.push('▱'); }
@@ -1198,7 +1748,7 @@ fn bar10(value_0_100: u32) -> String {
     s
 }
 
-fn emoji_for_item(i: ItemKey) -> &'static str {
+pub(crate) fn emoji_for_item(i: ItemKey) -> &'static str {
     match i {
         ItemKey::HackerLaptop  => "💻",
         ItemKey::ProGloves     => "🧤",
@@ -1209,7 +1759,21 @@ fn emoji_for_item(i: ItemKey) -> &'static str {
     }
 }
 
-fn emoji_for_risk(r: Risk) -> &'static str {
+pub(super) fn emoji_for_target(t: Option<TargetKey>) -> &'static str {
+    match t {
+        None                        => "🌃",
+        Some(TargetKey::Kiosk)       => "🏪",
+        Some(TargetKey::Jeweller)    => "💍",
+        Some(TargetKey::Bank)        => "🏦",
+        Some(TargetKey::CasinoVault) => "🎰",
+    }
+}
+
+pub(super) fn target_label(t: Option<TargetKey>) -> &'static str {
+    t.map(targets::target_name).unwrap_or("Ulica")
+}
+
+pub(super) fn emoji_for_risk(r: Risk) -> &'static str {
     match r {
         Risk::Low      => "🟢",
         Risk::Medium   => "🟡",
@@ -1218,7 +1782,7 @@ fn emoji_for_risk(r: Risk) -> &'static str {
     }
 }
 
-fn emoji_for_mode(m: CrimeMode) -> &'static str {
+pub(super) fn emoji_for_mode(m: CrimeMode) -> &'static str {
     match m {
         CrimeMode::Standard  => "⚙️",
         CrimeMode::Szybki    => "⚡",
@@ -1231,13 +1795,20 @@ fn emoji_for_mode(m: CrimeMode) -> &'static str {
     }
 }
 
-fn emoji_for_minigame(k: MinigameKind) -> &'static str {
+pub(super) fn emoji_for_minigame(k: MinigameKind) -> &'static str {
     match k {
-        MinigameKind::Qte   => "🎯", // nieużywane, ale zostawione dla kompletności
+        MinigameKind::Qte   => "🎯",
         MinigameKind::Simon => "🧠",
     }
 }
 
+pub(super) fn minigame_label(k: MinigameKind) -> &'static str {
+    match k {
+        MinigameKind::Qte => "QTE",
+        MinigameKind::Simon => "Simon",
+    }
+}
+
 fn chip_step(label: &str, done: bool) -> String {
     if done { format!("`{label}` ✅") } else { format!("`{label}` ⬜") }
 }
@@ -1272,42 +1843,65 @@ fn simon_preview_ms(risk: Risk, len: usize, time_mult: f32) -> u64 {
     total.clamp(500, 12_000)
 }
 
-// ===== TEMP: auto-DDL bootstrap (usuń po utworzeniu tabel) ===================
+// =======================
+// 🧭 Rejestr komend
+// =======================
 
-/// Utwórz wymagane tabele, jeśli jeszcze nie istnieją.
-pub async fn ensure_schema_all(db: &PgPool) -> Result<()> {
-    // 1) users: saldo
-    sqlx::query(r#"
-        CREATE TABLE IF NOT EXISTS users (
-            id BIGINT PRIMARY KEY,
-            balance BIGINT NOT NULL DEFAULT 0,
-            created_at  TIMESTAMPTZ NOT NULL DEFAULT now(),
-            updated_at  TIMESTAMPTZ NOT NULL DEFAULT now()
-        )
-    "#).execute(db).await?;
-
-    // 2) profiles: HEAT / PP / skill
-    sqlx::query(r#"
-        CREATE TABLE IF NOT EXISTS profiles (
-            user_id     BIGINT PRIMARY KEY,
-            heat        INTEGER NOT NULL DEFAULT 0,
-            pp          INTEGER NOT NULL DEFAULT 0,
-            thief_skill INTEGER NOT NULL DEFAULT 0,
-            created_at  TIMESTAMPTZ NOT NULL DEFAULT now(),
-            updated_at  TIMESTAMPTZ NOT NULL DEFAULT now()
-        )
-    "#).execute(db).await?;
-
-    // 3) crime_settings: ostatnie ustawienia gry
-    sqlx::query(r#"
-        CREATE TABLE IF NOT EXISTS crime_settings (
-            user_id    BIGINT PRIMARY KEY REFERENCES profiles(user_id) ON DELETE CASCADE,
-            mode       TEXT NULL,                         -- "standard" | "szybki" | ...
-            risk       TEXT NULL,                         -- "low" | "medium" | "high" | "hardcore"
-            loadout    TEXT[] NOT NULL DEFAULT ARRAY[]::TEXT[],  -- ["laptop","gloves",...]
-            updated_at TIMESTAMPTZ NOT NULL DEFAULT now()
-        )
-    "#).execute(db).await?;
+pub struct CrimeCommand;
 
-    Ok(())
-}
\ No newline at end of file
+#[async_trait]
+impl BotCommand for CrimeCommand {
+    fn name(&self) -> &'static str {
+        "crime"
+    }
+
+    fn economy(&self) -> bool {
+        true
+    }
+
+    // więzienie blokuje napady (solo i ekipa); profil, drzewko, statystyki i wznowienie panelu — nie
+    fn earning(&self, sub: Option<&str>) -> bool {
+        !matches!(sub, Some("profil" | "wznow" | "drzewko"))
+    }
+
+    fn earning_component(&self, custom_id: &str) -> bool {
+        custom_id.starts_with("crime:solo:") || custom_id.starts_with("crime:crew:")
+    }
+
+    fn register(&self) -> CreateCommand {
+        register()
+    }
+
+    fn cooldown(&self) -> Option<CooldownMeta> {
+        Some(cooldown_meta())
+    }
+
+    fn replay(&self, kind: &str, seed: u64, inputs: &str) -> Option<Result<serde_json::Value>> {
+        match kind {
+            "crime" => Some(rng::replay(seed, inputs, roll_solo)),
+            "crime.simon" => Some(rng::replay(seed, inputs, minigames::gen_simon_seq)),
+            "crime.crew" => Some(rng::replay(seed, inputs, roll_crew)),
+            _ => None,
+        }
+    }
+
+    async fn run(&self, ctx: &Context, cmd: &CommandInteraction, db: &PgPool) -> Result<()> {
+        run(ctx, cmd, db).await
+    }
+
+    fn component_prefixes(&self) -> &'static [&'static str] {
+        &["crime:"]
+    }
+
+    async fn handle_component(&self, ctx: &Context, ic: &ComponentInteraction, db: &PgPool) -> Result<()> {
+        handle_component(ctx, ic, db).await
+    }
+
+    fn modal_prefixes(&self) -> &'static [&'static str] {
+        &["crime:"]
+    }
+
+    async fn handle_modal(&self, ctx: &Context, mi: &ModalInteraction, db: &PgPool) -> Result<()> {
+        handle_modal(ctx, mi, db).await
+    }
+}
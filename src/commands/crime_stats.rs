@@ -0,0 +1,192 @@
+//! commands/crime_stats.rs — statystyki i historia napadów w `/crime profil` (i `/admcontrol napady`).
+//!
+//! Strony historii przewija się przyciskami `crime:hist:{gracz}:{strona}`; każdy klik czyta
+//! bazę od nowa. Cudzą historię mogą przewijać tylko admini (ta sama zasada co `/admcontrol`).
+
+use anyhow::Result;
+use serenity::all::{
+    ButtonStyle, ComponentInteraction, Context, CreateActionRow, CreateButton, CreateEmbed, CreateInteractionResponse,
+    CreateInteractionResponseMessage,
+};
+use sqlx::PgPool;
+
+use super::admcontrol;
+use super::crime::{
+    emoji_for_item, emoji_for_minigame, emoji_for_mode, emoji_for_risk, emoji_for_target, from_key_item,
+    from_key_minigame, from_key_mode, from_key_risk, from_key_target, minigame_label, mode_label, profile_embed,
+    reply_ephemeral, target_label,
+};
+use crate::economy::{self, Economy};
+use crate::engine::targets;
+use crate::engine::types::{MinigameKind, Risk};
+use crate::heists::{self, HeistRecord, Rate, Stats};
+
+/// Napadów na stronę historii.
+const PER_PAGE: u32 = 5;
+
+// =================== Widok ===================
+
+/// Profil + statystyki + strona historii (`page` od 0, przycinana do ostatniej).
+pub async fn view(db: &PgPool, eco: Economy, user: u64, page: u32) -> Result<(Vec<CreateEmbed>, Vec<CreateActionRow>)> {
+    let profile = profile_embed(db, eco, user).await?;
+    let stats = heists::stats(db, eco, user).await?;
+    if stats.total == 0 {
+        let empty = CreateEmbed::new()
+            .title("📊 Statystyki napadów")
+            .description("Jeszcze żadnego napadu — zacznij od `/crime start`.")
+            .color(0x95a5a6);
+        return Ok((vec![profile, empty], vec![]));
+    }
+
+    let pages = (stats.total as u64).div_ceil(u64::from(PER_PAGE)).max(1) as u32;
+    let page = page.min(pages - 1);
+    let rows = heists::page(db, eco, user, page, PER_PAGE).await?;
+
+    let history = CreateEmbed::new()
+        .title(format!("📜 Historia napadów — strona {}/{pages}", page + 1))
+        .description(rows.iter().map(history_line).collect::<Vec<_>>().join("\n\n"))
+        .color(0x64748b);
+
+    let nav = CreateActionRow::Buttons(vec![
+        CreateButton::new(format!("crime:hist:{user}:{}", page.saturating_sub(1)))
+            .label("◀️ Nowsze")
+            .style(ButtonStyle::Secondary)
+            .disabled(page == 0),
+        CreateButton::new(format!("crime:hist:{user}:{}", page + 1))
+            .label("Starsze ▶️")
+            .style(ButtonStyle::Secondary)
+            .disabled(page + 1 >= pages),
+    ]);
+
+    Ok((vec![profile, stats_embed(&stats), history], vec![nav]))
+}
+
+fn risk_label(r: Risk) -> &'static str {
+    match r {
+        Risk::Low => "Low",
+        Risk::Medium => "Medium",
+        Risk::High => "High",
+        Risk::Hardcore => "Hardcore",
+    }
+}
+
+fn rate_line(label: String, r: &Rate) -> String {
+    format!("{label}: **{}/{}** ({:.0}%)", r.wins, r.total, r.pct())
+}
+
+fn stats_embed(s: &Stats) -> CreateEmbed {
+    let pct = s.wins as f64 * 100.0 / s.total as f64;
+    let modes: Vec<String> = s
+        .by_mode
+        .iter()
+        .map(|r| {
+            let m = from_key_mode(&r.key);
+            rate_line(format!("{} {}", emoji_for_mode(m), mode_label(m)), r)
+        })
+        .collect();
+    let risks: Vec<String> = s
+        .by_risk
+        .iter()
+        .map(|r| {
+            let k = from_key_risk(&r.key);
+            rate_line(format!("{} {}", emoji_for_risk(k), risk_label(k)), r)
+        })
+        .collect();
+    let best = match &s.best {
+        Some(b) => {
+            let t = b.target.as_deref().and_then(from_key_target);
+            format!(
+                "**{:+} TK** — {} {} • <t:{}:d>",
+                b.amount,
+                emoji_for_target(t),
+                target_label(t),
+                b.created_at.timestamp()
+            )
+        }
+        None => "—".to_string(),
+    };
+
+    CreateEmbed::new()
+        .title("📊 Statystyki napadów")
+        .description(format!(
+            "Napady: **{}** • udane **{}** ({pct:.0}%) • najdłuższa seria **{}**",
+            s.total, s.wins, s.longest_streak
+        ))
+        .field("💰 Łupy", format!("{} TK", s.loot), true)
+        .field("📈 Bilans", format!("{:+} TK", s.net), true)
+        .field("🏆 Największy skok", best, true)
+        .field("Tryby", modes.join("\n"), true)
+        .field("Ryzyko", risks.join("\n"), true)
+        .color(0x3b82f6)
+}
+
+fn history_line(r: &HeistRecord) -> String {
+    let mode = from_key_mode(&r.mode);
+    let risk = from_key_risk(&r.risk);
+    let target = r.target.as_deref().and_then(from_key_target);
+    let verdict = if r.ambushed {
+        "🚨 zasadzka"
+    } else if r.success {
+        "🏆 udany"
+    } else {
+        "💥 wpadka"
+    };
+    let drop = match target {
+        Some(t) if r.loot_drop > 0 => format!(" (w tym {} +{})", targets::drop_name(t), r.loot_drop),
+        _ => String::new(),
+    };
+    let kind = from_key_minigame(&r.minigame).unwrap_or(MinigameKind::Qte);
+    let mg = match (r.mg_result.as_str(), r.mg_diff_ms) {
+        ("success", _) => "czysto".to_string(),
+        ("partial", Some(ms)) => format!("częściowo ({ms} ms)"),
+        ("partial", None) => "częściowo".to_string(),
+        ("fail", _) => "pudło".to_string(),
+        _ => "nie grano".to_string(),
+    };
+    let gear: String = r.items.iter().filter_map(|k| from_key_item(k)).map(emoji_for_item).collect();
+
+    format!(
+        "**#{}** <t:{}:R>{} • {} {} • {} {} • {} {}\n\
+         {verdict} **{:+} TK**{drop} • {} {}: {mg} • {} • HEAT {:+} • ziarno `{:#x}`",
+        r.id,
+        r.created_at.timestamp(),
+        if r.crew { " • 👥 ekipa" } else { "" },
+        emoji_for_target(target),
+        target_label(target),
+        emoji_for_mode(mode),
+        mode_label(mode),
+        emoji_for_risk(risk),
+        risk_label(risk),
+        r.amount,
+        emoji_for_minigame(kind),
+        minigame_label(kind),
+        if gear.is_empty() { "bez sprzętu".to_string() } else { gear },
+        r.heat_delta,
+        r.seed(),
+    )
+}
+
+// =================== Przyciski ===================
+
+pub async fn handle_component(ctx: &Context, mci: &ComponentInteraction, db: &PgPool) -> Result<()> {
+    // crime:hist:{gracz}:{strona}
+    let parts: Vec<&str> = mci.data.custom_id.split(':').collect();
+    let (Some(user), Some(page)) = (
+        parts.get(2).and_then(|s| s.parse::<u64>().ok()),
+        parts.get(3).and_then(|s| s.parse::<u32>().ok()),
+    ) else {
+        return Ok(());
+    };
+    if user != mci.user.id.get() && !admcontrol::is_authorized_member(mci.member.as_ref()) {
+        return reply_ephemeral(ctx, mci, "⛔ To nie twoja historia napadów.").await;
+    }
+
+    let eco = economy::of(db, mci.guild_id).await?;
+    let (embeds, rows) = view(db, eco, user, page).await?;
+    mci.create_response(
+        &ctx.http,
+        CreateInteractionResponse::UpdateMessage(CreateInteractionResponseMessage::new().embeds(embeds).components(rows)),
+    )
+    .await?;
+    Ok(())
+}
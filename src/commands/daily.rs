@@ -1,15 +1,29 @@
-use anyhow::{anyhow, Context as AnyCtx, Result};
-use chrono::{DateTime, NaiveDateTime, Utc};
+use anyhow::{Context as AnyCtx, Result};
+use chrono::{DateTime, Utc};
 use rand::Rng;
 use serenity::all::*;
 use serenity::builder::CreateCommand;
-use sqlx::{PgPool, Row, Postgres, Transaction};
+use serenity::async_trait;
+use sqlx::{PgPool, Postgres, Transaction};
 use serenity::builder::{CreateInteractionResponse, CreateInteractionResponseMessage};
 
-use crate::utils::log_action;
+use crate::cooldowns;
+use crate::economy::{self, Economy};
+use crate::reminders;
+use crate::rng::{self, Roll};
+use crate::ledger::{self, Entry, Overdraft};
+use crate::loans::{self, Garnished};
+use crate::utils::{get_log_channel_id, log_action};
+use crate::commands::loan::garnish_text;
+use crate::commands::registry::{BotCommand, CooldownMeta};
+use crate::engine::config;
 
-const DAILY_COOLDOWN_HOURS: i64 = 24;
-const COOLDOWN_SECS: i64 = DAILY_COOLDOWN_HOURS * 3600;
+const COOLDOWN_KEY: &str = "daily";
+
+/// Długość: `cooldowns.daily_secs`, nagroda: `daily.reward` (tabele balansu).
+fn cooldown_meta() -> CooldownMeta {
+    CooldownMeta { key: COOLDOWN_KEY, secs: config::get().cooldowns.daily_secs, heat_penalty: false }
+}
 
 pub fn register(cmd: &mut CreateCommand) -> &mut CreateCommand {
     *cmd = CreateCommand::new("daily")
@@ -17,35 +31,7 @@ pub fn register(cmd: &mut CreateCommand) -> &mut CreateCommand {
     cmd
 }
 
-pub async fn ensure_daily_schema(db: &PgPool) -> anyhow::Result<()> {
-    // Oddzielne zapytania – stabilniej na różnych setupach
-    sqlx::query(
-        r#"
-        CREATE TABLE IF NOT EXISTS users (
-          id BIGINT PRIMARY KEY,
-          balance BIGINT NOT NULL DEFAULT 0
-        );
-        "#,
-    )
-    .execute(db)
-    .await?;
-
-    sqlx::query(
-        r#"
-        ALTER TABLE users
-          ADD COLUMN IF NOT EXISTS last_daily TIMESTAMPTZ NULL;
-        "#,
-    )
-    .execute(db)
-    .await?;
-
-    Ok(())
-}
-
 pub async fn run(ctx: &Context, cmd: &CommandInteraction, db: &PgPool) -> Result<()> {
-    // Schema best-effort (bez paniki jak się nie uda)
-    let _ = ensure_daily_schema(db).await;
-
     // Defer z ephemeral, żeby nie złapać 3s timeoutu
     cmd.create_response(
         &ctx.http,
@@ -55,20 +41,23 @@ pub async fn run(ctx: &Context, cmd: &CommandInteraction, db: &PgPool) -> Result
     ).await?;
 
     let user_id_u64 = cmd.user.id.get();
-    let now = Utc::now();
-
-    // RNG w krótkim scope
-    let reward: i64 = {
-        let mut rng = rand::rng();        // rand 0.9
-        rng.random_range(250..=500)       // nowa metoda
-    };
+    let guild_id = cmd.guild_id.context("/daily poza serwerem")?;
+    let eco = economy::resolve(db, guild_id).await?;
 
-    match claim_daily(db, user_id_u64, reward, now).await? {
-        ClaimOutcome::Claimed { balance_after } => {
+    match claim_daily(db, eco, guild_id, user_id_u64, cmd.id.get()).await? {
+        ClaimOutcome::Claimed { reward, balance_after, ready_at, garnished } => {
             // Odpowiedź
-            let embed = build_daily_reward_embed(reward, &cmd.user, balance_after);
+            let mut embed = build_daily_reward_embed(reward, &cmd.user, balance_after);
+            if let Some(g) = garnished {
+                embed = embed.field("💳 Spłata pożyczki", garnish_text(&g), true);
+            }
             edit_embed(ctx, cmd, embed).await?;
 
+            // Przypomnienie o kolejnym daily (harmonogram)
+            if let Err(e) = reminders::schedule(db, guild_id, cmd.channel_id, user_id_u64, COOLDOWN_KEY, "/daily", ready_at).await {
+                eprintln!("❌ /daily: nie zapisano przypomnienia: {e:?}");
+            }
+
             // Log do bazy (best effort)
             let _ = log_action(
                 db,
@@ -80,7 +69,7 @@ pub async fn run(ctx: &Context, cmd: &CommandInteraction, db: &PgPool) -> Result
             ).await;
 
             // Log do kanału (opcjonalny)
-            if let Some(ch) = log_channel() {
+            if let Some(ch) = get_log_channel_id() {
                 let embed = CreateEmbed::new()
                     .title("🎁 Log: Codzienna nagroda (/daily)")
                     .description(format!(
@@ -112,114 +101,82 @@ pub async fn run(ctx: &Context, cmd: &CommandInteraction, db: &PgPool) -> Result
 
 /// Rezultat próby odebrania daily
 enum ClaimOutcome {
-    Claimed { balance_after: i64 },
+    Claimed { reward: i64, balance_after: i64, ready_at: DateTime<Utc>, garnished: Option<Garnished> },
     OnCooldown { remaining_secs: i64 },
 }
 
+/// Losowanie nagrody (zapisywane w `rolls`, powtarzalne z ziarna).
+fn roll_reward(_: &(), rng: &mut impl Rng) -> i64 {
+    let [min, max] = config::get().daily.reward;
+    rng.random_range(min..=max)
+}
+
 /// Cała logika cooldownu w jednej transakcji z blokadą wiersza
 async fn claim_daily(
     db: &PgPool,
+    eco: Economy,
+    guild: GuildId,
     user_id_u64: u64,
-    reward: i64,
-    now: DateTime<Utc>,
+    interaction_id: u64,
 ) -> Result<ClaimOutcome> {
     let user_id = i64::try_from(user_id_u64).context("ID usera nie mieści się w i64")?;
     let mut tx: Transaction<'_, Postgres> = db.begin().await?;
 
-    // Zablokuj rekord użytkownika jeśli istnieje
-    let row_opt = sqlx::query(
-        r#"SELECT balance, last_daily FROM users WHERE id = $1 FOR UPDATE"#,
+    ledger::ensure_user(&mut tx, eco, user_id_u64).await?;
+
+    // Zablokuj rekord użytkownika (równoległe /daily czekają na siebie)
+    sqlx::query(r#"SELECT 1 FROM users WHERE guild_id = $1 AND id = $2 FOR UPDATE"#)
+        .bind(eco.id())
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+
+    if let Some(remaining_secs) = cooldowns::remaining(&mut *tx, eco, user_id_u64, COOLDOWN_KEY).await? {
+        tx.rollback().await.ok();
+        return Ok(ClaimOutcome::OnCooldown { remaining_secs });
+    }
+
+    // Można przyznać (także pierwszy raz)
+    let (seed, mut rng) = rng::seeded();
+    let reward = roll_reward(&(), &mut rng);
+    rng::record(
+        &mut *tx,
+        eco,
+        &Roll { command: "daily", user: user_id_u64, interaction_id: Some(interaction_id), seed },
+        &(),
+        &reward,
     )
-    .bind(user_id)
-    .fetch_optional(&mut *tx)
     .await?;
 
-    // Helper: odczytaj last_daily niezależnie od typu kolumny
-    fn read_last_daily(row: &sqlx::postgres::PgRow) -> Result<Option<DateTime<Utc>>> {
-        // timestamptz
-        if let Ok(v) = row.try_get::<Option<DateTime<Utc>>, _>("last_daily") {
-            return Ok(v);
-        }
-        // timestamp (bez strefy)
-        if let Ok(v) = row.try_get::<Option<NaiveDateTime>, _>("last_daily") {
-            return Ok(v.map(|naive| DateTime::<Utc>::from_naive_utc_and_offset(naive, Utc)));
-        }
-        Err(anyhow!("Nieobsługiwany typ kolumny last_daily"))
-    }
+    let mut new_balance = ledger::settle(
+        &mut tx,
+        eco,
+        user_id_u64,
+        "daily",
+        reward,
+        &Entry { reason: "codzienna nagroda", command: "daily", interaction_id: Some(interaction_id) },
+        Overdraft::Forbid,
+    )
+    .await?;
 
-    let outcome = if let Some(row) = row_opt {
-        let last_daily = read_last_daily(&row)?;
-
-        if let Some(last) = last_daily {
-            let elapsed = now.signed_duration_since(last).num_seconds();
-            if elapsed < COOLDOWN_SECS {
-                // Nadal cooldown
-                let remaining = COOLDOWN_SECS - elapsed;
-                tx.rollback().await.ok();
-                ClaimOutcome::OnCooldown { remaining_secs: remaining }
-            } else {
-                // Można przyznać
-                let new_balance: i64 = sqlx::query(
-                    r#"
-                        UPDATE users
-                           SET balance = balance + $2,
-                               last_daily = $3
-                         WHERE id = $1
-                     RETURNING balance
-                    "#,
-                )
-                .bind(user_id)
-                .bind(reward)
-                .bind(now)
-                .fetch_one(&mut *tx)
-                .await?
-                .try_get("balance")?;
-
-                tx.commit().await?;
-                ClaimOutcome::Claimed { balance_after: new_balance }
-            }
-        } else {
-            // Pierwszy raz — ustaw last_daily teraz i dodaj nagrodę
-            let new_balance: i64 = sqlx::query(
-                r#"
-                    UPDATE users
-                       SET balance = balance + $2,
-                           last_daily = $3
-                     WHERE id = $1
-                 RETURNING balance
-                "#,
-            )
-            .bind(user_id)
-            .bind(reward)
-            .bind(now)
-            .fetch_one(&mut *tx)
-            .await?
-            .try_get("balance")?;
-
-            tx.commit().await?;
-            ClaimOutcome::Claimed { balance_after: new_balance }
-        }
-    } else {
-        // Brak wiersza — wstaw
-        let new_balance: i64 = sqlx::query(
-            r#"
-            INSERT INTO users (id, balance, last_daily)
-            VALUES ($1, $2, $3)
-            RETURNING balance
-            "#,
-        )
-        .bind(user_id)
-        .bind(reward)
-        .bind(now)
-        .fetch_one(&mut *tx)
-        .await?
-        .try_get("balance")?;
+    // Potrącenie na spłatę pożyczki (jeśli jest dług)
+    let garnished = loans::garnish(
+        &mut tx,
+        eco,
+        user_id_u64,
+        reward,
+        new_balance,
+        &Entry { reason: "spłata pożyczki z daily", command: "daily", interaction_id: Some(interaction_id) },
+    )
+    .await?;
+    if let Some(g) = garnished {
+        new_balance = g.wallet;
+    }
 
-        tx.commit().await?;
-        ClaimOutcome::Claimed { balance_after: new_balance }
-    };
+    let ready_at = cooldowns::start(&mut tx, eco, guild, user_id_u64, cooldown_meta()).await?;
 
-    Ok(outcome)
+    tx.commit().await?;
+    Ok(ClaimOutcome::Claimed { reward, balance_after: new_balance, ready_at, garnished })
 }
 
 fn build_cooldown_embed(remaining_secs: i64) -> CreateEmbed {
@@ -265,10 +222,60 @@ async fn edit_embed(ctx: &Context, cmd: &CommandInteraction, embed: CreateEmbed)
     Ok(())
 }
 
-fn log_channel() -> Option<ChannelId> {
-    std::env::var("LOG_CHANNEL_ID")
-        .ok()
-        .and_then(|s| s.parse::<u64>().ok())
-        .filter(|&id| id != 0)
-        .map(ChannelId::new)
+// =======================
+// 🧭 Rejestr komend
+// =======================
+
+pub struct DailyCommand;
+
+#[async_trait]
+impl BotCommand for DailyCommand {
+    fn name(&self) -> &'static str {
+        "daily"
+    }
+
+    fn economy(&self) -> bool {
+        true
+    }
+
+    fn register(&self) -> CreateCommand {
+        let mut c = CreateCommand::new("daily");
+        register(&mut c);
+        c
+    }
+
+    async fn run(&self, ctx: &Context, cmd: &CommandInteraction, db: &PgPool) -> Result<()> {
+        run(ctx, cmd, db).await
+    }
+
+    fn replay(&self, kind: &str, seed: u64, inputs: &str) -> Option<Result<serde_json::Value>> {
+        (kind == "daily").then(|| rng::replay(seed, inputs, roll_reward))
+    }
+
+    fn cooldown(&self) -> Option<CooldownMeta> {
+        Some(cooldown_meta())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn roll_reward_is_reproducible_from_seed() {
+        let [min, max] = config::get().daily.reward;
+        for seed in 0..50 {
+            let reward = roll_reward(&(), &mut rng::from_seed(seed));
+            assert_eq!(reward, roll_reward(&(), &mut rng::from_seed(seed)));
+            assert!((min..=max).contains(&reward), "seed {seed}: {reward}");
+        }
+    }
+
+    #[test]
+    fn daily_replays_recorded_reward() {
+        let seed = 2024;
+        let recorded = serde_json::to_value(roll_reward(&(), &mut rng::from_seed(seed))).unwrap();
+        let replayed = DailyCommand.replay("daily", seed, "null").expect("daily zna swoje losowanie").unwrap();
+        assert_eq!(replayed, recorded);
+    }
 }
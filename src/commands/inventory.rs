@@ -0,0 +1,399 @@
+//! commands/inventory.rs — `/ekwipunek`: sklep, warsztat i odsprzedaż przedmiotów do `/crime`.
+//!
+//! Oferta odsprzedaży to wiersz `item_offers` i publiczna wiadomość z przyciskami
+//! `inv:trade:{id}:{akcja}` dla kupującego. Kliknięcie blokuje ofertę i zamyka ją w transakcji
+//! wymiany, więc drugi klik nie przekaże przedmiotu drugi raz. Ważność: `config::ShopTables::offer_mins`.
+//! Cena to przelew `/pay` od kupującego — z limitem dziennym, opłatą i zaporami wieku kont.
+
+use anyhow::Result;
+use serenity::all::{
+    ButtonStyle, CommandDataOption, CommandDataOptionValue, CommandInteraction, CommandOptionType,
+    ComponentInteraction, Context, CreateActionRow, CreateButton, CreateCommand, CreateCommandOption, CreateEmbed,
+    CreateInteractionResponse, CreateInteractionResponseMessage,
+};
+use serenity::async_trait;
+use sqlx::PgPool;
+
+use super::pay::{self, Sent};
+use crate::clock;
+use crate::commands::crime::{emoji_for_item, from_key_item, key_item, reply_ephemeral};
+use crate::commands::registry::BotCommand;
+use crate::economy::{self, Economy};
+use crate::engine::{config, items, types::ItemKey};
+use crate::inventory::{self, charges_label, Owned, Purchase, Repair, Trade};
+use crate::utils::log_action;
+
+// =======================
+// 🔧 Rejestracja
+// =======================
+
+fn item_option(description: &str) -> CreateCommandOption {
+    items::ITEM_META.iter().fold(
+        CreateCommandOption::new(CommandOptionType::String, "przedmiot", description).required(true),
+        |o, (k, meta)| o.add_string_choice(meta.name, key_item(*k)),
+    )
+}
+
+pub fn register(cmd: &mut CreateCommand) -> &mut CreateCommand {
+    *cmd = CreateCommand::new("ekwipunek")
+        .description("Przedmioty do /crime: kup, napraw, odsprzedaj 🎒")
+        .add_option(CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "pokaz",
+            "Twój ekwipunek i ceny w sklepie",
+        ))
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::SubCommand, "kup", "Kup przedmiot (trwały albo sztukę jednorazowego)")
+                .add_sub_option(item_option("Co kupić")),
+        )
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::SubCommand, "napraw", "Napraw trwały przedmiot do pełna")
+                .add_sub_option(item_option("Co naprawić")),
+        )
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::SubCommand, "sprzedaj", "Zaproponuj przedmiot innemu graczowi")
+                .add_sub_option(CreateCommandOption::new(CommandOptionType::User, "gracz", "Kupujący").required(true))
+                .add_sub_option(item_option("Co sprzedać"))
+                .add_sub_option(
+                    CreateCommandOption::new(CommandOptionType::Integer, "cena", "Cena w TK (0 = prezent)")
+                        .required(true)
+                        .min_int_value(0),
+                ),
+        );
+    cmd
+}
+
+// =======================
+// ⌨️ Slash
+// =======================
+
+pub async fn run(ctx: &Context, cmd: &CommandInteraction, db: &PgPool) -> Result<()> {
+    let eco = economy::of(db, cmd.guild_id).await?;
+    let user = cmd.user.id.get();
+
+    let Some(sub) = cmd.data.options.first() else {
+        return show(ctx, cmd, db, eco, None).await;
+    };
+    let opts: &[CommandDataOption] = match &sub.value {
+        CommandDataOptionValue::SubCommand(opts) => opts,
+        _ => &[],
+    };
+    let item = opts.iter().find_map(|o| match &o.value {
+        CommandDataOptionValue::String(s) if o.name == "przedmiot" => from_key_item(s),
+        _ => None,
+    });
+
+    match (sub.name.as_str(), item) {
+        ("kup", Some(item)) => {
+            let name = items::item_name(item);
+            let note = match inventory::buy(db, eco, user, item, Some(cmd.id.get())).await? {
+                Purchase::Locked { required_pp } => format!("🔒 **{name}** odblokujesz od **{required_pp} PP**."),
+                Purchase::AlreadyOwned { charges } => {
+                    format!("🎒 Masz już **{name}** ({charges} użyć) — zamiast kupować drugi, użyj `/ekwipunek napraw`.")
+                }
+                Purchase::StackFull { max } => format!("🎒 Więcej niż **{max}** sztuk **{name}** nie uniesiesz."),
+                Purchase::TooPoor { balance, price } => format!("💸 **{name}** kosztuje **{price} TK**, a masz **{balance} TK**."),
+                Purchase::Defaulted { owed } => {
+                    format!("⛔ Masz pożyczkę po terminie (**{owed} TK**) — sklep odblokuje `/pozyczka splac`.")
+                }
+                Purchase::Bought { charges, price, balance } => {
+                    let desc = format!("Zakup: {name} za {price} TK");
+                    let _ = log_action(db, user, "item_buy", None, Some(-price), Some(&desc)).await;
+                    format!("🛒 Kupiono **{name}** za **{price} TK** ({}). Saldo: **{balance} TK**.", charges_label(item, charges))
+                }
+            };
+            show(ctx, cmd, db, eco, Some(note)).await
+        }
+
+        ("napraw", Some(item)) => {
+            let name = items::item_name(item);
+            let note = match inventory::repair(db, eco, user, item, Some(cmd.id.get())).await? {
+                Repair::NotOwned => format!("🕳️ Nie masz **{name}**."),
+                Repair::SingleUse => format!("🧨 **{name}** jest jednorazowy — tego się nie naprawia."),
+                Repair::AlreadyFull => format!("✨ **{name}** jest w pełni sprawny."),
+                Repair::TooPoor { balance, cost } => format!("💸 Naprawa kosztuje **{cost} TK**, a masz **{balance} TK**."),
+                Repair::Repaired { charges, cost, balance } => {
+                    let desc = format!("Naprawa: {name} za {cost} TK");
+                    let _ = log_action(db, user, "item_repair", None, Some(-cost), Some(&desc)).await;
+                    format!("🔧 **{name}** naprawiony ({charges} użyć) za **{cost} TK**. Saldo: **{balance} TK**.")
+                }
+            };
+            show(ctx, cmd, db, eco, Some(note)).await
+        }
+
+        ("sprzedaj", Some(item)) => offer(ctx, cmd, db, eco, opts, item).await,
+
+        _ => show(ctx, cmd, db, eco, None).await,
+    }
+}
+
+async fn show(ctx: &Context, cmd: &CommandInteraction, db: &PgPool, eco: Economy, note: Option<String>) -> Result<()> {
+    let owned = inventory::list(db, eco, cmd.user.id.get()).await?;
+    let mut msg = CreateInteractionResponseMessage::new().ephemeral(true).embed(render(&owned));
+    if let Some(n) = note {
+        msg = msg.content(n);
+    }
+    cmd.create_response(&ctx.http, CreateInteractionResponse::Message(msg)).await?;
+    Ok(())
+}
+
+/// Publiczna oferta dla kupującego (przyciski przyjmij/odrzuć).
+async fn offer(
+    ctx: &Context,
+    cmd: &CommandInteraction,
+    db: &PgPool,
+    eco: Economy,
+    opts: &[CommandDataOption],
+    item: ItemKey,
+) -> Result<()> {
+    let seller = cmd.user.id.get();
+    let buyer = opts.iter().find_map(|o| match o.value {
+        CommandDataOptionValue::User(uid) if o.name == "gracz" => Some(uid),
+        _ => None,
+    });
+    let price = opts
+        .iter()
+        .find_map(|o| match o.value {
+            CommandDataOptionValue::Integer(n) if o.name == "cena" => Some(n),
+            _ => None,
+        })
+        .unwrap_or(0)
+        .max(0);
+
+    let Some(buyer) = buyer else {
+        return ephemeral(ctx, cmd, "❌ Wskaż kupującego.").await;
+    };
+    let Some(target) = cmd.data.resolved.users.get(&buyer).filter(|u| buyer.get() != seller && !u.bot) else {
+        return ephemeral(ctx, cmd, "❌ Tej osobie nie sprzedasz.").await;
+    };
+    // 🛂 Cena to przelew /pay od kupującego — te same zapory wieku kont co przy przelewie
+    if price > 0 {
+        let seller_joined = cmd.member.as_ref().and_then(|m| m.joined_at);
+        let buyer_joined = cmd.data.resolved.members.get(&buyer).and_then(|m| m.joined_at);
+        if let Some(msg) = pay::age_gate("Twoje konto", &cmd.user, seller_joined)
+            .or_else(|| pay::age_gate("Konto kupującego", target, buyer_joined))
+        {
+            return ephemeral(ctx, cmd, msg).await;
+        }
+    }
+    let Some(have) = inventory::list(db, eco, seller).await?.into_iter().find(|o| o.item == item) else {
+        return ephemeral(ctx, cmd, format!("🕳️ Nie masz **{}**.", items::item_name(item))).await;
+    };
+
+    let offer = inventory::create_offer(db, eco, seller, buyer.get(), item, price, cmd.id.get()).await?;
+    let what = if config::get().items.get(item).single_use {
+        charges_label(item, 1)
+    } else {
+        charges_label(item, have.charges)
+    };
+    let price_txt = if price == 0 {
+        "w prezencie".to_string()
+    } else {
+        format!("za **{price} TK** (przelew: opłata {} TK i dzienny limit /pay po stronie kupującego)", pay::fee_for(price))
+    };
+    let embed = CreateEmbed::new()
+        .title("🤝 Oferta przedmiotu")
+        .description(format!(
+            "<@{seller}> oferuje <@{}> {} **{}** ({what}) {price_txt}.\nOferta wygasa <t:{}:R>.",
+            buyer.get(),
+            emoji_for_item(item),
+            items::item_name(item),
+            offer.expires_at.timestamp(),
+        ))
+        .color(0x3b82f6);
+
+    let id = |action: &str| format!("inv:trade:{}:{action}", offer.id);
+    let row = CreateActionRow::Buttons(vec![
+        CreateButton::new(id("accept")).label("✅ Przyjmij").style(ButtonStyle::Success),
+        CreateButton::new(id("reject")).label("✖️ Odrzuć").style(ButtonStyle::Secondary),
+    ]);
+    cmd.create_response(
+        &ctx.http,
+        CreateInteractionResponse::Message(
+            CreateInteractionResponseMessage::new().content(format!("<@{}>", buyer.get())).embed(embed).components(vec![row]),
+        ),
+    )
+    .await?;
+    Ok(())
+}
+
+async fn ephemeral(ctx: &Context, cmd: &CommandInteraction, content: impl Into<String>) -> Result<()> {
+    cmd.create_response(
+        &ctx.http,
+        CreateInteractionResponse::Message(CreateInteractionResponseMessage::new().ephemeral(true).content(content)),
+    )
+    .await?;
+    Ok(())
+}
+
+// =======================
+// 🔘 Przyciski
+// =======================
+
+pub async fn handle_component(ctx: &Context, mci: &ComponentInteraction, db: &PgPool) -> Result<()> {
+    // inv:trade:{id}:{accept|reject}
+    let parts: Vec<&str> = mci.data.custom_id.split(':').collect();
+    let (Some(id), Some(action)) = (parts.get(2).and_then(|s| s.parse::<i64>().ok()), parts.get(3).copied()) else {
+        return Ok(());
+    };
+    let user = mci.user.id.get();
+    let eco = economy::of(db, mci.guild_id).await?;
+
+    let mut tx = db.begin().await?;
+    let Some(offer) = inventory::lock_offer(&mut tx, eco, id).await? else {
+        return reply_ephemeral(ctx, mci, "🕳️ Nie ma takiej oferty.").await;
+    };
+    let Some(item) = from_key_item(&offer.item) else {
+        return Ok(());
+    };
+    let (seller, buyer, price) = (offer.seller as u64, offer.buyer as u64, offer.price);
+    if offer.status != "pending" {
+        return reply_ephemeral(ctx, mci, "🔒 Ta oferta jest już zamknięta.").await;
+    }
+    let allowed = match action {
+        "accept" => user == buyer,
+        "reject" => user == buyer || user == seller,
+        _ => false,
+    };
+    if !allowed {
+        return reply_ephemeral(ctx, mci, "⛔ Ta oferta nie jest dla ciebie.").await;
+    }
+    let name = items::item_name(item);
+
+    let result = if clock::now() > offer.expires_at {
+        inventory::close_offer(&mut tx, id, "expired").await?;
+        tx.commit().await?;
+        "⌛ Oferta wygasła.".to_string()
+    } else if action == "reject" {
+        let (status, who) = if user == seller { ("cancelled", "wycofana") } else { ("rejected", "odrzucona") };
+        inventory::close_offer(&mut tx, id, status).await?;
+        tx.commit().await?;
+        format!("✖️ Oferta **{name}** {who}.")
+    } else {
+        match inventory::trade(&mut tx, eco, seller, buyer, item).await? {
+            Trade::NotOwned => {
+                inventory::close_offer(&mut tx, id, "cancelled").await?;
+                tx.commit().await?;
+                format!("🕳️ <@{seller}> nie ma już **{name}**.")
+            }
+            // oferta zostaje otwarta — można zrobić miejsce albo dołożyć TK i spróbować jeszcze raz
+            Trade::BuyerFull => {
+                return reply_ephemeral(ctx, mci, format!("🎒 Nie zmieścisz **{name}** — masz już ten przedmiot.")).await;
+            }
+            Trade::Done { charges } => {
+                // cena idzie zwykłym przelewem /pay (limit, opłata, blokada dłużnika) w tej samej
+                // transakcji; przycisk „Przyjmij” pod kwotą i opłatą jest potwierdzeniem przelewu
+                let mut fee = 0;
+                if price > 0 {
+                    let sent = pay::send_in(&mut tx, eco, buyer, seller, price, "odkupienie przedmiotu", mci.id.get()).await?;
+                    let Sent::Done { fee: f, .. } = sent else {
+                        return reply_ephemeral(ctx, mci, pay::refusal(&sent)).await;
+                    };
+                    fee = f;
+                }
+                inventory::close_offer(&mut tx, id, "accepted").await?;
+                tx.commit().await?;
+                let desc = format!("{name} ({}) od {seller} za {price} TK (opłata {fee} TK)", charges_label(item, charges));
+                let _ = log_action(db, buyer, "item_trade", Some(seller), Some(price), Some(&desc)).await;
+                format!(
+                    "🤝 <@{buyer}> odkupił **{name}** ({}) od <@{seller}> za **{price} TK**.",
+                    charges_label(item, charges)
+                )
+            }
+        }
+    };
+
+    let embed = CreateEmbed::new().title("🤝 Oferta przedmiotu").description(result).color(0x95a5a6);
+    mci.create_response(
+        &ctx.http,
+        CreateInteractionResponse::UpdateMessage(
+            CreateInteractionResponseMessage::new().content("").embed(embed).components(vec![]),
+        ),
+    )
+    .await?;
+    Ok(())
+}
+
+// =======================
+// 🧱 Widok
+// =======================
+
+fn render(owned: &[Owned]) -> CreateEmbed {
+    let cfg = config::get();
+
+    let bag = if owned.is_empty() {
+        "Pusto — kup coś poniżej (`/ekwipunek kup`).".to_string()
+    } else {
+        owned
+            .iter()
+            .map(|o| {
+                let t = cfg.items.get(o.item);
+                let repair = if !t.single_use && o.charges < t.durability {
+                    format!(" • naprawa {} TK", inventory::repair_cost(t, o.charges))
+                } else {
+                    String::new()
+                };
+                format!(
+                    "{} **{}** — {}{repair}",
+                    emoji_for_item(o.item),
+                    items::item_name(o.item),
+                    charges_label(o.item, o.charges)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+
+    let shop = items::ITEM_META
+        .iter()
+        .map(|(k, meta)| {
+            let t = cfg.items.get(*k);
+            let kind = if t.single_use { "jednorazowy".to_string() } else { format!("{} użyć", t.durability) };
+            format!("{} **{}** — {} TK • {kind} • od {} PP", emoji_for_item(*k), meta.name, t.price, t.required_pp)
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    CreateEmbed::new()
+        .title("🎒 Ekwipunek")
+        .description("Każdy napad zużywa jedno użycie każdego przedmiotu z zestawu.")
+        .field("Twoje przedmioty", bag, false)
+        .field("🛒 Sklep", shop, false)
+        .color(0x3b82f6)
+        .timestamp(clock::now())
+}
+
+// =======================
+// 🧭 Rejestr komend
+// =======================
+
+pub struct InventoryCommand;
+
+#[async_trait]
+impl BotCommand for InventoryCommand {
+    fn name(&self) -> &'static str {
+        "ekwipunek"
+    }
+
+    fn economy(&self) -> bool {
+        true
+    }
+
+    fn register(&self) -> CreateCommand {
+        let mut c = CreateCommand::new("ekwipunek");
+        register(&mut c);
+        c
+    }
+
+    async fn run(&self, ctx: &Context, cmd: &CommandInteraction, db: &PgPool) -> Result<()> {
+        run(ctx, cmd, db).await
+    }
+
+    fn component_prefixes(&self) -> &'static [&'static str] {
+        &["inv:"]
+    }
+
+    async fn handle_component(&self, ctx: &Context, ic: &ComponentInteraction, db: &PgPool) -> Result<()> {
+        handle_component(ctx, ic, db).await
+    }
+}
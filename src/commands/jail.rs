@@ -0,0 +1,221 @@
+use anyhow::Result;
+use serenity::all::*;
+use serenity::builder::{CreateCommand, CreateEmbed};
+use serenity::async_trait;
+use sqlx::PgPool;
+
+use crate::clock;
+use crate::commands::crime::snowflake_ms;
+use crate::commands::registry::BotCommand;
+use crate::economy::{self, Economy};
+use crate::engine::{config, minigames, types::{MinigameResult, QteSpec, Risk}};
+use crate::jail::{self, Bail, Stay};
+use crate::utils::log_action;
+
+/// Rejestracja komendy `/wiezienie`
+pub fn register(cmd: &mut CreateCommand) -> &mut CreateCommand {
+    *cmd = CreateCommand::new("wiezienie").description("Twój wyrok: ile zostało, kaucja albo próba ucieczki 🔒");
+    cmd
+}
+
+/// Obsługa komendy `/wiezienie`
+pub async fn run(ctx: &Context, cmd: &CommandInteraction, db: &PgPool) -> Result<()> {
+    let eco = economy::of(db, cmd.guild_id).await?;
+    let stay = jail::current(db, eco, cmd.user.id.get()).await?;
+    let (embed, rows) = render(stay.as_ref(), None);
+
+    cmd.create_response(
+        &ctx.http,
+        CreateInteractionResponse::Message(
+            CreateInteractionResponseMessage::new().ephemeral(true).embed(embed).components(rows),
+        ),
+    )
+    .await?;
+    Ok(())
+}
+
+// =======================
+// 🔘 Przyciski
+// =======================
+
+pub async fn handle_component(ctx: &Context, mci: &ComponentInteraction, db: &PgPool) -> Result<()> {
+    let eco = economy::of(db, mci.guild_id).await?;
+    let user = mci.user.id.get();
+
+    let note = match mci.data.custom_id.as_str() {
+        "jail:bail" => match jail::pay_bail(db, eco, user, Some(mci.id.get())).await? {
+            Bail::NotJailed => "🕊️ Już jesteś na wolności.".to_string(),
+            Bail::TooPoor { balance, bail } => format!("💸 Kaucja to **{bail} TK**, a masz **{balance} TK**."),
+            Bail::Paid { bail, balance } => {
+                let _ = log_action(db, user, "jail_bail", None, Some(-bail), Some("Kaucja — wyjście z więzienia")).await;
+                format!("🔓 Kaucja **{bail} TK** zapłacona. Saldo: **{balance} TK**.")
+            }
+        },
+
+        // start odliczania — bez przerysowania (jak QTE w /crime)
+        "jail:escape_arm" => {
+            let mut tx = db.begin().await?;
+            if let Some(Stay { escape_armed_ms: None, escape_tried: false, .. }) = jail::lock(&mut tx, eco, user).await? {
+                jail::arm_escape(&mut tx, eco, user, snowflake_ms(mci.id.get())).await?;
+                tx.commit().await?;
+            }
+            mci.create_response(&ctx.http, CreateInteractionResponse::Acknowledge).await?;
+            return Ok(());
+        }
+
+        "jail:escape_hit" => escape(db, eco, user, snowflake_ms(mci.id.get())).await?,
+
+        _ => return Ok(()),
+    };
+
+    let stay = jail::current(db, eco, user).await?;
+    let (embed, rows) = render(stay.as_ref(), Some(&note));
+    mci.create_response(
+        &ctx.http,
+        CreateInteractionResponse::UpdateMessage(CreateInteractionResponseMessage::new().embed(embed).components(rows)),
+    )
+    .await?;
+    Ok(())
+}
+
+/// Okno ucieczki — jak QTE na wysokim ryzyku, z tym samym stałym zapasem na sieć co /crime.
+fn escape_spec() -> QteSpec {
+    let mut spec = minigames::qte_spec_for(Risk::High, 0);
+    spec.window_ms += config::get().minigames.qte_network_grace_ms;
+    spec
+}
+
+/// Jedna próba na wyrok: idealnie = wolność, blisko = połowa wyroku, pudło = +połowa (min. 10 min).
+async fn escape(db: &PgPool, eco: Economy, user: u64, hit_ms: i64) -> Result<String> {
+    let mut tx = db.begin().await?;
+    let Some(stay) = jail::lock(&mut tx, eco, user).await? else {
+        return Ok("🕊️ Już jesteś na wolności.".into());
+    };
+    if stay.escape_tried {
+        return Ok("⛓️ Ucieczkę już próbowałeś — strażnicy mają cię na oku.".into());
+    }
+
+    let spec = escape_spec();
+    let (result, elapsed) = match stay.escape_armed_ms {
+        Some(armed) => {
+            let elapsed = hit_ms - armed;
+            (minigames::score_qte(elapsed.clamp(0, i32::MAX as i64) as i32, &spec), Some(elapsed))
+        }
+        None => (MinigameResult::Fail, None),
+    };
+
+    let left = (stay.release_at - clock::now()).num_seconds().max(0);
+    let shift = match result {
+        MinigameResult::Success => -left,
+        MinigameResult::Partial(_) => -left / 2,
+        _ => (left / 2).max(600),
+    };
+    jail::mark_escape_tried(&mut tx, eco, user).await?;
+    let release_at = jail::shift(&mut tx, eco, user, shift).await?;
+    tx.commit().await?;
+
+    let timing = elapsed.map(|ms| format!(" ({ms} ms)")).unwrap_or_default();
+    let note = match result {
+        MinigameResult::Success => format!("🏃 Ucieczka udana{timing}! Jesteś wolny."),
+        MinigameResult::Partial(_) => {
+            format!("🧱 Prawie{timing} — złapali cię przy murze, ale wyrok skrócony do <t:{}:R>.", release_at.timestamp())
+        }
+        _ if elapsed.is_none() => format!("🚨 Falstart — strażnik widział. Siedzisz do <t:{}:R>.", release_at.timestamp()),
+        _ => format!("🚨 Nieudana ucieczka{timing}. Wyrok przedłużony do <t:{}:R>.", release_at.timestamp()),
+    };
+    let desc = format!("Ucieczka: {result:?}{timing}, wyjście {release_at}");
+    let _ = log_action(db, user, "jail_escape", None, None, Some(&desc)).await;
+    Ok(note)
+}
+
+// =======================
+// 🧱 Widok
+// =======================
+
+fn render(stay: Option<&Stay>, note: Option<&str>) -> (CreateEmbed, Vec<CreateActionRow>) {
+    let Some(s) = stay else {
+        let e = CreateEmbed::new()
+            .title("🕊️ Na wolności")
+            .description(note.unwrap_or("Nie masz wyroku. Uważaj na HEAT przy /crime i /rob."))
+            .color(0x2ECC71);
+        return (e, vec![]);
+    };
+
+    let spec = escape_spec();
+    let release = s.release_at.timestamp();
+    let escape = if s.escape_tried {
+        "Wykorzystana.".to_string()
+    } else {
+        format!(
+            "Jedna próba: **▶️ Start**, odlicz **{:.1}s** i **🏃 Teraz!** (±{} ms).\n\
+             Idealnie = wolność, blisko = połowa wyroku, pudło = dłuższy wyrok.",
+            spec.target_ms as f32 / 1000.0,
+            spec.window_ms,
+        )
+    };
+
+    let mut e = CreateEmbed::new()
+        .title("🔒 Więzienie")
+        .description(format!(
+            "Za: **{}**\nOd <t:{}:f> • wychodzisz <t:{release}:T> (<t:{release}:R>).\n\
+             Do tego czasu `/work`, `/slut`, `/crime` i `/rob` są zablokowane.",
+            s.reason,
+            s.jailed_at.timestamp(),
+        ))
+        .field("💸 Kaucja", format!("**{} TK**", s.bail()), true)
+        .field("🏃 Ucieczka", escape, false)
+        .color(0xE67E22)
+        .timestamp(clock::now());
+    if let Some(n) = note {
+        e = e.field("Ostatnio", n, false);
+    }
+
+    let rows = vec![CreateActionRow::Buttons(vec![
+        CreateButton::new("jail:bail").label("💸 Zapłać kaucję").style(ButtonStyle::Primary),
+        CreateButton::new("jail:escape_arm")
+            .label("▶️ Start ucieczki")
+            .style(ButtonStyle::Secondary)
+            .disabled(s.escape_tried || s.escape_armed_ms.is_some()),
+        CreateButton::new("jail:escape_hit")
+            .label("🏃 Teraz!")
+            .style(ButtonStyle::Danger)
+            .disabled(s.escape_tried),
+    ])];
+
+    (e, rows)
+}
+
+// =======================
+// 🧭 Rejestr komend
+// =======================
+
+pub struct JailCommand;
+
+#[async_trait]
+impl BotCommand for JailCommand {
+    fn name(&self) -> &'static str {
+        "wiezienie"
+    }
+
+    fn economy(&self) -> bool {
+        true
+    }
+
+    fn register(&self) -> CreateCommand {
+        let mut c = CreateCommand::new("wiezienie");
+        register(&mut c);
+        c
+    }
+
+    async fn run(&self, ctx: &Context, cmd: &CommandInteraction, db: &PgPool) -> Result<()> {
+        run(ctx, cmd, db).await
+    }
+
+    fn component_prefixes(&self) -> &'static [&'static str] {
+        &["jail:"]
+    }
+
+    async fn handle_component(&self, ctx: &Context, ic: &ComponentInteraction, db: &PgPool) -> Result<()> {
+        handle_component(ctx, ic, db).await
+    }
+}
@@ -0,0 +1,424 @@
+//! commands/loan.rs — `/pozyczka`: pożyczki z banku i między graczami, spłata i stan długu.
+//!
+//! Oferta pożyczki od gracza to wiersz `loan_offers` i publiczna wiadomość z przyciskami
+//! `loan:offer:{id}:{akcja}` dla pożyczającego; ważność: `config::LoanTables::offer_mins`.
+//! Przyjęcie wypłaca kapitał przelewem `/pay` (limit dzienny, opłata) i zamyka ofertę w tej samej
+//! transakcji, więc drugi klik nie wypłaci drugiej pożyczki. Oferta wymaga kont starych jak przy `/pay`.
+
+use anyhow::Result;
+use serenity::all::{
+    ButtonStyle, CommandDataOption, CommandDataOptionValue, CommandInteraction, CommandOptionType,
+    ComponentInteraction, Context, CreateActionRow, CreateButton, CreateCommand, CreateCommandOption, CreateEmbed,
+    CreateInteractionResponse, CreateInteractionResponseMessage,
+};
+use serenity::async_trait;
+use sqlx::PgPool;
+
+use crate::clock;
+use super::pay::{self, Sent};
+use crate::commands::crime::reply_ephemeral;
+use crate::commands::registry::BotCommand;
+use crate::economy::{self, Economy};
+use crate::engine::config;
+use crate::loans::{self, Borrow, Garnished, Lend, Loan, Repay};
+use crate::utils::log_action;
+
+// =======================
+// 🔧 Rejestracja
+// =======================
+
+pub fn register(cmd: &mut CreateCommand) -> &mut CreateCommand {
+    *cmd = CreateCommand::new("pozyczka")
+        .description("Pożycz TK z banku albo od gracza 💳")
+        .add_option(CreateCommandOption::new(CommandOptionType::SubCommand, "stan", "Twój dług i udzielone pożyczki"))
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::SubCommand, "wez", "Weź pożyczkę z banku")
+                .add_sub_option(
+                    CreateCommandOption::new(CommandOptionType::Integer, "kwota", "Ile pożyczyć")
+                        .required(true)
+                        .min_int_value(1),
+                ),
+        )
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::SubCommand, "splac", "Spłać dług z portfela")
+                .add_sub_option(
+                    CreateCommandOption::new(CommandOptionType::Integer, "kwota", "Ile spłacić (puste = ile się da)")
+                        .min_int_value(1),
+                ),
+        )
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::SubCommand, "pozycz", "Zaproponuj pożyczkę innemu graczowi")
+                .add_sub_option(CreateCommandOption::new(CommandOptionType::User, "gracz", "Komu pożyczyć").required(true))
+                .add_sub_option(
+                    CreateCommandOption::new(CommandOptionType::Integer, "kwota", "Ile pożyczyć")
+                        .required(true)
+                        .min_int_value(1),
+                )
+                .add_sub_option(
+                    CreateCommandOption::new(CommandOptionType::Integer, "procent", "Odsetki w % (domyślnie 0)")
+                        .min_int_value(0),
+                )
+                .add_sub_option(
+                    CreateCommandOption::new(CommandOptionType::Integer, "dni", "Termin spłaty w dniach").min_int_value(1),
+                ),
+        );
+    cmd
+}
+
+// =======================
+// ⌨️ Slash
+// =======================
+
+fn int_opt(opts: &[CommandDataOption], name: &str) -> Option<i64> {
+    opts.iter().find_map(|o| match o.value {
+        CommandDataOptionValue::Integer(n) if o.name == name => Some(n),
+        _ => None,
+    })
+}
+
+pub async fn run(ctx: &Context, cmd: &CommandInteraction, db: &PgPool) -> Result<()> {
+    let eco = economy::of(db, cmd.guild_id).await?;
+    let user = cmd.user.id.get();
+    let iid = Some(cmd.id.get());
+
+    let Some(sub) = cmd.data.options.first() else {
+        return show(ctx, cmd, db, eco, None).await;
+    };
+    let opts: &[CommandDataOption] = match &sub.value {
+        CommandDataOptionValue::SubCommand(opts) => opts,
+        _ => &[],
+    };
+    let amount = int_opt(opts, "kwota");
+    if amount.is_some_and(|a| a <= 0) {
+        return show(ctx, cmd, db, eco, Some("❌ Kwota musi być większa niż 0.".into())).await;
+    }
+
+    let note = match (sub.name.as_str(), amount) {
+        ("wez", Some(amount)) => {
+            let l = &config::get().loans;
+            let interest = loans::interest_for(amount, l.bank_rate);
+            match loans::borrow(db, eco, user, amount, interest, l.term_days, iid).await? {
+                Borrow::OutOfRange { min, max } => format!("🏦 Bank pożycza od **{min}** do **{max} TK**."),
+                Borrow::AlreadyOwing { owed } => {
+                    format!("⛓️ Najpierw spłać obecny dług (**{owed} TK**) — `/pozyczka splac`.")
+                }
+                Borrow::Done { loan, wallet } => {
+                    let desc = format!("Pożyczka z banku: {amount} TK (+{} TK odsetek)", loan.interest);
+                    let _ = log_action(db, user, "loan_take", None, Some(amount), Some(&desc)).await;
+                    format!(
+                        "💳 Wypłacono **{amount} TK**. Do oddania **{} TK** do <t:{}:f>. Portfel: **{wallet} TK**.",
+                        loan.owed,
+                        loan.due_at.timestamp()
+                    )
+                }
+            }
+        }
+        ("splac", amount) => match loans::repay(db, eco, user, amount, iid).await? {
+            Repay::NoLoan => "✨ Nie masz żadnego długu.".to_string(),
+            Repay::Nothing => "👛 Portfel jest pusty — nie ma czym spłacać.".to_string(),
+            Repay::TooPoor { wallet } => format!("💸 W portfelu masz tylko **{wallet} TK**."),
+            Repay::Done { amount, loan, wallet } => {
+                let desc = format!("Spłata pożyczki #{}: {amount} TK", loan.id);
+                let _ = log_action(db, user, "loan_repay", loan.lender.map(|id| id as u64), Some(-amount), Some(&desc)).await;
+                if loan.owed == 0 {
+                    format!("🎉 Spłacono **{amount} TK** — dług zamknięty! Portfel: **{wallet} TK**.")
+                } else {
+                    format!("📤 Spłacono **{amount} TK**, zostało **{} TK**. Portfel: **{wallet} TK**.", loan.owed)
+                }
+            }
+        },
+        ("pozycz", Some(amount)) => return offer(ctx, cmd, db, eco, opts, amount).await,
+        _ => String::new(),
+    };
+
+    show(ctx, cmd, db, eco, (!note.is_empty()).then_some(note)).await
+}
+
+async fn show(ctx: &Context, cmd: &CommandInteraction, db: &PgPool, eco: Economy, note: Option<String>) -> Result<()> {
+    let user = cmd.user.id.get();
+    let own = loans::open(db, eco, user).await?;
+    let given = loans::lent(db, eco, user).await?;
+    let mut msg = CreateInteractionResponseMessage::new().ephemeral(true).embed(render(own.as_ref(), &given));
+    if let Some(n) = note {
+        msg = msg.content(n);
+    }
+    cmd.create_response(&ctx.http, CreateInteractionResponse::Message(msg)).await?;
+    Ok(())
+}
+
+/// Publiczna oferta dla pożyczającego (przyciski przyjmij/odrzuć).
+async fn offer(
+    ctx: &Context,
+    cmd: &CommandInteraction,
+    db: &PgPool,
+    eco: Economy,
+    opts: &[CommandDataOption],
+    amount: i64,
+) -> Result<()> {
+    let l = &config::get().loans;
+    let lender = cmd.user.id.get();
+    let borrower = opts.iter().find_map(|o| match o.value {
+        CommandDataOptionValue::User(uid) if o.name == "gracz" => Some(uid),
+        _ => None,
+    });
+    let pct = int_opt(opts, "procent").unwrap_or(0);
+    let days = int_opt(opts, "dni").unwrap_or(l.term_days);
+
+    let Some(borrower) = borrower else {
+        return ephemeral(ctx, cmd, "❌ Wskaż, komu pożyczasz.").await;
+    };
+    let Some(target) = cmd.data.resolved.users.get(&borrower).filter(|u| borrower.get() != lender && !u.bot) else {
+        return ephemeral(ctx, cmd, "❌ Tej osobie nie pożyczysz.").await;
+    };
+    // 🛂 Wypłata idzie przelewem /pay — te same zapory wieku kont co przy przelewie
+    let lender_joined = cmd.member.as_ref().and_then(|m| m.joined_at);
+    let borrower_joined = cmd.data.resolved.members.get(&borrower).and_then(|m| m.joined_at);
+    if let Some(msg) = pay::age_gate("Twoje konto", &cmd.user, lender_joined)
+        .or_else(|| pay::age_gate("Konto pożyczającego", target, borrower_joined))
+    {
+        return ephemeral(ctx, cmd, msg).await;
+    }
+    let max_pct = (l.max_rate * 100.0).floor() as i64;
+    if !(0..=max_pct).contains(&pct) {
+        return ephemeral(ctx, cmd, format!("❌ Odsetki od 0 do {max_pct}%.")).await;
+    }
+    if !(1..=l.max_term_days).contains(&days) {
+        return ephemeral(ctx, cmd, format!("❌ Termin od 1 do {} dni.", l.max_term_days)).await;
+    }
+    if let Some(owed) = loans::in_default(db, eco, lender).await? {
+        return ephemeral(ctx, cmd, format!("⛔ Sam zalegasz ze spłatą (**{owed} TK**) — najpierw `/pozyczka splac`.")).await;
+    }
+
+    let interest = loans::interest_for(amount, pct as f64 / 100.0);
+    let offer = loans::create_offer(db, eco, lender, borrower.get(), amount, interest, days, cmd.id.get()).await?;
+    let embed = CreateEmbed::new()
+        .title("💳 Oferta pożyczki")
+        .description(format!(
+            "<@{lender}> pożycza <@{}> **{amount} TK** na **{days} dni** przy {pct}% — do oddania **{} TK**.\n\
+             Wypłata to przelew: opłata {} TK i dzienny limit /pay obciążają pożyczkodawcę.\n\
+             Niespłacona w terminie blokuje /pay i zakupy. Oferta wygasa <t:{}:R>.",
+            borrower.get(),
+            amount + interest,
+            pay::fee_for(amount),
+            offer.expires_at.timestamp()
+        ))
+        .color(0x3b82f6);
+
+    let id = |action: &str| format!("loan:offer:{}:{action}", offer.id);
+    let row = CreateActionRow::Buttons(vec![
+        CreateButton::new(id("accept")).label("✅ Przyjmij").style(ButtonStyle::Success),
+        CreateButton::new(id("reject")).label("✖️ Odrzuć").style(ButtonStyle::Secondary),
+    ]);
+    cmd.create_response(
+        &ctx.http,
+        CreateInteractionResponse::Message(
+            CreateInteractionResponseMessage::new()
+                .content(format!("<@{}>", borrower.get()))
+                .embed(embed)
+                .components(vec![row]),
+        ),
+    )
+    .await?;
+    Ok(())
+}
+
+async fn ephemeral(ctx: &Context, cmd: &CommandInteraction, content: impl Into<String>) -> Result<()> {
+    cmd.create_response(
+        &ctx.http,
+        CreateInteractionResponse::Message(CreateInteractionResponseMessage::new().ephemeral(true).content(content)),
+    )
+    .await?;
+    Ok(())
+}
+
+// =======================
+// 🔘 Przyciski
+// =======================
+
+pub async fn handle_component(ctx: &Context, mci: &ComponentInteraction, db: &PgPool) -> Result<()> {
+    // loan:offer:{id}:{accept|reject}
+    let parts: Vec<&str> = mci.data.custom_id.split(':').collect();
+    let (Some(id), Some(action)) = (parts.get(2).and_then(|s| s.parse::<i64>().ok()), parts.get(3).copied()) else {
+        return Ok(());
+    };
+    let user = mci.user.id.get();
+    let eco = economy::of(db, mci.guild_id).await?;
+
+    let mut tx = db.begin().await?;
+    let Some(offer) = loans::lock_offer(&mut tx, eco, id).await? else {
+        return reply_ephemeral(ctx, mci, "🕳️ Nie ma takiej oferty.").await;
+    };
+    let (lender, borrower, amount) = (offer.lender as u64, offer.borrower as u64, offer.amount);
+    if offer.status != "pending" {
+        return reply_ephemeral(ctx, mci, "🔒 Ta oferta jest już zamknięta.").await;
+    }
+    let allowed = match action {
+        "accept" => user == borrower,
+        "reject" => user == borrower || user == lender,
+        _ => false,
+    };
+    if !allowed {
+        return reply_ephemeral(ctx, mci, "⛔ Ta oferta nie jest dla ciebie.").await;
+    }
+
+    let result = if clock::now() > offer.expires_at {
+        loans::close_offer(&mut tx, id, "expired", None).await?;
+        tx.commit().await?;
+        "⌛ Oferta wygasła.".to_string()
+    } else if action == "reject" {
+        let (status, who) = if user == lender { ("cancelled", "wycofana") } else { ("rejected", "odrzucona") };
+        loans::close_offer(&mut tx, id, status, None).await?;
+        tx.commit().await?;
+        format!("✖️ Oferta pożyczki {who}.")
+    } else {
+        let iid = mci.id.get();
+
+        // kapitał idzie zwykłym przelewem /pay (limit, opłata) w jednej transakcji z pożyczką;
+        // odmowa cofa transakcję, a oferta zostaje otwarta do wygaśnięcia
+        let sent = pay::send_in(&mut tx, eco, lender, borrower, amount, "wypłata pożyczki", iid).await?;
+        let refused = match sent {
+            Sent::Done { fee, .. } => Ok(fee),
+            Sent::Defaulted { .. } => Err(format!("⛔ <@{lender}> zalega ze spłatą własnej pożyczki.")),
+            Sent::TooPoor { balance, needed } => {
+                Err(format!("💸 <@{lender}> ma w portfelu tylko **{balance} TK** (potrzeba **{needed} TK** z opłatą)."))
+            }
+            Sent::OverLimit { left, limit } => Err(format!(
+                "⛔ Pożyczka przekracza dzienny limit przelewów <@{lender}> (**{limit} TK**, zostało **{left} TK**)."
+            )),
+        };
+        let fee = match refused {
+            Ok(fee) => fee,
+            Err(msg) => return reply_ephemeral(ctx, mci, msg).await,
+        };
+
+        let days = offer.days as i64;
+        let loan = match loans::lend_in(&mut tx, eco, lender, borrower, amount, offer.interest, days, Some(iid)).await? {
+            Lend::AlreadyOwing { owed } => {
+                return reply_ephemeral(ctx, mci, format!("⛓️ Najpierw spłać obecny dług (**{owed} TK**).")).await;
+            }
+            Lend::Done { loan } => loan,
+        };
+        loans::close_offer(&mut tx, id, "accepted", Some(loan.id)).await?;
+        tx.commit().await?;
+
+        let desc = format!("Pożyczka #{} od {lender}: {amount} TK (+{} TK odsetek, opłata {fee} TK)", loan.id, loan.interest);
+        let _ = log_action(db, borrower, "loan_take", Some(lender), Some(amount), Some(&desc)).await;
+        format!(
+            "🤝 <@{borrower}> pożyczył **{amount} TK** od <@{lender}>. Do oddania **{} TK** do <t:{}:f>.",
+            loan.owed,
+            loan.due_at.timestamp()
+        )
+    };
+
+    let embed = CreateEmbed::new().title("💳 Oferta pożyczki").description(result).color(0x95a5a6);
+    mci.create_response(
+        &ctx.http,
+        CreateInteractionResponse::UpdateMessage(
+            CreateInteractionResponseMessage::new().content("").embed(embed).components(vec![]),
+        ),
+    )
+    .await?;
+    Ok(())
+}
+
+// =======================
+// 🧱 Widok
+// =======================
+
+/// Linijka o potrąceniu z zarobku — do embedów /work, /slut i /daily.
+pub(crate) fn garnish_text(g: &Garnished) -> String {
+    if g.owed == 0 {
+        format!("−{} TK • dług spłacony 🎉", g.amount)
+    } else {
+        format!("−{} TK • zostało {} TK", g.amount, g.owed)
+    }
+}
+
+fn loan_line(loan: &Loan, who: String) -> String {
+    let state = if loan.defaulted() {
+        format!("⛓️ po terminie (kara +{} TK)", loan.penalty)
+    } else {
+        format!("termin <t:{}:R>", loan.due_at.timestamp())
+    };
+    format!(
+        "**#{}** {who} (<t:{}:d>) — zostało **{} TK** z {} TK ({} + {} odsetek) • {state}",
+        loan.id,
+        loan.created_at.timestamp(),
+        loan.owed,
+        loan.principal + loan.interest + loan.penalty,
+        loan.principal,
+        loan.interest
+    )
+}
+
+fn render(own: Option<&Loan>, given: &[Loan]) -> CreateEmbed {
+    let l = &config::get().loans;
+    let debt = match own {
+        Some(loan) => {
+            let who = loan.lender.map_or("🏦 bank".to_string(), |id| format!("od <@{id}>"));
+            let pct = if loan.defaulted() { l.default_garnish_pct } else { l.garnish_pct };
+            format!(
+                "{}\nPotrącenie z /work, /slut, /daily: **{:.0}%** zarobku.",
+                loan_line(loan, who),
+                pct * 100.0
+            )
+        }
+        None => "Brak — możesz wziąć pożyczkę (`/pozyczka wez`).".to_string(),
+    };
+    let lent = if given.is_empty() {
+        "—".to_string()
+    } else {
+        given.iter().map(|loan| loan_line(loan, format!("dla <@{}>", loan.borrower))).collect::<Vec<_>>().join("\n")
+    };
+
+    CreateEmbed::new()
+        .title("💳 Pożyczki")
+        .description(format!(
+            "Bank pożycza {}–{} TK na {} dni przy {:.0}% odsetek. Po terminie dług rośnie o {:.0}%, \
+             a /pay i zakupy są zablokowane do spłaty.",
+            l.bank_min,
+            l.bank_max,
+            l.term_days,
+            l.bank_rate * 100.0,
+            l.default_penalty * 100.0
+        ))
+        .field("⛓️ Twój dług", debt, false)
+        .field("🤝 Udzielone", lent, false)
+        .color(0xf59e0b)
+        .timestamp(clock::now())
+}
+
+// =======================
+// 🧭 Rejestr komend
+// =======================
+
+pub struct LoanCommand;
+
+#[async_trait]
+impl BotCommand for LoanCommand {
+    fn name(&self) -> &'static str {
+        "pozyczka"
+    }
+
+    fn economy(&self) -> bool {
+        true
+    }
+
+    fn register(&self) -> CreateCommand {
+        let mut c = CreateCommand::new("pozyczka");
+        register(&mut c);
+        c
+    }
+
+    async fn run(&self, ctx: &Context, cmd: &CommandInteraction, db: &PgPool) -> Result<()> {
+        run(ctx, cmd, db).await
+    }
+
+    fn component_prefixes(&self) -> &'static [&'static str] {
+        &["loan:"]
+    }
+
+    async fn handle_component(&self, ctx: &Context, ic: &ComponentInteraction, db: &PgPool) -> Result<()> {
+        handle_component(ctx, ic, db).await
+    }
+}
@@ -1,10 +1,60 @@
+pub mod registry;
+
 pub mod work;
 pub mod crime;
+pub mod crew;
+pub mod crime_stats;
+pub mod skill_tree;
 pub mod slut;
 pub mod daily;
 pub mod rob;
 pub mod balance;
-pub  mod pay;
+pub mod bank;
+pub mod loan;
+pub mod pay;
+pub mod pay_request;
 pub mod admcontrol;
 pub mod shop_ui;
-pub mod subscribers;
\ No newline at end of file
+pub mod subscribers;
+pub mod reminders;
+pub mod jail;
+pub mod inventory;
+
+use std::sync::Arc;
+
+use anyhow::Result;
+use once_cell::sync::OnceCell;
+
+use registry::{BotCommand, CommandRegistry};
+
+static REGISTRY: OnceCell<CommandRegistry> = OnceCell::new();
+
+/// Wszystkie komendy bota — nowa komenda = nowy moduł + jedna linijka tutaj.
+fn all() -> Vec<Arc<dyn BotCommand>> {
+    vec![
+        Arc::new(slut::SlutCommand),
+        Arc::new(work::WorkCommand),
+        Arc::new(crime::CrimeCommand),
+        Arc::new(daily::DailyCommand),
+        Arc::new(rob::RobCommand),
+        Arc::new(balance::BalanceCommand),
+        Arc::new(bank::BankCommand),
+        Arc::new(loan::LoanCommand),
+        Arc::new(pay::PayCommand),
+        Arc::new(admcontrol::AdmControlCommand),
+        Arc::new(shop_ui::ShopCommand),
+        Arc::new(subscribers::SubscribersCommand),
+        Arc::new(reminders::RemindersCommand),
+        Arc::new(jail::JailCommand),
+        Arc::new(inventory::InventoryCommand),
+    ]
+}
+
+/// Buduje i waliduje rejestr (wołane raz przy starcie — błąd = bot nie wstaje).
+pub fn init_registry() -> Result<&'static CommandRegistry> {
+    REGISTRY.get_or_try_init(|| CommandRegistry::new(all()))
+}
+
+pub fn registry() -> &'static CommandRegistry {
+    REGISTRY.get().expect("rejestr komend nie został zainicjalizowany (init_registry)")
+}
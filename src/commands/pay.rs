@@ -1,34 +1,73 @@
 use anyhow::Result;
-use chrono::Utc;
+use chrono::{NaiveTime, Utc};
 use serenity::all::CommandDataOptionValue;
 use serenity::all::*;
 use serenity::builder::CreateCommand;
-use sqlx::{PgPool, Row};
+use serenity::async_trait;
+use sqlx::{PgConnection, PgPool};
+use crate::clock;
+use crate::commands::crime::{reply_ephemeral, snowflake_ms};
+use crate::commands::pay_request;
+use crate::economy::{self, Economy};
+use crate::engine::config;
+use crate::ledger::{self, Account, Entry, Overdraft};
+use crate::loans;
 use crate::utils::log_action;
+use crate::commands::registry::BotCommand;
 
 pub fn register(cmd: &mut CreateCommand) -> &mut CreateCommand {
     *cmd = CreateCommand::new("pay")
-        .description("Przelej TK innemu graczowi 💸")
+        .description("Przelej TK innemu graczowi albo poproś o zapłatę 💸")
         .add_option(
-            CreateCommandOption::new(CommandOptionType::User, "cel", "Odbiorca")
-                .required(true),
+            CreateCommandOption::new(CommandOptionType::SubCommand, "wyslij", "Przelej TK innemu graczowi")
+                .add_sub_option(
+                    CreateCommandOption::new(CommandOptionType::User, "cel", "Odbiorca")
+                        .required(true),
+                )
+                .add_sub_option(
+                    CreateCommandOption::new(
+                        CommandOptionType::Integer,
+                        "kwota",
+                        "Ile TK chcesz przelać?",
+                    )
+                    .required(true),
+                ),
         )
         .add_option(
-            CreateCommandOption::new(
-                CommandOptionType::Integer,
-                "kwota",
-                "Ile TK chcesz przelać?",
-            )
-            .required(true),
+            CreateCommandOption::new(CommandOptionType::SubCommand, "zadaj", "Poproś gracza o zapłatę (np. za usługę)")
+                .add_sub_option(
+                    CreateCommandOption::new(CommandOptionType::User, "cel", "Kto ma zapłacić")
+                        .required(true),
+                )
+                .add_sub_option(
+                    CreateCommandOption::new(CommandOptionType::Integer, "kwota", "Ile TK")
+                        .required(true)
+                        .min_int_value(1),
+                )
+                .add_sub_option(
+                    CreateCommandOption::new(CommandOptionType::String, "opis", "Za co (widzi płacący)")
+                        .max_length(200),
+                ),
         );
     cmd
 }
 
 pub async fn run(ctx: &Context, cmd: &CommandInteraction, db: &PgPool) -> Result<()> {
+    let Some(sub) = cmd.data.options.first() else {
+        return respond_error(ctx, cmd, "❌ Nieprawidłowe argumenty.").await;
+    };
+    let opts: &[CommandDataOption] = match &sub.value {
+        CommandDataOptionValue::SubCommand(opts) => opts,
+        _ => &[],
+    };
+    if sub.name == "zadaj" {
+        return pay_request::run(ctx, cmd, db, opts).await;
+    }
+
     let sender = &cmd.user;
     let sender_id = sender.id.get();
 
-    let (target_user, amount) = match parse_args(cmd) {
+    let (target_user, amount) = match parse_args(cmd, opts) {
         Some(v) => v,
         None => return respond_error(ctx, cmd, "❌ Nieprawidłowe argumenty.").await,
     };
@@ -36,77 +75,369 @@ pub async fn run(ctx: &Context, cmd: &CommandInteraction, db: &PgPool) -> Result
     if target_user.id.get() == sender_id {
         return respond_error(ctx, cmd, "❌ Nie możesz przelać TK samemu sobie!").await;
     }
+    if target_user.bot {
+        return respond_error(ctx, cmd, "❌ Botom nie przelewa się TK.").await;
+    }
     if amount <= 0 {
         return respond_error(ctx, cmd, "❌ Kwota musi być większa niż 0!").await;
     }
 
-    // 🔁 Transakcja atomowa
-    let mut tx = db.begin().await?;
+    // 🛂 Wiek kont i staż na serwerze — zapora na multikonta przelewające TK na główne
+    let target_joined = cmd.data.resolved.members.get(&target_user.id).and_then(|m| m.joined_at);
+    let sender_joined = cmd.member.as_ref().and_then(|m| m.joined_at);
+    if let Some(msg) = age_gate("Twoje konto", sender, sender_joined).or_else(|| age_gate("Konto odbiorcy", &target_user, target_joined)) {
+        return respond_error(ctx, cmd, &msg).await;
+    }
+
+    let eco = economy::of(db, cmd.guild_id).await?;
+
+    // ✋ Duże kwoty — najpierw potwierdzenie przyciskiem
+    let cfg = &config::get().pay;
+    if cfg.confirm_above > 0 && amount > cfg.confirm_above {
+        return ask_confirm(ctx, cmd, db, eco, &target_user, amount).await;
+    }
+
+    match send(db, eco, sender_id, target_user.id.get(), amount, cmd.id.get()).await? {
+        Sent::Done { fee, balance } => {
+            let embed = finish(ctx, db, sender, target_user.id, amount, fee, balance).await;
+            respond_embed(ctx, cmd, embed).await
+        }
+        refused => respond_error(ctx, cmd, &refusal(&refused)).await,
+    }
+}
 
-    // Upewnij się, że istnieją rekordy dla obu użytkowników
-    sqlx::query(
-        "INSERT INTO users (id, balance) VALUES ($1,0), ($2,0) ON CONFLICT (id) DO NOTHING",
+/// Komunikat, jeśli konto jest za młode albo za krótko na serwerze (`None` = w porządku).
+pub(super) fn age_gate(who: &str, user: &User, joined: Option<Timestamp>) -> Option<String> {
+    let cfg = &config::get().pay;
+    let now = clock::now().timestamp();
+    let days = |ts: Timestamp| (now - ts.unix_timestamp()) / 86_400;
+
+    if days(user.id.created_at()) < cfg.min_account_age_days {
+        return Some(format!("⛔ {who} ma mniej niż {} dni — przelewy są zablokowane.", cfg.min_account_age_days));
+    }
+    // brak daty dołączenia (np. przelew poza serwerem) traktujemy jak świeżego członka
+    if cfg.min_member_age_days > 0 && joined.is_none_or(|ts| days(ts) < cfg.min_member_age_days) {
+        return Some(format!(
+            "⛔ {who} jest na serwerze krócej niż {} dni — przelewy są zablokowane.",
+            cfg.min_member_age_days
+        ));
+    }
+    None
+}
+
+/// Opłata od przelewu (w górę) — płaci nadawca ponad kwotę.
+pub(super) fn fee_for(amount: i64) -> i64 {
+    (amount as f64 * config::get().pay.fee_rate).ceil() as i64
+}
+
+// =======================
+// 💾 Przelew (transakcja)
+// =======================
+
+pub(super) enum Sent {
+    Done { fee: i64, balance: i64 },
+    TooPoor { balance: i64, needed: i64 },
+    /// Dzienny limit: zostało `left` z `limit`.
+    OverLimit { left: i64, limit: i64 },
+    /// Nadawca ma pożyczkę po terminie.
+    Defaulted { owed: i64 },
+}
+
+/// Ile gracz przelał dziś (UTC) innym graczom.
+async fn sent_today(conn: &mut PgConnection, eco: Economy, sender: u64) -> Result<i64> {
+    let since = clock::now().date_naive().and_time(NaiveTime::MIN).and_utc();
+    let sum: i64 = sqlx::query_scalar(
+        r#"SELECT COALESCE(SUM(amount), 0)::BIGINT FROM ledger
+            WHERE guild_id = $1 AND source = $2 AND command = 'pay'
+              AND sink LIKE 'user:%' AND created_at >= $3"#,
     )
-    .bind(sender_id as i64)
-    .bind(target_user.id.get() as i64)
-    .execute(&mut *tx)
+    .bind(eco.id())
+    .bind(Account::User(sender).to_string())
+    .bind(since)
+    .fetch_one(&mut *conn)
     .await?;
+    Ok(sum)
+}
 
-    // Zablokuj saldo nadawcy
-    let sender_balance: i64 = sqlx::query("SELECT balance FROM users WHERE id = $1 FOR UPDATE")
-        .bind(sender_id as i64)
-        .fetch_one(&mut *tx)
-        .await?
-        .try_get("balance")?;
-
-    if sender_balance < amount {
-        tx.rollback().await?;
-        return respond_error(ctx, cmd, "❌ Nie masz wystarczającej ilości TK.").await;
+/// Przelew z opłatą do skarbca — wszystko albo nic.
+async fn send(db: &PgPool, eco: Economy, sender: u64, target: u64, amount: i64, interaction_id: u64) -> Result<Sent> {
+    let mut tx = db.begin().await?;
+    let sent = send_in(&mut tx, eco, sender, target, amount, "przelew", interaction_id).await?;
+    if matches!(sent, Sent::Done { .. }) {
+        tx.commit().await?;
     }
+    Ok(sent)
+}
 
-    // Odejmij nadawcy
-    sqlx::query("UPDATE users SET balance = balance - $1 WHERE id = $2")
-        .bind(amount)
-        .bind(sender_id as i64)
+/// Przelew w transakcji wywołującego (commit tylko przy `Sent::Done`).
+pub(super) async fn send_in(
+    tx: &mut PgConnection,
+    eco: Economy,
+    sender: u64,
+    target: u64,
+    amount: i64,
+    reason: &str,
+    interaction_id: u64,
+) -> Result<Sent> {
+    let cfg = &config::get().pay;
+
+    // Blokada nadawcy — równoległe przelewy liczą limit po kolei
+    ledger::ensure_user(tx, eco, sender).await?;
+    sqlx::query("SELECT 1 FROM users WHERE guild_id = $1 AND id = $2 FOR UPDATE")
+        .bind(eco.id())
+        .bind(sender as i64)
         .execute(&mut *tx)
         .await?;
 
-    // Dodaj odbiorcy
-    sqlx::query(
-        "UPDATE users SET balance = balance + $1 WHERE id = $2",
+    // ⛓️ Dłużnik po terminie nie przelewa, dopóki nie spłaci
+    if let Some(owed) = loans::in_default(&mut *tx, eco, sender).await? {
+        return Ok(Sent::Defaulted { owed });
+    }
+    if cfg.daily_limit > 0 {
+        let sent = sent_today(tx, eco, sender).await?;
+        if sent + amount > cfg.daily_limit {
+            return Ok(Sent::OverLimit { left: (cfg.daily_limit - sent).max(0), limit: cfg.daily_limit });
+        }
+    }
+
+    let fee = fee_for(amount);
+    let needed = amount + fee;
+    let iid = Some(interaction_id);
+
+    // Przelew przez księgę — saldo nadawcy blokowane i sprawdzane w jednym UPDATE
+    let moved = ledger::transfer(
+        tx,
+        eco,
+        Account::User(sender),
+        Account::User(target),
+        amount,
+        &Entry { reason, command: "pay", interaction_id: iid },
+        Overdraft::Forbid,
     )
-    .bind(amount)
-    .bind(target_user.id.get() as i64)
-    .execute(&mut *tx)
-    .await?;
+    .await;
+    let mut balance = match moved {
+        Ok(posted) => posted.source_balance.unwrap_or_default(),
+        Err(e) => {
+            return match ledger::insufficient(&e) {
+                Some(balance) => Ok(Sent::TooPoor { balance, needed }),
+                None => Err(e),
+            }
+        }
+    };
 
-    tx.commit().await?;
+    if fee > 0 {
+        let charged = ledger::transfer(
+            tx,
+            eco,
+            Account::User(sender),
+            Account::Treasury,
+            fee,
+            &Entry { reason: "opłata za przelew", command: "pay", interaction_id: iid },
+            Overdraft::Forbid,
+        )
+        .await;
+        balance = match charged {
+            Ok(posted) => posted.source_balance.unwrap_or_default(),
+            Err(e) => {
+                return match ledger::insufficient(&e) {
+                    // saldo sprzed przelewu (transakcja i tak się wycofa)
+                    Some(balance) => Ok(Sent::TooPoor { balance: balance + amount, needed }),
+                    None => Err(e),
+                }
+            }
+        };
+    }
 
-    // 🧾 Log do DB (fire-and-forget OK, ale tu czekamy na wynik)
-    log_action(
-        db,
-        sender_id,
-        "pay",
-        Some(target_user.id.get()),
-        Some(amount),
-        Some(&format!("Przelał {} TK do {}", amount, target_user.tag())),
-    ).await?;
+    Ok(Sent::Done { fee, balance })
+}
 
-    // 📢 Log na kanał (jeśli ustawiony)
-    let _ = send_log_to_channel(ctx, sender, target_user.clone(), amount).await;
+pub(super) fn refusal(sent: &Sent) -> String {
+    match sent {
+        Sent::TooPoor { balance, needed } => {
+            format!("❌ Nie masz wystarczającej ilości TK — potrzeba **{needed} TK** (z opłatą), masz **{balance} TK**.")
+        }
+        Sent::OverLimit { left: 0, limit } => format!("⛔ Wykorzystałeś dzienny limit przelewów (**{limit} TK**)."),
+        Sent::OverLimit { left, limit } => {
+            format!("⛔ Dzienny limit przelewów to **{limit} TK** — dziś możesz wysłać jeszcze **{left} TK**.")
+        }
+        Sent::Defaulted { owed } => {
+            format!("⛔ Masz pożyczkę po terminie (**{owed} TK**) — przelewy odblokuje `/pozyczka splac`.")
+        }
+        Sent::Done { .. } => String::new(),
+    }
+}
+
+/// Po udanym przelewie (już po commicie): log do DB i na kanał, embed dla nadawcy.
+pub(super) async fn finish(
+    ctx: &Context,
+    db: &PgPool,
+    sender: &User,
+    target: UserId,
+    amount: i64,
+    fee: i64,
+    balance: i64,
+) -> CreateEmbed {
+    log_sent(ctx, db, sender, target, amount, fee).await;
 
     // 📤 Potwierdzenie dla nadawcy
-    let embed = build_sender_embed(sender, &target_user, amount);
-    respond_embed(ctx, cmd, embed).await?;
+    build_sender_embed(target, amount, fee, balance)
+}
+
+/// Log udanego przelewu: do DB i na kanał. Pieniądze już poszły, więc błąd logu
+/// nie może zepsuć odpowiedzi na interakcję — tylko go wypisujemy.
+pub(super) async fn log_sent(ctx: &Context, db: &PgPool, sender: &User, target: UserId, amount: i64, fee: i64) {
+    // 🧾 Log do DB
+    let desc = format!("Przelał {} TK do {} (opłata {} TK)", amount, target.get(), fee);
+    if let Err(e) = log_action(db, sender.id.get(), "pay", Some(target.get()), Some(amount), Some(&desc)).await {
+        eprintln!("❌ /pay: nie zapisano logu przelewu: {e:?}");
+    }
 
+    // 📢 Log na kanał (jeśli ustawiony)
+    let _ = send_log_to_channel(ctx, sender, target, amount, fee).await;
+}
+
+// =======================
+// ✋ Potwierdzenie dużych przelewów
+// =======================
+
+/// Przelew czekający na przycisk — wiersz `pay_confirmations` (klucz: id interakcji `/pay`).
+#[derive(sqlx::FromRow)]
+struct Pending {
+    sender: i64,
+    target: i64,
+    amount: i64,
+    status: String,
+}
+
+async fn ask_confirm(
+    ctx: &Context,
+    cmd: &CommandInteraction,
+    db: &PgPool,
+    eco: Economy,
+    target: &User,
+    amount: i64,
+) -> Result<()> {
+    let cfg = &config::get().pay;
+    let sender = cmd.user.id.get();
+    let id = cmd.id.get();
+    sqlx::query("INSERT INTO pay_confirmations (interaction_id, guild_id, sender, target, amount) VALUES ($1, $2, $3, $4, $5)")
+        .bind(id as i64)
+        .bind(eco.id())
+        .bind(sender as i64)
+        .bind(target.id.get() as i64)
+        .bind(amount)
+        .execute(db)
+        .await?;
+
+    let fee = fee_for(amount);
+    let embed = CreateEmbed::new()
+        .title("✋ Potwierdź przelew")
+        .description(format!(
+            "Przelać **{amount} TK** do {}?\nOpłata: **{fee} TK** • razem z portfela: **{} TK**.\nPotwierdzenie ważne {} s.",
+            target.mention(),
+            amount + fee,
+            cfg.confirm_secs
+        ))
+        .color(0xF59E0B);
+    let row = CreateActionRow::Buttons(vec![
+        CreateButton::new(format!("pay:confirm:{id}")).label("✅ Wyślij").style(ButtonStyle::Success),
+        CreateButton::new(format!("pay:cancel:{id}")).label("✖️ Anuluj").style(ButtonStyle::Secondary),
+    ]);
+    cmd.create_response(
+        &ctx.http,
+        CreateInteractionResponse::Message(
+            CreateInteractionResponseMessage::new().ephemeral(true).embed(embed).components(vec![row]),
+        ),
+    ).await?;
     Ok(())
 }
 
-fn parse_args(cmd: &CommandInteraction) -> Option<(User, i64)> {
+async fn close_pending(conn: &mut PgConnection, eco: Economy, id: u64, status: &str) -> Result<()> {
+    sqlx::query("UPDATE pay_confirmations SET status = $3, resolved_at = $4 WHERE guild_id = $1 AND interaction_id = $2")
+        .bind(eco.id())
+        .bind(id as i64)
+        .bind(status)
+        .bind(clock::now())
+        .execute(&mut *conn)
+        .await?;
+    Ok(())
+}
+
+pub async fn handle_component(ctx: &Context, mci: &ComponentInteraction, db: &PgPool) -> Result<()> {
+    if mci.data.custom_id.starts_with("pay:req:") {
+        return pay_request::handle_component(ctx, mci, db).await;
+    }
+    // pay:{confirm|cancel}:{id interakcji /pay}
+    let parts: Vec<&str> = mci.data.custom_id.split(':').collect();
+    let (Some(action), Some(id)) = (parts.get(1).copied(), parts.get(2).and_then(|s| s.parse::<u64>().ok())) else {
+        return Ok(());
+    };
+
+    let update = |content: String, embed: Option<CreateEmbed>| {
+        let mut msg = CreateInteractionResponseMessage::new().content(content).components(vec![]);
+        msg = match embed {
+            Some(e) => msg.embed(e),
+            None => msg.embeds(vec![]),
+        };
+        CreateInteractionResponse::UpdateMessage(msg)
+    };
+
+    // 🔒 Blokada wiersza — drugi klik czeka na pierwszy i widzi już zmieniony status
+    let eco = economy::of(db, mci.guild_id).await?;
+    let mut tx = db.begin().await?;
+    let pending: Option<Pending> = sqlx::query_as(
+        "SELECT sender, target, amount, status FROM pay_confirmations WHERE guild_id = $1 AND interaction_id = $2 FOR UPDATE",
+    )
+    .bind(eco.id())
+    .bind(id as i64)
+    .fetch_optional(&mut *tx)
+    .await?;
+    let Some(p) = pending else {
+        return reply_ephemeral(ctx, mci, "🕳️ Nie ma takiego przelewu.").await;
+    };
+    let (sender, target, amount) = (p.sender as u64, p.target as u64, p.amount);
+    if mci.user.id.get() != sender {
+        return reply_ephemeral(ctx, mci, "⛔ To nie twój przelew.").await;
+    }
+    match p.status.as_str() {
+        "pending" => {}
+        "sent" => return reply_ephemeral(ctx, mci, "✅ Ten przelew został już wysłany.").await,
+        _ => return reply_ephemeral(ctx, mci, "🔒 Ten przelew jest już zamknięty.").await,
+    }
+
+    if action == "cancel" {
+        close_pending(&mut tx, eco, id, "cancelled").await?;
+        tx.commit().await?;
+        mci.create_response(&ctx.http, update("✖️ Przelew anulowany.".into(), None)).await?;
+        return Ok(());
+    }
+
+    let age_ms = snowflake_ms(mci.id.get()) - snowflake_ms(mci.message.id.get());
+    if age_ms > config::get().pay.confirm_secs * 1000 {
+        close_pending(&mut tx, eco, id, "expired").await?;
+        tx.commit().await?;
+        mci.create_response(&ctx.http, update("⌛ Potwierdzenie wygasło — użyj ponownie `/pay`.".into(), None)).await?;
+        return Ok(());
+    }
+
+    let sent = send_in(&mut tx, eco, sender, target, amount, "przelew", mci.id.get()).await?;
+    let Sent::Done { fee, balance } = sent else {
+        // odmowa — transakcja się wycofuje, a przyciski zostają: potwierdzenie jest otwarte do wygaśnięcia
+        return reply_ephemeral(ctx, mci, refusal(&sent)).await;
+    };
+    close_pending(&mut tx, eco, id, "sent").await?;
+    tx.commit().await?;
+
+    let embed = finish(ctx, db, &mci.user, UserId::new(target), amount, fee, balance).await;
+    mci.create_response(&ctx.http, update(String::new(), Some(embed))).await?;
+    Ok(())
+}
+
+pub(super) fn parse_args(cmd: &CommandInteraction, opts: &[CommandDataOption]) -> Option<(User, i64)> {
     let mut target_user: Option<User> = None;
     let mut amount: Option<i64> = None;
 
-    for opt in &cmd.data.options {
+    for opt in opts {
         match (&*opt.name, &opt.value) {
             ("cel", CommandDataOptionValue::User(uid)) => {
                 target_user = cmd.data.resolved.users.get(uid).cloned();
@@ -121,18 +452,19 @@ fn parse_args(cmd: &CommandInteraction) -> Option<(User, i64)> {
     Some((target_user?, amount?))
 }
 
-fn build_sender_embed(_sender: &User, target: &User, amount: i64) -> CreateEmbed {
+fn build_sender_embed(target: UserId, amount: i64, fee: i64, balance: i64) -> CreateEmbed {
     CreateEmbed::new()
         .title("📤 Przelew wysłany!")
         .description(format!("💸 Przesłałeś środki do {}!", target.mention()))
         .field("Kwota", format!("**{} TK**", amount), true)
-        .field("Do", target.tag(), true)
-        .footer(CreateEmbedFooter::new("Dziękujemy za korzystanie z Tigrus Bank™ 💼"))
+        .field("Do", target.mention().to_string(), true)
+        .field("Opłata", format!("{} TK", fee), true)
+        .footer(CreateEmbedFooter::new(format!("Saldo: {} TK • Dziękujemy za korzystanie z Tigrus Bank™ 💼", balance)))
         .color(0x00AAFF)
         .timestamp(chrono::Utc::now())
 }
 
-async fn respond_error(ctx: &Context, cmd: &CommandInteraction, message: &str) -> Result<()> {
+pub(super) async fn respond_error(ctx: &Context, cmd: &CommandInteraction, message: &str) -> Result<()> {
     cmd.create_response(
         &ctx.http,
         CreateInteractionResponse::Message(
@@ -156,7 +488,7 @@ async fn respond_embed(ctx: &Context, cmd: &CommandInteraction, embed: CreateEmb
     Ok(())
 }
 
-async fn send_log_to_channel(ctx: &Context, sender: &User, target: User, amount: i64) -> Result<()> {
+async fn send_log_to_channel(ctx: &Context, sender: &User, target: UserId, amount: i64, fee: i64) -> Result<()> {
     let log_channel_id = std::env::var("LOG_CHANNEL_ID")
         .ok()
         .and_then(|s| s.parse::<u64>().ok())
@@ -164,6 +496,7 @@ async fn send_log_to_channel(ctx: &Context, sender: &User, target: User, amount:
 
     if let Some(id) = log_channel_id {
         let channel = ChannelId::new(id);
+        let target = target.to_user(&ctx.http).await?;
 
         let embed = CreateEmbed::new()
     .title("📒 Log przelewu (/pay)")
@@ -179,7 +512,8 @@ async fn send_log_to_channel(ctx: &Context, sender: &User, target: User, amount:
         format!("{} (`{}`)\n{}", target.tag(), target.id.get(), target.mention()),
         true,
     )
-    .field("💰 Kwota", format!("**{} TK**", amount), false)
+    .field("💰 Kwota", format!("**{} TK**", amount), true)
+    .field("🏛️ Opłata", format!("{} TK", fee), true)
     .footer(CreateEmbedFooter::new("Zalogowano przez Tigrus Bank™"))
     .timestamp(Utc::now());
 
@@ -190,3 +524,38 @@ channel
 
     Ok(())
 }
+
+// =======================
+// 🧭 Rejestr komend
+// =======================
+
+pub struct PayCommand;
+
+#[async_trait]
+impl BotCommand for PayCommand {
+    fn name(&self) -> &'static str {
+        "pay"
+    }
+
+    fn economy(&self) -> bool {
+        true
+    }
+
+    fn register(&self) -> CreateCommand {
+        let mut c = CreateCommand::new("pay");
+        register(&mut c);
+        c
+    }
+
+    async fn run(&self, ctx: &Context, cmd: &CommandInteraction, db: &PgPool) -> Result<()> {
+        run(ctx, cmd, db).await
+    }
+
+    fn component_prefixes(&self) -> &'static [&'static str] {
+        &["pay:"]
+    }
+
+    async fn handle_component(&self, ctx: &Context, ic: &ComponentInteraction, db: &PgPool) -> Result<()> {
+        handle_component(ctx, ic, db).await
+    }
+}
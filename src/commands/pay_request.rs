@@ -0,0 +1,268 @@
+//! commands/pay_request.rs — `/pay zadaj`: prośba o zapłatę od innego gracza.
+//!
+//! Prośba to wiersz w `pay_requests` i publiczna wiadomość z przyciskami `pay:req:{id}:{akcja}`.
+//! Płacący przyjmuje albo odrzuca, proszący może wycofać. Przyjęcie to zwykły przelew `/pay`
+//! (limit dzienny, opłata, blokada dłużnika) w jednej transakcji ze zmianą statusu prośby,
+//! więc drugi klik nie zapłaci drugi raz. Ważność: `config::PayTables::request_mins`.
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use serenity::all::{
+    ButtonStyle, CommandDataOption, CommandDataOptionValue, CommandInteraction, ComponentInteraction, Context,
+    CreateActionRow, CreateButton, CreateEmbed, CreateInteractionResponse, CreateInteractionResponseMessage, Mentionable,
+    UserId,
+};
+use sqlx::{PgConnection, PgPool};
+
+use super::pay::{self, Sent};
+use crate::clock;
+use crate::commands::crime::reply_ephemeral;
+use crate::economy::{self, Economy};
+use crate::engine::config;
+use crate::ledger;
+use crate::utils::log_action;
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+struct PayRequest {
+    id: i64,
+    requester: i64,
+    payer: i64,
+    amount: i64,
+    note: Option<String>,
+    status: String,
+    expires_at: DateTime<Utc>,
+}
+
+// =======================
+// ⌨️ Slash
+// =======================
+
+pub async fn run(ctx: &Context, cmd: &CommandInteraction, db: &PgPool, opts: &[CommandDataOption]) -> Result<()> {
+    let requester = &cmd.user;
+    let Some((payer, amount)) = pay::parse_args(cmd, opts) else {
+        return pay::respond_error(ctx, cmd, "❌ Nieprawidłowe argumenty.").await;
+    };
+    let note = opts.iter().find_map(|o| match &o.value {
+        CommandDataOptionValue::String(s) if o.name == "opis" => Some(s.trim().to_string()),
+        _ => None,
+    });
+
+    if payer.id == requester.id {
+        return pay::respond_error(ctx, cmd, "❌ Nie możesz prosić o TK samego siebie!").await;
+    }
+    if payer.bot {
+        return pay::respond_error(ctx, cmd, "❌ Boty nie płacą.").await;
+    }
+    if amount <= 0 {
+        return pay::respond_error(ctx, cmd, "❌ Kwota musi być większa niż 0!").await;
+    }
+
+    // 🛂 Te same zapory co przy /pay — prośba to przelew w drugą stronę
+    let payer_joined = cmd.data.resolved.members.get(&payer.id).and_then(|m| m.joined_at);
+    let requester_joined = cmd.member.as_ref().and_then(|m| m.joined_at);
+    if let Some(msg) = pay::age_gate("Twoje konto", requester, requester_joined)
+        .or_else(|| pay::age_gate("Konto płacącego", &payer, payer_joined))
+    {
+        return pay::respond_error(ctx, cmd, &msg).await;
+    }
+
+    let eco = economy::of(db, cmd.guild_id).await?;
+    let note = note.filter(|n| !n.is_empty());
+    let Some(req) = create(db, eco, requester.id.get(), payer.id.get(), amount, note.as_deref(), cmd.id.get()).await?
+    else {
+        let max = config::get().pay.max_open_requests;
+        return pay::respond_error(ctx, cmd, &format!("⛔ Masz już {max} otwartych próśb — poczekaj na odpowiedź.")).await;
+    };
+
+    let desc = format!("Prośba #{} o {amount} TK od {}", req.id, payer.tag());
+    let _ = log_action(db, requester.id.get(), "pay_request", Some(payer.id.get()), Some(amount), Some(&desc)).await;
+
+    let id = |action: &str| format!("pay:req:{}:{action}", req.id);
+    let row = CreateActionRow::Buttons(vec![
+        CreateButton::new(id("accept")).label("✅ Zapłać").style(ButtonStyle::Success),
+        CreateButton::new(id("decline")).label("✖️ Odrzuć").style(ButtonStyle::Secondary),
+    ]);
+    let fee = pay::fee_for(amount);
+    let status = format!(
+        "Opłatę za przelew ({fee} TK) pokrywa płacący • wygasa <t:{}:R>.",
+        req.expires_at.timestamp()
+    );
+    cmd.create_response(
+        &ctx.http,
+        CreateInteractionResponse::Message(
+            CreateInteractionResponseMessage::new()
+                .content(payer.mention().to_string())
+                .embed(render(&req, &status, 0x00AAFF))
+                .components(vec![row]),
+        ),
+    )
+    .await?;
+    Ok(())
+}
+
+// =======================
+// 💾 DB
+// =======================
+
+const COLUMNS: &str = "id, requester, payer, amount, note, status, expires_at";
+
+/// Nowa prośba; `None`, jeśli proszący ma już `max_open_requests` otwartych.
+async fn create(
+    db: &PgPool,
+    eco: Economy,
+    requester: u64,
+    payer: u64,
+    amount: i64,
+    note: Option<&str>,
+    interaction_id: u64,
+) -> Result<Option<PayRequest>> {
+    let cfg = &config::get().pay;
+    let now = clock::now();
+    let mut tx = db.begin().await?;
+
+    // blokada proszącego — równoległe /pay zadaj liczą limit po kolei
+    ledger::ensure_user(&mut tx, eco, requester).await?;
+    sqlx::query("SELECT 1 FROM users WHERE guild_id = $1 AND id = $2 FOR UPDATE")
+        .bind(eco.id())
+        .bind(requester as i64)
+        .execute(&mut *tx)
+        .await?;
+    let open: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM pay_requests WHERE guild_id = $1 AND requester = $2 AND status = 'pending' AND expires_at > $3",
+    )
+    .bind(eco.id())
+    .bind(requester as i64)
+    .bind(now)
+    .fetch_one(&mut *tx)
+    .await?;
+    if open >= cfg.max_open_requests {
+        return Ok(None);
+    }
+
+    let sql = format!(
+        r#"INSERT INTO pay_requests (guild_id, requester, payer, amount, note, expires_at, interaction_id)
+           VALUES ($1, $2, $3, $4, $5, $6, $7)
+        RETURNING {COLUMNS}"#
+    );
+    let req: PayRequest = sqlx::query_as(&sql)
+        .bind(eco.id())
+        .bind(requester as i64)
+        .bind(payer as i64)
+        .bind(amount)
+        .bind(note)
+        .bind(now + Duration::minutes(cfg.request_mins))
+        .bind(interaction_id as i64)
+        .fetch_one(&mut *tx)
+        .await?;
+    tx.commit().await?;
+    Ok(Some(req))
+}
+
+async fn lock(conn: &mut PgConnection, eco: Economy, id: i64) -> Result<Option<PayRequest>> {
+    let sql = format!("SELECT {COLUMNS} FROM pay_requests WHERE guild_id = $1 AND id = $2 FOR UPDATE");
+    Ok(sqlx::query_as(&sql).bind(eco.id()).bind(id).fetch_optional(&mut *conn).await?)
+}
+
+async fn close(conn: &mut PgConnection, id: i64, status: &str) -> Result<()> {
+    sqlx::query("UPDATE pay_requests SET status = $2, resolved_at = $3 WHERE id = $1")
+        .bind(id)
+        .bind(status)
+        .bind(clock::now())
+        .execute(&mut *conn)
+        .await?;
+    Ok(())
+}
+
+/// Zamyka jako `expired` prośby, których nikt nie kliknął przed `expires_at`; zwraca ich liczbę.
+pub(crate) async fn expire_pending(db: &PgPool) -> Result<u64> {
+    let now = clock::now();
+    let done = sqlx::query(
+        "UPDATE pay_requests SET status = 'expired', resolved_at = $1 WHERE status = 'pending' AND expires_at <= $1",
+    )
+    .bind(now)
+    .execute(db)
+    .await?;
+    Ok(done.rows_affected())
+}
+
+// =======================
+// 🔘 Przyciski
+// =======================
+
+pub async fn handle_component(ctx: &Context, mci: &ComponentInteraction, db: &PgPool) -> Result<()> {
+    // pay:req:{id}:{accept|decline}
+    let parts: Vec<&str> = mci.data.custom_id.split(':').collect();
+    let (Some(id), Some(action)) = (parts.get(2).and_then(|s| s.parse::<i64>().ok()), parts.get(3).copied()) else {
+        return Ok(());
+    };
+    let user = mci.user.id.get() as i64;
+    let eco = economy::of(db, mci.guild_id).await?;
+
+    let mut tx = db.begin().await?;
+    let Some(req) = lock(&mut tx, eco, id).await? else {
+        return reply_ephemeral(ctx, mci, "🕳️ Nie ma takiej prośby.").await;
+    };
+    if req.status != "pending" {
+        return reply_ephemeral(ctx, mci, "🔒 Ta prośba jest już zamknięta.").await;
+    }
+    let allowed = match action {
+        "accept" => user == req.payer,
+        "decline" => user == req.payer || user == req.requester,
+        _ => false,
+    };
+    if !allowed {
+        return reply_ephemeral(ctx, mci, "⛔ Ta prośba nie jest do ciebie.").await;
+    }
+
+    let (status, color) = if clock::now() > req.expires_at {
+        close(&mut tx, req.id, "expired").await?;
+        tx.commit().await?;
+        ("⌛ Prośba wygasła.".to_string(), 0x95a5a6)
+    } else if action == "decline" {
+        let (state, line) = if user == req.payer {
+            ("declined", format!("✖️ <@{}> odrzucił prośbę.", req.payer))
+        } else {
+            ("cancelled", "✖️ Prośba wycofana.".to_string())
+        };
+        close(&mut tx, req.id, state).await?;
+        tx.commit().await?;
+        (line, 0x95a5a6)
+    } else {
+        let sent =
+            pay::send_in(&mut tx, eco, req.payer as u64, req.requester as u64, req.amount, "zapłata na prośbę", mci.id.get())
+                .await?;
+        let Sent::Done { fee, .. } = sent else {
+            // prośba zostaje otwarta — można dołożyć TK i spróbować jeszcze raz
+            return reply_ephemeral(ctx, mci, pay::refusal(&sent)).await;
+        };
+        close(&mut tx, req.id, "paid").await?;
+        tx.commit().await?;
+
+        pay::log_sent(ctx, db, &mci.user, UserId::new(req.requester as u64), req.amount, fee).await;
+        (format!("✅ Opłacone przez <@{}> (opłata {fee} TK).", req.payer), 0x2ECC71)
+    };
+
+    mci.create_response(
+        &ctx.http,
+        CreateInteractionResponse::UpdateMessage(
+            CreateInteractionResponseMessage::new().content("").embed(render(&req, &status, color)).components(vec![]),
+        ),
+    )
+    .await?;
+    Ok(())
+}
+
+// =======================
+// 🧱 Widok
+// =======================
+
+fn render(req: &PayRequest, status: &str, color: u32) -> CreateEmbed {
+    let mut embed = CreateEmbed::new()
+        .title(format!("🧾 Prośba o zapłatę #{}", req.id))
+        .description(format!("<@{}> prosi <@{}> o **{} TK**.", req.requester, req.payer, req.amount))
+        .color(color);
+    if let Some(note) = &req.note {
+        embed = embed.field("Za co", note, false);
+    }
+    embed.field("Status", status, false)
+}
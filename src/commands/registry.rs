@@ -0,0 +1,162 @@
+//! commands/registry.rs — jeden rejestr komend zamiast ręcznych `match`-y w Handlerze.
+//!
+//! Każda komenda implementuje [`BotCommand`] i jest dopisana w `commands::all()`.
+//! Rejestr na tej podstawie rejestruje slash-komendy, routuje komponenty/modale
+//! po prefiksach `custom_id` i przy starcie odrzuca kolizje nazw oraz prefiksów.
+
+use std::sync::Arc;
+
+use anyhow::{bail, Result};
+use serenity::all::{CommandInteraction, ComponentInteraction, Context, CreateCommand, ModalInteraction};
+use serenity::async_trait;
+use sqlx::PgPool;
+
+/// Metadane cooldownu komendy (klucz w `cooldowns` + długość bazowa).
+#[derive(Debug, Clone, Copy)]
+pub struct CooldownMeta {
+    pub key: &'static str,
+    pub secs: i64,
+    /// Czy HEAT gracza wydłuża cooldown (komendy „przestępcze”).
+    pub heat_penalty: bool,
+}
+
+#[async_trait]
+pub trait BotCommand: Send + Sync {
+    /// Nazwa slash-komendy (klucz in-flight i metryk).
+    fn name(&self) -> &'static str;
+
+    /// Dodatkowe nazwy kierowane do tej samej komendy (np. stare rejestracje).
+    fn aliases(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    fn register(&self) -> CreateCommand;
+
+    async fn run(&self, ctx: &Context, cmd: &CommandInteraction, db: &PgPool) -> Result<()>;
+
+    /// Prefiksy `custom_id` przycisków/selectów obsługiwanych przez komendę.
+    fn component_prefixes(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    async fn handle_component(&self, _ctx: &Context, _ic: &ComponentInteraction, _db: &PgPool) -> Result<()> {
+        Ok(())
+    }
+
+    /// Prefiksy `custom_id` modali obsługiwanych przez komendę.
+    fn modal_prefixes(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    async fn handle_modal(&self, _ctx: &Context, _mi: &ModalInteraction, _db: &PgPool) -> Result<()> {
+        Ok(())
+    }
+
+    fn cooldown(&self) -> Option<CooldownMeta> {
+        None
+    }
+
+    /// Powtarza zapisane losowanie (`rolls`) rodzaju `kind` z ziarna i wejścia.
+    /// `None` = komenda nie zna takiego losowania.
+    fn replay(&self, _kind: &str, _seed: u64, _inputs: &str) -> Option<Result<serde_json::Value>> {
+        None
+    }
+
+    /// Komenda działa na ekonomii serwera — poza gildią (DM) Handler ją odrzuca.
+    fn economy(&self) -> bool {
+        false
+    }
+
+    /// Komenda zarabiająca (praca, napady) — Handler odrzuca ją, gdy gracz siedzi w więzieniu.
+    /// `sub` = wybrana podkomenda (`None` = bez podkomendy).
+    fn earning(&self, _sub: Option<&str>) -> bool {
+        false
+    }
+
+    /// Jak [`earning`](Self::earning), dla komponentu o danym `custom_id`.
+    fn earning_component(&self, _custom_id: &str) -> bool {
+        self.earning(None)
+    }
+}
+
+pub struct CommandRegistry {
+    commands: Vec<Arc<dyn BotCommand>>,
+}
+
+impl CommandRegistry {
+    /// Buduje rejestr i waliduje go — duplikat nazwy albo nachodzące prefiksy to błąd startu.
+    pub fn new(commands: Vec<Arc<dyn BotCommand>>) -> Result<Self> {
+        let mut names: Vec<&'static str> = Vec::new();
+        let mut components: Vec<(&'static str, &'static str)> = Vec::new();
+        let mut modals: Vec<(&'static str, &'static str)> = Vec::new();
+
+        for c in &commands {
+            for n in std::iter::once(c.name()).chain(c.aliases().iter().copied()) {
+                if names.contains(&n) {
+                    bail!("Komenda `/{n}` zarejestrowana więcej niż raz");
+                }
+                names.push(n);
+            }
+            check_prefixes(&mut components, c.name(), c.component_prefixes(), "komponentu")?;
+            check_prefixes(&mut modals, c.name(), c.modal_prefixes(), "modala")?;
+        }
+
+        Ok(Self { commands })
+    }
+
+    pub fn definitions(&self) -> Vec<CreateCommand> {
+        self.commands.iter().map(|c| c.register()).collect()
+    }
+
+    pub fn by_name(&self, name: &str) -> Option<&Arc<dyn BotCommand>> {
+        self.commands
+            .iter()
+            .find(|c| c.name() == name || c.aliases().contains(&name))
+    }
+
+    pub fn by_component(&self, custom_id: &str) -> Option<&Arc<dyn BotCommand>> {
+        self.commands
+            .iter()
+            .find(|c| c.component_prefixes().iter().any(|p| custom_id.starts_with(p)))
+    }
+
+    pub fn by_modal(&self, custom_id: &str) -> Option<&Arc<dyn BotCommand>> {
+        self.commands
+            .iter()
+            .find(|c| c.modal_prefixes().iter().any(|p| custom_id.starts_with(p)))
+    }
+
+    /// Komenda właściwa dla rodzaju losowania (`crime.simon` → `/crime`).
+    pub fn by_roll(&self, kind: &str) -> Option<&Arc<dyn BotCommand>> {
+        self.by_name(kind.split('.').next().unwrap_or(kind))
+    }
+
+    /// Wszystkie zadeklarowane cooldowny (np. do resetu w /admcontrol).
+    pub fn cooldowns(&self) -> Vec<(&'static str, CooldownMeta)> {
+        self.commands
+            .iter()
+            .filter_map(|c| c.cooldown().map(|m| (c.name(), m)))
+            .collect()
+    }
+}
+
+fn check_prefixes(
+    seen: &mut Vec<(&'static str, &'static str)>,
+    owner: &'static str,
+    prefixes: &'static [&'static str],
+    what: &str,
+) -> Result<()> {
+    for &p in prefixes {
+        if p.is_empty() {
+            bail!("/{owner}: pusty prefiks {what}");
+        }
+        if let Some((other, op)) = seen
+            .iter()
+            .find(|(o, op)| *o != owner && (op.starts_with(p) || p.starts_with(op)))
+        {
+            bail!("Prefiks {what} `{p}` (/{owner}) koliduje z `{op}` (/{other})");
+        }
+        seen.push((owner, p));
+    }
+    Ok(())
+}
@@ -0,0 +1,137 @@
+use anyhow::Result;
+use chrono::Utc;
+use serenity::all::*;
+use serenity::builder::{
+    CreateCommand, CreateCommandOption, CreateEmbed, CreateInteractionResponse, CreateInteractionResponseMessage,
+};
+use serenity::async_trait;
+use sqlx::PgPool;
+
+use crate::commands::registry::BotCommand;
+use crate::reminders::{self, Mode};
+
+/// Rejestracja komendy `/przypomnienia`
+pub fn register(cmd: &mut CreateCommand) -> &mut CreateCommand {
+    *cmd = CreateCommand::new("przypomnienia")
+        .description("Powiadomienia o końcu cooldownów (/work, /slut, /rob, /daily, /crime)")
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::SubCommand, "tryb", "Jak mam cię powiadamiać")
+                .add_sub_option(
+                    CreateCommandOption::new(CommandOptionType::String, "tryb", "Sposób powiadomienia")
+                        .add_string_choice("📩 Wiadomość prywatna", "dm")
+                        .add_string_choice("📢 Ping na kanale komendy", "channel")
+                        .add_string_choice("🔕 Wyłączone", "off")
+                        .required(true),
+                ),
+        )
+        .add_option(CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "lista",
+            "Pokaż tryb i oczekujące przypomnienia",
+        ))
+        .add_option(CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "wyczysc",
+            "Anuluj oczekujące przypomnienia",
+        ));
+    cmd
+}
+
+/// Obsługa komendy `/przypomnienia`
+pub async fn run(ctx: &Context, cmd: &CommandInteraction, db: &PgPool) -> Result<()> {
+    let user_id = cmd.user.id.get();
+    let Some(sub) = cmd.data.options.first() else {
+        return Ok(());
+    };
+
+    let embed = match sub.name.as_str() {
+        "tryb" => {
+            let mode = match &sub.value {
+                CommandDataOptionValue::SubCommand(items) => items.iter().find_map(|o| match &o.value {
+                    CommandDataOptionValue::String(s) if o.name == "tryb" => Mode::parse(s),
+                    _ => None,
+                }),
+                _ => None,
+            };
+            let Some(mode) = mode else {
+                return respond(ctx, cmd, CreateEmbed::new().description("❌ Nieznany tryb.").color(0xE74C3C)).await;
+            };
+
+            reminders::set_mode(db, user_id, mode).await?;
+            let note = match mode {
+                Mode::Dm => "Napiszę do ciebie prywatnie, gdy cooldown się skończy.",
+                Mode::Channel => "Oznaczę cię na kanale, na którym użyłeś komendy.",
+                Mode::Off => "Nie będę przypominać. Oczekujące przypomnienia zostały anulowane.",
+            };
+            CreateEmbed::new()
+                .title("⏰ Przypomnienia")
+                .description(format!("Tryb: **{}**\n{note}", mode.label()))
+                .color(0x2ECC71)
+        }
+
+        "lista" => {
+            let mode = reminders::mode_of(db, user_id).await?;
+            let pending = reminders::pending(db, user_id).await?;
+            let lines: Vec<String> = pending
+                .iter()
+                .take(20)
+                .map(|(command, due)| format!("• `{command}` — <t:{}:R>", due.timestamp()))
+                .collect();
+            let desc = if lines.is_empty() {
+                "Brak oczekujących przypomnień.".to_string()
+            } else {
+                lines.join("\n")
+            };
+            CreateEmbed::new()
+                .title("⏰ Przypomnienia")
+                .description(desc)
+                .field("Tryb", mode.label(), true)
+                .field("Oczekujące", pending.len().to_string(), true)
+                .color(0x3498DB)
+        }
+
+        "wyczysc" => {
+            let cleared = reminders::clear(db, user_id).await?;
+            CreateEmbed::new()
+                .title("⏰ Przypomnienia")
+                .description(format!("🧹 Anulowano przypomnienia: **{cleared}**."))
+                .color(0xE67E22)
+        }
+
+        _ => return Ok(()),
+    };
+
+    respond(ctx, cmd, embed.timestamp(Utc::now())).await
+}
+
+async fn respond(ctx: &Context, cmd: &CommandInteraction, embed: CreateEmbed) -> Result<()> {
+    cmd.create_response(
+        &ctx.http,
+        CreateInteractionResponse::Message(CreateInteractionResponseMessage::new().ephemeral(true).embed(embed)),
+    )
+    .await?;
+    Ok(())
+}
+
+// =======================
+// 🧭 Rejestr komend
+// =======================
+
+pub struct RemindersCommand;
+
+#[async_trait]
+impl BotCommand for RemindersCommand {
+    fn name(&self) -> &'static str {
+        "przypomnienia"
+    }
+
+    fn register(&self) -> CreateCommand {
+        let mut c = CreateCommand::new("przypomnienia");
+        register(&mut c);
+        c
+    }
+
+    async fn run(&self, ctx: &Context, cmd: &CommandInteraction, db: &PgPool) -> Result<()> {
+        run(ctx, cmd, db).await
+    }
+}
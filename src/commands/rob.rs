@@ -2,24 +2,36 @@ use anyhow::Result;
 use chrono::{DateTime, Duration, Utc};
 use once_cell::sync::OnceCell as SyncOnceCell;
 use rand::Rng; // rand 0.9: daje random_bool / random_range
+use serde::{Deserialize, Serialize};
 use serenity::all::*;
 use serenity::all::CommandOptionType;
 use serenity::builder::{CreateCommand, CreateCommandOption, CreateEmbed, CreateEmbedAuthor, CreateMessage};
+use serenity::async_trait;
 use sqlx::{PgPool, Row};
-use tokio::sync::OnceCell as AsyncOnceCell;
 
+use crate::clock;
+use crate::cooldowns;
+use crate::economy::{self, Economy};
+use crate::engine::{config, types::Risk};
+use crate::jail;
+use crate::reminders;
+use crate::rng::{self, Roll};
+use crate::ledger::{self, Account, Entry, Overdraft};
 use crate::utils::log_action;
+use crate::commands::registry::{BotCommand, CooldownMeta};
 
 // =======================
 // ⚙️ Stałe
 // =======================
 
-const ROB_COOLDOWN_SECS: i64 = 600;
-const MIN_BALANCE_TO_ROB: i64 = 50;
-const MIN_STOLEN: i64 = 25;
-const MAX_STOLEN: i64 = 150;
-const MIN_FINE: i64 = 25;
-const MAX_FINE: i64 = 75;
+const COOLDOWN_KEY: &str = "rob";
+
+// długość: `cooldowns.rob_secs` z tabel balansu
+fn cooldown_meta() -> CooldownMeta {
+    CooldownMeta { key: COOLDOWN_KEY, secs: config::get().cooldowns.rob_secs, heat_penalty: false }
+}
+
+// kwoty i szansa: tabele balansu (`config::RobTables`)
 
 // Cache kanału logów z ENV (raz na proces)
 static LOG_CHAN: SyncOnceCell<Option<ChannelId>> = SyncOnceCell::new();
@@ -33,9 +45,6 @@ fn log_channel_id() -> Option<ChannelId> {
     })
 }
 
-// ensure_schema tylko raz na proces
-static ENSURE_SCHEMA_ONCE: AsyncOnceCell<()> = AsyncOnceCell::const_new();
-
 // =======================
 // 🔧 Rejestracja komendy
 // =======================
@@ -55,14 +64,6 @@ pub fn register(cmd: &mut CreateCommand) -> &mut CreateCommand {
 // =======================
 
 pub async fn run(ctx: &Context, cmd: &CommandInteraction, db: &PgPool) -> Result<()> {
-    // Jednorazowy bootstrap schematu
-    let _ = ENSURE_SCHEMA_ONCE
-        .get_or_try_init(|| async {
-            ensure_schema(db).await?;
-            Ok::<(), anyhow::Error>(())
-        })
-        .await;
-
     let robber = &cmd.user;
     let robber_id = robber.id.get();
 
@@ -74,46 +75,32 @@ pub async fn run(ctx: &Context, cmd: &CommandInteraction, db: &PgPool) -> Result
         return respond_ephemeral(ctx, cmd, "🙅‍♂️ Nie możesz okradać samego siebie.").await;
     }
 
-    // RNG (rand 0.9)
-    // RNG (rand 0.9) — zamknięty w bloku, żeby nie żył przez await
-    let (success, amount_opt, fine_opt) = {
-    let mut rng = rand::rng();
-    let success = rng.random_bool(0.5);
-    let amount_opt = if success {
-        Some(rng.random_range(MIN_STOLEN..=MAX_STOLEN))
-    } else {
-        None
-    };
-    let fine_opt = if success {
-        None
-    } else {
-        Some(rng.random_range(MIN_FINE..=MAX_FINE))
-    };
-    (success, amount_opt, fine_opt)
+    let Some(guild_id) = cmd.guild_id else {
+        return respond_ephemeral(ctx, cmd, "❌ Ta komenda działa tylko na serwerze.").await;
     };
+    let eco = economy::resolve(db, guild_id).await?;
 
     // Próba rabunku (atomicznie)
     match try_rob(
         db,
+        eco,
+        guild_id,
         robber_id as i64,
         target_user.id.get() as i64,
-        success,
-        amount_opt,
-        fine_opt,
+        cmd.id.get(),
     )
     .await?
     {
         RobState::Cooldown { remaining_secs } => {
             let embed = build_cooldown_embed(remaining_secs);
             respond_embed(ctx, cmd, embed).await?;
-            spawn_ready_after(ctx.clone(), cmd.clone(), robber.clone(), remaining_secs, "/rob".to_string());
         }
         RobState::TargetTooPoor => {
-            return respond_ephemeral(ctx, cmd, "👛 Cel jest zbyt biedny, nic nie ukradniesz!").await;
+            return respond_ephemeral(ctx, cmd, "👛 Portfel celu jest prawie pusty, nic nie ukradniesz!").await;
         }
-        RobState::Success { amount, robber_balance, when } => {
+        RobState::Success { amount, robber_balance, ready_at } => {
             let embed = build_result_embed(
-                true, amount, ROB_COOLDOWN_SECS, when, robber, &target_user, robber_balance,
+                true, amount, ready_at, robber, &target_user, robber_balance,
             );
             respond_embed(ctx, cmd, embed).await?;
 
@@ -152,12 +139,19 @@ pub async fn run(ctx: &Context, cmd: &CommandInteraction, db: &PgPool) -> Result
                 }
             });
 
-            spawn_ready_after(ctx.clone(), cmd.clone(), robber.clone(), ROB_COOLDOWN_SECS, "/rob".to_string());
+            schedule_reminder(db, guild_id, cmd.channel_id, robber_id, ready_at).await;
         }
-        RobState::Failure { fine, robber_balance, when } => {
-            let embed = build_result_embed(
-                false, fine, ROB_COOLDOWN_SECS, when, robber, &target_user, robber_balance,
+        RobState::Failure { fine, robber_balance, ready_at, jailed_until } => {
+            let mut embed = build_result_embed(
+                false, fine, ready_at, robber, &target_user, robber_balance,
             );
+            if let Some(t) = jailed_until {
+                embed = embed.field(
+                    "🔒 Więzienie",
+                    format!("do <t:{0}:T> • <t:{0}:R>\nKaucja albo ucieczka: `/wiezienie`", t.timestamp()),
+                    false,
+                );
+            }
             respond_embed(ctx, cmd, embed).await?;
 
             // Logi w tle
@@ -195,27 +189,18 @@ pub async fn run(ctx: &Context, cmd: &CommandInteraction, db: &PgPool) -> Result
                 }
             });
 
-            spawn_ready_after(ctx.clone(), cmd.clone(), robber.clone(), ROB_COOLDOWN_SECS, "/rob".to_string());
+            schedule_reminder(db, guild_id, cmd.channel_id, robber_id, ready_at).await;
         }
     }
 
     Ok(())
 }
 
-fn spawn_ready_after(
-    ctx: Context,
-    cmd: CommandInteraction,
-    user: User,
-    secs: i64,
-    command: String,
-) {
-    tokio::spawn(async move {
-        tokio::time::sleep(std::time::Duration::from_secs(secs as u64)).await;
-        let ready = build_cooldown_ready_embed(&user, &command);
-        let _ = cmd
-            .edit_response(&ctx.http, EditInteractionResponse::new().embed(ready))
-            .await;
-    });
+/// Przypomnienie o końcu cooldownu (doręcza harmonogram — przeżywa restart).
+async fn schedule_reminder(db: &PgPool, guild: GuildId, channel: ChannelId, user: u64, ready_at: DateTime<Utc>) {
+    if let Err(e) = reminders::schedule(db, guild, channel, user, COOLDOWN_KEY, "/rob", ready_at).await {
+        eprintln!("❌ /rob: nie zapisano przypomnienia: {e:?}");
+    }
 }
 
 // =======================
@@ -223,7 +208,7 @@ fn spawn_ready_after(
 // =======================
 
 fn parse_target_user(cmd: &CommandInteraction) -> Option<User> {
-    let opt = cmd.data.options.get(0)?;
+    let opt = cmd.data.options.first()?;
     if opt.name != "cel" {
         return None;
     }
@@ -240,132 +225,155 @@ fn parse_target_user(cmd: &CommandInteraction) -> Option<User> {
 enum RobState {
     Cooldown { remaining_secs: i64 },
     TargetTooPoor,
-    Success { amount: i64, robber_balance: i64, when: DateTime<Utc> },
-    Failure { fine: i64, robber_balance: i64, when: DateTime<Utc> },
+    Success { amount: i64, robber_balance: i64, ready_at: DateTime<Utc> },
+    Failure { fine: i64, robber_balance: i64, ready_at: DateTime<Utc>, jailed_until: Option<DateTime<Utc>> },
+}
+
+/// Wynik losowania /rob: sukces i kwota (łup albo grzywna, przed przycięciem do salda celu).
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+struct RobRoll {
+    success: bool,
+    amount: i64,
+}
+
+fn roll_rob(_: &(), rng: &mut impl Rng) -> RobRoll {
+    let t = &config::get().rob;
+    let success = rng.random_bool(t.success_chance);
+    let amount = if success {
+        rng.random_range(t.stolen[0]..=t.stolen[1])
+    } else {
+        rng.random_range(t.fine[0]..=t.fine[1])
+    };
+    RobRoll { success, amount }
 }
 
 async fn try_rob(
     db: &PgPool,
+    eco: Economy,
+    guild: GuildId,
     robber_id: i64,
     target_id: i64,
-    success: bool,
-    amount_opt: Option<i64>,
-    fine_opt: Option<i64>,
+    interaction_id: u64,
 ) -> Result<RobState> {
-    let now = Utc::now();
-
     // Jedna transakcja, minimalne RTT
     let mut tx = db.begin().await?;
 
     // Upewnij się, że rekordy istnieją
     sqlx::query(
         r#"
-        INSERT INTO users (id, balance)
-        VALUES ($1, 0), ($2, 0)
-        ON CONFLICT (id) DO NOTHING
+        INSERT INTO users (guild_id, id, balance)
+        VALUES ($1, $2, 0), ($1, $3, 0)
+        ON CONFLICT (guild_id, id) DO NOTHING
         "#,
     )
+    .bind(eco.id())
     .bind(robber_id)
     .bind(target_id)
     .execute(&mut *tx)
     .await?;
 
     // Zablokuj oba wiersze do końca transakcji
-    let robber_row = sqlx::query(
-        r#"SELECT balance, last_rob FROM users WHERE id = $1 FOR UPDATE"#,
-    )
-    .bind(robber_id)
-    .fetch_one(&mut *tx)
-    .await?;
-    let _initial_balance: i64 = robber_row.try_get("balance")?;
-    let last_rob: Option<DateTime<Utc>> = robber_row.try_get("last_rob")?;
+    sqlx::query(r#"SELECT 1 FROM users WHERE guild_id = $1 AND id = $2 FOR UPDATE"#)
+        .bind(eco.id())
+        .bind(robber_id)
+        .execute(&mut *tx)
+        .await?;
     let robber_balance: i64; // ustawimy w gałęziach success/failure
 
     let target_row = sqlx::query(
-        r#"SELECT balance FROM users WHERE id = $1 FOR UPDATE"#,
+        r#"SELECT balance FROM users WHERE guild_id = $1 AND id = $2 FOR UPDATE"#,
     )
+    .bind(eco.id())
     .bind(target_id)
     .fetch_one(&mut *tx)
     .await?;
     let target_balance: i64 = target_row.try_get("balance")?;
 
     // Cooldown
-    if let Some(last) = last_rob {
-        let elapsed = (now - last).num_seconds();
-        if elapsed < ROB_COOLDOWN_SECS {
-            tx.rollback().await?;
-            return Ok(RobState::Cooldown { remaining_secs: ROB_COOLDOWN_SECS - elapsed });
-        }
+    if let Some(remaining_secs) = cooldowns::remaining(&mut *tx, eco, robber_id as u64, COOLDOWN_KEY).await? {
+        tx.rollback().await?;
+        return Ok(RobState::Cooldown { remaining_secs });
     }
 
     // Za biedny cel
-    if target_balance < MIN_BALANCE_TO_ROB {
+    let t = config::get().rob.clone();
+    if target_balance < t.min_balance_to_rob {
         tx.rollback().await?;
         return Ok(RobState::TargetTooPoor);
     }
 
-    if success {
+    // Losowanie dopiero po warunkach wstępnych — zapisujemy tylko rzuty, które coś zmieniły
+    let (seed, mut rng) = rng::seeded();
+    let roll = roll_rob(&(), &mut rng);
+    rng::record(
+        &mut *tx,
+        eco,
+        &Roll { command: "rob", user: robber_id as u64, interaction_id: Some(interaction_id), seed },
+        &(),
+        &roll,
+    )
+    .await?;
+
+    if roll.success {
         // Kwota kradzieży ograniczona saldem celu
-        let mut steal_amount = amount_opt.unwrap_or(MIN_STOLEN);
-        steal_amount = steal_amount.clamp(1, MAX_STOLEN);
-        let steal_amount = steal_amount.min(target_balance).max(1);
-
-        // 1) Odejmiemy z celu (warunek zapobiega zejściu poniżej zera)
-        let updated = sqlx::query(
-            r#"
-            UPDATE users
-            SET balance = balance - $1
-            WHERE id = $2 AND balance >= $1
-            RETURNING balance
-            "#,
+        let steal_amount = roll.amount.clamp(1, t.stolen[1].max(1)).min(target_balance).max(1);
+
+        // 1) Cel → złodziej przez księgę (Forbid pilnuje, by cel nie zszedł poniżej zera)
+        let moved = ledger::transfer(
+            &mut tx,
+            eco,
+            Account::User(target_id as u64),
+            Account::User(robber_id as u64),
+            steal_amount,
+            &Entry { reason: "kradzież", command: "rob", interaction_id: Some(interaction_id) },
+            Overdraft::Forbid,
         )
-        .bind(steal_amount)
-        .bind(target_id)
-        .fetch_optional(&mut *tx)
-        .await?;
+        .await;
 
-        if updated.is_none() {
-            tx.rollback().await?;
-            return Ok(RobState::TargetTooPoor);
-        }
+        let posted = match moved {
+            Ok(p) => p,
+            Err(e) if ledger::insufficient(&e).is_some() => {
+                tx.rollback().await?;
+                return Ok(RobState::TargetTooPoor);
+            }
+            Err(e) => return Err(e),
+        };
+        robber_balance = posted.sink_balance.unwrap_or_default();
 
-        // 2) Dodamy złodziejowi i ustawimy cooldown
-        robber_balance = sqlx::query_scalar(
-            r#"
-            UPDATE users
-            SET balance = balance + $1, last_rob = $2
-            WHERE id = $3
-            RETURNING balance
-            "#,
-        )
-        .bind(steal_amount)
-        .bind(now)
-        .bind(robber_id)
-        .fetch_one(&mut *tx)
-        .await?;
+        // 2) Cooldown
+        let ready_at = cooldowns::start(&mut tx, eco, guild, robber_id as u64, cooldown_meta()).await?;
 
         tx.commit().await?;
-        Ok(RobState::Success { amount: steal_amount, robber_balance, when: now })
+        Ok(RobState::Success { amount: steal_amount, robber_balance, ready_at })
     } else {
-        let fine = fine_opt.unwrap_or(MIN_FINE).clamp(MIN_FINE, MAX_FINE);
-
-        // Odejmij grzywnę od złodzieja + cooldown
-        robber_balance = sqlx::query_scalar(
-            r#"
-            UPDATE users
-            SET balance = balance - $1, last_rob = $2
-            WHERE id = $3
-            RETURNING balance
-            "#,
+        let fine = roll.amount.clamp(t.fine[0], t.fine[1]);
+
+        // Grzywna może zepchnąć złodzieja na minus (jak dotąd)
+        robber_balance = ledger::settle(
+            &mut tx,
+            eco,
+            robber_id as u64,
+            "rob_fines",
+            -fine,
+            &Entry { reason: "grzywna za nieudany rabunek", command: "rob", interaction_id: Some(interaction_id) },
+            Overdraft::Allow,
         )
-        .bind(fine)
-        .bind(now)
-        .bind(robber_id)
-        .fetch_one(&mut *tx)
         .await?;
+        let ready_at = cooldowns::start(&mut tx, eco, guild, robber_id as u64, cooldown_meta()).await?;
+
+        // Złapany z wysokim HEAT (z /crime) — idzie siedzieć
+        let heat: Option<i32> = sqlx::query_scalar("SELECT heat FROM profiles WHERE guild_id = $1 AND user_id = $2")
+            .bind(eco.id())
+            .bind(robber_id)
+            .fetch_optional(&mut *tx)
+            .await?;
+        let jailed_until = match jail::sentence_mins(Risk::Low, heat.unwrap_or(0) as i64, false) {
+            Some(mins) => Some(jail::imprison(&mut tx, eco, robber_id as u64, mins, "złapany na rabunku (/rob)").await?),
+            None => None,
+        };
 
         tx.commit().await?;
-        Ok(RobState::Failure { fine, robber_balance, when: now })
+        Ok(RobState::Failure { fine, robber_balance, ready_at, jailed_until })
     }
 }
 
@@ -374,7 +382,7 @@ async fn try_rob(
 // =======================
 
 fn build_cooldown_embed(remaining_secs: i64) -> CreateEmbed {
-    let retry_time = Utc::now() + Duration::seconds(remaining_secs.max(0));
+    let retry_time = clock::now() + Duration::seconds(remaining_secs.max(0));
     let next_unix = retry_time.timestamp();
 
     CreateEmbed::new()
@@ -394,29 +402,17 @@ fn build_cooldown_embed(remaining_secs: i64) -> CreateEmbed {
         .color(0xFFA500)
 }
 
-fn build_cooldown_ready_embed(user: &User, command: &str) -> CreateEmbed {
-    CreateEmbed::new()
-        .color(0x2ECC71)
-        .author(
-            CreateEmbedAuthor::new(&user.name)
-                .icon_url(user.avatar_url().unwrap_or_default()),
-        )
-        .title("✅ Cooldown zakończony")
-        .description(format!("Możesz już spróbować ponownie — użyj `{}`.", command))
-        .timestamp(Utc::now())
-}
+
 fn build_result_embed(
     success: bool,
     amount: i64,
-    cooldown_secs: i64,
-    when: DateTime<Utc>,
+    ready_at: DateTime<Utc>,
     robber: &User,
     target: &User,
     robber_balance: i64,
 ) -> CreateEmbed {
-    let next_at = when + Duration::seconds(cooldown_secs);
-    let next_unix = next_at.timestamp();
-    let remaining = (next_at - Utc::now()).num_seconds().max(0);
+    let next_unix = ready_at.timestamp();
+    let remaining = (ready_at - clock::now()).num_seconds().max(0);
 
     CreateEmbed::new()
         .title(if success { "💼 Udany skok!" } else { "🚨 Porażka!" })
@@ -454,7 +450,7 @@ fn build_result_embed(
             CreateEmbedAuthor::new(&robber.name)
                 .icon_url(robber.avatar_url().unwrap_or_default()),
         )
-        .timestamp(when)
+        .timestamp(clock::now())
 }
 
 // =======================
@@ -494,31 +490,71 @@ async fn respond_embed(
 }
 
 // =======================
-// 🗄️ Schemat (idempotentny)
+// 🧭 Rejestr komend
 // =======================
 
-async fn ensure_schema(db: &PgPool) -> anyhow::Result<()> {
-    sqlx::query(
-        r#"
-        CREATE TABLE IF NOT EXISTS users (
-            id          BIGINT PRIMARY KEY,
-            balance     BIGINT NOT NULL DEFAULT 0,
-            last_work   TIMESTAMPTZ,
-            last_slut   TIMESTAMPTZ,
-            last_crime  TIMESTAMPTZ,
-            last_rob    TIMESTAMPTZ
-        );
-        "#,
-    )
-    .execute(db)
-    .await?;
+pub struct RobCommand;
 
-    // Na wypadek starych tabel bez kolumn:
-    sqlx::query(r#"ALTER TABLE users ADD COLUMN IF NOT EXISTS balance    BIGINT     NOT NULL DEFAULT 0"#).execute(db).await?;
-    sqlx::query(r#"ALTER TABLE users ADD COLUMN IF NOT EXISTS last_work  TIMESTAMPTZ"#).execute(db).await?;
-    sqlx::query(r#"ALTER TABLE users ADD COLUMN IF NOT EXISTS last_slut  TIMESTAMPTZ"#).execute(db).await?;
-    sqlx::query(r#"ALTER TABLE users ADD COLUMN IF NOT EXISTS last_crime TIMESTAMPTZ"#).execute(db).await?;
-    sqlx::query(r#"ALTER TABLE users ADD COLUMN IF NOT EXISTS last_rob   TIMESTAMPTZ"#).execute(db).await?;
+#[async_trait]
+impl BotCommand for RobCommand {
+    fn name(&self) -> &'static str {
+        "rob"
+    }
 
-    Ok(())
+    fn economy(&self) -> bool {
+        true
+    }
+
+    fn earning(&self, _sub: Option<&str>) -> bool {
+        true
+    }
+
+    fn register(&self) -> CreateCommand {
+        let mut c = CreateCommand::new("rob");
+        register(&mut c);
+        c
+    }
+
+    async fn run(&self, ctx: &Context, cmd: &CommandInteraction, db: &PgPool) -> Result<()> {
+        run(ctx, cmd, db).await
+    }
+
+    fn replay(&self, kind: &str, seed: u64, inputs: &str) -> Option<Result<serde_json::Value>> {
+        (kind == "rob").then(|| rng::replay(seed, inputs, roll_rob))
+    }
+
+    fn cooldown(&self) -> Option<CooldownMeta> {
+        Some(cooldown_meta())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn roll_rob_is_reproducible_from_seed() {
+        for seed in 0..50 {
+            let a = roll_rob(&(), &mut rng::from_seed(seed));
+            let b = roll_rob(&(), &mut rng::from_seed(seed));
+            assert_eq!((a.success, a.amount), (b.success, b.amount));
+
+            let t = &config::get().rob;
+            let span = if a.success { t.stolen } else { t.fine };
+            assert!((span[0]..=span[1]).contains(&a.amount), "seed {seed}: {a:?}");
+        }
+    }
+
+    #[test]
+    fn disputed_rob_replays_recorded_outcome() {
+        // to, co `try_rob` zapisuje w `rolls`: wejście `()` i wynik jako JSON
+        let seed = 0xDEAD_BEEF;
+        let inputs = serde_json::to_string(&()).unwrap();
+        let outcome = serde_json::to_string(&roll_rob(&(), &mut rng::from_seed(seed))).unwrap();
+        let recorded: serde_json::Value = serde_json::from_str(&outcome).unwrap();
+
+        let replayed = RobCommand.replay("rob", seed, &inputs).expect("rob zna swoje losowanie").unwrap();
+        assert_eq!(replayed, recorded);
+        assert!(RobCommand.replay("daily", seed, &inputs).is_none());
+    }
 }
@@ -7,7 +7,13 @@ use serenity::builder::{
     CreateInteractionResponseMessage, CreateMessage, CreateSelectMenu, CreateSelectMenuKind,
     EditInteractionResponse,
 };
-use sqlx::{PgPool, Row};
+use serenity::async_trait;
+use sqlx::PgPool;
+
+use crate::commands::registry::BotCommand;
+use crate::economy;
+use crate::ledger::{self, Account, Entry, Overdraft};
+use crate::loans;
 use std::{env, fmt, num::NonZeroU64};
 
 // =======================================
@@ -226,11 +232,6 @@ fn render_panel(
 // =======================================
 
 pub async fn run(ctx: &Context, cmd: &CommandInteraction, db: &PgPool) -> Result<()> {
-    ensure_schema(db).await?;
-    if let Some(gid) = cmd.guild_id {
-        let _ = expire_roles_tick(ctx, db, gid).await;
-    }
-
     let opener_id = cmd.user.id.get();
     let units = 1i64;
 
@@ -335,6 +336,7 @@ pub async fn handle_component(ctx: &Context, ic: &ComponentInteraction, db: &PgP
             total,
             cfg.role_id.get() as i64,
             guild_id.get() as i64,
+            ic.id.get(),
         ).await? {
             BuyRoleResult::Ok { buyer_balance, new_expires_at } => {
                 ensure_role_added(&ctx.http, guild_id, UserId::new(target_id_u64)).await;
@@ -415,6 +417,14 @@ pub async fn handle_component(ctx: &Context, ic: &ComponentInteraction, db: &PgP
                         .components(Vec::<CreateActionRow>::new()),
                 ).await.ok();
             }
+            BuyRoleResult::Defaulted { owed } => {
+                ic.edit_response(
+                    &ctx.http,
+                    EditInteractionResponse::new()
+                        .content(defaulted_msg(owed))
+                        .components(Vec::<CreateActionRow>::new()),
+                ).await.ok();
+            }
         }
 
         return Ok(());
@@ -466,7 +476,7 @@ pub async fn handle_component(ctx: &Context, ic: &ComponentInteraction, db: &PgP
         if !owner_ok { return Ok(()); }
 
         let target_id_u64 = match &ic.data.kind {
-            ComponentInteractionDataKind::UserSelect { values, .. } => values.get(0).map(|u| u.get()),
+            ComponentInteractionDataKind::UserSelect { values, .. } => values.first().map(|u| u.get()),
             _ => None,
         };
 
@@ -568,7 +578,7 @@ pub async fn handle_component(ctx: &Context, ic: &ComponentInteraction, db: &PgP
             let total = price.saturating_mul(units);
             let buyer_id = ic.user.id.get() as i64;
 
-            match buy_role_tx(db, buyer_id, buyer_id, units, total, cfg.role_id.get() as i64, guild_id.get() as i64).await? {
+            match buy_role_tx(db, buyer_id, buyer_id, units, total, cfg.role_id.get() as i64, guild_id.get() as i64, ic.id.get()).await? {
                 BuyRoleResult::Ok { buyer_balance, new_expires_at } => {
                     ensure_role_added(&ctx.http, guild_id, ic.user.id).await;
 
@@ -643,6 +653,15 @@ pub async fn handle_component(ctx: &Context, ic: &ComponentInteraction, db: &PgP
                     ).await.ok();
                     return Ok(());
                 }
+                BuyRoleResult::Defaulted { owed } => {
+                    ic.edit_response(
+                        &ctx.http,
+                        EditInteractionResponse::new()
+                            .content(defaulted_msg(owed))
+                            .components(Vec::<CreateActionRow>::new()),
+                    ).await.ok();
+                    return Ok(());
+                }
             }
         }
         PanelOp::Gift => {
@@ -703,11 +722,18 @@ pub async fn handle_modal(_: &Context, _: &ModalInteraction, _: &PgPool) -> Resu
 // 💾 DB + logika zakupów
 // =======================================
 
+fn defaulted_msg(owed: i64) -> String {
+    format!("⛔ Masz pożyczkę po terminie (**{owed} TK**) — sklep odblokuje `/pozyczka splac`.")
+}
+
 enum BuyRoleResult {
     Ok { buyer_balance: i64, new_expires_at: DateTime<Utc> },
     InsufficientFunds { balance: i64 },
+    /// Kupujący ma pożyczkę po terminie.
+    Defaulted { owed: i64 },
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn buy_role_tx(
     db: &PgPool,
     buyer_id: i64,
@@ -716,73 +742,72 @@ async fn buy_role_tx(
     total_cost: i64,
     role_id: i64,
     guild_id: i64,
+    interaction_id: u64,
 ) -> Result<BuyRoleResult> {
+    // rola jest per serwer, ale płaci się z ekonomii, do której serwer należy
+    let eco = economy::resolve(db, GuildId::new(guild_id as u64)).await?;
     let mut tx = db.begin().await?;
 
-    sqlx::query(
-        r#"INSERT INTO users (id,balance) VALUES ($1,0),($2,0)
-           ON CONFLICT (id) DO NOTHING"#,
+    if let Some(owed) = loans::in_default(&mut *tx, eco, buyer_id as u64).await? {
+        tx.rollback().await?;
+        return Ok(BuyRoleResult::Defaulted { owed });
+    }
+
+    let reason = if buyer_id == target_id { "zakup roli" } else { "zakup roli (prezent)" };
+    let bal = match ledger::transfer(
+        &mut tx,
+        eco,
+        Account::User(buyer_id as u64),
+        Account::System("shop"),
+        total_cost,
+        &Entry { reason, command: "shop", interaction_id: Some(interaction_id) },
+        Overdraft::Forbid,
     )
-    .bind(buyer_id)
-    .bind(target_id)
-    .execute(&mut *tx)
-    .await?;
+    .await
+    {
+        Ok(posted) => posted.source_balance.unwrap_or_default(),
+        Err(e) => {
+            tx.rollback().await?;
+            return match ledger::insufficient(&e) {
+                Some(balance) => Ok(BuyRoleResult::InsufficientFunds { balance }),
+                None => Err(e),
+            };
+        }
+    };
 
-    let new_balance: Option<i64> = sqlx::query_scalar(
-        r#"UPDATE users
-           SET balance = balance - $1
-           WHERE id=$2 AND balance >= $1
-           RETURNING balance"#,
+    let now = Utc::now();
+    let current: Option<DateTime<Utc>> = sqlx::query_scalar(
+        r#"SELECT expires_at FROM role_subscriptions
+           WHERE user_id=$1 AND role_id=$2 AND guild_id=$3 AND active=true
+           FOR UPDATE"#,
     )
-    .bind(total_cost)
-    .bind(buyer_id)
+    .bind(target_id)
+    .bind(role_id)
+    .bind(guild_id)
     .fetch_optional(&mut *tx)
     .await?;
 
-    if let Some(bal) = new_balance {
-        let now = Utc::now();
-        let current: Option<DateTime<Utc>> = sqlx::query_scalar(
-            r#"SELECT expires_at FROM role_subscriptions
-               WHERE user_id=$1 AND role_id=$2 AND guild_id=$3 AND active=true
-               FOR UPDATE"#,
-        )
-        .bind(target_id)
-        .bind(role_id)
-        .bind(guild_id)
-        .fetch_optional(&mut *tx)
-        .await?;
-
-        let base = current.unwrap_or(now);
-        let base = if base > now { base } else { now };
-        let new_expires = base + Duration::days(config().days_per_unit * units);
-
-        sqlx::query(
-            r#"
-            INSERT INTO role_subscriptions (user_id, role_id, guild_id, expires_at, active)
-            VALUES ($1,$2,$3,$4,true)
-            ON CONFLICT (user_id,role_id,guild_id)
-            DO UPDATE SET expires_at = EXCLUDED.expires_at, active=true
-            "#,
-        )
-        .bind(target_id)
-        .bind(role_id)
-        .bind(guild_id)
-        .bind(new_expires)
-        .execute(&mut *tx)
-        .await?;
-
-        tx.commit().await?;
-        Ok(BuyRoleResult::Ok { buyer_balance: bal, new_expires_at: new_expires })
-    } else {
-        let balance: i64 = sqlx::query(r#"SELECT balance FROM users WHERE id=$1"#)
-            .bind(buyer_id)
-            .fetch_one(&mut *tx)
-            .await?
-            .try_get("balance")?;
+    let base = current.unwrap_or(now);
+    let base = if base > now { base } else { now };
+    let new_expires = base + Duration::days(config().days_per_unit * units);
 
-        tx.rollback().await?;
-        Ok(BuyRoleResult::InsufficientFunds { balance })
-    }
+    sqlx::query(
+        r#"
+        INSERT INTO role_subscriptions (user_id, role_id, guild_id, expires_at, active)
+        VALUES ($1,$2,$3,$4,true)
+        ON CONFLICT (user_id,role_id,guild_id)
+        DO UPDATE SET expires_at = EXCLUDED.expires_at, active=true
+        "#,
+    )
+    .bind(target_id)
+    .bind(role_id)
+    .bind(guild_id)
+    .bind(new_expires)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(BuyRoleResult::Ok { buyer_balance: bal, new_expires_at: new_expires })
 }
 
 async fn get_current_expiry(
@@ -803,33 +828,27 @@ async fn get_current_expiry(
     Ok(exp)
 }
 
-async fn expire_roles_tick(ctx: &Context, db: &PgPool, guild_id: GuildId) -> Result<()> {
-    let expired: Vec<(i64,)> = sqlx::query_as(
-        r#"SELECT user_id FROM role_subscriptions
-           WHERE guild_id=$1 AND active=true AND expires_at <= NOW()"#,
-    )
-    .bind(guild_id.get() as i64)
-    .fetch_all(db)
-    .await?;
-
-    if expired.is_empty() { return Ok(()); }
-
-    sqlx::query(
+/// Dezaktywuje wygasłe subskrypcje na wszystkich serwerach i zdejmuje role.
+/// Woła to zadanie harmonogramu (`jobs::subscriptions`). Zwraca liczbę zdjętych ról.
+pub(crate) async fn expire_subscriptions(http: &Http, db: &PgPool) -> Result<usize> {
+    let expired: Vec<(i64, i64)> = sqlx::query_as(
         r#"UPDATE role_subscriptions
            SET active=false
-           WHERE guild_id=$1 AND active=true AND expires_at <= NOW()"#,
+           WHERE active=true AND expires_at <= NOW()
+           RETURNING guild_id, user_id"#,
     )
-    .bind(guild_id.get() as i64)
-    .execute(db)
+    .fetch_all(db)
     .await?;
 
+    if expired.is_empty() { return Ok(0); }
+
     let removed_count = expired.len();
-    for (uid,) in expired {
-        ensure_role_removed(&ctx.http, guild_id, UserId::new(uid as u64)).await;
+    for (gid, uid) in expired {
+        ensure_role_removed(http, GuildId::new(gid as u64), UserId::new(uid as u64)).await;
     }
 
     log_embed(
-        &ctx.http,
+        http,
         CreateEmbed::new()
             .title("🧹 Subskrypcje: wygasłe role zdjęte")
             .description(format!("Usunięto rolę <@&{}> {} użytkownikom.", config().role_id.get(), removed_count))
@@ -837,35 +856,52 @@ async fn expire_roles_tick(ctx: &Context, db: &PgPool, guild_id: GuildId) -> Res
             .timestamp(Utc::now()),
     ).await;
 
-    Ok(())
+    Ok(removed_count)
 }
 
-async fn ensure_schema(db: &PgPool) -> Result<()> {
-    sqlx::query(
-        r#"
-        CREATE TABLE IF NOT EXISTS users (
-            id BIGINT PRIMARY KEY,
-            balance BIGINT NOT NULL DEFAULT 0,
-            last_work TIMESTAMPTZ,
-            last_slut TIMESTAMPTZ,
-            last_crime TIMESTAMPTZ,
-            last_rob TIMESTAMPTZ
-        );
-        "#,
-    ).execute(db).await?;
+// =======================
+// 🧭 Rejestr komend
+// =======================
 
-    sqlx::query(
-        r#"
-        CREATE TABLE IF NOT EXISTS role_subscriptions (
-            user_id BIGINT NOT NULL,
-            role_id BIGINT NOT NULL,
-            guild_id BIGINT NOT NULL,
-            expires_at TIMESTAMPTZ NOT NULL,
-            active BOOLEAN NOT NULL DEFAULT true,
-            PRIMARY KEY (user_id, role_id, guild_id)
-        );
-        "#,
-    ).execute(db).await?;
+pub struct ShopCommand;
 
-    Ok(())
+#[async_trait]
+impl BotCommand for ShopCommand {
+    fn name(&self) -> &'static str {
+        "shop"
+    }
+
+    fn economy(&self) -> bool {
+        true
+    }
+
+    fn aliases(&self) -> &'static [&'static str] {
+        &["tigrisshop"]
+    }
+
+    fn register(&self) -> CreateCommand {
+        let mut c = CreateCommand::new("shop");
+        register(&mut c);
+        c
+    }
+
+    async fn run(&self, ctx: &Context, cmd: &CommandInteraction, db: &PgPool) -> Result<()> {
+        run(ctx, cmd, db).await
+    }
+
+    fn component_prefixes(&self) -> &'static [&'static str] {
+        &["shop|", "shopgift|"]
+    }
+
+    async fn handle_component(&self, ctx: &Context, ic: &ComponentInteraction, db: &PgPool) -> Result<()> {
+        handle_component(ctx, ic, db).await
+    }
+
+    fn modal_prefixes(&self) -> &'static [&'static str] {
+        &["shop"]
+    }
+
+    async fn handle_modal(&self, ctx: &Context, mi: &ModalInteraction, db: &PgPool) -> Result<()> {
+        handle_modal(ctx, mi, db).await
+    }
 }
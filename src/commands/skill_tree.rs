@@ -0,0 +1,199 @@
+//! commands/skill_tree.rs — `/crime drzewko`: umiejętności złodzieja kupowane za wolne PP.
+//!
+//! Panel jest efemeryczny; każdy przycisk (`crime:tree:*`) czyta stan z bazy od nowa,
+//! więc stary panel nie sprzeda poziomu drugi raz. Reset wymaga potwierdzenia, bo kosztuje TK.
+
+use anyhow::Result;
+use serenity::all::{
+    ButtonStyle, CommandInteraction, CommandOptionType, ComponentInteraction, Context, CreateActionRow, CreateButton,
+    CreateCommandOption, CreateEmbed, CreateInteractionResponse, CreateInteractionResponseMessage,
+};
+use sqlx::PgPool;
+
+use crate::clock;
+use crate::economy;
+use crate::engine::config;
+use crate::engine::perks::{self, Branch, PERK_META};
+use crate::engine::types::PerkKey;
+use crate::skill_tree::{self, Learn, Respec, Tree};
+use crate::utils::log_action;
+
+// =================== Rejestracja ===================
+
+/// Podkomenda `/crime drzewko` (dopinana w `crime::register`).
+pub fn subcommand() -> CreateCommandOption {
+    CreateCommandOption::new(CommandOptionType::SubCommand, "drzewko", "Drzewko złodzieja — wydaj PP na umiejętności 🌳")
+}
+
+pub async fn open(ctx: &Context, cmd: &CommandInteraction, db: &PgPool) -> Result<()> {
+    let eco = economy::of(db, cmd.guild_id).await?;
+    let tree = skill_tree::load(db, eco, cmd.user.id.get()).await?;
+    let (embed, rows) = render(&tree, None, false);
+    cmd.create_response(
+        &ctx.http,
+        CreateInteractionResponse::Message(
+            CreateInteractionResponseMessage::new().ephemeral(true).embed(embed).components(rows),
+        ),
+    )
+    .await?;
+    Ok(())
+}
+
+// =================== Przyciski ===================
+
+pub async fn handle_component(ctx: &Context, mci: &ComponentInteraction, db: &PgPool) -> Result<()> {
+    let eco = economy::of(db, mci.guild_id).await?;
+    let user = mci.user.id.get();
+
+    // crime:tree:{action}[:perk]
+    let parts: Vec<&str> = mci.data.custom_id.split(':').collect();
+    let mut confirm = false;
+    let note = match (parts.get(2).copied(), parts.get(3).and_then(|k| from_key_perk(k))) {
+        (Some("learn"), Some(perk)) => {
+            let name = perks::perk_name(perk);
+            match skill_tree::learn(db, eco, user, perk).await? {
+                Learn::Maxed { max } => Some(format!("✨ **{name}** ma już maksymalny poziom ({max}).")),
+                Learn::Requires(req) => Some(format!("🔒 Najpierw weź **{}**.", perks::perk_name(req))),
+                Learn::NotEnoughPp { free, cost } => Some(format!("📉 Potrzeba **{cost} PP**, wolnych masz **{free}**.")),
+                Learn::Learned { rank, free } => {
+                    let desc = format!("Umiejętność: {name} → poziom {rank}");
+                    let _ = log_action(db, user, "perk_learn", None, None, Some(&desc)).await;
+                    Some(format!("🌱 **{name}** — poziom **{rank}**. Wolne PP: **{free}**."))
+                }
+            }
+        }
+        (Some("respec"), _) => {
+            confirm = true;
+            None
+        }
+        (Some("respec_ok"), _) => match skill_tree::respec(db, eco, user, Some(mci.id.get())).await? {
+            Respec::Empty => Some("🌱 Drzewko jest puste — nie ma czego resetować.".to_string()),
+            Respec::TooPoor { balance, cost } => Some(format!("💸 Reset kosztuje **{cost} TK**, a masz **{balance} TK**.")),
+            Respec::Done { refunded, cost, balance } => {
+                let desc = format!("Reset drzewka: zwrot {refunded} PP za {cost} TK");
+                let _ = log_action(db, user, "perk_respec", None, Some(-cost), Some(&desc)).await;
+                Some(format!("♻️ Drzewko zresetowane — wraca **{refunded} PP** za **{cost} TK**. Saldo: **{balance} TK**."))
+            }
+        },
+        (Some("cancel"), _) => None,
+        _ => return Ok(()),
+    };
+
+    let tree = skill_tree::load(db, eco, user).await?;
+    let (embed, rows) = render(&tree, note.as_deref(), confirm);
+    mci.create_response(
+        &ctx.http,
+        CreateInteractionResponse::UpdateMessage(CreateInteractionResponseMessage::new().embed(embed).components(rows)),
+    )
+    .await?;
+    Ok(())
+}
+
+// =================== Widok ===================
+
+fn pips(rank: u8, max: u8) -> String {
+    (0..max).map(|i| if i < rank { '▰' } else { '▱' }).collect()
+}
+
+fn render(tree: &Tree, note: Option<&str>, confirm_respec: bool) -> (CreateEmbed, Vec<CreateActionRow>) {
+    let table = &config::get().perks;
+
+    let mut e = CreateEmbed::new()
+        .title("🌳 Drzewko złodzieja")
+        .description(format!(
+            "Wolne PP: **{}** (zdobyte {}, wydane {}).\n\
+             Przedmioty i cele odblokowują się od zdobytych PP — wydawanie ich niczego nie zamyka.\n\
+             Umiejętności działają w napadach solo.",
+            tree.free(),
+            tree.pp,
+            tree.spent,
+        ))
+        .color(0x22c55e)
+        .timestamp(clock::now());
+
+    for branch in Branch::ALL {
+        let lines = PERK_META
+            .iter()
+            .filter(|(_, m)| m.branch == branch)
+            .map(|(k, m)| {
+                let t = table.get(*k);
+                let rank = perks::rank(&tree.ranks, *k);
+                let req = match m.requires {
+                    Some(r) if perks::rank(&tree.ranks, r) == 0 => format!(" • 🔒 wymaga: {}", perks::perk_name(r)),
+                    _ => String::new(),
+                };
+                format!(
+                    "**{}** {} {rank}/{}\n{} / poziom • {} PP{req}",
+                    m.name,
+                    pips(rank, t.max_rank),
+                    t.max_rank,
+                    perks::describe(*k),
+                    t.cost_pp,
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        e = e.field(branch.name(), lines, true);
+    }
+    if let Some(n) = note {
+        e = e.field("Ostatnio", n, false);
+    }
+
+    // wiersz 1: pierwsze umiejętności gałęzi, wiersz 2: drugie (z wymaganiem)
+    let learn_btn = |k: PerkKey| {
+        let t = table.get(k);
+        let rank = perks::rank(&tree.ranks, k);
+        let locked = perks::meta(k).requires.is_some_and(|r| perks::rank(&tree.ranks, r) == 0);
+        CreateButton::new(format!("crime:tree:learn:{}", key_perk(k)))
+            .label(format!("➕ {} ({} PP)", perks::perk_name(k), t.cost_pp))
+            .style(ButtonStyle::Primary)
+            .disabled(rank >= t.max_rank || locked || tree.free() < t.cost_pp)
+    };
+    let tier = |first: bool| {
+        CreateActionRow::Buttons(
+            PERK_META.iter().filter(|(_, m)| m.requires.is_none() == first).map(|(k, _)| learn_btn(*k)).collect(),
+        )
+    };
+
+    let cost = skill_tree::respec_cost(tree.spent);
+    let respec = if confirm_respec {
+        vec![
+            CreateButton::new("crime:tree:respec_ok")
+                .label(format!("✅ Potwierdź reset ({cost} TK)"))
+                .style(ButtonStyle::Danger),
+            CreateButton::new("crime:tree:cancel").label("✖️ Anuluj").style(ButtonStyle::Secondary),
+        ]
+    } else {
+        vec![CreateButton::new("crime:tree:respec")
+            .label(format!("♻️ Reset drzewka ({cost} TK)"))
+            .style(ButtonStyle::Secondary)
+            .disabled(tree.spent == 0)]
+    };
+
+    (e, vec![tier(true), tier(false), CreateActionRow::Buttons(respec)])
+}
+
+// =================== Klucze ===================
+
+pub(crate) fn from_key_perk(k: &str) -> Option<PerkKey> {
+    Some(match k {
+        "shadow_step" => PerkKey::ShadowStep,
+        "lookout" => PerkKey::Lookout,
+        "photographic_memory" => PerkKey::PhotographicMemory,
+        "reflexes" => PerkKey::Reflexes,
+        "thick_skin" => PerkKey::ThickSkin,
+        "heavy_hands" => PerkKey::HeavyHands,
+        _ => return None,
+    })
+}
+
+pub(crate) fn key_perk(k: PerkKey) -> &'static str {
+    match k {
+        PerkKey::ShadowStep         => "shadow_step",
+        PerkKey::Lookout            => "lookout",
+        PerkKey::PhotographicMemory => "photographic_memory",
+        PerkKey::Reflexes           => "reflexes",
+        PerkKey::ThickSkin          => "thick_skin",
+        PerkKey::HeavyHands         => "heavy_hands",
+    }
+}
@@ -3,34 +3,46 @@
 use anyhow::{Context as AnyhowContext, Result};
 use chrono::{DateTime, Duration, Utc};
 use once_cell::sync::OnceCell as SyncOnceCell;
-use rand::{rng, Rng};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
 use serenity::all::*;
 use serenity::builder::{
     CreateActionRow, CreateButton, CreateCommand, CreateEmbed, CreateEmbedAuthor,
     CreateEmbedFooter, CreateInteractionResponse, CreateInteractionResponseMessage, CreateMessage,
-    EditInteractionResponse,
 };
+use serenity::async_trait;
 use sqlx::PgPool;
 
 use num_format::{Locale, ToFormattedString};
-use tokio::sync::OnceCell as AsyncOnceCell;
 
+use crate::clock;
+use crate::cooldowns;
+use crate::economy::{self, Economy};
+use crate::engine::config::{self, FlirtStyle};
+use crate::reminders;
+use crate::rng::{self, Roll};
+use crate::ledger::{self, Entry, Overdraft};
+use crate::loans::{self, Garnished};
 use crate::utils::log_action;
+use crate::commands::loan::garnish_text;
+use crate::commands::registry::{BotCommand, CooldownMeta};
 
 // ========================
 // ⚙️ Konfiguracja
 // ========================
 
-/// Cooldown w sekundach
-const CD_SECS: i64 = 30;
-/// 📱 Numer – rzadki drop
-const RARE_DROP_BONUS: i64 = 150;
+pub(crate) const COOLDOWN_KEY: &str = "slut";
+
+/// Cooldown (długość: `cooldowns.slut_secs` z tabel balansu)
+pub(crate) fn cooldown_meta() -> CooldownMeta {
+    CooldownMeta { key: COOLDOWN_KEY, secs: config::get().cooldowns.slut_secs, heat_penalty: false }
+}
+
 /// Minimalna/maksymalna reputacja
 const REP_MIN: i32 = -100;
 const REP_MAX: i32 = 100;
 
-/// Ile „części szansy” daje 1 punkt reputacji (±0.08)
-const REP_POINT_BONUS: f32 = 0.0008;
+// szanse, widełki, serie i 📱 rzadki drop: tabele balansu (`config::SlutTables`)
 
 // Cache kanału logów
 static LOG_CHAN: SyncOnceCell<Option<ChannelId>> = SyncOnceCell::new();
@@ -44,9 +56,6 @@ fn log_channel_id() -> Option<ChannelId> {
     })
 }
 
-// ensure_schema tylko raz
-static ENSURE_SCHEMA_ONCE: AsyncOnceCell<()> = AsyncOnceCell::const_new();
-
 // ========================
 // 🧾 Rejestracja
 // ========================
@@ -59,26 +68,20 @@ pub fn register() -> CreateCommand {
 // 🎭 Style flirtu
 // ========================
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum Approach {
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) enum Approach {
     Gentle,  // stabilne
     Daring,  // balans
     Chaotic, // hazard
 }
 
 impl Approach {
+    pub(crate) const ALL: [Approach; 3] = [Approach::Gentle, Approach::Daring, Approach::Chaotic];
+
     const GENTLE_ID: &str = "slut:gentle";
     const DARING_ID: &str = "slut:daring";
     const CHAOTIC_ID: &str = "slut:chaotic";
 
-    fn id(self) -> &'static str {
-        match self {
-            Self::Gentle => Self::GENTLE_ID,
-            Self::Daring => Self::DARING_ID,
-            Self::Chaotic => Self::CHAOTIC_ID,
-        }
-    }
-
     fn from_id(id: &str) -> Option<Self> {
         match id {
             Self::GENTLE_ID => Some(Self::Gentle),
@@ -102,11 +105,8 @@ impl Approach {
 // ========================
 
 pub async fn run(ctx: &Context, cmd: &CommandInteraction, db: &PgPool) -> Result<()> {
-    ENSURE_SCHEMA_ONCE
-        .get_or_try_init(|| async { ensure_schema(db).await?; Ok::<(), anyhow::Error>(()) })
-        .await?;
-
-    if let Some(rem) = current_cd(db, cmd.user.id.get() as i64).await? {
+    let eco = economy::of(db, cmd.guild_id).await?;
+    if let Some(rem) = cooldowns::remaining(db, eco, cmd.user.id.get(), COOLDOWN_KEY).await? {
         let emb = build_cd_embed(&cmd.user, rem);
         cmd.create_response(
             &ctx.http,
@@ -176,9 +176,10 @@ pub async fn handle_component(
     let user = &ic.user;
     let uid_u64 = user.id.get();
     let uid_i64 = uid_u64 as i64;
+    let eco = economy::of(db, ic.guild_id).await?;
 
     // CD/expired?
-    if let Some(_rem) = current_cd(db, uid_i64).await? {
+    if cooldowns::remaining(db, eco, uid_u64, COOLDOWN_KEY).await?.is_some() {
         ic.create_response(
             &ctx.http,
             CreateInteractionResponse::Message(
@@ -192,7 +193,8 @@ pub async fn handle_component(
     }
 
     // wynik flirtu
-    let out = match process_flirt(db, uid_i64, style).await {
+    let guild = ic.guild_id.context("Brak serwera")?;
+    let out = match process_flirt(db, eco, guild, uid_i64, style, ic.id.get()).await {
         Ok(o) => o,
         Err(e) => {
             eprintln!("process_flirt error: {e:?}");
@@ -246,21 +248,9 @@ pub async fn handle_component(
         });
     }
 
-    // Po cooldownie — gotowy embed + przyciski
-    {
-        let ctx_c = ctx.clone();
-        let ic_c = ic.clone();
-        let u_c = user.clone();
-        tokio::spawn(async move {
-            tokio::time::sleep(std::time::Duration::from_secs(CD_SECS as u64)).await;
-            let (ready_embed, rows) = build_cd_ready_with_buttons(&u_c);
-            let _ = ic_c
-                .edit_response(
-                    &ctx_c.http,
-                    EditInteractionResponse::new().embed(ready_embed).components(rows),
-                )
-                .await;
-        });
+    // przypomnienie o końcu CD (doręcza harmonogram — przeżywa restart)
+    if let Err(e) = reminders::schedule(db, guild, ic.channel_id, uid_u64, COOLDOWN_KEY, "/slut", out.ready_at).await {
+        eprintln!("❌ /slut: nie zapisano przypomnienia: {e:?}");
     }
 
     Ok(())
@@ -273,7 +263,6 @@ pub async fn handle_component(
 #[derive(sqlx::FromRow)]
 struct UserRow {
     balance: i64,
-    last_slut: Option<DateTime<Utc>>,
     flirt_rep: i32,
     flirt_streak: i32,
     flirt_fails: i32,
@@ -292,110 +281,111 @@ struct Outcome {
     streak_after: i32,
     multiplier: f32,
     now: DateTime<Utc>,
+    ready_at: DateTime<Utc>,
     balance_after: i64,
-}
-
-async fn ensure_schema(db: &PgPool) -> Result<()> {
-    sqlx::query(
-        r#"
-        CREATE TABLE IF NOT EXISTS users(
-          id BIGINT PRIMARY KEY,
-          balance BIGINT NOT NULL DEFAULT 0,
-          last_work TIMESTAMPTZ,
-          last_slut TIMESTAMPTZ,
-          flirt_rep INTEGER NOT NULL DEFAULT 0,
-          flirt_streak INTEGER NOT NULL DEFAULT 0,
-          flirt_fails INTEGER NOT NULL DEFAULT 0
-        );
-        "#,
-    )
-    .execute(db)
-    .await
-    .context("creating users table")?;
-
-    sqlx::query(
-        r#"
-        CREATE TABLE IF NOT EXISTS logs(
-          id BIGSERIAL PRIMARY KEY,
-          user_id BIGINT NOT NULL,
-          action TEXT NOT NULL,
-          amount BIGINT,
-          message TEXT,
-          meta JSONB,
-          created_at TIMESTAMPTZ NOT NULL DEFAULT now()
-        );
-        "#,
-    )
-    .execute(db)
-    .await
-    .ok();
-
-    Ok(())
-}
-
-async fn current_cd(db: &PgPool, uid: i64) -> Result<Option<i64>> {
-    let rem: Option<i64> = sqlx::query_scalar(
-        r#"
-        SELECT GREATEST(0, $1 - EXTRACT(EPOCH FROM (now() - last_slut))::BIGINT)
-          FROM users WHERE id = $2
-        "#,
-    )
-    .bind(CD_SECS)
-    .bind(uid)
-    .fetch_optional(db)
-    .await?;
-    Ok(rem.filter(|v| *v > 0))
+    garnished: Option<Garnished>, // potrącenie na spłatę pożyczki
 }
 
 fn rep_bonus_percent(rep: i32) -> f32 {
-    (rep as f32 * REP_POINT_BONUS * 100.0).clamp(-8.0, 8.0)
+    let per_point = config::get().slut.rep_point_bonus * 100.0;
+    let max = REP_MAX as f32 * per_point;
+    (rep as f32 * per_point).clamp(-max, max)
 }
 
 #[inline]
-fn clamp_rep(r: i32) -> i32 {
+pub(crate) fn clamp_rep(r: i32) -> i32 {
     r.clamp(REP_MIN, REP_MAX)
 }
 
 #[inline]
 fn streak_mult(streak: i32) -> f32 {
-    (1.0 + (streak as f32) * 0.06).clamp(1.0, 1.60)
+    let t = &config::get().slut;
+    (1.0 + (streak as f32) * t.streak_step).clamp(1.0, t.streak_cap)
 }
 
-fn base_for(style: Approach, rng: &mut impl Rng) -> (i64, i32, &'static str) {
-    match style {
-        Approach::Gentle => (rng.random_range(30..=80) as i64, 2, "Delikatny urok działa."),
-        Approach::Daring => (rng.random_range(50..=140) as i64, 3, "Śmiały krok robi wrażenie."),
-        Approach::Chaotic => (rng.random_range(0..=200) as i64, 4, "Chaotyczna energia przyciąga."),
+/// Wejście losowania flirtu (zapisywane w `rolls`).
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub(crate) struct FlirtInput {
+    pub(crate) style: Approach,
+    pub(crate) rep: i32,
+    pub(crate) fails: i32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct FlirtRoll {
+    pub(crate) success: bool,
+    base_tk: i64,
+    rep_succ: i32,
+    base_msg: &'static str,
+    rare: bool,
+}
+
+impl FlirtRoll {
+    pub(crate) fn rep_delta(&self) -> i32 {
+        if self.success { self.rep_succ } else { config::get().slut.fail_rep }
     }
 }
 
-fn success_chance(style: Approach, rep: i32) -> f32 {
-    let base = match style {
-        Approach::Gentle => 0.70,
-        Approach::Daring => 0.55,
-        Approach::Chaotic => 0.45,
-    };
-    let rep_boost = (rep as f32) * REP_POINT_BONUS;
-    (base + rep_boost).clamp(0.10, 0.95)
+/// Rozbicie wypłaty flirtu: część „za robotę” (porażka = −połowa bazy) i premie stałe.
+pub(crate) struct FlirtPayout {
+    pub(crate) mult: f32,
+    pub(crate) work_part: i64,
+    pub(crate) flat_bonus: i64,
 }
 
-fn series_bonus(streak_after: i32) -> i64 {
-    match streak_after {
-        s if s >= 15 => 50,
-        s if s >= 10 => 25,
-        s if s >= 5 => 10,
-        _ => 0,
+pub(crate) fn flirt_payout(style: Approach, roll: &FlirtRoll, streak_after: i32) -> FlirtPayout {
+    if !roll.success {
+        return FlirtPayout { mult: 1.0, work_part: -(roll.base_tk / 2), flat_bonus: 0 };
+    }
+    let mult = streak_mult(streak_after);
+    let rare_bonus = if roll.rare { config::get().slut.rare_drop_bonus } else { 0 };
+    FlirtPayout {
+        mult,
+        work_part: ((roll.base_tk as f32) * mult * style_table(style).pay_mult).round() as i64,
+        flat_bonus: series_bonus(streak_after) + rare_bonus,
     }
 }
 
-fn style_tweak(style: Approach) -> f32 {
+pub(crate) fn roll_flirt(input: &FlirtInput, rng: &mut impl Rng) -> FlirtRoll {
+    let t = &config::get().slut;
+    let pity = input.fails >= t.pity_after_fails;
+    let chance = if pity { 1.0 } else { success_chance(input.style, input.rep) };
+    let success = rng.random_bool(chance as f64);
+    let (base_tk, rep_succ, base_msg) = base_for(input.style, rng);
+    let rare = success && rng.random_bool(t.rare_chance);
+    FlirtRoll { success, base_tk, rep_succ, base_msg, rare }
+}
+
+/// Wiersz tabeli balansu dla stylu.
+fn style_table(style: Approach) -> FlirtStyle {
+    let t = &config::get().slut;
     match style {
-        Approach::Gentle => 0.95,
-        Approach::Daring => 1.00,
-        Approach::Chaotic => 1.05,
+        Approach::Gentle => t.gentle,
+        Approach::Daring => t.daring,
+        Approach::Chaotic => t.chaotic,
     }
 }
 
+fn base_for(style: Approach, rng: &mut impl Rng) -> (i64, i32, &'static str) {
+    let t = style_table(style);
+    let msg = match style {
+        Approach::Gentle => "Delikatny urok działa.",
+        Approach::Daring => "Śmiały krok robi wrażenie.",
+        Approach::Chaotic => "Chaotyczna energia przyciąga.",
+    };
+    (rng.random_range(t.pay[0]..=t.pay[1]) as i64, t.rep_gain, msg)
+}
+
+fn success_chance(style: Approach, rep: i32) -> f32 {
+    let t = &config::get().slut;
+    let rep_boost = (rep as f32) * t.rep_point_bonus;
+    (style_table(style).chance + rep_boost).clamp(t.min_chance, t.max_chance)
+}
+
+fn series_bonus(streak_after: i32) -> i64 {
+    config::get().slut.series.iter().rev().find(|s| streak_after >= s.from).map_or(0, |s| s.flat)
+}
+
 fn rep_bar(rep: i32) -> String {
     let width = 10;
     let normalized = ((rep - REP_MIN) as f32) / ((REP_MAX - REP_MIN) as f32);
@@ -403,80 +393,105 @@ fn rep_bar(rep: i32) -> String {
     bar("💞", fill, width) + &format!(" | {:+}", rep)
 }
 
-async fn process_flirt(db: &PgPool, uid: i64, style: Approach) -> Result<Outcome> {
+async fn process_flirt(
+    db: &PgPool,
+    eco: Economy,
+    guild: GuildId,
+    uid: i64,
+    style: Approach,
+    interaction_id: u64,
+) -> Result<Outcome> {
     let mut tx = db.begin().await?;
 
     // insert jeśli brak
-    sqlx::query("INSERT INTO users(id) VALUES($1) ON CONFLICT DO NOTHING")
-        .bind(uid)
-        .execute(&mut *tx)
-        .await?;
+    ledger::ensure_user(&mut tx, eco, uid as u64).await?;
 
     let mut u: UserRow = sqlx::query_as(
         r#"
-        SELECT balance, last_slut, flirt_rep, flirt_streak, flirt_fails
+        SELECT balance, flirt_rep, flirt_streak, flirt_fails
           FROM users
-         WHERE id = $1
+         WHERE guild_id = $1 AND id = $2
          FOR UPDATE
         "#,
     )
+    .bind(eco.id())
     .bind(uid)
     .fetch_one(&mut *tx)
     .await?;
 
-    let now = Utc::now();
-    if let Some(last) = u.last_slut {
-        if (now - last).num_seconds() < CD_SECS {
-            tx.rollback().await?;
-            return Ok(Outcome {
-                success: false,
-                amount: 0,
-                work_part: 0,
-                flat_bonus: 0,
-                rare_drop: false,
-                message: "⏳ Cooldown jeszcze trwa…".into(),
-                rep_delta: 0,
-                rep_after: u.flirt_rep,
-                streak_after: u.flirt_streak,
-                multiplier: 1.0,
-                now,
-                balance_after: u.balance,
-            });
-        }
+    let now = clock::now();
+    if let Some(left) = cooldowns::remaining(&mut *tx, eco, uid as u64, COOLDOWN_KEY).await? {
+        tx.rollback().await?;
+        return Ok(Outcome {
+            success: false,
+            amount: 0,
+            work_part: 0,
+            flat_bonus: 0,
+            rare_drop: false,
+            message: "⏳ Cooldown jeszcze trwa…".into(),
+            rep_delta: 0,
+            rep_after: u.flirt_rep,
+            streak_after: u.flirt_streak,
+            multiplier: 1.0,
+            now,
+            ready_at: now + Duration::seconds(left),
+            balance_after: u.balance,
+            garnished: None,
+        });
     }
 
-    // Losowania w krótkim bloku
-    let (success, base_tk, rep_succ, base_msg, rare) = {
-        let mut r = rng();
-        let pity = u.flirt_fails >= 3;
-        let chance = if pity { 1.0 } else { success_chance(style, u.flirt_rep) };
-        let success = r.random_bool(chance as f64);
-        let (base_tk, rep_succ, base_msg) = base_for(style, &mut r);
-        let rare = success && r.random_bool(0.03);
-        (success, base_tk, rep_succ, base_msg, rare)
-    };
+    // Losowanie z zapisanym ziarnem (powtarzalne w /admcontrol rzut)
+    let input = FlirtInput { style, rep: u.flirt_rep, fails: u.flirt_fails };
+    let (seed, mut r) = rng::seeded();
+    let roll = roll_flirt(&input, &mut r);
+    rng::record(
+        &mut *tx,
+        eco,
+        &Roll { command: "slut", user: uid as u64, interaction_id: Some(interaction_id), seed },
+        &input,
+        &roll,
+    )
+    .await?;
+    let FlirtRoll { success, base_msg, rare, .. } = roll;
 
     // Obliczenia bez RNG
-    let rep_fail = -3;
-    let rep_delta = if success { rep_succ } else { rep_fail };
+    let rep_delta = roll.rep_delta();
     let streak_after = if success { u.flirt_streak + 1 } else { 0 };
-    let mult = if success { streak_mult(streak_after) } else { 1.0 };
-
-    let rare_bonus = if rare { RARE_DROP_BONUS } else { 0 };
-    let s_bonus = if success { series_bonus(streak_after) } else { 0 };
+    let FlirtPayout { mult, work_part, flat_bonus } = flirt_payout(style, &roll, streak_after);
+    let amount = work_part + flat_bonus;
 
-    let work_part = if success {
-        ((base_tk as f32) * mult * style_tweak(style)).round() as i64
-    } else {
-        -(base_tk / 2)
-    };
+    // saldo przez księgę — nieudany flirt może zejść pod zero (jak dotąd)
+    u.balance = ledger::settle(
+        &mut tx,
+        eco,
+        uid as u64,
+        "slut",
+        amount,
+        &Entry {
+            reason: if success { "udany flirt" } else { "nieudany flirt" },
+            command: "slut",
+            interaction_id: Some(interaction_id),
+        },
+        Overdraft::Allow,
+    )
+    .await?;
 
-    let flat_bonus = s_bonus + rare_bonus;
-    let amount = work_part + flat_bonus;
+    // potrącenie na spłatę pożyczki (tylko z zarobku)
+    let garnished = loans::garnish(
+        &mut tx,
+        eco,
+        uid as u64,
+        amount,
+        u.balance,
+        &Entry { reason: "spłata pożyczki z flirtu", command: "slut", interaction_id: Some(interaction_id) },
+    )
+    .await?;
+    if let Some(g) = garnished {
+        u.balance = g.wallet;
+    }
 
-    // update usera
-    u.balance += amount;
-    u.last_slut = Some(now);
+    // update usera + cooldown
+    let ready_at = cooldowns::start(&mut tx, eco, guild, uid as u64, cooldown_meta()).await?;
     u.flirt_rep = clamp_rep(u.flirt_rep + rep_delta);
     u.flirt_streak = streak_after;
     u.flirt_fails = if success { 0 } else { u.flirt_fails + 1 };
@@ -484,16 +499,15 @@ async fn process_flirt(db: &PgPool, uid: i64, style: Approach) -> Result<Outcome
     sqlx::query(
         r#"
         UPDATE users
-           SET balance=$2, last_slut=$3, flirt_rep=$4, flirt_streak=$5, flirt_fails=$6
-         WHERE id=$1
+           SET flirt_rep=$2, flirt_streak=$3, flirt_fails=$4
+         WHERE guild_id=$5 AND id=$1
         "#,
     )
     .bind(uid)
-    .bind(u.balance)
-    .bind(now)
     .bind(u.flirt_rep)
     .bind(u.flirt_streak)
     .bind(u.flirt_fails)
+    .bind(eco.id())
     .execute(&mut *tx)
     .await?;
 
@@ -517,7 +531,9 @@ async fn process_flirt(db: &PgPool, uid: i64, style: Approach) -> Result<Outcome
         streak_after,
         multiplier: mult,
         now,
+        ready_at,
         balance_after: u.balance,
+        garnished,
     })
 }
 
@@ -539,18 +555,6 @@ fn build_choice_row() -> CreateActionRow {
     ])
 }
 
-fn build_cd_ready_with_buttons(user: &User) -> (CreateEmbed, Vec<CreateActionRow>) {
-    let embed = CreateEmbed::new()
-        .color(0x2ECC71)
-        .author(
-            CreateEmbedAuthor::new(&user.name).icon_url(user.avatar_url().unwrap_or_default()),
-        )
-        .title("✅ Cooldown zakończony")
-        .description(format!("{} – wybierz styl flirtu:", user.mention()))
-        .timestamp(Utc::now());
-    (embed, vec![build_choice_row()])
-}
-
 fn fmt_tk(n: i64) -> String {
     format!("{} TK", n.to_formatted_string(&Locale::pl))
 }
@@ -605,9 +609,8 @@ fn format_breakdown(o: &Outcome) -> String {
 // ========================
 
 fn outcome_embed_ultra(user: &User, o: &Outcome, style: Approach) -> CreateEmbed {
-    let next_at = o.now + Duration::seconds(CD_SECS);
-    let next_unix = next_at.timestamp();
-    let remain = (next_at - o.now).num_seconds().max(0);
+    let next_unix = o.ready_at.timestamp();
+    let remain = (o.ready_at - o.now).num_seconds().max(0);
 
     let color = if o.success { 0x00C853 } else { 0xD50000 };
     let chance_now = success_chance(style, o.rep_after) * 100.0;
@@ -618,7 +621,7 @@ fn outcome_embed_ultra(user: &User, o: &Outcome, style: Approach) -> CreateEmbed
         format!("**{}**", fmt_tk(o.amount))
     };
 
-    CreateEmbed::new()
+    let embed = CreateEmbed::new()
         .color(color)
         .author(
             CreateEmbedAuthor::new(&user.name).icon_url(user.avatar_url().unwrap_or_default()),
@@ -657,11 +660,17 @@ fn outcome_embed_ultra(user: &User, o: &Outcome, style: Approach) -> CreateEmbed
             true,
         )
         .footer(CreateEmbedFooter::new(format!(
-            "Styl: {} • +{} rep przy sukcesie, porażka −3",
+            "Styl: {} • +{} rep przy sukcesie, porażka {}",
             style_name(style),
-            rep_gain_for(style)
+            style_table(style).rep_gain,
+            config::get().slut.fail_rep
         )))
-        .timestamp(o.now)
+        .timestamp(o.now);
+
+    match o.garnished {
+        Some(g) => embed.field("💳 Spłata pożyczki", garnish_text(&g), true),
+        None => embed,
+    }
 }
 
 fn style_name(style: Approach) -> &'static str {
@@ -672,14 +681,6 @@ fn style_name(style: Approach) -> &'static str {
     }
 }
 
-fn rep_gain_for(style: Approach) -> i32 {
-    match style {
-        Approach::Gentle => 2,
-        Approach::Daring => 3,
-        Approach::Chaotic => 4,
-    }
-}
-
 // ========================
 // 🛰️ Log kanałowy
 // ========================
@@ -728,3 +729,48 @@ async fn send_log(
         .context("sending log message")?;
     Ok(())
 }
+
+// =======================
+// 🧭 Rejestr komend
+// =======================
+
+pub struct SlutCommand;
+
+#[async_trait]
+impl BotCommand for SlutCommand {
+    fn name(&self) -> &'static str {
+        "slut"
+    }
+
+    fn economy(&self) -> bool {
+        true
+    }
+
+    fn earning(&self, _sub: Option<&str>) -> bool {
+        true
+    }
+
+    fn register(&self) -> CreateCommand {
+        register()
+    }
+
+    async fn run(&self, ctx: &Context, cmd: &CommandInteraction, db: &PgPool) -> Result<()> {
+        run(ctx, cmd, db).await
+    }
+
+    fn replay(&self, kind: &str, seed: u64, inputs: &str) -> Option<Result<serde_json::Value>> {
+        (kind == "slut").then(|| rng::replay(seed, inputs, roll_flirt))
+    }
+
+    fn component_prefixes(&self) -> &'static [&'static str] {
+        &["slut:"]
+    }
+
+    async fn handle_component(&self, ctx: &Context, ic: &ComponentInteraction, db: &PgPool) -> Result<()> {
+        handle_component(ctx, ic, db).await
+    }
+
+    fn cooldown(&self) -> Option<CooldownMeta> {
+        Some(cooldown_meta())
+    }
+}
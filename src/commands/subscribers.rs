@@ -4,8 +4,11 @@ use serenity::all::*;
 use serenity::builder::{
     CreateCommand, CreateEmbed, CreateInteractionResponse, CreateInteractionResponseMessage,
 };
+use serenity::async_trait;
 use sqlx::PgPool;
 
+use crate::commands::registry::BotCommand;
+
 /// Rejestracja komendy `/subskrypcje`
 pub fn register(cmd: &mut CreateCommand) -> &mut CreateCommand {
     *cmd = CreateCommand::new("subskrypcje")
@@ -89,3 +92,26 @@ pub async fn run(ctx: &Context, cmd: &CommandInteraction, db: &PgPool) -> Result
 
     Ok(())
 }
+
+// =======================
+// 🧭 Rejestr komend
+// =======================
+
+pub struct SubscribersCommand;
+
+#[async_trait]
+impl BotCommand for SubscribersCommand {
+    fn name(&self) -> &'static str {
+        "subskrypcje"
+    }
+
+    fn register(&self) -> CreateCommand {
+        let mut c = CreateCommand::new("subskrypcje");
+        register(&mut c);
+        c
+    }
+
+    async fn run(&self, ctx: &Context, cmd: &CommandInteraction, db: &PgPool) -> Result<()> {
+        run(ctx, cmd, db).await
+    }
+}
@@ -6,22 +6,37 @@ use anyhow::{Context as _, Result};
 use chrono::{DateTime, Duration, Utc};
 use once_cell::sync::{Lazy, OnceCell as SyncOnceCell};
 use rand::Rng;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use serenity::all::*;
 use serenity::builder::{CreateCommand, CreateInteractionResponseMessage};
+use serenity::async_trait;
 use sqlx::PgPool;
 
 use num_format::{Locale, ToFormattedString};
-use tokio::sync::OnceCell as AsyncOnceCell;
 
+use crate::clock;
+use crate::cooldowns;
+use crate::economy::{self, Economy};
+use crate::engine::config;
+use crate::reminders;
+use crate::rng::{self, Roll};
+use crate::ledger::{self, Entry, Overdraft};
+use crate::loans::{self, Garnished};
 use crate::utils::log_action;
+use crate::commands::loan::garnish_text;
+use crate::commands::registry::{BotCommand, CooldownMeta};
 
 // ========================
 // ⚙️ Konfiguracja i dane
 // ========================
 
 const TEXTS_JSON: &str = include_str!("../../texts.json");
-const COOLDOWN_SECS: i64 = 30;
+pub(crate) const COOLDOWN_KEY: &str = "work";
+
+/// Długość: `cooldowns.work_secs` z tabel balansu.
+pub(crate) fn cooldown_meta() -> CooldownMeta {
+    CooldownMeta { key: COOLDOWN_KEY, secs: config::get().cooldowns.work_secs, heat_penalty: false }
+}
 
 // stałe dla custom_id przycisków
 const BTN_SAFE: &str = "work:choose:safe";
@@ -61,9 +76,6 @@ fn log_channel_id() -> Option<ChannelId> {
     })
 }
 
-// Jednorazowe ensure_schema na proces
-static ENSURE_SCHEMA_ONCE: AsyncOnceCell<()> = AsyncOnceCell::const_new();
-
 // ========================
 // 🧾 Rejestracja komendy
 // ========================
@@ -78,14 +90,16 @@ pub fn register(cmd: &mut CreateCommand) -> &mut CreateCommand {
 // 🔀 Kontrakty pracy
 // ========================
 
-#[derive(Debug, Clone, Copy)]
-enum WorkChoice {
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub(crate) enum WorkChoice {
     Safe,      // stała wypłata low
     Balanced,  // średnia z lekkim ryzykiem
     HighRisk,  // wysoka z dużym ryzykiem
 }
 
 impl WorkChoice {
+    pub(crate) const ALL: [WorkChoice; 3] = [WorkChoice::Safe, WorkChoice::Balanced, WorkChoice::HighRisk];
+
     fn from_custom_id(s: &str) -> Option<Self> {
         match s {
             BTN_SAFE => Some(Self::Safe),
@@ -109,19 +123,11 @@ impl WorkChoice {
 // ========================
 
 pub async fn run(ctx: &Context, cmd: &CommandInteraction, db: &PgPool) -> Result<()> {
-    // Schemat odpalany tylko raz
-    ENSURE_SCHEMA_ONCE
-        .get_or_try_init(|| async {
-            ensure_schema(db).await?;
-            Ok::<(), anyhow::Error>(())
-        })
-        .await?;
-
     let user = &cmd.user;
+    let eco = economy::of(db, cmd.guild_id).await?;
 
     // Sprawdź tylko cooldown – bez wypłaty jeszcze
-    let cd = current_cooldown(db, user.id.get() as i64, COOLDOWN_SECS).await?;
-    if cd > 0 {
+    if let Some(cd) = cooldowns::remaining(db, eco, user.id.get(), COOLDOWN_KEY).await? {
         let embed = build_cooldown_embed(user, cd);
         return send_embed(ctx, cmd, embed).await;
     }
@@ -156,9 +162,10 @@ pub async fn handle_component(ctx: &Context, ic: &ComponentInteraction, db: &PgP
         return Ok(());
     };
     let user = &ic.user;
+    let eco = economy::of(db, ic.guild_id).await?;
 
     // szybki check cooldownu
-    if current_cooldown(db, user.id.get() as i64, COOLDOWN_SECS).await? > 0 {
+    if cooldowns::remaining(db, eco, user.id.get(), COOLDOWN_KEY).await?.is_some() {
         ic.create_response(
             &ctx.http,
             CreateInteractionResponse::Message(
@@ -171,8 +178,9 @@ pub async fn handle_component(ctx: &Context, ic: &ComponentInteraction, db: &PgP
     }
 
     // wynik transakcji
-    let WorkOutcome { amount, message, place, new_balance, now, streak, multiplier } =
-        process_work_tx(db, user.id.get() as i64, choice).await?;
+    let guild = ic.guild_id.context("Brak serwera")?;
+    let WorkOutcome { amount, message, place, new_balance, now, ready_at, streak, multiplier, garnished } =
+        process_work_tx(db, eco, guild, user.id.get() as i64, choice, ic.id.get()).await?;
 
     // paski + opis bonusu (prezentacja)
     let streak_total = 10;
@@ -181,8 +189,8 @@ pub async fn handle_component(ctx: &Context, ic: &ComponentInteraction, db: &PgP
     let bonus_bar = bonus_progress_bar(streak);
     let bonus_text = bonus_series_text(streak);
 
-    // płaski bonus +TK z progów 5/10/15
-    let extra = bonus_flat_for_tier(bonus_tier(streak));
+    // płaski bonus +TK z progów serii
+    let extra = series_flat(streak);
     let work_part = amount.saturating_sub(extra); // ile z samej pracy (po mnożniku)
     let work_part_fmt = format!("{} TK", format_tk(work_part));
     let extra_fmt = format!("{} TK", format_tk(extra));
@@ -190,14 +198,14 @@ pub async fn handle_component(ctx: &Context, ic: &ComponentInteraction, db: &PgP
 
     // zbuduj embed zależnie od wyniku
     let mut embed = if amount == 0 {
-        build_fail_embed(user, &message, &place, new_balance, now, false)
+        build_fail_embed(user, &message, &place, new_balance, now, ready_at)
             .field(
                 "🎯 Kontrakt",
                 format!("{} {}", contract_emoji(Some(choice)), choice.label()),
                 true,
             )
     } else {
-        build_result_embed(user, amount, &message, &place, new_balance, now, false)
+        build_result_embed(user, amount, &message, &place, new_balance, now, ready_at)
             .field(
                 "🎯 Kontrakt",
                 format!("{} {}", contract_emoji(Some(choice)), choice.label()),
@@ -224,6 +232,9 @@ pub async fn handle_component(ctx: &Context, ic: &ComponentInteraction, db: &PgP
     if amount > 0 && extra > 0 {
         embed = embed.field("🎁 Bonus tej zmiany", format!("**+{} TK**", extra), true);
     }
+    if let Some(g) = garnished {
+        embed = embed.field("💳 Spłata pożyczki", garnish_text(&g), true);
+    }
 
     // aktualizujemy oryginalną wiadomość (ukrywamy przyciski)
     ic.create_response(
@@ -253,152 +264,15 @@ pub async fn handle_component(ctx: &Context, ic: &ComponentInteraction, db: &PgP
         });
     }
 
-    // po cooldownie zaktualizuj *ephemeral* odpowiedź przez edit_response
-    let ctx_clone = ctx.clone();
-    let ic_clone = ic.clone();
-    let user_clone = user.clone();
-    let place_clone = place.clone();
-    let msg_clone = message.clone();
-    let choice_clone = choice;
-    let amount_clone = amount;
-    let new_balance_clone = new_balance;
-    let streak_clone = streak;
-    let multiplier_clone = multiplier;
-
-    // ile zostało do końca CD
-    let now_ts = Utc::now();
-    let ready_at = now_ts + chrono::Duration::seconds(COOLDOWN_SECS);
-    let sleep_secs = (ready_at - now_ts).num_seconds().max(0) as u64;
-
-    tokio::spawn(async move {
-        tokio::time::sleep(std::time::Duration::from_secs(sleep_secs)).await;
-
-        let now_ready = Utc::now();
-
-        // odśwież paski i bonus
-        let streak_total = 10;
-        let filled = streak_clone.min(streak_total);
-        let streak_bar = bar(filled, streak_total);
-        let bonus_bar = bonus_progress_bar(streak_clone);
-        let bonus_text = bonus_series_text(streak_clone);
-        let extra = bonus_flat_for_tier(bonus_tier(streak_clone));
-
-        let mut updated = if amount_clone == 0 {
-            build_fail_embed(&user_clone, &msg_clone, &place_clone, new_balance_clone, now_ready, true)
-                .field(
-                    "🎯 Kontrakt",
-                    format!("{} {}", contract_emoji(Some(choice_clone)), choice_clone.label()),
-                    true,
-                )
-        } else {
-            build_result_embed(&user_clone, amount_clone, &msg_clone, &place_clone, new_balance_clone, now_ready, true)
-                .field(
-                    "🎯 Kontrakt",
-                    format!("{} {}", contract_emoji(Some(choice_clone)), choice_clone.label()),
-                    true,
-                )
-                .field(
-                    "🔥 Seria",
-                    format!("{} | x{:.2} (streak: {})", streak_bar, multiplier_clone, streak_clone),
-                    true,
-                )
-                .field(
-                    "🎁 Bonus serii",
-                    format!("{} | {}", bonus_bar, bonus_text),
-                    true,
-                )
-        };
-
-        if amount_clone > 0 && extra > 0 {
-            updated = updated.field("🎁 Bonus tej zmiany", format!("**+{} TK**", extra), true);
-        }
-
-        let _ = ic_clone
-            .edit_response(&ctx_clone.http, EditInteractionResponse::new().embeds(vec![updated]))
-            .await;
-    });
-
-    Ok(())
-}
-
-
-
-// ========================
-// 🗄️ Schemat DB (jednorazowo)
-// ========================
-
-async fn ensure_schema(db: &PgPool) -> Result<()> {
-    // users
-    sqlx::query(
-        r#"
-        CREATE TABLE IF NOT EXISTS users (
-            id          BIGINT PRIMARY KEY,
-            balance     BIGINT NOT NULL DEFAULT 0,
-            last_work   TIMESTAMPTZ,
-            streak      INTEGER NOT NULL DEFAULT 0,
-            last_streak TIMESTAMPTZ
-        );
-        "#,
-    )
-    .execute(db)
-    .await?;
-
-    // logs – przykładowa tabela (na wypadek użycia log_action)
-    sqlx::query(
-        r#"
-        CREATE TABLE IF NOT EXISTS logs (
-            id         BIGSERIAL PRIMARY KEY,
-            user_id    BIGINT NOT NULL,
-            action     TEXT   NOT NULL,
-            amount     BIGINT,
-            message    TEXT,
-            meta       JSONB,
-            created_at TIMESTAMPTZ NOT NULL DEFAULT now()
-        );
-        "#,
-    )
-    .execute(db)
-    .await?;
-
-    sqlx::query(r#"CREATE INDEX IF NOT EXISTS idx_logs_user_id ON logs(user_id);"#)
-        .execute(db)
-        .await?;
-    sqlx::query(r#"CREATE INDEX IF NOT EXISTS idx_logs_action  ON logs(action);"#)
-        .execute(db)
-        .await?;
-
-    // bezpieczne ALTER-y (jeśli tabela była starsza)
-    sqlx::query(
-        r#"
-        ALTER TABLE users
-            ADD COLUMN IF NOT EXISTS streak INTEGER NOT NULL DEFAULT 0,
-            ADD COLUMN IF NOT EXISTS last_streak TIMESTAMPTZ;
-        "#,
-    )
-    .execute(db)
-    .await?;
+    // przypomnienie o końcu CD (doręcza harmonogram — przeżywa restart)
+    if let Err(e) = reminders::schedule(db, guild, ic.channel_id, user.id.get(), COOLDOWN_KEY, "/work", ready_at).await {
+        eprintln!("❌ /work: nie zapisano przypomnienia: {e:?}");
+    }
 
     Ok(())
 }
 
-// ========================
-// 🔁 Cooldown helper
-// ========================
-
-async fn current_cooldown(db: &PgPool, user_id: i64, cooldown_secs: i64) -> Result<i64> {
-    let remaining: Option<i64> = sqlx::query_scalar(
-        r#"
-        SELECT GREATEST(0, $1 - EXTRACT(EPOCH FROM (now() - last_work))::BIGINT)
-        FROM users WHERE id = $2
-        "#,
-    )
-    .bind(cooldown_secs)
-    .bind(user_id)
-    .fetch_optional(db)
-    .await?;
 
-    Ok(remaining.unwrap_or(0))
-}
 
 // ========================
 // 🔧 Helpery do pasków postępu, bonusów i emoji kontraktów
@@ -421,92 +295,73 @@ fn contract_emoji(choice: Option<WorkChoice>) -> &'static str {
     }
 }
 
-// progi bonusów
-const BONUS_STEPS: [i32; 3] = [5, 10, 15];
-
-#[inline]
-fn bonus_cap() -> f32 {
-    1.50
-}
+// progi bonusów i mnożnik serii: tabele balansu (`config::WorkTables`)
 
 /// Określa mnożnik dla danej liczby streak
 #[inline]
 fn streak_multiplier(streak: i32) -> f32 {
-    (1.0 + (streak as f32 - 1.0) * 0.05).clamp(1.0, bonus_cap())
+    let t = &config::get().work;
+    (1.0 + (streak as f32 - 1.0) * t.streak_step).clamp(1.0, t.streak_cap)
 }
 
 /// Zwraca następny próg i jego mnożnik, jeśli jeszcze nie osiągnięto CAP
 fn next_bonus_tier(streak: i32) -> Option<(i32, f32)> {
-    for &t in &BONUS_STEPS {
-        if streak < t {
-            return Some((t, streak_multiplier(t)));
-        }
-    }
-    None
+    config::get()
+        .work
+        .series
+        .iter()
+        .find(|s| streak < s.from)
+        .map(|s| (s.from, streak_multiplier(s.from)))
 }
 
 /// Zwraca opis bonusu serii – pokazuje próg, mnożnik i dodatkowe TK
 fn bonus_series_text(streak: i32) -> String {
     if let Some((next, mult)) = next_bonus_tier(streak) {
         let rem = (next - streak).max(0);
-        let extra_tk = bonus_flat_for_tier(bonus_tier(next));
+        let extra_tk = series_flat(next);
         format!(
             "następny próg: **{}** (x{:.2} + **+{} TK**) • brakuje **{}** zmian",
             next, mult, extra_tk, rem
         )
     } else {
-        format!("osiągnięto CAP: **x{:.2}** + **+{} TK** na zmianę", bonus_cap(), bonus_flat_for_tier(3))
+        let cfg = config::get();
+        format!("osiągnięto CAP: **x{:.2}** + **+{} TK** na zmianę", cfg.work.streak_cap, series_flat(i32::MAX))
     }
 }
 
-/// Pasek postępu do najbliższego progu (co 5 udanych zmian)
+/// Pasek postępu do najbliższego progu (pełny na progu, częściowy między progami)
 fn bonus_progress_bar(streak: i32) -> String {
     let width = 5;
+    let cfg = config::get();
+    let steps = &cfg.work.series;
 
-    // CAP: od 15 wzwyż pasek zawsze pełny
-    if streak >= BONUS_STEPS[BONUS_STEPS.len() - 1] {
+    // CAP: od ostatniego progu wzwyż pasek zawsze pełny
+    let Some(next) = steps.iter().find(|s| streak < s.from).map(|s| s.from) else {
         return bar(width, width);
-    }
+    };
+    let prev = steps.iter().rev().find(|s| streak >= s.from).map_or(0, |s| s.from);
 
-    // W przeciwnym razie: pełny na 5/10, częściowy między progami
-    let mut fill = streak % width;
-    if fill == 0 && streak > 0 {
-        fill = width;
+    // Dokładnie na progu: pełny; w przeciwnym razie proporcjonalnie do następnego
+    if streak == prev && prev > 0 {
+        return bar(width, width);
     }
-    bar(fill, width)
-}
-
-/// Zwraca numer progu bonusu
-#[inline]
-fn bonus_tier(streak: i32) -> u8 {
-    if streak >= 15 { 3 }
-    else if streak >= 10 { 2 }
-    else if streak >= 5 { 1 }
-    else { 0 }
+    bar((streak - prev) * width / (next - prev).max(1), width)
 }
 
-/// Zwraca dodatkowe TK dla danego progu
+/// Zwraca dodatkowe TK za najwyższy osiągnięty próg serii
 #[inline]
-fn bonus_flat_for_tier(tier: u8) -> i64 {
-    match tier {
-        1 => 10,   // od 5+
-        2 => 25,   // od 10+
-        3 => 50,   // od 15+
-        _ => 0,
-    }
+fn series_flat(streak: i32) -> i64 {
+    config::get().work.series.iter().rev().find(|s| streak >= s.from).map_or(0, |s| s.flat)
 }
 
 // ========================
 // 💰 Losowanie narracji
 // ========================
 
-/// Zwraca sformatowaną wiadomość i miejsce na podstawie `WORK_TASKS`,
+/// Zwraca sformatowaną wiadomość i miejsce zadania `task` z `WORK_TASKS`,
 /// wstawiając `final_amount` do placeholdera `{amount}` (jeśli wystąpi).
-fn narrative_for_amount(final_amount: i64) -> (String, String) {
-    let mut rng = rand::rng();
-    let tasks = WORK_TASKS.as_slice();
-    let idx = rng.random_range(0..tasks.len());
-    let task = &tasks[idx];
+fn narrative_for_amount(task: usize, final_amount: i64) -> (String, String) {
+    let task = &WORK_TASKS[task % WORK_TASKS.len()];
 
     let message = task.text.replace("{amount}", &final_amount.to_string());
     (message, task.place.clone())
@@ -522,131 +377,196 @@ struct WorkOutcome {
     place: String,
     new_balance: i64,
     now: DateTime<Utc>,
+    ready_at: DateTime<Utc>,
     streak: i32,
     multiplier: f32,
+    /// Część wypłaty potrącona na spłatę pożyczki.
+    garnished: Option<Garnished>,
 }
 
-// Baza nagrody wg kontraktu (bez mnożnika)
-fn generate_contract_base(choice: WorkChoice) -> (i64, &'static str) {
-    let mut rng = rand::rng();
-    match choice {
-        WorkChoice::Safe => (rng.random_range(30..=50), "Ukończyłeś rutynowe zadania bez potknięć."),
-        WorkChoice::Balanced => {
-            if rng.random_bool(0.10) {
-                (0, "Projekt się wykrzaczył i zamknąłeś dzień na zero.")
-            } else {
-                (rng.random_range(40..=90), "Dopiąłeś sprint z przyzwoitym wynikiem.")
-            }
-        }
-        WorkChoice::HighRisk => {
-            if rng.random_bool(0.10) {
-                (rng.random_range(120..=200), "💥 Krytyczny sukces! Zrobiłeś robotę życia.")
-            } else if rng.random_bool(0.30) {
-                (0, "Ups… ryzyko nie wypaliło. Dziś nic nie zarobiłeś.")
-            } else {
-                (rng.random_range(60..=140), "Duży deal, duże nerwy — udało się.")
-            }
-        }
+/// Wynik losowania /work (zapisywany w `rolls`): baza kontraktu + wylosowane zadanie.
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct WorkRoll {
+    pub(crate) base_amount: i64,
+    base_msg: &'static str,
+    task: usize,
+}
+
+pub(crate) fn roll_work(choice: &WorkChoice, rng: &mut impl Rng) -> WorkRoll {
+    let (base_amount, base_msg) = generate_contract_base(*choice, rng);
+    let task = rng.random_range(0..WORK_TASKS.len());
+    WorkRoll { base_amount, base_msg, task }
+}
+
+/// Mnożnik i wypłata za zmianę z bazą `base_amount` przy streaku po zmianie; porażka (0) = (1.0, 0).
+pub(crate) fn shift_payout(base_amount: i64, streak: i32) -> (f32, i64) {
+    if base_amount == 0 {
+        return (1.0, 0);
+    }
+    let multiplier = streak_multiplier(streak);
+    let extra = series_flat(streak);
+    (multiplier, ((base_amount as f32) * multiplier).round() as i64 + extra)
+}
+
+// Baza nagrody wg kontraktu (bez mnożnika): najpierw krytyk, potem zero, inaczej zwykłe widełki.
+// Szanse 0 nie losują — kolejność rzutów jak w zapisanych powtórkach.
+fn generate_contract_base(choice: WorkChoice, rng: &mut impl Rng) -> (i64, &'static str) {
+    let cfg = config::get();
+    let (c, bust_msg, pay_msg) = match choice {
+        WorkChoice::Safe => (
+            &cfg.work.safe,
+            "Dzień bez efektów — zamknąłeś go na zero.",
+            "Ukończyłeś rutynowe zadania bez potknięć.",
+        ),
+        WorkChoice::Balanced => (
+            &cfg.work.balanced,
+            "Projekt się wykrzaczył i zamknąłeś dzień na zero.",
+            "Dopiąłeś sprint z przyzwoitym wynikiem.",
+        ),
+        WorkChoice::HighRisk => (
+            &cfg.work.high_risk,
+            "Ups… ryzyko nie wypaliło. Dziś nic nie zarobiłeś.",
+            "Duży deal, duże nerwy — udało się.",
+        ),
+    };
+
+    if c.crit_chance > 0.0 && rng.random_bool(c.crit_chance) {
+        (rng.random_range(c.crit_pay[0]..=c.crit_pay[1]), "💥 Krytyczny sukces! Zrobiłeś robotę życia.")
+    } else if c.bust_chance > 0.0 && rng.random_bool(c.bust_chance) {
+        (0, bust_msg)
+    } else {
+        (rng.random_range(c.pay[0]..=c.pay[1]), pay_msg)
     }
 }
 
 // transakcja: wiersz użytkownika, cooldown, streak, update
-async fn process_work_tx(db: &PgPool, user_id: i64, choice: WorkChoice) -> Result<WorkOutcome> {
+async fn process_work_tx(
+    db: &PgPool,
+    eco: Economy,
+    guild: GuildId,
+    user_id: i64,
+    choice: WorkChoice,
+    interaction_id: u64,
+) -> Result<WorkOutcome> {
     let mut tx = db.begin().await?;
 
     // 0) upewnij się, że user istnieje
-    sqlx::query("INSERT INTO users (id) VALUES ($1) ON CONFLICT DO NOTHING")
-        .bind(user_id)
-        .execute(&mut *tx)
-        .await?;
+    ledger::ensure_user(&mut tx, eco, user_id as u64).await?;
 
     // 1) wczytaj usera z blokadą
     #[derive(sqlx::FromRow)]
     struct UserRow {
         balance: i64,
-        last_work: Option<DateTime<Utc>>,
         streak: i32,
         last_streak: Option<DateTime<Utc>>,
     }
 
     let user_row: UserRow = sqlx::query_as(
         r#"
-        SELECT balance, last_work, streak, last_streak
+        SELECT balance, streak, last_streak
         FROM users
-        WHERE id = $1
+        WHERE guild_id = $1 AND id = $2
         FOR UPDATE
         "#,
     )
+    .bind(eco.id())
     .bind(user_id)
     .fetch_one(&mut *tx)
     .await
     .context("Nie udało się pobrać użytkownika")?;
 
-    let now = Utc::now();
+    let now = clock::now();
 
     // 2) cooldown check w transakcji
-    if let Some(lw) = user_row.last_work {
-        if (now - lw).num_seconds() < COOLDOWN_SECS {
-            tx.rollback().await?;
-            return Ok(WorkOutcome {
-                amount: 0,
-                message: "⏳ Wciąż masz cooldown — spróbuj później.".into(),
-                place: "—".into(),
-                new_balance: user_row.balance,
-                now,
-                streak: user_row.streak,
-                multiplier: 1.0,
-            });
-        }
+    if let Some(left) = cooldowns::remaining(&mut *tx, eco, user_id as u64, COOLDOWN_KEY).await? {
+        tx.rollback().await?;
+        return Ok(WorkOutcome {
+            amount: 0,
+            message: "⏳ Wciąż masz cooldown — spróbuj później.".into(),
+            place: "—".into(),
+            new_balance: user_row.balance,
+            now,
+            ready_at: now + Duration::seconds(left),
+            streak: user_row.streak,
+            multiplier: 1.0,
+            garnished: None,
+        });
     }
 
-    // 3) baza wyniku z wyboru
-let (base_amount, base_msg) = generate_contract_base(choice);
+    // 3) baza wyniku z wyboru (losowanie zapisane razem z wypłatą)
+let (seed, mut rng) = rng::seeded();
+let roll = roll_work(&choice, &mut rng);
+rng::record(
+    &mut *tx,
+    eco,
+    &Roll { command: "work", user: user_id as u64, interaction_id: Some(interaction_id), seed },
+    &choice,
+    &roll,
+)
+.await?;
+let WorkRoll { base_amount, base_msg, task } = roll;
 let fail = base_amount == 0;
 
 // 4) streak
 let new_streak = if fail {
     0
 } else {
-    match user_row.last_work {
+    // last_streak = ostatnia udana zmiana (po porażce streak i tak startuje od zera)
+    match user_row.last_streak {
         Some(prev) if (now - prev).num_seconds() <= 2 * 3600 => (user_row.streak + 1).max(1),
         _ => 1,
     }
 };
 
-let multiplier = if fail { 1.0 } else { streak_multiplier(new_streak) };
-let tier = bonus_tier(new_streak);
-let extra = bonus_flat_for_tier(tier);
+let (multiplier, final_amount) = shift_payout(base_amount, new_streak);
+
+// 5) wypłata przez księgę (porażka = 0 TK, bez wpisu)
+let mut new_balance = ledger::settle(
+    &mut tx,
+    eco,
+    user_id as u64,
+    "work",
+    final_amount,
+    &Entry { reason: "wypłata za pracę", command: "work", interaction_id: Some(interaction_id) },
+    Overdraft::Forbid,
+)
+.await?;
 
-let final_amount = if fail {
-    0
-} else {
-    ((base_amount as f32) * multiplier).round() as i64 + extra
-};
+// 5b) potrącenie na spłatę pożyczki (jeśli jest dług)
+let garnished = loans::garnish(
+    &mut tx,
+    eco,
+    user_id as u64,
+    final_amount,
+    new_balance,
+    &Entry { reason: "spłata pożyczki z wypłaty", command: "work", interaction_id: Some(interaction_id) },
+)
+.await?;
+if let Some(g) = garnished {
+    new_balance = g.wallet;
+}
 
-// 5) update usera (last_streak aktualizujemy tylko jeśli streak > 0)
-let new_balance: i64 = sqlx::query_scalar(
+// 6) update usera (last_streak aktualizujemy tylko jeśli streak > 0) + cooldown
+let ready_at = cooldowns::start(&mut tx, eco, guild, user_id as u64, cooldown_meta()).await?;
+sqlx::query(
     r#"
     UPDATE users
-       SET balance = balance + $2,
-           last_work = $3,
-           streak    = $4,
-           last_streak = CASE WHEN $4 > 0 THEN $3 ELSE last_streak END
-     WHERE id = $1
- RETURNING balance
+       SET streak    = $3,
+           last_streak = CASE WHEN $3 > 0 THEN $2 ELSE last_streak END
+     WHERE guild_id = $4 AND id = $1
     "#,
 )
 .bind(user_id)
-.bind(final_amount)
 .bind(now)
 .bind(new_streak)
-.fetch_one(&mut *tx)
+.bind(eco.id())
+.execute(&mut *tx)
 .await?;
 
     tx.commit().await?;
 
-    // 6) narracja – zawsze wstawiaj final_amount do {amount}
-    let (narrative, place) = narrative_for_amount(final_amount);
+    // 7) narracja – zawsze wstawiaj final_amount do {amount}
+    let (narrative, place) = narrative_for_amount(task, final_amount);
     let message = format!("{base_msg} {narrative}");
 
     Ok(WorkOutcome {
@@ -655,8 +575,10 @@ let new_balance: i64 = sqlx::query_scalar(
         place,
         new_balance,
         now,
+        ready_at,
         streak: new_streak,
         multiplier,
+        garnished,
     })
 }
 
@@ -677,16 +599,6 @@ fn build_cooldown_embed(user: &User, remaining: i64) -> CreateEmbed {
         .timestamp(Utc::now())
 }
 
-#[allow(dead_code)]
-fn build_cooldown_ready_embed(user: &User) -> CreateEmbed {
-    CreateEmbed::new()
-        .color(0x2ECC71)
-        .author(CreateEmbedAuthor::new(&user.name).icon_url(user.avatar_url().unwrap_or_default()))
-        .title("✅ Cooldown zakończony")
-        .description("Możesz już pracować — użyj `/work`.")
-        .timestamp(Utc::now())
-}
-
 fn format_tk(n: i64) -> String {
     n.to_formatted_string(&Locale::pl)
 }
@@ -703,14 +615,14 @@ pub fn build_result_embed(
     place: &str,
     balance: i64,
     now: DateTime<Utc>,
-    ready: bool,
+    ready_at: DateTime<Utc>,
 ) -> CreateEmbed {
     let amount_fmt = format!("{} TK", format_tk(amount));
     let balance_fmt = format!("{} TK", format_tk(balance));
 
-    let next_at = now + Duration::seconds(COOLDOWN_SECS);
-    let next_unix = next_at.timestamp();
-    let remaining = (next_at - now).num_seconds().max(0);
+    let ready = now >= ready_at;
+    let next_unix = ready_at.timestamp();
+    let remaining = (ready_at - now).num_seconds().max(0);
 
     let (color, status) = if ready {
         (0x21D19F, "✅ **Gotowe do pracy.** Użyj `/work`!".to_string())
@@ -742,11 +654,11 @@ fn build_fail_embed(
     place: &str,
     balance: i64,
     now: DateTime<Utc>,
-    ready: bool,
+    ready_at: DateTime<Utc>,
 ) -> CreateEmbed {
-    let next_at = now + Duration::seconds(COOLDOWN_SECS);
-    let next_unix = next_at.timestamp();
-    let remaining = (next_at - now).num_seconds().max(0);
+    let ready = now >= ready_at;
+    let next_unix = ready_at.timestamp();
+    let remaining = (ready_at - now).num_seconds().max(0);
 
     let (color, status) = if ready {
         (0x2ECC71, "✅ **Gotowe do pracy.** Użyj `/work`!".to_string())
@@ -822,3 +734,50 @@ async fn send_log_to_channel_http(
         .await?;
     Ok(())
 }
+
+// =======================
+// 🧭 Rejestr komend
+// =======================
+
+pub struct WorkCommand;
+
+#[async_trait]
+impl BotCommand for WorkCommand {
+    fn name(&self) -> &'static str {
+        "work"
+    }
+
+    fn economy(&self) -> bool {
+        true
+    }
+
+    fn earning(&self, _sub: Option<&str>) -> bool {
+        true
+    }
+
+    fn register(&self) -> CreateCommand {
+        let mut c = CreateCommand::new("work");
+        register(&mut c);
+        c
+    }
+
+    async fn run(&self, ctx: &Context, cmd: &CommandInteraction, db: &PgPool) -> Result<()> {
+        run(ctx, cmd, db).await
+    }
+
+    fn replay(&self, kind: &str, seed: u64, inputs: &str) -> Option<Result<serde_json::Value>> {
+        (kind == "work").then(|| rng::replay(seed, inputs, roll_work))
+    }
+
+    fn component_prefixes(&self) -> &'static [&'static str] {
+        &["work:"]
+    }
+
+    async fn handle_component(&self, ctx: &Context, ic: &ComponentInteraction, db: &PgPool) -> Result<()> {
+        handle_component(ctx, ic, db).await
+    }
+
+    fn cooldown(&self) -> Option<CooldownMeta> {
+        Some(cooldown_meta())
+    }
+}
@@ -0,0 +1,215 @@
+//! Wspólny serwis cooldownów.
+//!
+//! Jeden wiersz `cooldowns(guild_id, user_id, key, ready_at)` na aktywny cooldown.
+//! Długość bazowa pochodzi z [`CooldownMeta`] komendy (`cooldowns` w pliku balansu), a modyfikatory
+//! (HEAT, subskrypcja rangi) liczone są tutaj — raz, przy starcie cooldownu.
+//! Czas zawsze z [`crate::clock`], nigdy z `now()` bazy.
+
+use std::collections::HashMap;
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use serenity::all::GuildId;
+use sqlx::{PgConnection, PgExecutor};
+
+use crate::clock;
+use crate::commands::registry::CooldownMeta;
+use crate::economy::Economy;
+use crate::engine::balance::heat_cooldown_secs;
+use crate::engine::config;
+
+/// Ile sekund zostało do końca cooldownu (`None` = można używać).
+pub async fn remaining(db: impl PgExecutor<'_>, eco: Economy, user: u64, key: &str) -> Result<Option<i64>> {
+    let ready_at: Option<DateTime<Utc>> = sqlx::query_scalar(
+        "SELECT ready_at FROM cooldowns WHERE guild_id = $1 AND user_id = $2 AND key = $3",
+    )
+    .bind(eco.id())
+    .bind(user as i64)
+    .bind(key)
+    .fetch_optional(db)
+    .await?;
+
+    Ok(ready_at.and_then(left))
+}
+
+/// Ile zostało do `ready_at` według zegara gry ([`clock::now`]).
+fn left(ready_at: DateTime<Utc>) -> Option<i64> {
+    secs_left(ready_at, clock::now())
+}
+
+/// Pełne sekundy do `ready_at`, zaokrąglone w górę (0,9 s to jeszcze 1 s); `None` = gotowe.
+fn secs_left(ready_at: DateTime<Utc>, now: DateTime<Utc>) -> Option<i64> {
+    let ms = (ready_at - now).num_milliseconds();
+    (ms > 0).then(|| (ms + 999) / 1000)
+}
+
+/// Startuje cooldown z modyfikatorami i zwraca moment gotowości.
+/// Wołać w transakcji, która trzyma blokadę wiersza gracza.
+pub async fn start(
+    conn: &mut PgConnection,
+    eco: Economy,
+    guild: GuildId,
+    user: u64,
+    meta: CooldownMeta,
+) -> Result<DateTime<Utc>> {
+    let secs = effective_secs(conn, eco, guild, user, meta).await?;
+    let now = clock::now();
+    let ready_at = now + Duration::seconds(secs);
+
+    sqlx::query(
+        r#"
+        INSERT INTO cooldowns (guild_id, user_id, key, ready_at, started_at)
+        VALUES ($1, $2, $3, $4, $5)
+        ON CONFLICT (guild_id, user_id, key)
+        DO UPDATE SET ready_at = EXCLUDED.ready_at, started_at = EXCLUDED.started_at
+        "#,
+    )
+    .bind(eco.id())
+    .bind(user as i64)
+    .bind(meta.key)
+    .bind(ready_at)
+    .bind(now)
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(ready_at)
+}
+
+/// Długość cooldownu po modyfikatorach: baza + HEAT (jeśli komenda go uwzględnia) − % subskrybenta.
+pub async fn effective_secs(
+    conn: &mut PgConnection,
+    eco: Economy,
+    guild: GuildId,
+    user: u64,
+    meta: CooldownMeta,
+) -> Result<i64> {
+    let mut heat = 0;
+    if meta.heat_penalty {
+        let h: Option<i32> = sqlx::query_scalar("SELECT heat FROM profiles WHERE guild_id = $1 AND user_id = $2")
+            .bind(eco.id())
+            .bind(user as i64)
+            .fetch_optional(&mut *conn)
+            .await?;
+        heat = h.unwrap_or(0).max(0) as u32;
+    }
+
+    let sub_pct = config::get().cooldowns.sub_reduction_pct;
+    let mut sub_until = None;
+    if sub_pct > 0 {
+        // ranga jest per serwer (nie per ekonomia)
+        sub_until = sqlx::query_scalar(
+            "SELECT MAX(expires_at) FROM role_subscriptions WHERE user_id = $1 AND guild_id = $2 AND active = true",
+        )
+        .bind(user as i64)
+        .bind(guild.get() as i64)
+        .fetch_one(&mut *conn)
+        .await?;
+    }
+
+    Ok(modified_secs(meta, heat, sub_until, sub_pct, clock::now()))
+}
+
+/// Czysta część [`effective_secs`]: `sub_until` = koniec najdłuższej aktywnej subskrypcji.
+fn modified_secs(meta: CooldownMeta, heat: u32, sub_until: Option<DateTime<Utc>>, sub_pct: i64, now: DateTime<Utc>) -> i64 {
+    let mut secs = meta.secs;
+    if meta.heat_penalty {
+        secs += heat_cooldown_secs(heat) as i64;
+    }
+    if sub_pct > 0 && sub_until.is_some_and(|t| t > now) {
+        secs -= secs * sub_pct / 100;
+    }
+    secs.max(0)
+}
+
+/// Kasuje wszystkie cooldowny gracza w ekonomii. Zwraca `klucz → ile zostało (s)`.
+pub async fn reset(db: impl PgExecutor<'_>, eco: Economy, user: u64) -> Result<HashMap<String, i64>> {
+    let rows: Vec<(String, DateTime<Utc>)> = sqlx::query_as(
+        "DELETE FROM cooldowns WHERE guild_id = $1 AND user_id = $2 RETURNING key, ready_at",
+    )
+    .bind(eco.id())
+    .bind(user as i64)
+    .fetch_all(db)
+    .await?;
+
+    let now = clock::now();
+    Ok(rows
+        .into_iter()
+        .map(|(key, ready_at)| (key, secs_left(ready_at, now).unwrap_or(0)))
+        .collect())
+}
+
+/// Usuwa zakończone cooldowny (czyści tabelę; `remaining` i tak je pomija).
+pub async fn purge_expired(db: impl PgExecutor<'_>) -> Result<u64> {
+    let res = sqlx::query("DELETE FROM cooldowns WHERE ready_at <= $1")
+        .bind(clock::now())
+        .execute(db)
+        .await?;
+    Ok(res.rows_affected())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::{Clock, ManualClock};
+    use chrono::TimeZone;
+
+    fn t0() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 1, 1, 12, 0, 0).unwrap()
+    }
+
+    const META: CooldownMeta = CooldownMeta { key: "test", secs: 600, heat_penalty: false };
+
+    #[test]
+    fn remaining_follows_installed_clock() {
+        let clock = clock::test_clock();
+        let ready_at = clock::now() + Duration::seconds(30);
+
+        assert_eq!(left(ready_at), Some(30));
+        clock.advance(Duration::milliseconds(29_500));
+        assert_eq!(left(ready_at), Some(1));
+        clock.advance(Duration::milliseconds(500));
+        assert_eq!(left(ready_at), None, "zegar gry, nie systemowy, decyduje o końcu cooldownu");
+    }
+
+    #[test]
+    fn remaining_rounds_up_and_expires() {
+        let clock = ManualClock::new(t0());
+        let ready_at = t0() + Duration::seconds(10);
+
+        assert_eq!(secs_left(ready_at, clock.now()), Some(10));
+        clock.advance(Duration::milliseconds(9_100));
+        assert_eq!(secs_left(ready_at, clock.now()), Some(1), "0,9 s to jeszcze nie koniec");
+        clock.advance(Duration::milliseconds(899));
+        assert_eq!(secs_left(ready_at, clock.now()), Some(1));
+        clock.advance(Duration::milliseconds(1));
+        assert_eq!(secs_left(ready_at, clock.now()), None);
+        clock.advance(Duration::seconds(5));
+        assert_eq!(secs_left(ready_at, clock.now()), None);
+    }
+
+    #[test]
+    fn effective_secs_subscription_follows_clock() {
+        let clock = ManualClock::new(t0());
+        let sub_until = Some(t0() + Duration::hours(1));
+
+        assert_eq!(modified_secs(META, 0, sub_until, 25, clock.now()), 450);
+        assert_eq!(modified_secs(META, 0, sub_until, 0, clock.now()), 600, "0% = wyłączone");
+        assert_eq!(modified_secs(META, 0, None, 25, clock.now()), 600);
+
+        clock.advance(Duration::hours(1));
+        assert_eq!(modified_secs(META, 0, sub_until, 25, clock.now()), 600, "subskrypcja już wygasła");
+    }
+
+    #[test]
+    fn effective_secs_heat_only_with_penalty() {
+        let now = ManualClock::new(t0()).now();
+        let hot = 100;
+        let extra = heat_cooldown_secs(hot) as i64;
+        assert!(extra > 0);
+
+        assert_eq!(modified_secs(META, hot, None, 0, now), 600);
+        let penalized = CooldownMeta { heat_penalty: true, ..META };
+        assert_eq!(modified_secs(penalized, hot, None, 0, now), 600 + extra);
+        assert_eq!(modified_secs(penalized, hot, Some(t0() + Duration::days(1)), 50, now), (600 + extra) / 2);
+    }
+}
@@ -0,0 +1,116 @@
+//! Ekonomie per serwer.
+//!
+//! Tabele ekonomii (`users`, `profiles`, `crime_settings`, `ledger`) są kluczowane
+//! `guild_id` = id ekonomii. Domyślnie serwer ma własną ekonomię (id = id gildii);
+//! wpis w `economy_links` przepina serwer na ekonomię innego serwera (wspólna ekonomia).
+//! Ekonomia `0` to dane sprzed podziału — przejmuje ją serwer z `LEGACY_GUILD_ID`.
+
+use anyhow::{bail, Result};
+use serenity::all::GuildId;
+use sqlx::PgPool;
+
+/// Ekonomia legacy (wiersze sprzed migracji 0003).
+pub const LEGACY_ECONOMY: u64 = 0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Economy(pub u64);
+
+impl Economy {
+    /// Wartość kolumny `guild_id` w tabelach ekonomii.
+    pub fn id(self) -> i64 {
+        self.0 as i64
+    }
+}
+
+/// Ekonomia, w której działa dany serwer.
+pub async fn resolve(db: &PgPool, guild: GuildId) -> Result<Economy> {
+    let linked: Option<i64> = sqlx::query_scalar("SELECT economy_id FROM economy_links WHERE guild_id = $1")
+        .bind(guild.get() as i64)
+        .fetch_optional(db)
+        .await?;
+    Ok(Economy(linked.map(|v| v as u64).unwrap_or(guild.get())))
+}
+
+/// Ekonomia interakcji — komendy ekonomii działają tylko na serwerze
+/// (DM odrzuca Handler, więc `None` tutaj to błąd programisty).
+pub async fn of(db: &PgPool, guild: Option<GuildId>) -> Result<Economy> {
+    let Some(guild) = guild else {
+        bail!("komenda ekonomii wywołana poza serwerem");
+    };
+    resolve(db, guild).await
+}
+
+/// Przepina `guild` na ekonomię serwera `target` (łańcuchy są spłaszczane).
+/// Dotychczasowe dane ekonomii `guild` zostają w bazie, ale przestają być widoczne.
+pub async fn link(db: &PgPool, guild: GuildId, target: GuildId, by: u64) -> Result<Economy> {
+    if guild == target {
+        bail!("serwer nie może dołączyć do własnej ekonomii");
+    }
+    let eco = resolve(db, target).await?;
+    if eco.0 == guild.get() {
+        bail!("serwer {} już korzysta z ekonomii tego serwera", target.get());
+    }
+
+    let mut tx = db.begin().await?;
+    sqlx::query(
+        r#"
+        INSERT INTO economy_links (guild_id, economy_id, linked_by)
+        VALUES ($1, $2, $3)
+        ON CONFLICT (guild_id) DO UPDATE
+        SET economy_id = EXCLUDED.economy_id, linked_by = EXCLUDED.linked_by, linked_at = now()
+        "#,
+    )
+    .bind(guild.get() as i64)
+    .bind(eco.id())
+    .bind(by as i64)
+    .execute(&mut *tx)
+    .await?;
+
+    // serwery podpięte dotąd pod `guild` idą razem z nim
+    sqlx::query("UPDATE economy_links SET economy_id = $2 WHERE economy_id = $1")
+        .bind(guild.get() as i64)
+        .bind(eco.id())
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+
+    Ok(eco)
+}
+
+/// Odpina serwer — wraca do własnej ekonomii. `false`, jeśli nie był podpięty.
+pub async fn unlink(db: &PgPool, guild: GuildId) -> Result<bool> {
+    let res = sqlx::query("DELETE FROM economy_links WHERE guild_id = $1")
+        .bind(guild.get() as i64)
+        .execute(db)
+        .await?;
+    Ok(res.rows_affected() > 0)
+}
+
+/// Pozostałe serwery dzielące ekonomię `eco`.
+pub async fn linked_guilds(db: &PgPool, eco: Economy) -> Result<Vec<u64>> {
+    let rows: Vec<i64> = sqlx::query_scalar("SELECT guild_id FROM economy_links WHERE economy_id = $1 ORDER BY guild_id")
+        .bind(eco.id())
+        .fetch_all(db)
+        .await?;
+    Ok(rows.into_iter().map(|v| v as u64).collect())
+}
+
+/// Przy starcie: serwer z `LEGACY_GUILD_ID` przejmuje ekonomię legacy (jeśli nie ma już linku).
+pub async fn adopt_legacy(db: &PgPool) -> Result<()> {
+    let Some(guild) = std::env::var("LEGACY_GUILD_ID")
+        .ok()
+        .and_then(|s| s.parse::<u64>().ok())
+        .filter(|&id| id != 0)
+    else {
+        return Ok(());
+    };
+
+    sqlx::query(
+        "INSERT INTO economy_links (guild_id, economy_id) VALUES ($1, $2) ON CONFLICT (guild_id) DO NOTHING",
+    )
+    .bind(guild as i64)
+    .bind(LEGACY_ECONOMY as i64)
+    .execute(db)
+    .await?;
+    Ok(())
+}
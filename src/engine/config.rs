@@ -0,0 +1,1459 @@
+//! Tabele balansu — liczby ekonomii gier (`/crime` z celami i drzewkiem, `/ekwipunek`, więzienie, cooldowny,
+//! `/daily`, `/rob`, `/work`, `/slut`, `/bank`, odsetki i podatek, `/pozyczka`, `/pay`) w pliku JSON.
+//!
+//! Plik wskazuje ENV `BALANCE_CONFIG_PATH`; bez niego działają wartości domyślne poniżej
+//! (takie same jak dawne stałe w kodzie). Plik musi mieć `version` równe [`BALANCE_VERSION`],
+//! każda sekcja i pole może zostać pominięte (bierze domyślne), a nieznany klucz to błąd —
+//! literówka nie przejdzie po cichu. Zły plik przy starcie zatrzymuje bota, a przy
+//! `/admcontrol balans` zostaje poprzednia wersja.
+//!
+//! Przeładowanie działa w obrębie procesu. Powtórki rzutów (`/admcontrol rzut`) liczą się
+//! na bieżących tabelach — po zmianie balansu stare rzuty mogą wyjść inaczej.
+
+use std::path::PathBuf;
+use std::sync::{Arc, PoisonError, RwLock};
+
+use anyhow::{bail, ensure, Context, Result};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use super::types::{CrimeMode, ItemKey, MinigameKind, PerkKey, Risk, TargetKey};
+
+/// Obsługiwana wersja formatu pliku.
+pub const BALANCE_VERSION: u32 = 1;
+
+/// Ścieżka pliku balansu (ENV `BALANCE_CONFIG_PATH`; brak = wartości domyślne).
+static PATH: Lazy<Option<PathBuf>> = Lazy::new(|| {
+    std::env::var("BALANCE_CONFIG_PATH")
+        .ok()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .map(PathBuf::from)
+});
+
+static CURRENT: Lazy<RwLock<Arc<Loaded>>> = Lazy::new(|| RwLock::new(Arc::new(Loaded::defaults())));
+
+// =======================
+// 📐 Pomocnicze tabele
+// =======================
+
+/// Wartość na każdy poziom ryzyka.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PerRisk<T> {
+    pub low: T,
+    pub medium: T,
+    pub high: T,
+    pub hardcore: T,
+}
+
+impl<T: Copy> PerRisk<T> {
+    pub fn get(&self, r: Risk) -> T {
+        match r {
+            Risk::Low => self.low,
+            Risk::Medium => self.medium,
+            Risk::High => self.high,
+            Risk::Hardcore => self.hardcore,
+        }
+    }
+
+    fn all(&self) -> [(&'static str, T); 4] {
+        [("low", self.low), ("medium", self.medium), ("high", self.high), ("hardcore", self.hardcore)]
+    }
+}
+
+/// Wartość na każdy tryb napadu.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PerMode<T> {
+    pub standard: T,
+    pub szybki: T,
+    pub ostrozny: T,
+    pub shadow: T,
+    pub hardcore: T,
+    pub ryzykowny: T,
+    pub planowany: T,
+    pub szalony: T,
+}
+
+impl<T: Copy> PerMode<T> {
+    pub fn get(&self, m: CrimeMode) -> T {
+        match m {
+            CrimeMode::Standard => self.standard,
+            CrimeMode::Szybki => self.szybki,
+            CrimeMode::Ostrozny => self.ostrozny,
+            CrimeMode::Shadow => self.shadow,
+            CrimeMode::Hardcore => self.hardcore,
+            CrimeMode::Ryzykowny => self.ryzykowny,
+            CrimeMode::Planowany => self.planowany,
+            CrimeMode::Szalony => self.szalony,
+        }
+    }
+
+    fn all(&self) -> [(&'static str, T); 8] {
+        [
+            ("standard", self.standard),
+            ("szybki", self.szybki),
+            ("ostrozny", self.ostrozny),
+            ("shadow", self.shadow),
+            ("hardcore", self.hardcore),
+            ("ryzykowny", self.ryzykowny),
+            ("planowany", self.planowany),
+            ("szalony", self.szalony),
+        ]
+    }
+}
+
+/// Próg serii: od `from` kolejnych udanych akcji premia `flat` TK.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SeriesStep {
+    pub from: i32,
+    pub flat: i64,
+}
+
+// =======================
+// 🕵️ /crime
+// =======================
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CrimeTables {
+    /// Szansa bazowa (pp) wg ryzyka, przesuwana przez tryb i przycinana do widełek.
+    pub base_chance: PerRisk<f32>,
+    pub mode_chance: PerMode<f32>,
+    pub min_base_chance: f32,
+    pub max_base_chance: f32,
+    /// Widełki łupu wg ryzyka i mnożnik trybu.
+    pub reward: PerRisk<[i64; 2]>,
+    pub mode_reward_mult: PerMode<f32>,
+    pub heat_gain: PerRisk<i64>,
+    /// Bonus (pp) za pełną umiejętność (50).
+    pub skill_bonus: f32,
+    pub minigame: MinigameBonus,
+    /// Kara za wpadkę jako ułamek wylosowanego łupu.
+    pub fail_penalty: f32,
+    pub fail_heat: i64,
+}
+
+/// Wpływ minigierki na szansę (pp). Partial: `partial_max` minus 1 pp za każde `partial_ms_per_pp` ms.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MinigameBonus {
+    pub success: f32,
+    pub partial_max: f32,
+    pub partial_ms_per_pp: f32,
+    pub fail: f32,
+    pub not_played: f32,
+}
+
+impl Default for CrimeTables {
+    fn default() -> Self {
+        Self {
+            base_chance: PerRisk { low: 62.0, medium: 52.0, high: 42.0, hardcore: 32.0 },
+            mode_chance: PerMode {
+                standard: 0.0,
+                szybki: -3.0,
+                ostrozny: 3.0,
+                shadow: 2.0,
+                hardcore: -6.0,
+                ryzykowny: -4.0,
+                planowany: 4.0,
+                szalony: -8.0,
+            },
+            min_base_chance: 5.0,
+            max_base_chance: 95.0,
+            reward: PerRisk { low: [300, 600], medium: [600, 1200], high: [1200, 2400], hardcore: [2400, 4200] },
+            mode_reward_mult: PerMode {
+                standard: 1.0,
+                szybki: 0.95,
+                ostrozny: 1.05,
+                shadow: 1.15,
+                hardcore: 1.2,
+                ryzykowny: 1.1,
+                planowany: 1.15,
+                szalony: 1.25,
+            },
+            heat_gain: PerRisk { low: 4, medium: 7, high: 10, hardcore: 14 },
+            skill_bonus: 15.0,
+            minigame: MinigameBonus::default(),
+            fail_penalty: 0.35,
+            fail_heat: 2,
+        }
+    }
+}
+
+impl Default for MinigameBonus {
+    fn default() -> Self {
+        Self { success: 18.0, partial_max: 12.0, partial_ms_per_pp: 25.0, fail: -22.0, not_played: -10.0 }
+    }
+}
+
+// =======================
+// 🔥 HEAT
+// =======================
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HeatTables {
+    /// Progi rosnąco po `from`; pierwszy musi zaczynać się od 0.
+    pub tiers: Vec<HeatTier>,
+    /// Im większe ryzyko, tym mocniej „gryzie” HEAT.
+    pub risk_factor: PerRisk<f32>,
+    /// Niektóre tryby łagodzą, inne zaostrzają.
+    pub mode_scale: PerMode<ModeScale>,
+    pub max_extra_cooldown_secs: u64,
+    /// Ile HEAT ubywa za każdą pełną godzinę (zadanie `heat.decay`; 0 = wyłączone).
+    pub decay_per_hour: i64,
+    /// „Przyczaj się” w `/crime`: ile HEAT schodzi i na ile minut blokuje napady.
+    pub lay_low_heat: i64,
+    pub lay_low_mins: i64,
+}
+
+/// Bazowe efekty od progu HEAT (przed wagami ryzyka i trybu).
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct HeatTier {
+    pub from: u32,
+    pub chance_mult: f32,
+    pub reward_mult: f32,
+    pub qte_window_mult: f32,
+    pub simon_seq_delta: i32,
+    pub extra_cooldown_secs: u64,
+    pub ambush_chance_pct: u8,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ModeScale {
+    pub all: f32,    // ogólna „ostrość” (szansa/łup/QTE/CD)
+    pub simon: f32,  // skala dla simon_seq_delta
+    pub ambush: f32, // skala dla szansy zasadzki
+}
+
+impl Default for HeatTables {
+    fn default() -> Self {
+        let tier = |from, chance_mult, reward_mult, qte_window_mult, simon_seq_delta, extra_cooldown_secs, ambush_chance_pct| {
+            HeatTier { from, chance_mult, reward_mult, qte_window_mult, simon_seq_delta, extra_cooldown_secs, ambush_chance_pct }
+        };
+        let scale = |all, simon, ambush| ModeScale { all, simon, ambush };
+        Self {
+            tiers: vec![
+                tier(0, 1.00, 1.00, 1.00, 0, 0, 0),
+                tier(25, 0.95, 0.95, 0.95, 0, 0, 0),
+                tier(50, 0.90, 0.90, 0.85, 1, 2, 0),
+                tier(75, 0.80, 0.85, 0.75, 2, 5, 0),
+                tier(90, 0.65, 0.75, 0.60, 3, 10, 20),
+            ],
+            risk_factor: PerRisk { low: 0.70, medium: 1.00, high: 1.25, hardcore: 1.50 },
+            mode_scale: PerMode {
+                standard: scale(1.00, 1.00, 1.00),
+                szybki: scale(1.10, 1.00, 1.10),
+                ostrozny: scale(0.85, 0.85, 0.85),
+                shadow: scale(0.90, 0.90, 0.50), // stealth: mniejsza szansa zasadzki
+                hardcore: scale(1.60, 1.30, 1.60),
+                ryzykowny: scale(1.25, 1.15, 1.40),
+                planowany: scale(0.90, 0.85, 0.90),
+                szalony: scale(1.40, 1.25, 1.50),
+            },
+            max_extra_cooldown_secs: 60,
+            decay_per_hour: 2,
+            lay_low_heat: 20,
+            lay_low_mins: 30,
+        }
+    }
+}
+
+// =======================
+// 🎮 Minigierki
+// =======================
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MinigameTables {
+    pub qte_target_ms: i32,
+    pub qte_window_ms: PerRisk<i32>,
+    pub qte_min_window_ms: i32,
+    pub simon_length: PerRisk<i32>,
+    pub simon_min_length: i32,
+    pub simon_max_length: i32,
+    /// Stały zapas (ms) dodawany do okna QTE na rozrzut opóźnień Discorda między dwoma kliknięciami.
+    /// Nie jest mierzony — ten sam dla każdego gracza i bramki; dotyczy też ucieczki z więzienia.
+    pub qte_network_grace_ms: i32,
+}
+
+impl Default for MinigameTables {
+    fn default() -> Self {
+        Self {
+            qte_target_ms: 1200,
+            qte_window_ms: PerRisk { low: 220, medium: 150, high: 100, hardcore: 70 },
+            qte_min_window_ms: 40,
+            simon_length: PerRisk { low: 4, medium: 5, high: 6, hardcore: 7 },
+            simon_min_length: 3,
+            simon_max_length: 8,
+            qte_network_grace_ms: 50,
+        }
+    }
+}
+
+// =======================
+// 🧰 Przedmioty
+// =======================
+
+/// Jeden przedmiot: cena i zużycie w sklepie (`/ekwipunek`) oraz efekt w napadzie —
+/// pola `*_mult` mnożą się, reszta sumuje (potem `items::clamp_effects`).
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ItemTable {
+    /// Od ilu PP przedmiot można kupić.
+    pub required_pp: u32,
+    pub price: i64,
+    /// Ile napadów wytrzymuje jeden egzemplarz (jednorazowe: 1, kupowane na sztuki).
+    pub durability: i32,
+    pub single_use: bool,
+    pub qte_window_mult: f32,
+    pub qte_grace_ms: i32,
+    pub simon_seq_delta: i32,
+    pub simon_time_mult: f32,
+    pub timer_extend_pct: f32,
+    pub heat_reduce_pct: f32,
+    pub payout_bonus_pct: f32,
+    pub success_pp_bonus: f32,
+    pub heat_mult: f32,
+    pub fail_penalty_mult: f32,
+}
+
+impl Default for ItemTable {
+    fn default() -> Self {
+        Self {
+            required_pp: 0,
+            price: 0,
+            durability: 1,
+            single_use: false,
+            qte_window_mult: 1.0,
+            qte_grace_ms: 0,
+            simon_seq_delta: 0,
+            simon_time_mult: 1.0,
+            timer_extend_pct: 0.0,
+            heat_reduce_pct: 0.0,
+            payout_bonus_pct: 0.0,
+            success_pp_bonus: 0.0,
+            heat_mult: 1.0,
+            fail_penalty_mult: 1.0,
+        }
+    }
+}
+
+/// Tabela efektów na każdy przedmiot.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ItemTables {
+    pub lockpick_set: ItemTable,
+    pub pro_gloves: ItemTable,
+    pub toolkit: ItemTable,
+    pub smoke_grenade: ItemTable,
+    pub hacker_laptop: ItemTable,
+    pub adrenaline: ItemTable,
+}
+
+impl ItemTables {
+    pub fn get(&self, k: ItemKey) -> &ItemTable {
+        match k {
+            ItemKey::LockpickSet => &self.lockpick_set,
+            ItemKey::ProGloves => &self.pro_gloves,
+            ItemKey::Toolkit => &self.toolkit,
+            ItemKey::SmokeGrenade => &self.smoke_grenade,
+            ItemKey::HackerLaptop => &self.hacker_laptop,
+            ItemKey::Adrenaline => &self.adrenaline,
+        }
+    }
+
+    fn all(&self) -> [(&'static str, &ItemTable); 6] {
+        [
+            ("lockpick_set", &self.lockpick_set),
+            ("pro_gloves", &self.pro_gloves),
+            ("toolkit", &self.toolkit),
+            ("smoke_grenade", &self.smoke_grenade),
+            ("hacker_laptop", &self.hacker_laptop),
+            ("adrenaline", &self.adrenaline),
+        ]
+    }
+}
+
+impl Default for ItemTables {
+    fn default() -> Self {
+        let base = ItemTable::default();
+        Self {
+            lockpick_set: ItemTable { price: 200, durability: 10, simon_seq_delta: -1, ..base },
+            pro_gloves: ItemTable {
+                required_pp: 5,
+                price: 350,
+                durability: 8,
+                simon_seq_delta: -1,
+                simon_time_mult: 1.05,
+                ..base
+            },
+            toolkit: ItemTable { required_pp: 10, price: 500, durability: 10, payout_bonus_pct: 0.05, ..base },
+            smoke_grenade: ItemTable {
+                required_pp: 15,
+                price: 120,
+                single_use: true,
+                heat_reduce_pct: 0.08,
+                timer_extend_pct: 0.05,
+                ..base
+            },
+            hacker_laptop: ItemTable {
+                required_pp: 22,
+                price: 1200,
+                durability: 15,
+                qte_grace_ms: 40,
+                qte_window_mult: 1.10,
+                ..base
+            },
+            adrenaline: ItemTable {
+                required_pp: 30,
+                price: 180,
+                single_use: true,
+                qte_window_mult: 1.05,
+                simon_time_mult: 1.08,
+                fail_penalty_mult: 0.9,
+                heat_mult: 1.05,
+                ..base
+            },
+        }
+    }
+}
+
+/// Sklep i warsztat `/ekwipunek`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ShopTables {
+    /// Koszt pełnej naprawy jako ułamek ceny (naprawa częściowa — proporcjonalnie).
+    pub repair_cost_pct: f32,
+    /// Ile sztuk jednorazowego przedmiotu można mieć naraz.
+    pub max_stack: i32,
+    /// Ile minut ważna jest oferta odsprzedaży innemu graczowi.
+    pub offer_mins: i64,
+}
+
+impl Default for ShopTables {
+    fn default() -> Self {
+        Self { repair_cost_pct: 0.5, max_stack: 5, offer_mins: 15 }
+    }
+}
+
+// =======================
+// 🎯 Cele napadów
+// =======================
+
+/// Jeden cel: próg PP, modyfikatory napadu (względem trybu i ryzyka) i łup specjalny.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TargetTable {
+    /// Od ilu PP cel można wybrać.
+    pub required_pp: u32,
+    /// Punkty procentowe do szansy bazowej (może być ujemne).
+    pub chance_delta: f32,
+    /// Mnożnik widełek łupu z ryzyka i trybu.
+    pub reward_mult: f32,
+    /// Mnożnik HEAT za napad.
+    pub heat_mult: f32,
+    /// Minigierka, pod którą cel jest „skrojony” — gra w nią daje `preferred_bonus` pp szansy.
+    pub preferred_minigame: MinigameKind,
+    pub preferred_bonus: f32,
+    /// Szansa (0…1) na łup specjalny przy udanym napadzie i jego widełki w TK.
+    pub drop_chance: f64,
+    pub drop: [i64; 2],
+}
+
+impl Default for TargetTable {
+    fn default() -> Self {
+        Self {
+            required_pp: 0,
+            chance_delta: 0.0,
+            reward_mult: 1.0,
+            heat_mult: 1.0,
+            preferred_minigame: MinigameKind::Qte,
+            preferred_bonus: 0.0,
+            drop_chance: 0.0,
+            drop: [0, 0],
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TargetTables {
+    pub kiosk: TargetTable,
+    pub jeweller: TargetTable,
+    pub bank: TargetTable,
+    pub casino_vault: TargetTable,
+}
+
+impl TargetTables {
+    pub fn get(&self, k: TargetKey) -> &TargetTable {
+        match k {
+            TargetKey::Kiosk => &self.kiosk,
+            TargetKey::Jeweller => &self.jeweller,
+            TargetKey::Bank => &self.bank,
+            TargetKey::CasinoVault => &self.casino_vault,
+        }
+    }
+
+    fn all(&self) -> [(&'static str, &TargetTable); 4] {
+        [
+            ("kiosk", &self.kiosk),
+            ("jeweller", &self.jeweller),
+            ("bank", &self.bank),
+            ("casino_vault", &self.casino_vault),
+        ]
+    }
+}
+
+impl Default for TargetTables {
+    fn default() -> Self {
+        let base = TargetTable::default();
+        Self {
+            kiosk: TargetTable {
+                chance_delta: 8.0,
+                reward_mult: 0.6,
+                heat_mult: 0.7,
+                preferred_bonus: 3.0,
+                drop_chance: 0.10,
+                drop: [20, 60],
+                ..base
+            },
+            jeweller: TargetTable {
+                required_pp: 10,
+                reward_mult: 1.2,
+                preferred_minigame: MinigameKind::Simon,
+                preferred_bonus: 4.0,
+                drop_chance: 0.08,
+                drop: [150, 400],
+                ..base
+            },
+            bank: TargetTable {
+                required_pp: 25,
+                chance_delta: -8.0,
+                reward_mult: 1.8,
+                heat_mult: 1.4,
+                preferred_minigame: MinigameKind::Simon,
+                preferred_bonus: 5.0,
+                drop_chance: 0.05,
+                drop: [400, 1000],
+            },
+            casino_vault: TargetTable {
+                required_pp: 40,
+                chance_delta: -14.0,
+                reward_mult: 2.6,
+                heat_mult: 1.7,
+                preferred_bonus: 6.0,
+                drop_chance: 0.04,
+                drop: [800, 2000],
+                ..base
+            },
+        }
+    }
+}
+
+// =======================
+// 🌳 Drzewko złodzieja
+// =======================
+
+/// Jedna umiejętność: koszt poziomu w PP, limit poziomów i efekt na poziom
+/// (znaczenie `per_rank` zależy od umiejętności — zob. `perks::PERK_META`).
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PerkTable {
+    pub cost_pp: u32,
+    pub max_rank: u8,
+    pub per_rank: f32,
+}
+
+impl Default for PerkTable {
+    fn default() -> Self {
+        Self { cost_pp: 2, max_rank: 3, per_rank: 0.0 }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PerkTables {
+    pub shadow_step: PerkTable,
+    pub lookout: PerkTable,
+    pub photographic_memory: PerkTable,
+    pub reflexes: PerkTable,
+    pub thick_skin: PerkTable,
+    pub heavy_hands: PerkTable,
+    /// Reset drzewka: stała opłata + TK za każdy oddany PP.
+    pub respec_base: i64,
+    pub respec_per_pp: i64,
+}
+
+impl PerkTables {
+    pub fn get(&self, k: PerkKey) -> &PerkTable {
+        match k {
+            PerkKey::ShadowStep => &self.shadow_step,
+            PerkKey::Lookout => &self.lookout,
+            PerkKey::PhotographicMemory => &self.photographic_memory,
+            PerkKey::Reflexes => &self.reflexes,
+            PerkKey::ThickSkin => &self.thick_skin,
+            PerkKey::HeavyHands => &self.heavy_hands,
+        }
+    }
+
+    fn all(&self) -> [(&'static str, &PerkTable); 6] {
+        [
+            ("shadow_step", &self.shadow_step),
+            ("lookout", &self.lookout),
+            ("photographic_memory", &self.photographic_memory),
+            ("reflexes", &self.reflexes),
+            ("thick_skin", &self.thick_skin),
+            ("heavy_hands", &self.heavy_hands),
+        ]
+    }
+}
+
+impl Default for PerkTables {
+    fn default() -> Self {
+        Self {
+            shadow_step: PerkTable { cost_pp: 2, max_rank: 3, per_rank: 0.06 },
+            lookout: PerkTable { cost_pp: 3, max_rank: 3, per_rank: 0.2 },
+            photographic_memory: PerkTable { cost_pp: 3, max_rank: 2, per_rank: 1.0 },
+            reflexes: PerkTable { cost_pp: 2, max_rank: 3, per_rank: 15.0 },
+            thick_skin: PerkTable { cost_pp: 2, max_rank: 3, per_rank: 0.08 },
+            heavy_hands: PerkTable { cost_pp: 3, max_rank: 3, per_rank: 0.04 },
+            respec_base: 250,
+            respec_per_pp: 20,
+        }
+    }
+}
+
+// =======================
+// 🚔 Więzienie
+// =======================
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct JailTables {
+    /// Od jakiego HEAT (po wpadce) grozi więzienie; zasadzka zamyka zawsze.
+    pub min_heat: i64,
+    /// Wyrok bazowy (min) z ryzyka, +1 min za każde `heat_per_min` HEAT ponad próg.
+    pub sentence_mins: PerRisk<i64>,
+    pub heat_per_min: i64,
+    /// Mnożnik wyroku po zasadzce.
+    pub ambush_mult: f64,
+    pub max_sentence_mins: i64,
+    /// Kaucja za każdą rozpoczętą minutę wyroku.
+    pub bail_per_min: i64,
+}
+
+impl Default for JailTables {
+    fn default() -> Self {
+        Self {
+            min_heat: 40,
+            sentence_mins: PerRisk { low: 5, medium: 10, high: 20, hardcore: 30 },
+            heat_per_min: 4,
+            ambush_mult: 1.5,
+            max_sentence_mins: 180,
+            bail_per_min: 15,
+        }
+    }
+}
+
+// =======================
+// ⏳ Cooldowny
+// =======================
+
+/// Bazowe cooldowny komend w sekundach (przed dodatkiem z HEAT i zniżką subskrybenta).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CooldownTables {
+    pub crime_secs: i64,
+    pub rob_secs: i64,
+    pub work_secs: i64,
+    pub slut_secs: i64,
+    pub daily_secs: i64,
+    /// % skrócenia cooldownów dla aktywnych subskrybentów rangi (0 = wyłączone).
+    pub sub_reduction_pct: i64,
+}
+
+impl Default for CooldownTables {
+    fn default() -> Self {
+        Self { crime_secs: 120, rob_secs: 600, work_secs: 30, slut_secs: 30, daily_secs: 24 * 3600, sub_reduction_pct: 0 }
+    }
+}
+
+// =======================
+// 🎁 /daily
+// =======================
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DailyTables {
+    pub reward: [i64; 2],
+}
+
+impl Default for DailyTables {
+    fn default() -> Self {
+        Self { reward: [250, 500] }
+    }
+}
+
+// =======================
+// 🦹 /rob
+// =======================
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RobTables {
+    pub min_balance_to_rob: i64,
+    pub success_chance: f64,
+    pub stolen: [i64; 2],
+    pub fine: [i64; 2],
+}
+
+impl Default for RobTables {
+    fn default() -> Self {
+        Self { min_balance_to_rob: 50, success_chance: 0.5, stolen: [25, 150], fine: [25, 75] }
+    }
+}
+
+// =======================
+// 💼 /work
+// =======================
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WorkTables {
+    /// Mnożnik serii: +`streak_step` za każdą zmianę po pierwszej, do `streak_cap`.
+    pub streak_step: f32,
+    pub streak_cap: f32,
+    pub series: Vec<SeriesStep>,
+    pub safe: Contract,
+    pub balanced: Contract,
+    pub high_risk: Contract,
+}
+
+/// Kontrakt: najpierw szansa na krytyk, potem na zero, inaczej zwykłe widełki.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Contract {
+    pub pay: [i64; 2],
+    pub crit_chance: f64,
+    pub crit_pay: [i64; 2],
+    pub bust_chance: f64,
+}
+
+impl Default for Contract {
+    fn default() -> Self {
+        Self { pay: [0, 0], crit_chance: 0.0, crit_pay: [0, 0], bust_chance: 0.0 }
+    }
+}
+
+impl Default for WorkTables {
+    fn default() -> Self {
+        Self {
+            streak_step: 0.05,
+            streak_cap: 1.50,
+            series: default_series(),
+            safe: Contract { pay: [30, 50], ..Contract::default() },
+            balanced: Contract { pay: [40, 90], bust_chance: 0.10, ..Contract::default() },
+            high_risk: Contract { pay: [60, 140], crit_chance: 0.10, crit_pay: [120, 200], bust_chance: 0.30 },
+        }
+    }
+}
+
+fn default_series() -> Vec<SeriesStep> {
+    vec![
+        SeriesStep { from: 5, flat: 10 },
+        SeriesStep { from: 10, flat: 25 },
+        SeriesStep { from: 15, flat: 50 },
+    ]
+}
+
+// =======================
+// 💋 /slut
+// =======================
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SlutTables {
+    pub gentle: FlirtStyle,
+    pub daring: FlirtStyle,
+    pub chaotic: FlirtStyle,
+    /// +pp szansy za każdy punkt reputacji (ujemna reputacja odejmuje).
+    pub rep_point_bonus: f32,
+    pub min_chance: f32,
+    pub max_chance: f32,
+    pub fail_rep: i32,
+    /// Po tylu porażkach z rzędu flirt wchodzi na pewno.
+    pub pity_after_fails: i32,
+    pub rare_chance: f64,
+    pub rare_drop_bonus: i64,
+    pub streak_step: f32,
+    pub streak_cap: f32,
+    pub series: Vec<SeriesStep>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FlirtStyle {
+    pub chance: f32,
+    pub pay: [i32; 2],
+    pub rep_gain: i32,
+    pub pay_mult: f32,
+}
+
+impl Default for SlutTables {
+    fn default() -> Self {
+        Self {
+            gentle: FlirtStyle { chance: 0.70, pay: [30, 80], rep_gain: 2, pay_mult: 0.95 },
+            daring: FlirtStyle { chance: 0.55, pay: [50, 140], rep_gain: 3, pay_mult: 1.00 },
+            chaotic: FlirtStyle { chance: 0.45, pay: [0, 200], rep_gain: 4, pay_mult: 1.05 },
+            rep_point_bonus: 0.0008,
+            min_chance: 0.10,
+            max_chance: 0.95,
+            fail_rep: -3,
+            pity_after_fails: 3,
+            rare_chance: 0.03,
+            rare_drop_bonus: 150,
+            streak_step: 0.06,
+            streak_cap: 1.60,
+            series: default_series(),
+        }
+    }
+}
+
+// =======================
+// 🏦 /bank
+// =======================
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BankTables {
+    /// Pojemność konta na poziomie 0 i przyrost za każdy kupiony poziom.
+    pub base_capacity: i64,
+    pub capacity_per_level: i64,
+    pub max_level: u8,
+    /// Cena poziomu L → L+1: `upgrade_cost + upgrade_cost_step × L`.
+    pub upgrade_cost: i64,
+    pub upgrade_cost_step: i64,
+    /// Odsetki za jedno naliczenie (raz dziennie) od salda banku do `interest_cap`.
+    pub interest_rate: f64,
+    pub interest_cap: i64,
+}
+
+impl Default for BankTables {
+    fn default() -> Self {
+        Self {
+            base_capacity: 2_000,
+            capacity_per_level: 3_000,
+            max_level: 10,
+            upgrade_cost: 1_500,
+            upgrade_cost_step: 1_000,
+            interest_rate: 0.002,
+            interest_cap: 20_000,
+        }
+    }
+}
+
+// =======================
+// 🏛️ Podatek od majątku
+// =======================
+
+/// Próg podatku: stawka dotyczy części majątku (portfel + bank) ponad `from`, do następnego progu.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TaxBracket {
+    pub from: i64,
+    pub rate: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TaxTables {
+    /// Progi rosnąco; pusta lista = bez podatku.
+    pub brackets: Vec<TaxBracket>,
+}
+
+impl Default for TaxTables {
+    fn default() -> Self {
+        Self {
+            brackets: vec![
+                TaxBracket { from: 50_000, rate: 0.002 },
+                TaxBracket { from: 150_000, rate: 0.005 },
+                TaxBracket { from: 500_000, rate: 0.01 },
+            ],
+        }
+    }
+}
+
+// =======================
+// 💳 /pozyczka
+// =======================
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LoanTables {
+    /// Widełki kwoty pożyczki z banku.
+    pub bank_min: i64,
+    pub bank_max: i64,
+    /// Odsetki banku — jednorazowo od kapitału, doliczane przy wypłacie.
+    pub bank_rate: f64,
+    /// Termin spłaty pożyczki z banku (i domyślny dla pożyczek między graczami).
+    pub term_days: i64,
+    /// Granice dla pożyczek między graczami.
+    pub max_rate: f64,
+    pub max_term_days: i64,
+    /// Jak długo oferta pożyczki od gracza czeka na przyjęcie (minuty).
+    pub offer_mins: i64,
+    /// Część każdego zarobku z /work, /slut i /daily potrącana na spłatę (po terminie — `default_garnish_pct`).
+    pub garnish_pct: f64,
+    pub default_garnish_pct: f64,
+    /// Kara za zwłokę: część długu doliczana w chwili przekroczenia terminu.
+    pub default_penalty: f64,
+}
+
+impl Default for LoanTables {
+    fn default() -> Self {
+        Self {
+            bank_min: 100,
+            bank_max: 5_000,
+            bank_rate: 0.10,
+            term_days: 7,
+            max_rate: 0.50,
+            max_term_days: 30,
+            offer_mins: 15,
+            garnish_pct: 0.25,
+            default_garnish_pct: 0.50,
+            default_penalty: 0.20,
+        }
+    }
+}
+
+// =======================
+// 💸 /pay
+// =======================
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PayTables {
+    /// Ile TK gracz może przelać innym w ciągu doby (UTC); 0 = bez limitu.
+    pub daily_limit: i64,
+    /// Opłata od przelewu (część kwoty, w górę) — płaci nadawca, trafia do skarbca.
+    pub fee_rate: f64,
+    /// Przelew powyżej tej kwoty trzeba potwierdzić przyciskiem (ważnym `confirm_secs`); 0 = bez potwierdzeń.
+    pub confirm_above: i64,
+    pub confirm_secs: i64,
+    /// Minimalny wiek konta Discord i staż na serwerze — dla nadawcy i odbiorcy.
+    pub min_account_age_days: i64,
+    pub min_member_age_days: i64,
+    /// Prośba o zapłatę (`/pay zadaj`) wygasa po tylu minutach; gracz może mieć naraz `max_open_requests` otwartych.
+    pub request_mins: i64,
+    pub max_open_requests: i64,
+}
+
+impl Default for PayTables {
+    fn default() -> Self {
+        Self {
+            daily_limit: 20_000,
+            fee_rate: 0.02,
+            confirm_above: 5_000,
+            confirm_secs: 60,
+            min_account_age_days: 14,
+            min_member_age_days: 3,
+            request_mins: 60,
+            max_open_requests: 5,
+        }
+    }
+}
+
+// =======================
+// 📦 Całość
+// =======================
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BalanceConfig {
+    pub version: u32,
+    pub crime: CrimeTables,
+    pub heat: HeatTables,
+    pub minigames: MinigameTables,
+    pub items: ItemTables,
+    pub shop: ShopTables,
+    pub targets: TargetTables,
+    pub perks: PerkTables,
+    pub jail: JailTables,
+    pub cooldowns: CooldownTables,
+    pub daily: DailyTables,
+    pub rob: RobTables,
+    pub work: WorkTables,
+    pub slut: SlutTables,
+    pub bank: BankTables,
+    pub tax: TaxTables,
+    pub loans: LoanTables,
+    pub pay: PayTables,
+}
+
+impl Default for BalanceConfig {
+    fn default() -> Self {
+        Self {
+            version: BALANCE_VERSION,
+            crime: CrimeTables::default(),
+            heat: HeatTables::default(),
+            minigames: MinigameTables::default(),
+            items: ItemTables::default(),
+            shop: ShopTables::default(),
+            targets: TargetTables::default(),
+            perks: PerkTables::default(),
+            jail: JailTables::default(),
+            cooldowns: CooldownTables::default(),
+            daily: DailyTables::default(),
+            rob: RobTables::default(),
+            work: WorkTables::default(),
+            slut: SlutTables::default(),
+            bank: BankTables::default(),
+            tax: TaxTables::default(),
+            loans: LoanTables::default(),
+            pay: PayTables::default(),
+        }
+    }
+}
+
+impl BalanceConfig {
+    /// Parsuje i waliduje treść pliku (wersja jest obowiązkowa).
+    pub fn parse(text: &str) -> Result<Self> {
+        let raw: serde_json::Value = serde_json::from_str(text).context("niepoprawny JSON")?;
+        match raw.get("version").and_then(|v| v.as_u64()) {
+            Some(v) if v == u64::from(BALANCE_VERSION) => {}
+            Some(v) => bail!("nieobsługiwana wersja balansu {v} (obsługiwana: {BALANCE_VERSION})"),
+            None => bail!("brak pola `version` (obsługiwana: {BALANCE_VERSION})"),
+        }
+        let cfg: Self = serde_json::from_value(raw).context("niepoprawna struktura pliku balansu")?;
+        cfg.validate()?;
+        Ok(cfg)
+    }
+
+    /// Sprawdza zakresy: widełki min ≤ max, szanse w [0, 1] / [0, 100], mnożniki > 0, progi rosnąco.
+    pub fn validate(&self) -> Result<()> {
+        let c = &self.crime;
+        for (k, v) in c.base_chance.all() {
+            in_range(&format!("crime.base_chance.{k}"), v, 0.0, 100.0)?;
+        }
+        for (k, v) in c.mode_chance.all() {
+            in_range(&format!("crime.mode_chance.{k}"), v, -100.0, 100.0)?;
+        }
+        ensure!(
+            0.0 <= c.min_base_chance && c.min_base_chance <= c.max_base_chance && c.max_base_chance <= 100.0,
+            "crime: wymagane 0 ≤ min_base_chance ≤ max_base_chance ≤ 100"
+        );
+        for (k, r) in c.reward.all() {
+            span(&format!("crime.reward.{k}"), r)?;
+        }
+        for (k, v) in c.mode_reward_mult.all() {
+            positive(&format!("crime.mode_reward_mult.{k}"), v)?;
+        }
+        for (k, v) in c.heat_gain.all() {
+            ensure!(v >= 0, "crime.heat_gain.{k}: nie może być ujemne");
+        }
+        in_range("crime.skill_bonus", c.skill_bonus, 0.0, 100.0)?;
+        positive("crime.minigame.partial_ms_per_pp", c.minigame.partial_ms_per_pp)?;
+        in_range("crime.fail_penalty", c.fail_penalty, 0.0, 1.0)?;
+        ensure!(c.fail_heat >= 0, "crime.fail_heat: nie może być ujemne");
+
+        let h = &self.heat;
+        ensure!(h.tiers.first().is_some_and(|t| t.from == 0), "heat.tiers: pierwszy próg musi mieć from = 0");
+        ensure!(h.tiers.windows(2).all(|w| w[0].from < w[1].from), "heat.tiers: progi muszą rosnąć");
+        for t in &h.tiers {
+            let name = format!("heat.tiers[from={}]", t.from);
+            in_range(&format!("{name}.chance_mult"), t.chance_mult, 0.05, 1.0)?;
+            in_range(&format!("{name}.reward_mult"), t.reward_mult, 0.05, 1.0)?;
+            in_range(&format!("{name}.qte_window_mult"), t.qte_window_mult, 0.05, 1.0)?;
+            ensure!(t.simon_seq_delta >= 0, "{name}.simon_seq_delta: nie może być ujemne");
+            ensure!(t.ambush_chance_pct <= 100, "{name}.ambush_chance_pct: maks. 100");
+        }
+        for (k, v) in h.risk_factor.all() {
+            positive(&format!("heat.risk_factor.{k}"), v)?;
+        }
+        for (k, s) in h.mode_scale.all() {
+            positive(&format!("heat.mode_scale.{k}.all"), s.all)?;
+            positive(&format!("heat.mode_scale.{k}.simon"), s.simon)?;
+            positive(&format!("heat.mode_scale.{k}.ambush"), s.ambush)?;
+        }
+        ensure!((0..=100).contains(&h.decay_per_hour), "heat.decay_per_hour: {} poza zakresem 0…100", h.decay_per_hour);
+        ensure!(h.lay_low_heat >= 1, "heat.lay_low_heat: musi być ≥ 1");
+        ensure!(h.lay_low_mins >= 1, "heat.lay_low_mins: musi być ≥ 1");
+
+        let m = &self.minigames;
+        ensure!(m.qte_target_ms > 0, "minigames.qte_target_ms: musi być > 0");
+        ensure!(m.qte_min_window_ms > 0, "minigames.qte_min_window_ms: musi być > 0");
+        for (k, v) in m.qte_window_ms.all() {
+            ensure!(v > 0, "minigames.qte_window_ms.{k}: musi być > 0");
+        }
+        ensure!(
+            1 <= m.simon_min_length && m.simon_min_length <= m.simon_max_length,
+            "minigames: wymagane 1 ≤ simon_min_length ≤ simon_max_length"
+        );
+        for (k, v) in m.simon_length.all() {
+            ensure!(v > 0, "minigames.simon_length.{k}: musi być > 0");
+        }
+        ensure!(
+            (0..=500).contains(&m.qte_network_grace_ms),
+            "minigames.qte_network_grace_ms: {} poza zakresem 0…500",
+            m.qte_network_grace_ms
+        );
+
+        for (k, it) in self.items.all() {
+            ensure!(it.price >= 0, "items.{k}.price: nie może być ujemna");
+            ensure!(it.durability >= 1, "items.{k}.durability: musi być ≥ 1");
+            ensure!(!it.single_use || it.durability == 1, "items.{k}: jednorazowy przedmiot ma durability = 1");
+            positive(&format!("items.{k}.qte_window_mult"), it.qte_window_mult)?;
+            positive(&format!("items.{k}.simon_time_mult"), it.simon_time_mult)?;
+            positive(&format!("items.{k}.heat_mult"), it.heat_mult)?;
+            positive(&format!("items.{k}.fail_penalty_mult"), it.fail_penalty_mult)?;
+        }
+
+        let sh = &self.shop;
+        in_range("shop.repair_cost_pct", sh.repair_cost_pct, 0.0, 10.0)?;
+        ensure!(sh.max_stack >= 1, "shop.max_stack: musi być ≥ 1");
+        ensure!(sh.offer_mins >= 1, "shop.offer_mins: musi być ≥ 1");
+
+        for (k, t) in self.targets.all() {
+            in_range(&format!("targets.{k}.chance_delta"), t.chance_delta, -100.0, 100.0)?;
+            positive(&format!("targets.{k}.reward_mult"), t.reward_mult)?;
+            positive(&format!("targets.{k}.heat_mult"), t.heat_mult)?;
+            in_range(&format!("targets.{k}.preferred_bonus"), t.preferred_bonus, 0.0, 100.0)?;
+            probability(&format!("targets.{k}.drop_chance"), t.drop_chance)?;
+            span(&format!("targets.{k}.drop"), t.drop)?;
+        }
+
+        let p = &self.perks;
+        for (k, t) in p.all() {
+            ensure!(t.cost_pp >= 1, "perks.{k}.cost_pp: musi być ≥ 1");
+            ensure!(t.max_rank >= 1, "perks.{k}.max_rank: musi być ≥ 1");
+            ensure!(t.per_rank.is_finite() && t.per_rank >= 0.0, "perks.{k}.per_rank: nie może być ujemne");
+        }
+        // umiejętności-mnożniki nie mogą zjechać do zera na maksymalnym poziomie
+        for (k, t) in [("shadow_step", p.shadow_step), ("lookout", p.lookout), ("thick_skin", p.thick_skin)] {
+            ensure!(t.per_rank * f32::from(t.max_rank) < 1.0, "perks.{k}: per_rank × max_rank musi być < 1");
+        }
+        ensure!(p.respec_base >= 0 && p.respec_per_pp >= 0, "perks: koszty resetu nie mogą być ujemne");
+
+        let j = &self.jail;
+        ensure!(j.min_heat >= 1, "jail.min_heat: musi być ≥ 1");
+        for (k, v) in j.sentence_mins.all() {
+            ensure!(v >= 1, "jail.sentence_mins.{k}: musi być ≥ 1");
+        }
+        ensure!(j.heat_per_min >= 1, "jail.heat_per_min: musi być ≥ 1");
+        ensure!(j.ambush_mult.is_finite() && j.ambush_mult >= 1.0, "jail.ambush_mult: musi być ≥ 1");
+        ensure!(j.max_sentence_mins >= 1, "jail.max_sentence_mins: musi być ≥ 1");
+        ensure!(j.bail_per_min >= 1, "jail.bail_per_min: musi być ≥ 1");
+
+        let cd = &self.cooldowns;
+        for (k, v) in [
+            ("crime_secs", cd.crime_secs),
+            ("rob_secs", cd.rob_secs),
+            ("work_secs", cd.work_secs),
+            ("slut_secs", cd.slut_secs),
+            ("daily_secs", cd.daily_secs),
+        ] {
+            ensure!(v >= 1, "cooldowns.{k}: musi być ≥ 1");
+        }
+        ensure!(
+            (0..=90).contains(&cd.sub_reduction_pct),
+            "cooldowns.sub_reduction_pct: {} poza zakresem 0…90",
+            cd.sub_reduction_pct
+        );
+
+        span("daily.reward", self.daily.reward)?;
+
+        let r = &self.rob;
+        ensure!(r.min_balance_to_rob >= 0, "rob.min_balance_to_rob: nie może być ujemne");
+        probability("rob.success_chance", r.success_chance)?;
+        span("rob.stolen", r.stolen)?;
+        span("rob.fine", r.fine)?;
+
+        let w = &self.work;
+        positive("work.streak_step", w.streak_step)?;
+        ensure!(w.streak_cap >= 1.0, "work.streak_cap: musi być ≥ 1");
+        series("work.series", &w.series)?;
+        for (k, ct) in [("safe", w.safe), ("balanced", w.balanced), ("high_risk", w.high_risk)] {
+            span(&format!("work.{k}.pay"), ct.pay)?;
+            span(&format!("work.{k}.crit_pay"), ct.crit_pay)?;
+            probability(&format!("work.{k}.crit_chance"), ct.crit_chance)?;
+            probability(&format!("work.{k}.bust_chance"), ct.bust_chance)?;
+        }
+
+        let s = &self.slut;
+        for (k, a) in [("gentle", s.gentle), ("daring", s.daring), ("chaotic", s.chaotic)] {
+            in_range(&format!("slut.{k}.chance"), a.chance, 0.0, 1.0)?;
+            span(&format!("slut.{k}.pay"), [i64::from(a.pay[0]), i64::from(a.pay[1])])?;
+            ensure!(a.rep_gain >= 0, "slut.{k}.rep_gain: nie może być ujemne");
+            positive(&format!("slut.{k}.pay_mult"), a.pay_mult)?;
+        }
+        ensure!(
+            0.0 <= s.min_chance && s.min_chance <= s.max_chance && s.max_chance <= 1.0,
+            "slut: wymagane 0 ≤ min_chance ≤ max_chance ≤ 1"
+        );
+        ensure!(s.fail_rep <= 0, "slut.fail_rep: nie może być dodatnie");
+        ensure!(s.pity_after_fails >= 1, "slut.pity_after_fails: musi być ≥ 1");
+        probability("slut.rare_chance", s.rare_chance)?;
+        ensure!(s.rare_drop_bonus >= 0, "slut.rare_drop_bonus: nie może być ujemne");
+        positive("slut.streak_step", s.streak_step)?;
+        ensure!(s.streak_cap >= 1.0, "slut.streak_cap: musi być ≥ 1");
+        series("slut.series", &s.series)?;
+
+        let b = &self.bank;
+        ensure!(b.base_capacity >= 0, "bank.base_capacity: nie może być ujemne");
+        ensure!(b.capacity_per_level >= 0, "bank.capacity_per_level: nie może być ujemne");
+        ensure!(b.upgrade_cost >= 0 && b.upgrade_cost_step >= 0, "bank: ceny poziomów nie mogą być ujemne");
+        probability("bank.interest_rate", b.interest_rate)?;
+        ensure!(b.interest_cap >= 0, "bank.interest_cap: nie może być ujemne");
+
+        let t = &self.tax;
+        ensure!(t.brackets.windows(2).all(|w| w[0].from < w[1].from), "tax.brackets: progi muszą rosnąć");
+        for br in &t.brackets {
+            ensure!(br.from >= 0, "tax.brackets[from={}]: próg nie może być ujemny", br.from);
+            probability(&format!("tax.brackets[from={}].rate", br.from), br.rate)?;
+        }
+
+        let l = &self.loans;
+        ensure!(1 <= l.bank_min && l.bank_min <= l.bank_max, "loans: wymagane 1 ≤ bank_min ≤ bank_max");
+        ensure!(l.bank_rate >= 0.0 && l.bank_rate <= l.max_rate, "loans: wymagane 0 ≤ bank_rate ≤ max_rate");
+        ensure!(l.max_rate.is_finite() && l.max_rate <= 10.0, "loans.max_rate: maks. 10");
+        ensure!(
+            1 <= l.term_days && l.term_days <= l.max_term_days,
+            "loans: wymagane 1 ≤ term_days ≤ max_term_days"
+        );
+        ensure!(l.offer_mins >= 1, "loans.offer_mins: musi być ≥ 1");
+        probability("loans.garnish_pct", l.garnish_pct)?;
+        probability("loans.default_garnish_pct", l.default_garnish_pct)?;
+        probability("loans.default_penalty", l.default_penalty)?;
+
+        let pay = &self.pay;
+        ensure!(pay.daily_limit >= 0, "pay.daily_limit: nie może być ujemne");
+        probability("pay.fee_rate", pay.fee_rate)?;
+        ensure!(pay.confirm_above >= 0, "pay.confirm_above: nie może być ujemne");
+        ensure!(pay.confirm_secs >= 10, "pay.confirm_secs: musi być ≥ 10");
+        ensure!(
+            pay.min_account_age_days >= 0 && pay.min_member_age_days >= 0,
+            "pay: minimalny wiek konta i staż nie mogą być ujemne"
+        );
+        ensure!(pay.request_mins >= 1, "pay.request_mins: musi być ≥ 1");
+        ensure!(pay.max_open_requests >= 1, "pay.max_open_requests: musi być ≥ 1");
+
+        Ok(())
+    }
+
+    /// Nazwy sekcji, które różnią się od `other` — do raportu po przeładowaniu.
+    pub fn changed_sections(&self, other: &Self) -> Vec<&'static str> {
+        let mut out = Vec::new();
+        if self.crime != other.crime {
+            out.push("crime");
+        }
+        if self.heat != other.heat {
+            out.push("heat");
+        }
+        if self.minigames != other.minigames {
+            out.push("minigames");
+        }
+        if self.items != other.items {
+            out.push("items");
+        }
+        if self.shop != other.shop {
+            out.push("shop");
+        }
+        if self.targets != other.targets {
+            out.push("targets");
+        }
+        if self.perks != other.perks {
+            out.push("perks");
+        }
+        if self.jail != other.jail {
+            out.push("jail");
+        }
+        if self.cooldowns != other.cooldowns {
+            out.push("cooldowns");
+        }
+        if self.daily != other.daily {
+            out.push("daily");
+        }
+        if self.rob != other.rob {
+            out.push("rob");
+        }
+        if self.work != other.work {
+            out.push("work");
+        }
+        if self.slut != other.slut {
+            out.push("slut");
+        }
+        if self.bank != other.bank {
+            out.push("bank");
+        }
+        if self.tax != other.tax {
+            out.push("tax");
+        }
+        if self.loans != other.loans {
+            out.push("loans");
+        }
+        if self.pay != other.pay {
+            out.push("pay");
+        }
+        out
+    }
+}
+
+fn in_range(name: &str, v: f32, min: f32, max: f32) -> Result<()> {
+    ensure!(v.is_finite() && (min..=max).contains(&v), "{name}: {v} poza zakresem {min}…{max}");
+    Ok(())
+}
+
+fn positive(name: &str, v: f32) -> Result<()> {
+    ensure!(v.is_finite() && v > 0.0, "{name}: musi być > 0 (jest {v})");
+    Ok(())
+}
+
+fn probability(name: &str, v: f64) -> Result<()> {
+    ensure!((0.0..=1.0).contains(&v), "{name}: {v} poza zakresem 0…1");
+    Ok(())
+}
+
+fn span(name: &str, r: [i64; 2]) -> Result<()> {
+    ensure!(0 <= r[0] && r[0] <= r[1], "{name}: wymagane 0 ≤ min ≤ max (jest {:?})", r);
+    Ok(())
+}
+
+fn series(name: &str, steps: &[SeriesStep]) -> Result<()> {
+    ensure!(steps.windows(2).all(|w| w[0].from < w[1].from), "{name}: progi muszą rosnąć");
+    ensure!(steps.iter().all(|s| s.from > 0 && s.flat >= 0), "{name}: from > 0 i flat ≥ 0");
+    Ok(())
+}
+
+// =======================
+// 🔄 Wczytywanie
+// =======================
+
+/// Wczytany balans razem z pochodzeniem (do `/admcontrol balans`).
+#[derive(Debug)]
+pub struct Loaded {
+    pub cfg: Arc<BalanceConfig>,
+    /// Ścieżka pliku (`None` = wartości domyślne).
+    pub source: Option<PathBuf>,
+    /// Skrót SHA-256 treści pliku (pierwsze 12 znaków hex).
+    pub checksum: Option<String>,
+}
+
+impl Loaded {
+    fn defaults() -> Self {
+        Self { cfg: Arc::new(BalanceConfig::default()), source: None, checksum: None }
+    }
+}
+
+/// Bieżące tabele balansu.
+pub fn get() -> Arc<BalanceConfig> {
+    current().cfg.clone()
+}
+
+/// Bieżące tabele z informacją o pochodzeniu.
+pub fn current() -> Arc<Loaded> {
+    CURRENT.read().unwrap_or_else(PoisonError::into_inner).clone()
+}
+
+fn read(path: &PathBuf) -> Result<Loaded> {
+    let text = std::fs::read_to_string(path).with_context(|| format!("odczyt {}", path.display()))?;
+    let cfg = BalanceConfig::parse(&text).with_context(|| format!("plik balansu {}", path.display()))?;
+    let checksum = hex::encode(Sha256::digest(text.as_bytes()))[..12].to_string();
+    Ok(Loaded { cfg: Arc::new(cfg), source: Some(path.clone()), checksum: Some(checksum) })
+}
+
+/// Wczytuje balans przy starcie. Zły plik = błąd (bot nie wstaje na złych tabelach).
+pub fn init() -> Result<()> {
+    let loaded = match PATH.as_ref() {
+        Some(path) => read(path)?,
+        None => Loaded::defaults(),
+    };
+    match &loaded.source {
+        Some(p) => tracing::info!(path = %p.display(), version = loaded.cfg.version, "balans wczytany z pliku"),
+        None => tracing::info!("balans: wartości domyślne (brak BALANCE_CONFIG_PATH)"),
+    }
+    *CURRENT.write().unwrap_or_else(PoisonError::into_inner) = Arc::new(loaded);
+    Ok(())
+}
+
+/// Przeładowuje plik w locie. Przy błędzie zostaje poprzednia wersja.
+/// Zwraca nowy stan i listę zmienionych sekcji.
+pub fn reload() -> Result<(Arc<Loaded>, Vec<&'static str>)> {
+    let Some(path) = PATH.as_ref() else {
+        bail!("BALANCE_CONFIG_PATH nie jest ustawiony — działają wartości domyślne");
+    };
+    let loaded = Arc::new(read(path)?);
+    let mut slot = CURRENT.write().unwrap_or_else(PoisonError::into_inner);
+    let changed = loaded.cfg.changed_sections(&slot.cfg);
+    *slot = loaded.clone();
+    Ok((loaded, changed))
+}
+
+/// Domyślne tabele jako JSON — punkt wyjścia do własnego pliku.
+pub fn defaults_json() -> String {
+    serde_json::to_string_pretty(&BalanceConfig::default()).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rejects(cfg: BalanceConfig, needle: &str) {
+        let err = cfg.validate().expect_err("walidacja powinna odrzucić plik").to_string();
+        assert!(err.contains(needle), "{err}");
+    }
+
+    #[test]
+    fn defaults_are_valid() {
+        BalanceConfig::default().validate().unwrap();
+        let parsed = BalanceConfig::parse(&defaults_json()).unwrap();
+        assert_eq!(parsed, BalanceConfig::default());
+    }
+
+    #[test]
+    fn version_is_required_and_checked() {
+        let err = BalanceConfig::parse(r#"{"version": 99}"#).unwrap_err().to_string();
+        assert!(err.contains("nieobsługiwana wersja"), "{err}");
+        let err = BalanceConfig::parse(r#"{"rob": {}}"#).unwrap_err().to_string();
+        assert!(err.contains("brak pola `version`"), "{err}");
+        assert!(BalanceConfig::parse(&format!(r#"{{"version": {BALANCE_VERSION}}}"#)).is_ok());
+    }
+
+    #[test]
+    fn unknown_key_is_rejected() {
+        let text = format!(r#"{{"version": {BALANCE_VERSION}, "rob": {{"succes_chance": 0.4}}}}"#);
+        assert!(BalanceConfig::parse(&text).is_err());
+    }
+
+    #[test]
+    fn tiers_must_ascend() {
+        let mut cfg = BalanceConfig::default();
+        cfg.tax.brackets.swap(0, 1);
+        rejects(cfg, "tax.brackets");
+
+        let mut cfg = BalanceConfig::default();
+        cfg.heat.tiers.swap(1, 2);
+        rejects(cfg, "heat.tiers");
+
+        let mut cfg = BalanceConfig::default();
+        cfg.heat.tiers[0].from = 5;
+        rejects(cfg, "from = 0");
+    }
+
+    #[test]
+    fn inverted_span_is_rejected() {
+        let mut cfg = BalanceConfig::default();
+        cfg.rob.stolen = [150, 25];
+        rejects(cfg, "rob.stolen");
+
+        let mut cfg = BalanceConfig::default();
+        cfg.daily.reward = [500, 250];
+        rejects(cfg, "daily.reward");
+    }
+}
@@ -1,60 +1,88 @@
 use rand::Rng;
+use serde::{Deserialize, Serialize};
 
 use super::{
-    balance,
+    balance, config,
     items::aggregate,
+    perks, targets,
     types::{HeistOutcome, MinigameResult, PlayerProfile, Risk, SoloHeistConfig, CrimeMode},
 };
 
+/// Wejście losowania napadu — zapisywane w `rolls` (`crime`), z niego powtórka.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SoloRoll {
+    pub profile: PlayerProfile,
+    pub cfg: SoloHeistConfig,
+    pub mg: MinigameResult,
+}
+
+pub fn roll_solo(input: &SoloRoll, rng: &mut impl Rng) -> (PlayerProfile, HeistOutcome) {
+    resolve_solo(input.profile.clone(), &input.cfg, input.mg, rng)
+}
+
 pub fn resolve_solo(
     mut profile: PlayerProfile,
     cfg: &SoloHeistConfig,
     mg: MinigameResult,
+    rng: &mut impl Rng,
 ) -> (PlayerProfile, HeistOutcome) {
     let mode = cfg.mode.unwrap_or(CrimeMode::Standard);
     let risk = cfg.risk.unwrap_or(Risk::Medium);
 
+    let t = &config::get().crime;
     let effects = aggregate(&cfg.items);
+    let perk = perks::effects(&cfg.perks);
+    let heat_fx = balance::heat_effects(mode, risk, profile.heat.max(0) as u32);
 
-    // bazowa szansa
-    let mut chance = balance::base_chance(mode, risk);
+    // bazowa szansa (tryb × ryzyko, cel)
+    let mut chance = targets::base_chance(mode, risk, cfg.target, cfg.minigame);
 
-    // umiejętność 0..50 -> do +15 pp
-    chance += (profile.thief_skill as f32 / 50.0) * 15.0;
+    // umiejętność 0..50 -> do +skill_bonus pp
+    chance += (profile.thief_skill as f32 / 50.0) * t.skill_bonus;
 
     // przedmioty
     chance += effects.success_pp_bonus;
 
     // minigierka
+    let mb = &t.minigame;
     match mg {
-        MinigameResult::Success => chance += 18.0,
+        MinigameResult::Success => chance += mb.success,
         MinigameResult::Partial(diff) => {
-            // im bliżej, tym więcej (do +12)
-            let bonus = (12.0 - (diff as f32 / 25.0)).clamp(0.0, 12.0);
+            // im bliżej, tym więcej (do +partial_max)
+            let bonus = (mb.partial_max - (diff as f32 / mb.partial_ms_per_pp)).clamp(0.0, mb.partial_max);
             chance += bonus;
         }
-        MinigameResult::Fail => chance -= 22.0,
-        MinigameResult::NotPlayed => chance -= 10.0,
+        MinigameResult::Fail => chance += mb.fail,
+        MinigameResult::NotPlayed => chance += mb.not_played,
     }
 
+    // HEAT: policja czeka — mnożnik na końcu, po wszystkich bonusach
+    chance *= heat_fx.chance_mult;
     chance = chance.clamp(1.0, 99.0);
 
-    // losowanie (rand 0.9)
-    let roll = rand::rng().random_range(0.0..100.0);
-    let success = roll < chance;
+    // losowanie — kolejność wywołań rng jest częścią zapisu (powtórki z ziarna)
+    let roll = rng.random_range(0.0..100.0);
+
+    let (min_r, max_r) = targets::reward_range(mode, risk, cfg.target);
+    let reward = rng.random_range(min_r..=max_r);
 
-    let (min_r, max_r) = balance::reward_range(mode, risk);
-    let reward = rand::rng().random_range(min_r..=max_r);
+    // zasadzka losowana na końcu (i tylko przy wysokim HEAT), żeby nie przesuwać wcześniejszych losowań
+    let ambush_pct = ((heat_fx.ambush_chance_pct as f32) * perk.ambush_mult).round() as u32;
+    let ambushed = ambush_pct > 0 && rng.random_range(0..100u32) < ambush_pct;
+    let success = !ambushed && roll < chance;
+    // łup specjalny celu — jeszcze dalej w kolejce losowań
+    let drop = if success { targets::roll_drop(cfg.target, rng) } else { 0 };
 
     // HEAT
-    let mut heat = balance::heat_gain(risk);
-    heat = ((heat as f32) * effects.heat_mult).round() as i64;
+    let mut heat = targets::heat_gain(risk, cfg.target);
+    heat = ((heat as f32) * effects.heat_mult * perk.heat_mult).round() as i64;
 
     let (amount_base, amount_final, heat_delta) = if success {
-        (reward, reward, heat)
+        let loot = ((reward as f32) * heat_fx.reward_mult * perk.payout_mult).round() as i64;
+        (reward, loot + drop, heat)
     } else {
-        let penalty = ((reward as f32) * 0.35 * effects.fail_penalty_mult) as i64;
-        (-penalty, -penalty, heat + 2)
+        let penalty = ((reward as f32) * t.fail_penalty * effects.fail_penalty_mult * perk.fail_penalty_mult) as i64;
+        (-penalty, -penalty, heat + t.fail_heat)
     };
 
     profile.balance += amount_final;
@@ -74,6 +102,8 @@ pub fn resolve_solo(
             amount_base,
             amount_final,
             heat_delta,
+            ambushed,
+            drop,
         },
     )
 }
\ No newline at end of file
@@ -0,0 +1,241 @@
+//! Napady ekipowe: 2–5 graczy, każdy w swojej roli i z własną minigierką.
+//!
+//! Role działają na wspólny wynik (haker i włamywacz → szansa, kierowca → kara
+//! za wpadkę, czujka → zasadzka i HEAT). Przedmioty ekipy sumują się jako zbiór
+//! (ten sam przedmiot u dwóch osób liczy się raz), HEAT liczy się per gracz,
+//! a łup dzieli się wg `LootSplit`.
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    balance, config,
+    items::aggregate,
+    types::{CrimeMode, ItemKey, MinigameResult, PlayerProfile, Risk},
+};
+
+pub const MIN_CREW: usize = 2;
+pub const MAX_CREW: usize = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CrewRole {
+    Hacker,
+    Lockpicker,
+    Driver,
+    Lookout,
+}
+
+impl CrewRole {
+    pub const ALL: [CrewRole; 4] = [CrewRole::Hacker, CrewRole::Lockpicker, CrewRole::Driver, CrewRole::Lookout];
+
+    pub fn key(self) -> &'static str {
+        match self {
+            CrewRole::Hacker => "hacker",
+            CrewRole::Lockpicker => "lockpicker",
+            CrewRole::Driver => "driver",
+            CrewRole::Lookout => "lookout",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        CrewRole::ALL.into_iter().find(|r| r.key() == s)
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            CrewRole::Hacker => "🖥️ Haker",
+            CrewRole::Lockpicker => "🔓 Włamywacz",
+            CrewRole::Driver => "🚗 Kierowca",
+            CrewRole::Lookout => "👀 Czujka",
+        }
+    }
+
+    /// Waga w podziale „wg ról”.
+    pub fn share_weight(self) -> i64 {
+        match self {
+            CrewRole::Hacker => 12,
+            CrewRole::Lockpicker => 11,
+            CrewRole::Driver => 10,
+            CrewRole::Lookout => 8,
+        }
+    }
+}
+
+/// Podział łupu. `LeaderCut(p)` = lider bierze `p`% z góry, reszta po równo (z liderem).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LootSplit {
+    Equal,
+    ByRole,
+    LeaderCut(u8),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrewHeistConfig {
+    pub mode: CrimeMode,
+    pub risk: Risk,
+    pub split: LootSplit,
+}
+
+/// Miejsce w ekipie przy rozstrzygnięciu. `seats[0]` to lider.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrewSeat {
+    pub profile: PlayerProfile,
+    pub role: CrewRole,
+    pub items: Vec<ItemKey>,
+    pub mg: MinigameResult,
+}
+
+/// Wynik napadu ekipy; `shares`/`heat_deltas` w kolejności miejsc.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrewOutcome {
+    pub success: bool,
+    pub ambushed: bool,
+    pub loot: i64,
+    pub shares: Vec<i64>,
+    pub heat_deltas: Vec<i64>,
+}
+
+/// Wejście losowania napadu ekipy — zapisywane w `rolls` (`crime.crew`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrewRoll {
+    pub seats: Vec<CrewSeat>,
+    pub cfg: CrewHeistConfig,
+}
+
+pub fn roll_crew(input: &CrewRoll, rng: &mut impl Rng) -> (Vec<PlayerProfile>, CrewOutcome) {
+    resolve_crew(&input.seats, &input.cfg, rng)
+}
+
+/// Wkład minigierki w rolę: -1 (wtopa) … 1 (perfekcyjnie).
+fn mg_score(mg: MinigameResult) -> f32 {
+    match mg {
+        MinigameResult::Success => 1.0,
+        MinigameResult::Partial(_) => 0.5,
+        MinigameResult::NotPlayed => -0.5,
+        MinigameResult::Fail => -1.0,
+    }
+}
+
+/// Najlepszy wynik wśród graczy w danej roli (`None` = rola nieobsadzona).
+fn role_score(seats: &[CrewSeat], role: CrewRole) -> Option<f32> {
+    seats
+        .iter()
+        .filter(|s| s.role == role)
+        .map(|s| mg_score(s.mg))
+        .reduce(f32::max)
+}
+
+pub fn resolve_crew(
+    seats: &[CrewSeat],
+    cfg: &CrewHeistConfig,
+    rng: &mut impl Rng,
+) -> (Vec<PlayerProfile>, CrewOutcome) {
+    let n = seats.len().max(1);
+
+    // przedmioty ekipy jako zbiór
+    let mut pooled: Vec<ItemKey> = Vec::new();
+    for item in seats.iter().flat_map(|s| s.items.iter().copied()) {
+        if !pooled.contains(&item) {
+            pooled.push(item);
+        }
+    }
+    let t = &config::get().crime;
+    let effects = aggregate(&pooled);
+
+    // policja patrzy na najgorętszego w ekipie
+    let crew_heat = seats.iter().map(|s| s.profile.heat.max(0)).max().unwrap_or(0) as u32;
+    let heat_fx = balance::heat_effects(cfg.mode, cfg.risk, crew_heat);
+
+    let avg_skill = seats.iter().map(|s| s.profile.thief_skill as f32).sum::<f32>() / n as f32;
+
+    let mut chance = balance::base_chance(cfg.mode, cfg.risk);
+    chance += (avg_skill / 50.0) * t.skill_bonus;
+    chance += effects.success_pp_bonus;
+    chance += role_score(seats, CrewRole::Hacker).map_or(0.0, |s| s * 10.0);
+    chance += role_score(seats, CrewRole::Lockpicker).map_or(0.0, |s| s * 8.0);
+    chance *= heat_fx.chance_mult;
+    chance = chance.clamp(1.0, 99.0);
+
+    // kierowca: szybka ucieczka = mniejsza kara; brak kierowcy boli
+    let penalty_mult = role_score(seats, CrewRole::Driver).map_or(1.2, |s| 1.0 - 0.4 * s);
+    // czujka: widzi zasadzkę i zaciera ślady
+    let lookout = role_score(seats, CrewRole::Lookout);
+    let ambush_pct = lookout.map_or(heat_fx.ambush_chance_pct as f32, |s| {
+        heat_fx.ambush_chance_pct as f32 * (1.0 - s).clamp(0.0, 2.0)
+    });
+    let heat_mult = effects.heat_mult * lookout.map_or(1.0, |s| 1.0 - 0.25 * s);
+
+    // losowania: rzut, łup, zasadzka (ta sama kolejność co w solo)
+    let roll = rng.random_range(0.0..100.0);
+    let (min_r, max_r) = balance::reward_range(cfg.mode, cfg.risk);
+    let reward = rng.random_range(min_r..=max_r);
+    let ambushed = ambush_pct > 0.0 && rng.random_range(0.0..100.0) < ambush_pct;
+    let success = !ambushed && roll < chance;
+
+    // większa ekipa = większy skok (+60% za każdą dodatkową osobę)
+    let loot = if success {
+        ((reward as f32) * (1.0 + 0.6 * (n as f32 - 1.0)) * heat_fx.reward_mult).round() as i64
+    } else {
+        0
+    };
+
+    let shares: Vec<i64> = if success {
+        split_loot(loot, seats, cfg.split)
+    } else {
+        let each = ((reward as f32) * t.fail_penalty * effects.fail_penalty_mult * penalty_mult.max(0.0)) as i64;
+        vec![-each; seats.len()]
+    };
+
+    let base_heat = ((balance::heat_gain(cfg.risk) as f32) * heat_mult).round() as i64;
+    let heat_deltas: Vec<i64> = seats
+        .iter()
+        .map(|s| {
+            // kto zawalił swoją część, tego widziały kamery
+            let own = if s.mg == MinigameResult::Fail { 2 } else { 0 };
+            (base_heat + own + if success { 0 } else { t.fail_heat }).max(0)
+        })
+        .collect();
+
+    let after = seats
+        .iter()
+        .zip(shares.iter().zip(&heat_deltas))
+        .map(|(s, (&share, &heat))| {
+            let mut p = s.profile.clone();
+            p.balance += share;
+            p.heat += heat;
+            if p.thief_skill < 50 {
+                p.thief_skill += 1;
+            }
+            if success {
+                p.pp = p.pp.saturating_add(1);
+            }
+            p
+        })
+        .collect();
+
+    (after, CrewOutcome { success, ambushed, loot, shares, heat_deltas })
+}
+
+/// Dzieli łup; reszta z zaokrągleń trafia do lidera (`seats[0]`).
+fn split_loot(loot: i64, seats: &[CrewSeat], split: LootSplit) -> Vec<i64> {
+    if seats.is_empty() {
+        return Vec::new();
+    }
+    let n = seats.len() as i64;
+    let mut shares = match split {
+        LootSplit::Equal => vec![loot / n; seats.len()],
+        LootSplit::ByRole => {
+            let total: i64 = seats.iter().map(|s| s.role.share_weight()).sum();
+            seats.iter().map(|s| loot * s.role.share_weight() / total.max(1)).collect()
+        }
+        LootSplit::LeaderCut(pct) => {
+            let cut = loot * i64::from(pct.min(100)) / 100;
+            let mut v = vec![(loot - cut) / n; seats.len()];
+            v[0] += cut;
+            v
+        }
+    };
+    let rest = loot - shares.iter().sum::<i64>();
+    shares[0] += rest;
+    shares
+}
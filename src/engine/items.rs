@@ -1,3 +1,4 @@
+use super::config;
 use super::types::ItemKey;
 
 /// Skumulowany efekt przedmiotów.
@@ -19,20 +20,19 @@ pub struct ItemEffects {
     pub fail_penalty_mult: f32, // mnożnik kary przy failu (1.0 = brak zmiany)
 }
 
-/// Progi odblokowań i nazwy
+/// Nazwy (progi odblokowań i efekty są w tabelach balansu — `config::ItemTables`)
 #[derive(Debug, Clone, Copy)]
 pub struct ItemMeta {
     pub name: &'static str,
-    pub required_pp: u32,
 }
 
 pub const ITEM_META: &[(ItemKey, ItemMeta)] = &[
-    (ItemKey::LockpickSet, ItemMeta { name: "Zestaw wytrychów", required_pp: 0  }),
-    (ItemKey::ProGloves,   ItemMeta { name: "Rękawice PRO",     required_pp: 5  }),
-    (ItemKey::Toolkit,     ItemMeta { name: "Zestaw narzędzi",  required_pp: 10 }),
-    (ItemKey::SmokeGrenade,ItemMeta { name: "Granat dymny",     required_pp: 15 }),
-    (ItemKey::HackerLaptop,ItemMeta { name: "Laptop hakera",    required_pp: 22 }),
-    (ItemKey::Adrenaline,  ItemMeta { name: "Adrenalina",       required_pp: 30 }),
+    (ItemKey::LockpickSet, ItemMeta { name: "Zestaw wytrychów" }),
+    (ItemKey::ProGloves,   ItemMeta { name: "Rękawice PRO"     }),
+    (ItemKey::Toolkit,     ItemMeta { name: "Zestaw narzędzi"  }),
+    (ItemKey::SmokeGrenade,ItemMeta { name: "Granat dymny"     }),
+    (ItemKey::HackerLaptop,ItemMeta { name: "Laptop hakera"    }),
+    (ItemKey::Adrenaline,  ItemMeta { name: "Adrenalina"       }),
 ];
 
 #[inline]
@@ -42,12 +42,26 @@ pub fn item_name(k: ItemKey) -> &'static str {
 
 #[inline]
 pub fn required_pp(k: ItemKey) -> u32 {
-    ITEM_META.iter().find(|(kk, _)| *kk == k).map(|(_, m)| m.required_pp).unwrap_or(0)
+    config::get().items.get(k).required_pp
 }
 
 #[inline]
 pub fn available_items(pp: u32) -> Vec<ItemKey> {
-    ITEM_META.iter().filter(|(_, m)| pp >= m.required_pp).map(|(k, _)| *k).collect()
+    let items = &config::get().items;
+    ITEM_META.iter().filter(|(k, _)| pp >= items.get(*k).required_pp).map(|(k, _)| *k).collect()
+}
+
+/// Koszt zużycia zestawu na jeden napad (cena / wytrzymałość) — do symulacji i podglądu.
+pub fn wear_cost(items: &[ItemKey]) -> i64 {
+    let table = &config::get().items;
+    items
+        .iter()
+        .map(|k| {
+            let t = table.get(*k);
+            t.price as f32 / t.durability.max(1) as f32
+        })
+        .sum::<f32>()
+        .round() as i64
 }
 
 /// Agregacja efektów
@@ -66,33 +80,19 @@ pub fn aggregate(items: &[ItemKey]) -> ItemEffects {
         fail_penalty_mult: 1.0,
     };
 
+    let table = &config::get().items;
     for it in items {
-        match it {
-            ItemKey::HackerLaptop => {
-                eff.qte_grace_ms += 40;
-                eff.qte_window_mult *= 1.10;
-            }
-            ItemKey::ProGloves => {
-                eff.simon_seq_delta -= 1;      // precyzja
-                eff.simon_time_mult *= 1.05;   // trochę więcej czasu
-            }
-            ItemKey::Toolkit => {
-                eff.payout_bonus_pct += 0.05;  // „czyściej” = lepszy łup
-            }
-            ItemKey::Adrenaline => {
-                eff.qte_window_mult *= 1.05;
-                eff.simon_time_mult *= 1.08;
-                eff.fail_penalty_mult *= 0.9;  // mniejszy „tilt” na failu
-                eff.heat_mult *= 1.05;         // ale lekko bardziej ryzykowne
-            }
-            ItemKey::SmokeGrenade => {
-                eff.heat_reduce_pct += 0.08;   // mniej HEAT
-                eff.timer_extend_pct += 0.05;  // łatwiejsza ewakuacja
-            }
-            ItemKey::LockpickSet => {
-                eff.simon_seq_delta -= 1;
-            }
-        }
+        let t = table.get(*it);
+        eff.qte_window_mult *= t.qte_window_mult;
+        eff.qte_grace_ms += t.qte_grace_ms;
+        eff.simon_seq_delta += t.simon_seq_delta;
+        eff.simon_time_mult *= t.simon_time_mult;
+        eff.timer_extend_pct += t.timer_extend_pct;
+        eff.heat_reduce_pct += t.heat_reduce_pct;
+        eff.payout_bonus_pct += t.payout_bonus_pct;
+        eff.success_pp_bonus += t.success_pp_bonus;
+        eff.heat_mult *= t.heat_mult;
+        eff.fail_penalty_mult *= t.fail_penalty_mult;
     }
 
     clamp_effects(&mut eff);
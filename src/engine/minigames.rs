@@ -1,18 +1,17 @@
 use rand::Rng;
 
+use super::config;
 use super::types::{MinigameResult, QteSpec, Risk, SimonSpec};
 
+/// Znane alfabety Simona (zapisane sesje odwołują się do nich po treści).
+pub const SIMON_ALPHABETS: &[&[char]] = &[&['A', 'B', 'C', 'D']];
+
 pub fn qte_spec_for(risk: Risk, window_bonus_ms: i32) -> QteSpec {
     // target ok. 1.2s, okno zależne od ryzyka
-    let base_window = match risk {
-        Risk::Low => 220,
-        Risk::Medium => 150,
-        Risk::High => 100,
-        Risk::Hardcore => 70,
-    };
+    let t = &config::get().minigames;
     QteSpec {
-        target_ms: 1200,
-        window_ms: (base_window + window_bonus_ms).max(40),
+        target_ms: t.qte_target_ms,
+        window_ms: (t.qte_window_ms.get(risk) + window_bonus_ms).max(t.qte_min_window_ms),
     }
 }
 
@@ -28,20 +27,14 @@ pub fn score_qte(elapsed_ms: i32, spec: &QteSpec) -> MinigameResult {
 }
 
 pub fn simon_spec_for(risk: Risk, len_delta: i32) -> SimonSpec {
-    let base_len = match risk {
-        Risk::Low => 4,
-        Risk::Medium => 5,
-        Risk::High => 6,
-        Risk::Hardcore => 7,
-    };
+    let t = &config::get().minigames;
     SimonSpec {
-        length: (base_len as i32 + len_delta).clamp(3, 8) as usize,
-        alphabet: &['A', 'B', 'C', 'D'],
+        length: (t.simon_length.get(risk) + len_delta).clamp(t.simon_min_length, t.simon_max_length) as usize,
+        alphabet: SIMON_ALPHABETS[0],
     }
 }
 
-pub fn gen_simon_seq(spec: &SimonSpec) -> Vec<char> {
-    let mut rng = rand::rng();
+pub fn gen_simon_seq(spec: &SimonSpec, rng: &mut impl Rng) -> Vec<char> {
     (0..spec.length)
         .map(|_| {
             let i = rng.random_range(0..spec.alphabet.len());
@@ -53,3 +46,26 @@ pub fn gen_simon_seq(spec: &SimonSpec) -> Vec<char> {
 pub fn check_simon_step(expected: char, got: char) -> bool {
     expected == got
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng;
+
+    #[test]
+    fn simon_seq_is_reproducible_from_seed() {
+        let spec = simon_spec_for(Risk::High, 1);
+        let seq = gen_simon_seq(&spec, &mut rng::from_seed(99));
+
+        assert_eq!(seq, gen_simon_seq(&spec, &mut rng::from_seed(99)));
+        assert_eq!(seq.len(), spec.length);
+        assert!(seq.iter().all(|c| spec.alphabet.contains(c)));
+    }
+
+    #[test]
+    fn simon_seq_differs_between_seeds() {
+        let spec = simon_spec_for(Risk::Hardcore, 0);
+        let seqs: Vec<_> = (0..8).map(|s| gen_simon_seq(&spec, &mut rng::from_seed(s))).collect();
+        assert!(seqs.iter().any(|s| s != &seqs[0]));
+    }
+}
@@ -1,6 +1,10 @@
 pub mod types;
 pub mod items;
+pub mod targets;
+pub mod perks;
 pub mod minigames;
+pub mod config;
 pub mod balance;
 pub mod core;
 pub mod repo;
+pub mod crew;
@@ -0,0 +1,110 @@
+use super::config;
+use super::types::{PerkKey, PerkRanks};
+
+/// Gałąź drzewka złodzieja.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Branch {
+    Stealth,
+    Tech,
+    Muscle,
+}
+
+impl Branch {
+    pub const ALL: [Branch; 3] = [Branch::Stealth, Branch::Tech, Branch::Muscle];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Branch::Stealth => "🌑 Cień",
+            Branch::Tech => "💻 Technika",
+            Branch::Muscle => "💪 Siła",
+        }
+    }
+}
+
+/// Nazwa, gałąź i wymaganie umiejętności (koszty i efekty są w tabelach balansu — `config::PerkTables`)
+#[derive(Debug, Clone, Copy)]
+pub struct PerkMeta {
+    pub name: &'static str,
+    pub branch: Branch,
+    /// Umiejętność, którą trzeba mieć choć na 1. poziomie.
+    pub requires: Option<PerkKey>,
+}
+
+pub const PERK_META: &[(PerkKey, PerkMeta)] = &[
+    (PerkKey::ShadowStep,         PerkMeta { name: "Cichy krok",            branch: Branch::Stealth, requires: None }),
+    (PerkKey::Lookout,            PerkMeta { name: "Czujność",              branch: Branch::Stealth, requires: Some(PerkKey::ShadowStep) }),
+    (PerkKey::PhotographicMemory, PerkMeta { name: "Pamięć fotograficzna",  branch: Branch::Tech,    requires: None }),
+    (PerkKey::Reflexes,           PerkMeta { name: "Refleks",               branch: Branch::Tech,    requires: Some(PerkKey::PhotographicMemory) }),
+    (PerkKey::ThickSkin,          PerkMeta { name: "Gruba skóra",           branch: Branch::Muscle,  requires: None }),
+    (PerkKey::HeavyHands,         PerkMeta { name: "Ciężkie łapy",          branch: Branch::Muscle,  requires: Some(PerkKey::ThickSkin) }),
+];
+
+pub fn meta(k: PerkKey) -> PerkMeta {
+    PERK_META.iter().find(|(kk, _)| *kk == k).map(|(_, m)| *m).expect("każda umiejętność ma wpis w PERK_META")
+}
+
+#[inline]
+pub fn perk_name(k: PerkKey) -> &'static str {
+    meta(k).name
+}
+
+#[inline]
+pub fn rank(ranks: &PerkRanks, k: PerkKey) -> u8 {
+    ranks.get(&k).copied().unwrap_or(0)
+}
+
+/// Efekt jednego poziomu w słowach (do drzewka).
+pub fn describe(k: PerkKey) -> String {
+    let v = config::get().perks.get(k).per_rank;
+    match k {
+        PerkKey::ShadowStep => format!("−{:.0}% HEAT za napad", v * 100.0),
+        PerkKey::Lookout => format!("−{:.0}% szansy na zasadzkę", v * 100.0),
+        PerkKey::PhotographicMemory => format!("+{v:.0} podgląd sekwencji w Simonie"),
+        PerkKey::Reflexes => format!("+{v:.0} ms okna QTE"),
+        PerkKey::ThickSkin => format!("−{:.0}% kary za porażkę", v * 100.0),
+        PerkKey::HeavyHands => format!("+{:.0}% łupu", v * 100.0),
+    }
+}
+
+/// Skumulowany efekt drzewka (neutralny = brak umiejętności).
+#[derive(Debug, Clone, Copy)]
+pub struct PerkEffects {
+    pub heat_mult: f32,
+    pub ambush_mult: f32,
+    pub simon_reveals: u8,
+    pub qte_grace_ms: i32,
+    pub fail_penalty_mult: f32,
+    pub payout_mult: f32,
+}
+
+impl Default for PerkEffects {
+    fn default() -> Self {
+        Self {
+            heat_mult: 1.0,
+            ambush_mult: 1.0,
+            simon_reveals: 0,
+            qte_grace_ms: 0,
+            fail_penalty_mult: 1.0,
+            payout_mult: 1.0,
+        }
+    }
+}
+
+/// Agregacja efektów — poziomy ponad `max_rank` (np. po obniżeniu limitu w balansie) się nie liczą.
+pub fn effects(ranks: &PerkRanks) -> PerkEffects {
+    let table = &config::get().perks;
+    let mut eff = PerkEffects::default();
+    for (&k, &r) in ranks {
+        let t = table.get(k);
+        let amount = t.per_rank * f32::from(r.min(t.max_rank));
+        match k {
+            PerkKey::ShadowStep => eff.heat_mult = (1.0 - amount).max(0.05),
+            PerkKey::Lookout => eff.ambush_mult = (1.0 - amount).max(0.0),
+            PerkKey::PhotographicMemory => eff.simon_reveals = amount.round() as u8,
+            PerkKey::Reflexes => eff.qte_grace_ms = amount.round() as i32,
+            PerkKey::ThickSkin => eff.fail_penalty_mult = (1.0 - amount).max(0.05),
+            PerkKey::HeavyHands => eff.payout_mult = 1.0 + amount,
+        }
+    }
+    eff
+}
@@ -1,12 +1,59 @@
+use anyhow::Result;
+use async_trait::async_trait;
 use dashmap::DashMap;
+use sqlx::{PgConnection, PgPool};
 
-use super::types::PlayerProfile;
+use super::core::{roll_solo, SoloRoll};
+use super::crew::{roll_crew, CrewHeistConfig, CrewOutcome, CrewRole, CrewRoll, CrewSeat};
+use super::types::{CrimeMode, HeistOutcome, ItemKey, MinigameKind, MinigameResult, PlayerProfile, Risk, SoloHeistConfig};
+use crate::clock;
+use crate::economy::Economy;
+use crate::heists::{self, Heist};
+use crate::ledger::{self, Entry, Overdraft};
+use crate::rng::{self, Roll};
 
-pub trait SoloRepo {
-    fn get_or_create(&self, user_id: u64) -> PlayerProfile;
-    fn save(&self, profile: &PlayerProfile);
+/// Wynik rozstrzygniętego napadu: profil przed/po (z saldem) i sam wynik.
+#[derive(Debug, Clone)]
+pub struct HeistResolution {
+    pub before: PlayerProfile,
+    pub after: PlayerProfile,
+    pub outcome: HeistOutcome,
 }
 
+/// Członek ekipy przed rozstrzygnięciem (profil dociąga repo). Pierwszy na liście = lider.
+#[derive(Debug, Clone)]
+pub struct CrewMember {
+    pub user_id: u64,
+    pub role: CrewRole,
+    pub items: Vec<ItemKey>,
+    pub mg: MinigameResult,
+}
+
+/// Wynik napadu ekipy: profile przed/po (z saldami) w kolejności członków.
+#[derive(Debug, Clone)]
+pub struct CrewResolution {
+    pub before: Vec<PlayerProfile>,
+    pub after: Vec<PlayerProfile>,
+    pub outcome: CrewOutcome,
+}
+
+#[async_trait]
+pub trait SoloRepo: Send + Sync {
+    /// Profil gracza razem z saldem (tworzy, jeśli brak).
+    async fn get_or_create(&self, user_id: u64) -> Result<PlayerProfile>;
+
+    /// Zapisuje HEAT/PP/umiejętność. Saldo jest pomijane — zmienia je tylko rozstrzygnięcie.
+    async fn save(&self, profile: &PlayerProfile) -> Result<()>;
+}
+
+fn seat(m: &CrewMember, profile: PlayerProfile) -> CrewSeat {
+    CrewSeat { profile, role: m.role, items: m.items.clone(), mg: m.mg }
+}
+
+// =====================
+// Pamięć (symulacje)
+// =====================
+
 #[derive(Default)]
 pub struct MemorySoloRepo {
     users: DashMap<u64, PlayerProfile>,
@@ -16,20 +63,318 @@ impl MemorySoloRepo {
     pub fn new() -> Self {
         Self::default()
     }
+
+    /// Rozstrzyga napad na świeżym stanie gracza.
+    pub async fn resolve(&self, user_id: u64, cfg: &SoloHeistConfig, mg: MinigameResult) -> Result<HeistResolution> {
+        let mut entry = self
+            .users
+            .entry(user_id)
+            .or_insert_with(|| PlayerProfile { user_id, ..Default::default() });
+        let before = entry.clone();
+        let (_, mut r) = rng::seeded();
+        let (after, outcome) = roll_solo(&SoloRoll { profile: before.clone(), cfg: cfg.clone(), mg }, &mut r);
+        *entry = after.clone();
+        Ok(HeistResolution { before, after, outcome })
+    }
+
+    /// Rozstrzyga napad ekipy.
+    pub async fn resolve_crew(&self, crew: &[CrewMember], cfg: &CrewHeistConfig) -> Result<CrewResolution> {
+        let mut seats = Vec::with_capacity(crew.len());
+        for m in crew {
+            seats.push(seat(m, self.get_or_create(m.user_id).await?));
+        }
+        let before: Vec<PlayerProfile> = seats.iter().map(|s| s.profile.clone()).collect();
+
+        let (_, mut r) = rng::seeded();
+        let (after, outcome) = roll_crew(&CrewRoll { seats, cfg: cfg.clone() }, &mut r);
+        for p in &after {
+            self.users.insert(p.user_id, p.clone());
+        }
+        Ok(CrewResolution { before, after, outcome })
+    }
 }
 
+#[async_trait]
 impl SoloRepo for MemorySoloRepo {
-    fn get_or_create(&self, user_id: u64) -> PlayerProfile {
-        if let Some(v) = self.users.get(&user_id) {
-            return v.clone();
+    async fn get_or_create(&self, user_id: u64) -> Result<PlayerProfile> {
+        Ok(self
+            .users
+            .entry(user_id)
+            .or_insert_with(|| PlayerProfile { user_id, ..Default::default() })
+            .clone())
+    }
+
+    async fn save(&self, profile: &PlayerProfile) -> Result<()> {
+        let mut entry = self.users.entry(profile.user_id).or_insert_with(|| profile.clone());
+        let balance = entry.balance;
+        *entry = PlayerProfile { balance, ..profile.clone() };
+        Ok(())
+    }
+}
+
+// =====================
+// Postgres (`profiles` + księga)
+// =====================
+
+/// Profile graczy jednej ekonomii. Saldo czytane i zmieniane wyłącznie przez `ledger`.
+#[derive(Clone)]
+pub struct PgSoloRepo {
+    db: PgPool,
+    eco: Economy,
+}
+
+impl PgSoloRepo {
+    pub fn new(db: PgPool, eco: Economy) -> Self {
+        Self { db, eco }
+    }
+
+    /// Wczytuje profil na połączeniu wywołującego; `lock` = `FOR UPDATE` (w transakcji).
+    async fn load(&self, conn: &mut PgConnection, user_id: u64, lock: bool) -> Result<PlayerProfile> {
+        sqlx::query(
+            r#"INSERT INTO profiles (guild_id, user_id, heat, pp, thief_skill)
+               VALUES ($1, $2, 0, 0, 0)
+               ON CONFLICT (guild_id, user_id) DO NOTHING"#,
+        )
+        .bind(self.eco.id())
+        .bind(user_id as i64)
+        .execute(&mut *conn)
+        .await?;
+
+        let sql = if lock {
+            "SELECT heat, pp, thief_skill FROM profiles WHERE guild_id = $1 AND user_id = $2 FOR UPDATE"
+        } else {
+            "SELECT heat, pp, thief_skill FROM profiles WHERE guild_id = $1 AND user_id = $2"
+        };
+        let (heat, pp, thief_skill): (i32, i32, i32) = sqlx::query_as(sql)
+            .bind(self.eco.id())
+            .bind(user_id as i64)
+            .fetch_one(&mut *conn)
+            .await?;
+
+        let balance = ledger::balance_of(conn, self.eco, user_id).await?;
+        Ok(PlayerProfile {
+            user_id,
+            balance,
+            heat: heat as i64,
+            pp: pp as u32,
+            thief_skill: thief_skill as u32,
+        })
+    }
+
+    async fn store(&self, conn: &mut PgConnection, p: &PlayerProfile) -> Result<()> {
+        // HEAT rośnie od zera → odliczanie spadku startuje od teraz (a nie od dawnego znacznika)
+        sqlx::query(
+            r#"INSERT INTO profiles (guild_id, user_id, heat, pp, thief_skill, heat_decayed_at)
+               VALUES ($1, $2, $3, $4, $5, $6)
+               ON CONFLICT (guild_id, user_id) DO UPDATE
+               SET heat = EXCLUDED.heat,
+                   pp = EXCLUDED.pp,
+                   thief_skill = EXCLUDED.thief_skill,
+                   heat_decayed_at = CASE WHEN profiles.heat <= 0 AND EXCLUDED.heat > 0
+                                          THEN EXCLUDED.heat_decayed_at
+                                          ELSE profiles.heat_decayed_at END,
+                   updated_at = now()"#,
+        )
+        .bind(self.eco.id())
+        .bind(p.user_id as i64)
+        .bind(p.heat)
+        .bind(p.pp as i32)
+        .bind(p.thief_skill as i32)
+        .bind(clock::now())
+        .execute(&mut *conn)
+        .await?;
+        Ok(())
+    }
+
+    /// Profil (tworzony w razie braku) czytany na połączeniu wywołującego — np. w otwartej transakcji.
+    pub async fn get_or_create_in(&self, conn: &mut PgConnection, user_id: u64) -> Result<PlayerProfile> {
+        self.load(conn, user_id, false).await
+    }
+
+    /// „Przyczajenie się”: zdejmuje do `by` HEAT na połączeniu wywołującego.
+    /// Zwraca (HEAT przed, HEAT po).
+    pub async fn lay_low(&self, conn: &mut PgConnection, user_id: u64, by: i64) -> Result<(i64, i64)> {
+        let before = self.load(conn, user_id, true).await?.heat;
+        let after = (before - by).max(0);
+        sqlx::query("UPDATE profiles SET heat = $3, updated_at = now() WHERE guild_id = $1 AND user_id = $2")
+            .bind(self.eco.id())
+            .bind(user_id as i64)
+            .bind(after as i32)
+            .execute(&mut *conn)
+            .await?;
+        Ok((before, after))
+    }
+
+    /// Rozstrzyga napad na świeżym stanie gracza i zapisuje profil razem ze zmianą salda —
+    /// w transakcji wywołującego (razem z cooldownem, odsiadką i stanem sesji).
+    pub async fn resolve(
+        &self,
+        conn: &mut PgConnection,
+        user_id: u64,
+        cfg: &SoloHeistConfig,
+        mg: MinigameResult,
+        interaction_id: Option<u64>,
+    ) -> Result<HeistResolution> {
+        let before = self.load(conn, user_id, true).await?;
+        let input = SoloRoll { profile: before.clone(), cfg: cfg.clone(), mg };
+        let (seed, mut r) = rng::seeded();
+        let (mut after, outcome) = roll_solo(&input, &mut r);
+        rng::record(
+            &mut *conn,
+            self.eco,
+            &Roll { command: "crime", user: user_id, interaction_id, seed },
+            &input,
+            &(&after, &outcome),
+        )
+        .await?;
+        let heist = Heist {
+            user: user_id,
+            crew: false,
+            mode: cfg.mode.unwrap_or(CrimeMode::Standard),
+            risk: cfg.risk.unwrap_or(Risk::Medium),
+            target: cfg.target,
+            items: &cfg.items,
+            minigame: cfg.minigame,
+            mg,
+            success: outcome.success,
+            ambushed: outcome.ambushed,
+            amount: outcome.amount_final,
+            drop: outcome.drop,
+            heat_delta: outcome.heat_delta,
+        };
+        heists::record(conn, self.eco, &heist, seed, interaction_id).await?;
+
+        // saldo „po” zawsze z księgi — to ono jest prawdą, nie arytmetyka resolvera
+        after.balance = ledger::settle(
+            conn,
+            self.eco,
+            user_id,
+            "crime",
+            outcome.amount_final,
+            &Entry {
+                reason: if outcome.amount_final >= 0 { "łup ze skoku" } else { "strata na skoku" },
+                command: "crime",
+                interaction_id,
+            },
+            Overdraft::Allow,
+        )
+        .await?;
+        self.store(conn, &after).await?;
+
+        Ok(HeistResolution { before, after, outcome })
+    }
+
+    /// Rozstrzyga napad ekipy w transakcji wywołującego — wszystkie profile i salda
+    /// zmieniają się razem albo wcale.
+    pub async fn resolve_crew(
+        &self,
+        conn: &mut PgConnection,
+        crew: &[CrewMember],
+        cfg: &CrewHeistConfig,
+        interaction_id: Option<u64>,
+    ) -> Result<CrewResolution> {
+        // blokady zawsze w kolejności ID — dwie ekipy z tym samym graczem nie zakleszczą się
+        let mut order: Vec<u64> = crew.iter().map(|m| m.user_id).collect();
+        order.sort_unstable();
+        order.dedup();
+        let mut locked = std::collections::HashMap::new();
+        for uid in order {
+            locked.insert(uid, self.load(conn, uid, true).await?);
+        }
+
+        let seats: Vec<CrewSeat> = crew.iter().map(|m| seat(m, locked[&m.user_id].clone())).collect();
+        let before: Vec<PlayerProfile> = seats.iter().map(|s| s.profile.clone()).collect();
+        let input = CrewRoll { seats, cfg: cfg.clone() };
+
+        let (seed, mut r) = rng::seeded();
+        let (mut after, outcome) = roll_crew(&input, &mut r);
+        let leader = crew.first().map(|m| m.user_id).unwrap_or_default();
+        rng::record(
+            &mut *conn,
+            self.eco,
+            &Roll { command: "crime.crew", user: leader, interaction_id, seed },
+            &input,
+            &(&after, &outcome),
+        )
+        .await?;
+
+        for ((m, &share), &heat_delta) in crew.iter().zip(&outcome.shares).zip(&outcome.heat_deltas) {
+            let heist = Heist {
+                user: m.user_id,
+                crew: true,
+                mode: cfg.mode,
+                risk: cfg.risk,
+                target: None,
+                items: &m.items,
+                // ekipa gra zawsze QTE
+                minigame: MinigameKind::Qte,
+                mg: m.mg,
+                success: outcome.success,
+                ambushed: outcome.ambushed,
+                amount: share,
+                drop: 0,
+                heat_delta,
+            };
+            heists::record(conn, self.eco, &heist, seed, interaction_id).await?;
+        }
+
+        for (p, &share) in after.iter_mut().zip(&outcome.shares) {
+            p.balance = ledger::settle(
+                conn,
+                self.eco,
+                p.user_id,
+                "crime",
+                share,
+                &Entry {
+                    reason: if share >= 0 { "łup ze skoku ekipy" } else { "strata na skoku ekipy" },
+                    command: "crime",
+                    interaction_id,
+                },
+                Overdraft::Allow,
+            )
+            .await?;
+            self.store(conn, p).await?;
         }
-        let mut p = PlayerProfile::default();
-        p.user_id = user_id;
-        self.users.insert(user_id, p.clone());
-        p
+
+        Ok(CrewResolution { before, after, outcome })
+    }
+}
+
+/// Spadek HEAT o `per_hour` za każdą pełną godzinę od `heat_decayed_at` (wszystkie ekonomie).
+/// Zwraca liczbę ochłodzonych profili.
+pub async fn decay_heat(db: &PgPool, per_hour: i64) -> Result<u64> {
+    let res = sqlx::query(
+        r#"
+        WITH due AS (
+            SELECT guild_id, user_id,
+                   floor(extract(epoch FROM ($1 - heat_decayed_at)) / 3600)::int AS hours
+              FROM profiles
+             WHERE heat > 0 AND heat_decayed_at <= $1 - interval '1 hour'
+        )
+        UPDATE profiles p
+           SET heat = GREATEST(p.heat - due.hours * $2, 0),
+               heat_decayed_at = p.heat_decayed_at + make_interval(hours => due.hours),
+               updated_at = now()
+          FROM due
+         WHERE p.guild_id = due.guild_id AND p.user_id = due.user_id
+        "#,
+    )
+    .bind(clock::now())
+    .bind(per_hour as i32)
+    .execute(db)
+    .await?;
+    Ok(res.rows_affected())
+}
+
+#[async_trait]
+impl SoloRepo for PgSoloRepo {
+    async fn get_or_create(&self, user_id: u64) -> Result<PlayerProfile> {
+        let mut conn = self.db.acquire().await?;
+        self.load(&mut conn, user_id, false).await
     }
 
-    fn save(&self, profile: &PlayerProfile) {
-        self.users.insert(profile.user_id, profile.clone());
+    async fn save(&self, profile: &PlayerProfile) -> Result<()> {
+        let mut conn = self.db.acquire().await?;
+        self.store(&mut conn, profile).await
     }
 }
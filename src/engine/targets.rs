@@ -0,0 +1,89 @@
+use rand::Rng;
+
+use super::balance;
+use super::config::{self, TargetTable};
+use super::types::{CrimeMode, MinigameKind, Risk, TargetKey};
+
+/// Nazwy celów i ich łupu specjalnego (liczby są w tabelach balansu — `config::TargetTables`)
+#[derive(Debug, Clone, Copy)]
+pub struct TargetMeta {
+    pub name: &'static str,
+    pub drop_name: &'static str,
+}
+
+pub const TARGET_META: &[(TargetKey, TargetMeta)] = &[
+    (TargetKey::Kiosk,       TargetMeta { name: "Kiosk",           drop_name: "Wygrana zdrapka" }),
+    (TargetKey::Jeweller,    TargetMeta { name: "Jubiler",         drop_name: "Diament"         }),
+    (TargetKey::Bank,        TargetMeta { name: "Bank",            drop_name: "Sztabka złota"   }),
+    (TargetKey::CasinoVault, TargetMeta { name: "Skarbiec kasyna", drop_name: "Żetony VIP"      }),
+];
+
+fn meta(k: TargetKey) -> TargetMeta {
+    TARGET_META.iter().find(|(kk, _)| *kk == k).map(|(_, m)| *m).expect("każdy cel ma wpis w TARGET_META")
+}
+
+#[inline]
+pub fn target_name(k: TargetKey) -> &'static str {
+    meta(k).name
+}
+
+#[inline]
+pub fn drop_name(k: TargetKey) -> &'static str {
+    meta(k).drop_name
+}
+
+#[inline]
+pub fn table(k: TargetKey) -> TargetTable {
+    *config::get().targets.get(k)
+}
+
+#[inline]
+pub fn required_pp(k: TargetKey) -> u32 {
+    table(k).required_pp
+}
+
+#[inline]
+pub fn available_targets(pp: u32) -> Vec<TargetKey> {
+    let targets = &config::get().targets;
+    TARGET_META.iter().filter(|(k, _)| pp >= targets.get(*k).required_pp).map(|(k, _)| *k).collect()
+}
+
+/// Szansa bazowa z trybu i ryzyka + modyfikator celu (i premia za jego ulubioną minigierkę).
+pub fn base_chance(mode: CrimeMode, risk: Risk, target: Option<TargetKey>, minigame: MinigameKind) -> f32 {
+    let chance = balance::base_chance(mode, risk);
+    match target.map(table) {
+        Some(t) if t.preferred_minigame == minigame => chance + t.chance_delta + t.preferred_bonus,
+        Some(t) => chance + t.chance_delta,
+        None => chance,
+    }
+}
+
+/// Widełki łupu z trybu i ryzyka, przeskalowane przez cel.
+pub fn reward_range(mode: CrimeMode, risk: Risk, target: Option<TargetKey>) -> (i64, i64) {
+    let (lo, hi) = balance::reward_range(mode, risk);
+    match target.map(table) {
+        Some(t) => (((lo as f32) * t.reward_mult) as i64, ((hi as f32) * t.reward_mult) as i64),
+        None => (lo, hi),
+    }
+}
+
+/// HEAT za napad przed efektami przedmiotów.
+pub fn heat_gain(risk: Risk, target: Option<TargetKey>) -> i64 {
+    let heat = balance::heat_gain(risk);
+    match target.map(table) {
+        Some(t) => ((heat as f32) * t.heat_mult).round() as i64,
+        None => heat,
+    }
+}
+
+/// Łup specjalny po udanym napadzie (0 = nic). Bez celu nie losuje niczego —
+/// stare rzuty odtwarzają się z tego samego ziarna bez zmian.
+pub fn roll_drop(target: Option<TargetKey>, rng: &mut impl Rng) -> i64 {
+    let Some(t) = target.map(table) else {
+        return 0;
+    };
+    if t.drop_chance <= 0.0 || !rng.random_bool(t.drop_chance) {
+        return 0;
+    }
+    rng.random_range(t.drop[0]..=t.drop[1])
+}
@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::time::Instant;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -45,6 +46,29 @@ pub enum ItemKey {
     LockpickSet,      // + szansa sukcesu
 }
 
+/// Cel napadu — własne szanse, łup i HEAT (`config::TargetTables`); brak celu = „ulica”.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum TargetKey {
+    Kiosk,
+    Jeweller,
+    Bank,
+    CasinoVault,
+}
+
+/// Umiejętność z drzewka złodzieja (`/crime drzewko`), kupowana za PP.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum PerkKey {
+    ShadowStep,         // Cień: - HEAT
+    Lookout,            // Cień: - zasadzki
+    PhotographicMemory, // Technika: + podglądy Simona
+    Reflexes,           // Technika: + okno QTE
+    ThickSkin,          // Siła: - kara za fail
+    HeavyHands,         // Siła: + łup
+}
+
+/// Poziomy wykupionych umiejętności (brak wpisu = 0).
+pub type PerkRanks = BTreeMap<PerkKey, u8>;
+
 #[derive(Debug, Clone, Default)]
 pub struct ItemEffects {
     pub qte_window_bonus_ms: i32,
@@ -54,12 +78,18 @@ pub struct ItemEffects {
     pub simon_len_delta: i32,    // zmiana długości sekwencji
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SoloHeistConfig {
     pub mode: Option<CrimeMode>,
     pub risk: Option<Risk>,
     pub minigame: MinigameKind,
     pub items: Vec<ItemKey>,
+    /// Starsze sesje i rzuty nie mają celu — to zwykły napad na ulicy.
+    #[serde(default)]
+    pub target: Option<TargetKey>,
+    /// Umiejętności gracza z chwili startu napadu (w zapisie rzutu — powtórka je zna).
+    #[serde(default)]
+    pub perks: PerkRanks,
 }
 
 impl Default for SoloHeistConfig {
@@ -69,11 +99,13 @@ impl Default for SoloHeistConfig {
             risk: None,
             minigame: MinigameKind::Qte,
             items: vec![],
+            target: None,
+            perks: PerkRanks::new(),
         }
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PlayerProfile {
     pub user_id: u64,
     pub balance: i64,
@@ -94,26 +126,53 @@ impl Default for PlayerProfile {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HeistOutcome {
     pub success: bool,
     pub amount_base: i64,
     pub amount_final: i64,
     pub heat_delta: i64,
+    /// Zasadzka przy wysokim HEAT — napad przepada niezależnie od rzutu.
+    #[serde(default)]
+    pub ambushed: bool,
+    /// Łup specjalny celu (już wliczony w `amount_final`).
+    #[serde(default)]
+    pub drop: i64,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct QteSpec {
     pub target_ms: i32,
     pub window_ms: i32,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SimonSpec {
     pub length: usize,
+    #[serde(with = "simon_alphabet")]
     pub alphabet: &'static [char], // np. ['A','B','C','D']
 }
 
+/// Alfabet Simona zapisujemy jako tekst i mapujemy z powrotem na stałą z `minigames`.
+mod simon_alphabet {
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    use crate::engine::minigames::SIMON_ALPHABETS;
+
+    pub fn serialize<S: Serializer>(alphabet: &&'static [char], s: S) -> Result<S::Ok, S::Error> {
+        s.serialize_str(&alphabet.iter().collect::<String>())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<&'static [char], D::Error> {
+        let raw = String::deserialize(d)?;
+        SIMON_ALPHABETS
+            .iter()
+            .copied()
+            .find(|a| a.iter().copied().eq(raw.chars()))
+            .ok_or_else(|| D::Error::custom(format!("nieznany alfabet Simona: {raw}")))
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum SoloState {
     Config(SoloHeistConfig),
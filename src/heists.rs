@@ -0,0 +1,212 @@
+//! Historia napadów `/crime` — statystyki i stronicowana lista do `/crime profil`.
+//!
+//! Wiersz dopisuje `PgSoloRepo` w tej samej transakcji co rzut i zmiana salda, więc
+//! historia nie rozjedzie się z księgą. Napad ekipy to wiersz na członka z jego udziałem.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use sqlx::{PgConnection, PgPool};
+
+use crate::commands::crime::{key_item, key_target, minigame_to_str, mode_to_str, risk_to_str};
+use crate::economy::Economy;
+use crate::engine::types::{CrimeMode, ItemKey, MinigameKind, MinigameResult, Risk, TargetKey};
+
+/// Rozstrzygnięty napad jednego gracza.
+#[derive(Debug, Clone)]
+pub struct Heist<'a> {
+    pub user: u64,
+    pub crew: bool,
+    pub mode: CrimeMode,
+    pub risk: Risk,
+    pub target: Option<TargetKey>,
+    pub items: &'a [ItemKey],
+    pub minigame: MinigameKind,
+    pub mg: MinigameResult,
+    pub success: bool,
+    pub ambushed: bool,
+    pub amount: i64,
+    pub drop: i64,
+    pub heat_delta: i64,
+}
+
+fn mg_result_key(mg: MinigameResult) -> (&'static str, Option<i32>) {
+    match mg {
+        MinigameResult::NotPlayed => ("not_played", None),
+        MinigameResult::Success => ("success", None),
+        MinigameResult::Partial(diff) => ("partial", Some(diff)),
+        MinigameResult::Fail => ("fail", None),
+    }
+}
+
+/// Dopisuje napad na połączeniu wywołującego (w transakcji rozstrzygnięcia).
+pub async fn record(conn: &mut PgConnection, eco: Economy, h: &Heist<'_>, seed: u64, interaction_id: Option<u64>) -> Result<()> {
+    let (mg_result, mg_diff) = mg_result_key(h.mg);
+    let items: Vec<&str> = h.items.iter().map(|&k| key_item(k)).collect();
+    sqlx::query(
+        r#"INSERT INTO crime_heists (guild_id, user_id, crew, mode, risk, target, items, minigame, mg_result, mg_diff_ms,
+                                     seed, interaction_id, success, ambushed, amount, loot_drop, heat_delta)
+           VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17)"#,
+    )
+    .bind(eco.id())
+    .bind(h.user as i64)
+    .bind(h.crew)
+    .bind(mode_to_str(h.mode))
+    .bind(risk_to_str(h.risk))
+    .bind(h.target.map(key_target))
+    .bind(&items)
+    .bind(minigame_to_str(h.minigame))
+    .bind(mg_result)
+    .bind(mg_diff)
+    .bind(seed as i64)
+    .bind(interaction_id.map(|i| i as i64))
+    .bind(h.success)
+    .bind(h.ambushed)
+    .bind(h.amount)
+    .bind(h.drop)
+    .bind(h.heat_delta)
+    .execute(&mut *conn)
+    .await?;
+    Ok(())
+}
+
+/// Zapisany napad (klucze jak w bazie — widok tłumaczy je na nazwy).
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct HeistRecord {
+    pub id: i64,
+    pub crew: bool,
+    pub mode: String,
+    pub risk: String,
+    pub target: Option<String>,
+    pub items: Vec<String>,
+    pub minigame: String,
+    pub mg_result: String,
+    pub mg_diff_ms: Option<i32>,
+    pub seed: i64,
+    pub success: bool,
+    pub ambushed: bool,
+    pub amount: i64,
+    pub loot_drop: i64,
+    pub heat_delta: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+impl HeistRecord {
+    pub fn seed(&self) -> u64 {
+        self.seed as u64
+    }
+}
+
+const RECORD_COLUMNS: &str = "id, crew, mode, risk, target, items, minigame, mg_result, mg_diff_ms, \
+                              seed, success, ambushed, amount, loot_drop, heat_delta, created_at";
+
+/// Skuteczność w jednej grupie (tryb albo ryzyko).
+#[derive(Debug, Clone)]
+pub struct Rate {
+    pub key: String,
+    pub total: i64,
+    pub wins: i64,
+}
+
+impl Rate {
+    pub fn pct(&self) -> f64 {
+        if self.total == 0 { 0.0 } else { self.wins as f64 * 100.0 / self.total as f64 }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Stats {
+    pub total: i64,
+    pub wins: i64,
+    /// Suma łupów z udanych napadów.
+    pub loot: i64,
+    /// Łupy minus straty.
+    pub net: i64,
+    pub longest_streak: i64,
+    pub best: Option<HeistRecord>,
+    pub by_mode: Vec<Rate>,
+    pub by_risk: Vec<Rate>,
+}
+
+pub async fn stats(db: &PgPool, eco: Economy, user: u64) -> Result<Stats> {
+    let (total, wins, loot, net): (i64, i64, i64, i64) = sqlx::query_as(
+        r#"SELECT COUNT(*),
+                  COUNT(*) FILTER (WHERE success),
+                  COALESCE(SUM(amount) FILTER (WHERE success AND amount > 0), 0)::BIGINT,
+                  COALESCE(SUM(amount), 0)::BIGINT
+             FROM crime_heists
+            WHERE guild_id = $1 AND user_id = $2"#,
+    )
+    .bind(eco.id())
+    .bind(user as i64)
+    .fetch_one(db)
+    .await?;
+    if total == 0 {
+        return Ok(Stats::default());
+    }
+
+    let best: Option<HeistRecord> = sqlx::query_as(&format!(
+        "SELECT {RECORD_COLUMNS} FROM crime_heists
+          WHERE guild_id = $1 AND user_id = $2 AND success
+          ORDER BY amount DESC, id LIMIT 1"
+    ))
+    .bind(eco.id())
+    .bind(user as i64)
+    .fetch_optional(db)
+    .await?;
+
+    // najdłuższa seria: każda porażka otwiera nową grupę, liczymy sukcesy w grupie
+    let longest_streak: i64 = sqlx::query_scalar(
+        r#"SELECT COALESCE(MAX(n), 0)::BIGINT FROM (
+               SELECT COUNT(*) AS n FROM (
+                   SELECT success, SUM(CASE WHEN success THEN 0 ELSE 1 END) OVER (ORDER BY id) AS grp
+                     FROM crime_heists
+                    WHERE guild_id = $1 AND user_id = $2
+               ) s
+                WHERE success
+                GROUP BY grp
+           ) t"#,
+    )
+    .bind(eco.id())
+    .bind(user as i64)
+    .fetch_one(db)
+    .await?;
+
+    let rows: Vec<(Option<String>, Option<String>, i64, i64)> = sqlx::query_as(
+        r#"SELECT mode, risk, COUNT(*), COUNT(*) FILTER (WHERE success)
+             FROM crime_heists
+            WHERE guild_id = $1 AND user_id = $2
+            GROUP BY GROUPING SETS ((mode), (risk))
+            ORDER BY COUNT(*) DESC"#,
+    )
+    .bind(eco.id())
+    .bind(user as i64)
+    .fetch_all(db)
+    .await?;
+    let mut by_mode = Vec::new();
+    let mut by_risk = Vec::new();
+    for (mode, risk, total, wins) in rows {
+        match (mode, risk) {
+            (Some(key), _) => by_mode.push(Rate { key, total, wins }),
+            (None, Some(key)) => by_risk.push(Rate { key, total, wins }),
+            (None, None) => {}
+        }
+    }
+
+    Ok(Stats { total, wins, loot, net, longest_streak, best, by_mode, by_risk })
+}
+
+/// Strona historii od najnowszych (`page` od 0).
+pub async fn page(db: &PgPool, eco: Economy, user: u64, page: u32, per_page: u32) -> Result<Vec<HeistRecord>> {
+    let rows = sqlx::query_as(&format!(
+        "SELECT {RECORD_COLUMNS} FROM crime_heists
+          WHERE guild_id = $1 AND user_id = $2
+          ORDER BY id DESC LIMIT $3 OFFSET $4"
+    ))
+    .bind(eco.id())
+    .bind(user as i64)
+    .bind(i64::from(per_page))
+    .bind(i64::from(page) * i64::from(per_page))
+    .fetch_all(db)
+    .await?;
+    Ok(rows)
+}
@@ -0,0 +1,344 @@
+//! Ekwipunek `/crime` — przedmioty kupowane za TK i zużywane w napadach.
+//!
+//! Trwały przedmiot to jeden egzemplarz z wytrzymałością: każdy napad zdejmuje 1, naprawa
+//! w warsztacie kosztuje część ceny. Jednorazowe (granat dymny, adrenalina) kupuje się na sztuki.
+//! PP nie daje już przedmiotów za darmo — odblokowuje je w sklepie. Ceny, wytrzymałość i limity
+//! to tabele balansu (`config::ItemTables`, `config::ShopTables`). Zakup i naprawa spalają TK
+//! do `sys:shop`; odsprzedaż innemu graczowi płaci się przelewem `/pay` (limit dzienny, opłata,
+//! blokada dłużnika), a oferta to wiersz `item_offers` zamykany w tej samej transakcji co wymiana.
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use sqlx::{PgConnection, PgExecutor, PgPool};
+
+use crate::clock;
+
+use crate::commands::crime::{from_key_item, key_item};
+use crate::economy::Economy;
+use crate::engine::config::{self, ItemTable};
+use crate::engine::items::ITEM_META;
+use crate::engine::types::ItemKey;
+use crate::ledger::{self, Entry, Overdraft};
+use crate::loans;
+
+/// Konto systemowe, do którego idą zakupy i naprawy.
+const SHOP_ACCOUNT: &str = "shop";
+
+/// Przedmiot w ekwipunku: wytrzymałość (trwałe) albo liczba sztuk (jednorazowe).
+#[derive(Debug, Clone, Copy)]
+pub struct Owned {
+    pub item: ItemKey,
+    pub charges: i32,
+}
+
+/// Ekwipunek gracza w kolejności sklepu.
+pub async fn list(db: impl PgExecutor<'_>, eco: Economy, user: u64) -> Result<Vec<Owned>> {
+    let rows: Vec<(String, i32)> =
+        sqlx::query_as("SELECT item, charges FROM crime_inventory WHERE guild_id = $1 AND user_id = $2")
+            .bind(eco.id())
+            .bind(user as i64)
+            .fetch_all(db)
+            .await?;
+
+    let mut owned: Vec<Owned> = rows
+        .into_iter()
+        .filter_map(|(k, charges)| from_key_item(&k).map(|item| Owned { item, charges }))
+        .collect();
+    owned.sort_by_key(|o| ITEM_META.iter().position(|(k, _)| *k == o.item));
+    Ok(owned)
+}
+
+/// Same klucze posiadanych przedmiotów (do wyboru ekwipunku).
+pub async fn owned_keys(db: impl PgExecutor<'_>, eco: Economy, user: u64) -> Result<Vec<ItemKey>> {
+    Ok(list(db, eco, user).await?.into_iter().map(|o| o.item).collect())
+}
+
+async fn lock(conn: &mut PgConnection, eco: Economy, user: u64, item: ItemKey) -> Result<i32> {
+    let charges: Option<i32> = sqlx::query_scalar(
+        "SELECT charges FROM crime_inventory WHERE guild_id = $1 AND user_id = $2 AND item = $3 FOR UPDATE",
+    )
+    .bind(eco.id())
+    .bind(user as i64)
+    .bind(key_item(item))
+    .fetch_optional(&mut *conn)
+    .await?;
+    Ok(charges.unwrap_or(0))
+}
+
+/// Ustawia stan przedmiotu; 0 = przedmiot znika z ekwipunku.
+async fn store(conn: &mut PgConnection, eco: Economy, user: u64, item: ItemKey, charges: i32) -> Result<()> {
+    if charges <= 0 {
+        sqlx::query("DELETE FROM crime_inventory WHERE guild_id = $1 AND user_id = $2 AND item = $3")
+            .bind(eco.id())
+            .bind(user as i64)
+            .bind(key_item(item))
+            .execute(&mut *conn)
+            .await?;
+        return Ok(());
+    }
+    sqlx::query(
+        r#"INSERT INTO crime_inventory (guild_id, user_id, item, charges, updated_at)
+           VALUES ($1, $2, $3, $4, now())
+           ON CONFLICT (guild_id, user_id, item) DO UPDATE
+           SET charges = EXCLUDED.charges, updated_at = now()"#,
+    )
+    .bind(eco.id())
+    .bind(user as i64)
+    .bind(key_item(item))
+    .bind(charges)
+    .execute(&mut *conn)
+    .await?;
+    Ok(())
+}
+
+/// Dokłada `n` użyć jednym upsertem (bez czytania stanu wcześniej). `None` — nie zmieści się:
+/// trwały egzemplarz już jest albo stos jednorazowych przekroczyłby `max_stack`.
+async fn give(conn: &mut PgConnection, eco: Economy, user: u64, item: ItemKey, n: i32) -> Result<Option<i32>> {
+    let cfg = config::get();
+    let sql = if cfg.items.get(item).single_use {
+        r#"INSERT INTO crime_inventory (guild_id, user_id, item, charges, updated_at)
+           VALUES ($1, $2, $3, $4, now())
+           ON CONFLICT (guild_id, user_id, item) DO UPDATE
+           SET charges = crime_inventory.charges + EXCLUDED.charges, updated_at = now()
+           WHERE crime_inventory.charges + EXCLUDED.charges <= $5
+           RETURNING charges"#
+    } else {
+        r#"INSERT INTO crime_inventory (guild_id, user_id, item, charges, updated_at)
+           VALUES ($1, $2, $3, $4, now())
+           ON CONFLICT (guild_id, user_id, item) DO NOTHING
+           RETURNING charges"#
+    };
+    let charges: Option<i32> = sqlx::query_scalar(sql)
+        .bind(eco.id())
+        .bind(user as i64)
+        .bind(key_item(item))
+        .bind(n)
+        .bind(cfg.shop.max_stack)
+        .fetch_optional(&mut *conn)
+        .await?;
+    Ok(charges)
+}
+
+/// Zużywa po jednym użyciu każdego przedmiotu z zestawu (w transakcji startu napadu).
+/// Zwraca to, co faktycznie zużyto — brakujących przedmiotów po prostu nie ma na liście.
+pub async fn spend(conn: &mut PgConnection, eco: Economy, user: u64, items: &[ItemKey]) -> Result<Vec<ItemKey>> {
+    let mut used = Vec::with_capacity(items.len());
+    for &item in items {
+        if used.contains(&item) {
+            continue;
+        }
+        let charges = lock(conn, eco, user, item).await?;
+        if charges > 0 {
+            store(conn, eco, user, item, charges - 1).await?;
+            used.push(item);
+        }
+    }
+    Ok(used)
+}
+
+/// „7/10 użyć” dla trwałych, „3 szt.” dla jednorazowych.
+pub fn charges_label(item: ItemKey, charges: i32) -> String {
+    let t = *config::get().items.get(item);
+    if t.single_use {
+        format!("{charges} szt.")
+    } else {
+        format!("{charges}/{} użyć", t.durability)
+    }
+}
+
+/// Koszt naprawy do pełna (proporcjonalny do brakującej wytrzymałości, zaokrąglony w górę).
+pub fn repair_cost(t: &ItemTable, charges: i32) -> i64 {
+    let missing = (t.durability - charges).max(0);
+    let pct = config::get().shop.repair_cost_pct;
+    ((t.price as f32) * pct * missing as f32 / t.durability.max(1) as f32).ceil() as i64
+}
+
+async fn pay_shop(conn: &mut PgConnection, eco: Economy, user: u64, amount: i64, entry: &Entry<'_>) -> Result<Result<i64, i64>> {
+    match ledger::settle(conn, eco, user, SHOP_ACCOUNT, -amount, entry, Overdraft::Forbid).await {
+        Ok(balance) => Ok(Ok(balance)),
+        Err(e) => match ledger::insufficient(&e) {
+            Some(balance) => Ok(Err(balance)),
+            None => Err(e),
+        },
+    }
+}
+
+pub enum Purchase {
+    /// Za mało PP na ten przedmiot.
+    Locked { required_pp: u32 },
+    /// Trwały przedmiot już jest w ekwipunku (zamiast kupować — napraw).
+    AlreadyOwned { charges: i32 },
+    StackFull { max: i32 },
+    TooPoor { balance: i64, price: i64 },
+    /// Pożyczka po terminie — zakupy zablokowane do spłaty.
+    Defaulted { owed: i64 },
+    Bought { charges: i32, price: i64, balance: i64 },
+}
+
+/// Zakup: trwały = nowy egzemplarz z pełną wytrzymałością, jednorazowy = +1 sztuka.
+pub async fn buy(db: &PgPool, eco: Economy, user: u64, item: ItemKey, interaction_id: Option<u64>) -> Result<Purchase> {
+    let cfg = config::get();
+    let t = cfg.items.get(item);
+
+    let mut tx = db.begin().await?;
+    if let Some(owed) = loans::in_default(&mut *tx, eco, user).await? {
+        return Ok(Purchase::Defaulted { owed });
+    }
+    let pp: i32 = sqlx::query_scalar("SELECT pp FROM profiles WHERE guild_id = $1 AND user_id = $2")
+        .bind(eco.id())
+        .bind(user as i64)
+        .fetch_optional(&mut *tx)
+        .await?
+        .unwrap_or(0);
+    if (pp.max(0) as u32) < t.required_pp {
+        return Ok(Purchase::Locked { required_pp: t.required_pp });
+    }
+
+    // przyrost jednym upsertem: równoległy zakup (także z innej instancji bota) nie nadpisze
+    // tego stanu, a brak miejsca wycofuje całą transakcję razem z płatnością
+    let n = if t.single_use { 1 } else { t.durability };
+    let Some(next) = give(&mut tx, eco, user, item, n).await? else {
+        return Ok(if t.single_use {
+            Purchase::StackFull { max: cfg.shop.max_stack }
+        } else {
+            Purchase::AlreadyOwned { charges: lock(&mut tx, eco, user, item).await? }
+        });
+    };
+
+    let entry = Entry { reason: "zakup przedmiotu", command: "ekwipunek", interaction_id };
+    let balance = match pay_shop(&mut tx, eco, user, t.price, &entry).await? {
+        Ok(b) => b,
+        Err(balance) => return Ok(Purchase::TooPoor { balance, price: t.price }),
+    };
+    tx.commit().await?;
+    Ok(Purchase::Bought { charges: next, price: t.price, balance })
+}
+
+pub enum Repair {
+    NotOwned,
+    /// Jednorazowych się nie naprawia.
+    SingleUse,
+    AlreadyFull,
+    TooPoor { balance: i64, cost: i64 },
+    Repaired { charges: i32, cost: i64, balance: i64 },
+}
+
+/// Naprawa trwałego przedmiotu do pełnej wytrzymałości.
+pub async fn repair(db: &PgPool, eco: Economy, user: u64, item: ItemKey, interaction_id: Option<u64>) -> Result<Repair> {
+    let t = *config::get().items.get(item);
+    if t.single_use {
+        return Ok(Repair::SingleUse);
+    }
+
+    let mut tx = db.begin().await?;
+    let charges = lock(&mut tx, eco, user, item).await?;
+    if charges <= 0 {
+        return Ok(Repair::NotOwned);
+    }
+    if charges >= t.durability {
+        return Ok(Repair::AlreadyFull);
+    }
+
+    let cost = repair_cost(&t, charges);
+    let entry = Entry { reason: "naprawa przedmiotu", command: "ekwipunek", interaction_id };
+    let balance = match pay_shop(&mut tx, eco, user, cost, &entry).await? {
+        Ok(b) => b,
+        Err(balance) => return Ok(Repair::TooPoor { balance, cost }),
+    };
+    store(&mut tx, eco, user, item, t.durability).await?;
+    tx.commit().await?;
+    Ok(Repair::Repaired { charges: t.durability, cost, balance })
+}
+
+pub enum Trade {
+    /// Sprzedający już nie ma przedmiotu.
+    NotOwned,
+    /// Kupujący ma już trwały egzemplarz albo pełny stos jednorazowych.
+    BuyerFull,
+    /// Przekazano `charges` (trwały: cała wytrzymałość, jednorazowy: 1 sztuka).
+    Done { charges: i32 },
+}
+
+/// Przekazanie przedmiotu `seller → buyer` w transakcji wywołującego. Cenę płaci wołający
+/// przelewem `/pay` w tej samej transakcji, więc odmowa którejkolwiek strony cofa obie.
+pub async fn trade(conn: &mut PgConnection, eco: Economy, seller: u64, buyer: u64, item: ItemKey) -> Result<Trade> {
+    let t = *config::get().items.get(item);
+
+    // blokady w stałej kolejności — dwie wymiany naraz nie zakleszczą się
+    let (first, second) = if seller < buyer { (seller, buyer) } else { (buyer, seller) };
+    let a = lock(conn, eco, first, item).await?;
+    let b = lock(conn, eco, second, item).await?;
+    let have = if first == seller { a } else { b };
+
+    if have <= 0 {
+        return Ok(Trade::NotOwned);
+    }
+    let moved = if t.single_use { 1 } else { have };
+
+    // kupującemu dokłada upsert — dwie wymiany do tej samej osoby nie nadpiszą sobie stanu
+    if give(conn, eco, buyer, item, moved).await?.is_none() {
+        return Ok(Trade::BuyerFull);
+    }
+    store(conn, eco, seller, item, have - moved).await?;
+    Ok(Trade::Done { charges: moved })
+}
+
+// =======================
+// Oferty odsprzedaży
+// =======================
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct Offer {
+    pub id: i64,
+    pub seller: i64,
+    pub buyer: i64,
+    pub item: String,
+    pub price: i64,
+    pub status: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+const OFFER_COLUMNS: &str = "id, seller, buyer, item, price, status, expires_at";
+
+/// Nowa oferta ważna `config::ShopTables::offer_mins`.
+pub async fn create_offer(
+    db: &PgPool,
+    eco: Economy,
+    seller: u64,
+    buyer: u64,
+    item: ItemKey,
+    price: i64,
+    interaction_id: u64,
+) -> Result<Offer> {
+    let sql = format!(
+        r#"INSERT INTO item_offers (guild_id, seller, buyer, item, price, expires_at, interaction_id)
+           VALUES ($1, $2, $3, $4, $5, $6, $7)
+        RETURNING {OFFER_COLUMNS}"#
+    );
+    Ok(sqlx::query_as(&sql)
+        .bind(eco.id())
+        .bind(seller as i64)
+        .bind(buyer as i64)
+        .bind(key_item(item))
+        .bind(price)
+        .bind(clock::now() + Duration::minutes(config::get().shop.offer_mins))
+        .bind(interaction_id as i64)
+        .fetch_one(db)
+        .await?)
+}
+
+/// Oferta zablokowana `FOR UPDATE` — drugi klik czeka na pierwszy i widzi już nowy status.
+pub async fn lock_offer(conn: &mut PgConnection, eco: Economy, id: i64) -> Result<Option<Offer>> {
+    let sql = format!("SELECT {OFFER_COLUMNS} FROM item_offers WHERE guild_id = $1 AND id = $2 FOR UPDATE");
+    Ok(sqlx::query_as(&sql).bind(eco.id()).bind(id).fetch_optional(&mut *conn).await?)
+}
+
+pub async fn close_offer(conn: &mut PgConnection, id: i64, status: &str) -> Result<()> {
+    sqlx::query("UPDATE item_offers SET status = $2, resolved_at = $3 WHERE id = $1")
+        .bind(id)
+        .bind(status)
+        .bind(clock::now())
+        .execute(&mut *conn)
+        .await?;
+    Ok(())
+}
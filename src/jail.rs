@@ -0,0 +1,196 @@
+//! Więzienie — konsekwencja wpadki przy wysokim HEAT.
+//!
+//! Nieudany `/crime` albo `/rob` przy HEAT od `jail.min_heat` (albo zasadzka) zamyka gracza
+//! na czas zależny od ryzyka i HEAT (tabele: [`config::JailTables`]). Dopóki `release_at` jest w przyszłości, Handler odrzuca
+//! komendy oznaczone `BotCommand::earning`. Wyjścia: czekanie, kaucja (spalana do `sys:jail`)
+//! albo jedna próba ucieczki na wyrok (`/wiezienie`). Czas zawsze z [`crate::clock`].
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use serenity::all::GuildId;
+use sqlx::{PgConnection, PgExecutor, PgPool};
+
+use crate::clock;
+use crate::economy::{self, Economy};
+use crate::engine::config;
+use crate::engine::types::Risk;
+use crate::ledger::{self, Entry, Overdraft};
+
+/// Konto systemowe, do którego idą kaucje.
+const BAIL_ACCOUNT: &str = "jail";
+
+/// Długość wyroku w minutach (`None` = bez więzienia). Baza z ryzyka, +1 min za każde
+/// `heat_per_min` punktów HEAT ponad próg; zasadzka zamyka zawsze i na `ambush_mult` dłużej.
+pub fn sentence_mins(risk: Risk, heat: i64, ambushed: bool) -> Option<i64> {
+    let t = &config::get().jail;
+    if !ambushed && heat < t.min_heat {
+        return None;
+    }
+    let mut mins = t.sentence_mins.get(risk) + (heat - t.min_heat).max(0) / t.heat_per_min;
+    if ambushed {
+        mins = (mins as f64 * t.ambush_mult) as i64;
+    }
+    Some(mins.clamp(1, t.max_sentence_mins))
+}
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct Stay {
+    pub jailed_at: DateTime<Utc>,
+    pub release_at: DateTime<Utc>,
+    pub reason: String,
+    pub escape_armed_ms: Option<i64>,
+    pub escape_tried: bool,
+}
+
+impl Stay {
+    /// Kaucja za pozostały czas (każda rozpoczęta minuta).
+    pub fn bail(&self) -> i64 {
+        let secs = (self.release_at - clock::now()).num_seconds().max(0);
+        ((secs + 59) / 60).max(1) * config::get().jail.bail_per_min
+    }
+}
+
+/// Trwający wyrok gracza (`None` = wolny).
+pub async fn current(db: impl PgExecutor<'_>, eco: Economy, user: u64) -> Result<Option<Stay>> {
+    let stay = sqlx::query_as(
+        r#"SELECT jailed_at, release_at, reason, escape_armed_ms, escape_tried
+             FROM jail WHERE guild_id = $1 AND user_id = $2 AND release_at > $3"#,
+    )
+    .bind(eco.id())
+    .bind(user as i64)
+    .bind(clock::now())
+    .fetch_optional(db)
+    .await?;
+    Ok(stay)
+}
+
+/// Jak [`current`], ale z blokadą wiersza — do kaucji i ucieczki.
+pub async fn lock(conn: &mut PgConnection, eco: Economy, user: u64) -> Result<Option<Stay>> {
+    let stay = sqlx::query_as(
+        r#"SELECT jailed_at, release_at, reason, escape_armed_ms, escape_tried
+             FROM jail WHERE guild_id = $1 AND user_id = $2 AND release_at > $3
+             FOR UPDATE"#,
+    )
+    .bind(eco.id())
+    .bind(user as i64)
+    .bind(clock::now())
+    .fetch_optional(&mut *conn)
+    .await?;
+    Ok(stay)
+}
+
+/// Do kiedy gracz siedzi — dla bramki komend zarabiających w Handlerze.
+pub async fn blocked(db: &PgPool, guild: Option<GuildId>, user: u64) -> Result<Option<DateTime<Utc>>> {
+    let eco = economy::of(db, guild).await?;
+    Ok(current(db, eco, user).await?.map(|s| s.release_at))
+}
+
+/// Zamyka gracza na `mins` minut; trwający wyrok przedłuża (bez nowej próby ucieczki).
+/// Wołać w transakcji, która zapisuje skutek wpadki. Zwraca moment wyjścia.
+pub async fn imprison(conn: &mut PgConnection, eco: Economy, user: u64, mins: i64, reason: &str) -> Result<DateTime<Utc>> {
+    let now = clock::now();
+    let release_at = sqlx::query_scalar(
+        r#"
+        INSERT INTO jail (guild_id, user_id, jailed_at, release_at, reason)
+        VALUES ($1, $2, $3, $4, $5)
+        ON CONFLICT (guild_id, user_id) DO UPDATE SET
+            jailed_at       = CASE WHEN jail.release_at > $3 THEN jail.jailed_at ELSE EXCLUDED.jailed_at END,
+            release_at      = CASE WHEN jail.release_at > $3 THEN jail.release_at + ($4 - $3) ELSE EXCLUDED.release_at END,
+            reason          = EXCLUDED.reason,
+            escape_armed_ms = CASE WHEN jail.release_at > $3 THEN jail.escape_armed_ms END,
+            escape_tried    = jail.release_at > $3 AND jail.escape_tried
+        RETURNING release_at
+        "#,
+    )
+    .bind(eco.id())
+    .bind(user as i64)
+    .bind(now)
+    .bind(now + Duration::minutes(mins))
+    .bind(reason)
+    .fetch_one(&mut *conn)
+    .await?;
+    Ok(release_at)
+}
+
+/// Przesuwa koniec wyroku (`secs` < 0 skraca; skrócenie do „teraz” = wolność).
+pub async fn shift(conn: &mut PgConnection, eco: Economy, user: u64, secs: i64) -> Result<DateTime<Utc>> {
+    let now = clock::now();
+    let release_at = sqlx::query_scalar(
+        r#"UPDATE jail SET release_at = GREATEST(release_at + $3, $4)
+            WHERE guild_id = $1 AND user_id = $2
+        RETURNING release_at"#,
+    )
+    .bind(eco.id())
+    .bind(user as i64)
+    .bind(Duration::seconds(secs))
+    .bind(now)
+    .fetch_one(&mut *conn)
+    .await?;
+    Ok(release_at)
+}
+
+/// Zapamiętuje start odliczania ucieczki (znacznik Discorda w ms).
+pub async fn arm_escape(conn: &mut PgConnection, eco: Economy, user: u64, at_ms: i64) -> Result<()> {
+    sqlx::query("UPDATE jail SET escape_armed_ms = $3 WHERE guild_id = $1 AND user_id = $2")
+        .bind(eco.id())
+        .bind(user as i64)
+        .bind(at_ms)
+        .execute(&mut *conn)
+        .await?;
+    Ok(())
+}
+
+/// Zużywa jedyną próbę ucieczki w tym wyroku.
+pub async fn mark_escape_tried(conn: &mut PgConnection, eco: Economy, user: u64) -> Result<()> {
+    sqlx::query("UPDATE jail SET escape_tried = TRUE WHERE guild_id = $1 AND user_id = $2")
+        .bind(eco.id())
+        .bind(user as i64)
+        .execute(&mut *conn)
+        .await?;
+    Ok(())
+}
+
+pub enum Bail {
+    NotJailed,
+    /// Za mało TK — saldo gracza i wymagana kaucja.
+    TooPoor { balance: i64, bail: i64 },
+    Paid { bail: i64, balance: i64 },
+}
+
+/// Kaucja: spala TK do `sys:jail` i kończy wyrok — wszystko albo nic.
+pub async fn pay_bail(db: &PgPool, eco: Economy, user: u64, interaction_id: Option<u64>) -> Result<Bail> {
+    let mut tx = db.begin().await?;
+    let Some(stay) = lock(&mut tx, eco, user).await? else {
+        return Ok(Bail::NotJailed);
+    };
+    let bail = stay.bail();
+
+    let paid = ledger::settle(
+        &mut tx,
+        eco,
+        user,
+        BAIL_ACCOUNT,
+        -bail,
+        &Entry { reason: "kaucja", command: "wiezienie", interaction_id },
+        Overdraft::Forbid,
+    )
+    .await;
+    let balance = match paid {
+        Ok(b) => b,
+        Err(e) => {
+            if let Some(balance) = ledger::insufficient(&e) {
+                return Ok(Bail::TooPoor { balance, bail });
+            }
+            return Err(e);
+        }
+    };
+
+    sqlx::query("UPDATE jail SET release_at = $3 WHERE guild_id = $1 AND user_id = $2")
+        .bind(eco.id())
+        .bind(user as i64)
+        .bind(clock::now())
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+    Ok(Bail::Paid { bail, balance })
+}
@@ -0,0 +1,27 @@
+//! Nocne sprzątanie zakończonych cooldownów i przeterminowanych próśb o zapłatę.
+
+use anyhow::Result;
+use serenity::async_trait;
+
+use crate::commands::pay_request;
+use crate::cooldowns;
+use crate::scheduler::{Job, JobCtx, Schedule};
+
+pub struct PurgeCooldowns;
+
+#[async_trait]
+impl Job for PurgeCooldowns {
+    fn name(&self) -> &'static str {
+        "cooldowns.purge"
+    }
+
+    fn schedule(&self) -> Schedule {
+        Schedule::cron("17 4 * * *")
+    }
+
+    async fn run(&self, ctx: &JobCtx) -> Result<String> {
+        let purged = cooldowns::purge_expired(&*ctx.db).await?;
+        let requests = pay_request::expire_pending(&ctx.db).await?;
+        Ok(format!("usunięte wpisy: {purged}, wygasłe prośby o zapłatę: {requests}"))
+    }
+}
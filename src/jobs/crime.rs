@@ -0,0 +1,28 @@
+//! Wygasanie nieaktywnych sesji /crime i lobby ekip.
+
+use anyhow::Result;
+use serenity::async_trait;
+
+use crate::commands::{crew, crime};
+use crate::scheduler::{Job, JobCtx, Schedule};
+
+pub struct ExpireCrimeSessions;
+
+#[async_trait]
+impl Job for ExpireCrimeSessions {
+    fn name(&self) -> &'static str {
+        "crime.sessions.expire"
+    }
+
+    fn schedule(&self) -> Schedule {
+        Schedule::every_secs(60)
+    }
+
+    async fn run(&self, ctx: &JobCtx) -> Result<String> {
+        let (expired, abandoned) = crime::expire_sessions(&ctx.db).await?;
+        let (crews, crews_abandoned) = crew::expire_lobbies(&ctx.db).await?;
+        Ok(format!(
+            "wygasłe: {expired}, porzucone napady: {abandoned}, ekipy: {crews} (porzucone: {crews_abandoned})"
+        ))
+    }
+}
@@ -0,0 +1,31 @@
+//! Wygasanie HEAT: co godzinę „stygną” profile, które dawno nie kradły.
+
+use anyhow::Result;
+use serenity::async_trait;
+
+use crate::engine::config;
+use crate::engine::repo::decay_heat;
+use crate::scheduler::{Job, JobCtx, Schedule};
+
+pub struct DecayHeat;
+
+#[async_trait]
+impl Job for DecayHeat {
+    fn name(&self) -> &'static str {
+        "heat.decay"
+    }
+
+    fn schedule(&self) -> Schedule {
+        Schedule::cron("5 * * * *")
+    }
+
+    async fn run(&self, ctx: &JobCtx) -> Result<String> {
+        // ile HEAT ubywa na godzinę: `heat.decay_per_hour` z pliku balansu (0 = wyłączone)
+        let per_hour = config::get().heat.decay_per_hour;
+        if per_hour == 0 {
+            return Ok("wyłączone".to_string());
+        }
+        let cooled = decay_heat(&ctx.db, per_hour).await?;
+        Ok(format!("ochłodzone profile: {cooled}"))
+    }
+}
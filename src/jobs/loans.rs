@@ -0,0 +1,49 @@
+//! Zwłoka w spłacie: przeterminowane pożyczki dostają karę i status `defaulted` (ogłaszane na kanale logów).
+
+use anyhow::Result;
+use serenity::all::{CreateEmbed, CreateMessage};
+use serenity::async_trait;
+
+use crate::clock;
+use crate::loans::{self, Defaulted};
+use crate::scheduler::{Job, JobCtx, Schedule};
+use crate::utils::get_log_channel_id;
+
+pub struct DefaultLoans;
+
+#[async_trait]
+impl Job for DefaultLoans {
+    fn name(&self) -> &'static str {
+        "loans.default"
+    }
+
+    fn schedule(&self) -> Schedule {
+        Schedule::every_secs(300)
+    }
+
+    async fn run(&self, ctx: &JobCtx) -> Result<String> {
+        let overdue = loans::default_overdue(&ctx.db).await?;
+
+        if let Some(ch) = get_log_channel_id() {
+            for d in &overdue {
+                let _ = ch.send_message(&ctx.http, CreateMessage::new().embed(announce(d))).await;
+            }
+        }
+
+        Ok(format!("przeterminowane pożyczki: {}", overdue.len()))
+    }
+}
+
+fn announce(d: &Defaulted) -> CreateEmbed {
+    let lender = d.lender.map_or("🏦 bank".to_string(), |id| format!("<@{id}>"));
+    CreateEmbed::new()
+        .title("⛓️ Pożyczka po terminie")
+        .description(format!(
+            "<@{}> nie spłacił pożyczki u {lender} (ekonomia `{}`). /pay i zakupy zablokowane do spłaty.",
+            d.borrower, d.guild_id
+        ))
+        .field("Kara", format!("+{} TK", d.penalty), true)
+        .field("Do spłaty", format!("{} TK", d.owed), true)
+        .color(0xdc2626)
+        .timestamp(clock::now())
+}
@@ -0,0 +1,24 @@
+pub mod cooldowns;
+pub mod crime;
+pub mod heat;
+pub mod loans;
+pub mod reminders;
+pub mod subscriptions;
+pub mod wealth;
+
+use std::sync::Arc;
+
+use crate::scheduler::Job;
+
+/// Wszystkie zadania harmonogramu — nowe zadanie = nowy moduł + jedna linijka tutaj.
+pub fn all() -> Vec<Arc<dyn Job>> {
+    vec![
+        Arc::new(subscriptions::ExpireSubscriptions),
+        Arc::new(cooldowns::PurgeCooldowns),
+        Arc::new(reminders::DeliverReminders),
+        Arc::new(crime::ExpireCrimeSessions),
+        Arc::new(heat::DecayHeat),
+        Arc::new(wealth::ApplyWealth),
+        Arc::new(loans::DefaultLoans),
+    ]
+}
@@ -0,0 +1,30 @@
+//! Doręczanie przypomnień o końcu cooldownu.
+
+use anyhow::Result;
+use chrono::Duration;
+use serenity::async_trait;
+
+use crate::reminders;
+use crate::scheduler::{Job, JobCtx, Schedule};
+
+pub struct DeliverReminders;
+
+#[async_trait]
+impl Job for DeliverReminders {
+    fn name(&self) -> &'static str {
+        "reminders.deliver"
+    }
+
+    fn schedule(&self) -> Schedule {
+        Schedule::every_secs(10)
+    }
+
+    fn lease(&self) -> Duration {
+        Duration::minutes(2)
+    }
+
+    async fn run(&self, ctx: &JobCtx) -> Result<String> {
+        let (sent, failed) = reminders::deliver_due(&ctx.http, &ctx.db).await?;
+        Ok(format!("doręczone: {sent}, nieudane: {failed}"))
+    }
+}
@@ -0,0 +1,25 @@
+//! Wygasanie subskrypcji roli ze sklepu (wcześniej tylko przy okazji `/shop`).
+
+use anyhow::Result;
+use serenity::async_trait;
+
+use crate::commands::shop_ui;
+use crate::scheduler::{Job, JobCtx, Schedule};
+
+pub struct ExpireSubscriptions;
+
+#[async_trait]
+impl Job for ExpireSubscriptions {
+    fn name(&self) -> &'static str {
+        "subscriptions.expire"
+    }
+
+    fn schedule(&self) -> Schedule {
+        Schedule::every_secs(60)
+    }
+
+    async fn run(&self, ctx: &JobCtx) -> Result<String> {
+        let removed = shop_ui::expire_subscriptions(&ctx.http, &ctx.db).await?;
+        Ok(format!("zdjęte role: {removed}"))
+    }
+}
@@ -0,0 +1,58 @@
+//! Dzienne naliczenie odsetek od banku i podatku od majątku (ogłaszane na kanale logów).
+
+use anyhow::Result;
+use serenity::all::{CreateEmbed, CreateMessage};
+use serenity::async_trait;
+
+use crate::clock;
+use crate::scheduler::{Job, JobCtx, Schedule};
+use crate::utils::get_log_channel_id;
+use crate::wealth::{self, Summary};
+
+pub struct ApplyWealth;
+
+#[async_trait]
+impl Job for ApplyWealth {
+    fn name(&self) -> &'static str {
+        "wealth.daily"
+    }
+
+    fn schedule(&self) -> Schedule {
+        Schedule::cron("0 3 * * *")
+    }
+
+    async fn run(&self, ctx: &JobCtx) -> Result<String> {
+        if !wealth::enabled() {
+            return Ok("wyłączone".to_string());
+        }
+        let day = clock::now().date_naive();
+
+        let mut done = Vec::new();
+        for eco in wealth::economies(&ctx.db).await? {
+            if let Some(sum) = wealth::run(&ctx.db, eco, day).await? {
+                done.push(sum);
+            }
+        }
+
+        if let Some(ch) = get_log_channel_id() {
+            for sum in done.iter().filter(|s| s.interest > 0 || s.tax > 0) {
+                let _ = ch.send_message(&ctx.http, CreateMessage::new().embed(announce(sum))).await;
+            }
+        }
+
+        let interest: i64 = done.iter().map(|s| s.interest).sum();
+        let tax: i64 = done.iter().map(|s| s.tax).sum();
+        Ok(format!("ekonomie: {}, odsetki: {interest} TK, podatek: {tax} TK", done.len()))
+    }
+}
+
+fn announce(s: &Summary) -> CreateEmbed {
+    CreateEmbed::new()
+        .title("🏛️ Odsetki i podatek od majątku")
+        .description(format!("Ekonomia `{}`", s.eco))
+        .field("📈 Odsetki", format!("**{} TK** dla {} graczy", s.interest, s.interest_to), true)
+        .field("🧾 Podatek", format!("**{} TK** od {} graczy", s.tax, s.taxed), true)
+        .field("🏛️ Skarbiec", format!("{} TK", s.treasury), true)
+        .color(0xa855f7)
+        .timestamp(clock::now())
+}
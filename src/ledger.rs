@@ -0,0 +1,278 @@
+//! Księga TK — jedyne API do zmiany `users.balance` (portfel), `users.bank` (konto bankowe)
+//! i `treasury.balance` (skarbiec ekonomii).
+//!
+//! Każdy ruch to wpis `source → sink` zapisany w tej samej transakcji co zmiana salda,
+//! więc saldo gracza zawsze da się odtworzyć z sumy wpisów (`sink` minus `source`).
+//! Konta systemowe (`sys:work`, `sys:shop`, …) to emisja/spalanie TK — ich saldo nie jest trzymane.
+
+use std::fmt;
+
+use anyhow::{bail, Result};
+use sqlx::PgConnection;
+
+use crate::economy::Economy;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Account {
+    /// Portfel gracza (`users.balance`).
+    User(u64),
+    /// Konto bankowe gracza (`users.bank`) — poza zasięgiem `/rob`, nigdy pod kreską.
+    Bank(u64),
+    /// Skarbiec ekonomii (`treasury.balance`) — trafia tu podatek od majątku; nigdy pod kreską.
+    Treasury,
+    /// Konto systemowe — źródło nagród albo ujście kar/zakupów.
+    System(&'static str),
+}
+
+impl fmt::Display for Account {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Account::User(id) => write!(f, "user:{id}"),
+            Account::Bank(id) => write!(f, "bank:{id}"),
+            Account::Treasury => write!(f, "treasury"),
+            Account::System(name) => write!(f, "sys:{name}"),
+        }
+    }
+}
+
+/// Czy ruch może zejść poniżej zera na koncie gracza.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Overdraft {
+    Forbid,
+    Allow,
+}
+
+/// Opis wpisu: po co, z jakiej komendy, z której interakcji.
+#[derive(Debug, Clone, Copy)]
+pub struct Entry<'a> {
+    pub reason: &'a str,
+    pub command: &'static str,
+    pub interaction_id: Option<u64>,
+}
+
+/// Wynik zaksięgowanego ruchu (salda tylko dla kont graczy i skarbca).
+#[derive(Debug, Clone, Copy)]
+pub struct Posted {
+    pub source_balance: Option<i64>,
+    pub sink_balance: Option<i64>,
+}
+
+/// Brak środków przy `Overdraft::Forbid` — sprawdzane przez [`insufficient`].
+#[derive(Debug, Clone, Copy)]
+pub struct InsufficientFunds {
+    pub balance: i64,
+}
+
+impl fmt::Display for InsufficientFunds {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "niewystarczające środki (saldo: {} TK)", self.balance)
+    }
+}
+
+impl std::error::Error for InsufficientFunds {}
+
+/// Saldo z błędu braku środków (None = inny błąd).
+pub fn insufficient(err: &anyhow::Error) -> Option<i64> {
+    err.downcast_ref::<InsufficientFunds>().map(|e| e.balance)
+}
+
+/// Przenosi `amount` (> 0) z `from` do `to` w ekonomii `eco` i zapisuje wpis w księdze.
+/// Wołać wewnątrz transakcji wywołującego — wpis i saldo commitują się razem.
+pub async fn transfer(
+    conn: &mut PgConnection,
+    eco: Economy,
+    from: Account,
+    to: Account,
+    amount: i64,
+    entry: &Entry<'_>,
+    overdraft: Overdraft,
+) -> Result<Posted> {
+    if amount <= 0 {
+        bail!("ledger: kwota musi być dodatnia (było {amount})");
+    }
+    if from == to {
+        bail!("ledger: źródło i cel to to samo konto ({from})");
+    }
+
+    let source_balance = debit(conn, eco, from, amount, overdraft).await?;
+    let sink_balance = credit(conn, eco, to, amount).await?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO ledger (guild_id, source, sink, amount, reason, command, interaction_id,
+                            source_balance_after, sink_balance_after)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+        "#,
+    )
+    .bind(eco.id())
+    .bind(from.to_string())
+    .bind(to.to_string())
+    .bind(amount)
+    .bind(entry.reason)
+    .bind(entry.command)
+    .bind(entry.interaction_id.map(|v| v as i64))
+    .bind(source_balance)
+    .bind(sink_balance)
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(Posted { source_balance, sink_balance })
+}
+
+/// Zmiana salda gracza o `delta` względem konta systemowego:
+/// `+` = emisja z `system`, `−` = spalenie do `system`, `0` = bez wpisu.
+/// Zwraca saldo gracza po zmianie.
+pub async fn settle(
+    conn: &mut PgConnection,
+    eco: Economy,
+    user: u64,
+    system: &'static str,
+    delta: i64,
+    entry: &Entry<'_>,
+    overdraft: Overdraft,
+) -> Result<i64> {
+    let posted = match delta {
+        d if d > 0 => transfer(conn, eco, Account::System(system), Account::User(user), d, entry, overdraft).await?,
+        d if d < 0 => transfer(conn, eco, Account::User(user), Account::System(system), -d, entry, overdraft).await?,
+        _ => return balance_of(conn, eco, user).await,
+    };
+    Ok(posted.sink_balance.or(posted.source_balance).unwrap_or_default())
+}
+
+/// Saldo gracza (tworzy wiersz, jeśli brak).
+pub async fn balance_of(conn: &mut PgConnection, eco: Economy, user: u64) -> Result<i64> {
+    ensure_user(conn, eco, user).await?;
+    let bal: i64 = sqlx::query_scalar("SELECT balance FROM users WHERE guild_id = $1 AND id = $2")
+        .bind(eco.id())
+        .bind(user as i64)
+        .fetch_one(&mut *conn)
+        .await?;
+    Ok(bal)
+}
+
+/// Tworzy wiersz gracza w ekonomii, jeśli go brak.
+pub async fn ensure_user(conn: &mut PgConnection, eco: Economy, user: u64) -> Result<()> {
+    sqlx::query("INSERT INTO users (guild_id, id) VALUES ($1, $2) ON CONFLICT (guild_id, id) DO NOTHING")
+        .bind(eco.id())
+        .bind(user as i64)
+        .execute(&mut *conn)
+        .await?;
+    Ok(())
+}
+
+/// Gracz i kolumna salda w `users` (None = skarbiec albo konto systemowe).
+fn player_column(acc: Account) -> Option<(u64, &'static str)> {
+    match acc {
+        Account::User(id) => Some((id, "balance")),
+        Account::Bank(id) => Some((id, "bank")),
+        Account::Treasury | Account::System(_) => None,
+    }
+}
+
+async fn debit(
+    conn: &mut PgConnection,
+    eco: Economy,
+    acc: Account,
+    amount: i64,
+    overdraft: Overdraft,
+) -> Result<Option<i64>> {
+    if acc == Account::Treasury {
+        return debit_treasury(conn, eco, amount).await.map(Some);
+    }
+    let Some((id, col)) = player_column(acc) else {
+        return Ok(None);
+    };
+    ensure_user(conn, eco, id).await?;
+
+    let updated: Option<i64> = sqlx::query_scalar(&format!(
+        r#"
+        UPDATE users
+           SET {col} = {col} - $1, updated_at = now()
+         WHERE guild_id = $4 AND id = $2 AND ($3 OR {col} >= $1)
+     RETURNING {col}
+        "#
+    ))
+    .bind(amount)
+    .bind(id as i64)
+    // bank nie schodzi pod kreskę niezależnie od wywołującego
+    .bind(overdraft == Overdraft::Allow && matches!(acc, Account::User(_)))
+    .bind(eco.id())
+    .fetch_optional(&mut *conn)
+    .await?;
+
+    match updated {
+        Some(bal) => Ok(Some(bal)),
+        None => {
+            let balance: i64 = sqlx::query_scalar(&format!("SELECT {col} FROM users WHERE guild_id = $1 AND id = $2"))
+                .bind(eco.id())
+                .bind(id as i64)
+                .fetch_one(&mut *conn)
+                .await?;
+            Err(InsufficientFunds { balance }.into())
+        }
+    }
+}
+
+async fn credit(conn: &mut PgConnection, eco: Economy, acc: Account, amount: i64) -> Result<Option<i64>> {
+    if acc == Account::Treasury {
+        return credit_treasury(conn, eco, amount).await.map(Some);
+    }
+    let Some((id, col)) = player_column(acc) else {
+        return Ok(None);
+    };
+    ensure_user(conn, eco, id).await?;
+
+    let bal: i64 = sqlx::query_scalar(&format!(
+        "UPDATE users SET {col} = {col} + $1, updated_at = now() WHERE guild_id = $3 AND id = $2 RETURNING {col}"
+    ))
+    .bind(amount)
+    .bind(id as i64)
+    .bind(eco.id())
+    .fetch_one(&mut *conn)
+    .await?;
+    Ok(Some(bal))
+}
+
+/// Saldo skarbca ekonomii (0, jeśli jeszcze nic do niego nie wpłynęło).
+pub async fn treasury_of(conn: &mut PgConnection, eco: Economy) -> Result<i64> {
+    let bal: Option<i64> = sqlx::query_scalar("SELECT balance FROM treasury WHERE guild_id = $1")
+        .bind(eco.id())
+        .fetch_optional(&mut *conn)
+        .await?;
+    Ok(bal.unwrap_or(0))
+}
+
+async fn debit_treasury(conn: &mut PgConnection, eco: Economy, amount: i64) -> Result<i64> {
+    let updated: Option<i64> = sqlx::query_scalar(
+        r#"
+        UPDATE treasury
+           SET balance = balance - $1, updated_at = now()
+         WHERE guild_id = $2 AND balance >= $1
+     RETURNING balance
+        "#,
+    )
+    .bind(amount)
+    .bind(eco.id())
+    .fetch_optional(&mut *conn)
+    .await?;
+    match updated {
+        Some(bal) => Ok(bal),
+        None => Err(InsufficientFunds { balance: treasury_of(conn, eco).await? }.into()),
+    }
+}
+
+async fn credit_treasury(conn: &mut PgConnection, eco: Economy, amount: i64) -> Result<i64> {
+    let bal: i64 = sqlx::query_scalar(
+        r#"
+        INSERT INTO treasury (guild_id, balance) VALUES ($1, $2)
+        ON CONFLICT (guild_id) DO UPDATE
+        SET balance = treasury.balance + EXCLUDED.balance, updated_at = now()
+        RETURNING balance
+        "#,
+    )
+    .bind(eco.id())
+    .bind(amount)
+    .fetch_one(&mut *conn)
+    .await?;
+    Ok(bal)
+}
@@ -17,10 +17,26 @@ pub mod engine;
 use dashmap::DashMap;
 use tokio::sync::Semaphore;
 
+pub mod clock;
+mod bank;
 mod commands;
-use crate::commands::{admcontrol, shop_ui};
-use commands::{balance, crime, daily, pay, rob, slut, work, subscribers};
+use crate::commands::registry::CommandRegistry;
+mod cooldowns;
+mod economy;
+mod heists;
+mod inventory;
+mod jail;
+mod jobs;
+mod ledger;
+mod loans;
+mod migrations;
+mod reminders;
+pub mod rng;
+mod scheduler;
+mod skill_tree;
+pub mod sim;
 mod utils;
+mod wealth;
 
 // ----------------------------
 // Entrypoint
@@ -39,6 +55,9 @@ pub async fn run() -> anyhow::Result<()> {
     let token = env::var("DISCORD_TOKEN")?;
     let database_url = env::var("DATABASE_URL")?;
 
+    // tabele balansu (fail-fast przy złym pliku)
+    engine::config::init()?;
+
     // privileged intent do guild_member_update
     let intents = GatewayIntents::non_privileged() | GatewayIntents::GUILD_MEMBERS;
 
@@ -62,33 +81,9 @@ pub async fn run() -> anyhow::Result<()> {
         .connect(&database_url)
         .await?;
 
-    // bootstrap w jednej transakcji
-    {
-        let mut tx = pool.begin().await?;
-        sqlx::query("SELECT 1").execute(&mut *tx).await?; // healthcheck
-
-        sqlx::query(
-            "CREATE TABLE IF NOT EXISTS users (
-                id BIGINT PRIMARY KEY,
-                balance BIGINT NOT NULL DEFAULT 0,
-                last_work TIMESTAMPTZ
-            )",
-        )
-        .execute(&mut *tx)
-        .await?;
-
-        // opcjonalne kolumny w logs
-        sqlx::query("ALTER TABLE IF EXISTS logs ADD COLUMN IF NOT EXISTS target_id BIGINT")
-            .execute(&mut *tx)
-            .await
-            .ok();
-        sqlx::query("ALTER TABLE IF EXISTS logs ADD COLUMN IF NOT EXISTS description TEXT")
-            .execute(&mut *tx)
-            .await
-            .ok();
-
-        tx.commit().await?;
-    }
+    // schemat: wersjonowane migracje (fail-fast przy rozjeździe)
+    migrations::run(&pool).await?;
+    economy::adopt_legacy(&pool).await?;
 
     let db = Arc::new(pool);
 
@@ -108,16 +103,27 @@ pub async fn run() -> anyhow::Result<()> {
         .filter(|&id| id != 0)
         .map(ChannelId::new);
 
+    // --- komendy (walidacja nazw/prefiksów przed połączeniem) ---
+    let registry = commands::init_registry()?;
+
     // --- Discord ---
     let mut client = Client::builder(token, intents)
         .event_handler(Handler {
-            db,
+            db: db.clone(),
+            registry,
             inflight,
             semaphore,
             metrics_channel,
         })
         .await?;
 
+    // --- zadania w tle (dzierżawa w bazie — bezpieczne przy kilku procesach) ---
+    scheduler::start(
+        scheduler::JobCtx { http: client.http.clone(), db },
+        jobs::all(),
+    )
+    .await?;
+
     client.start().await?;
     Ok(())
 }
@@ -127,63 +133,31 @@ pub async fn run() -> anyhow::Result<()> {
 // ----------------------------
 struct Handler {
     db: Arc<PgPool>,
+    registry: &'static CommandRegistry,
     inflight: Arc<DashMap<(u64, String), Instant>>, // (user_id, command)
     semaphore: Arc<Semaphore>,
     metrics_channel: Option<ChannelId>,
 }
 
+impl Handler {
+    /// Koniec wyroku gracza; błąd bazy nie blokuje komendy (tylko log).
+    async fn jailed_until(&self, guild: Option<GuildId>, user: u64) -> Option<chrono::DateTime<Utc>> {
+        match jail::blocked(&self.db, guild, user).await {
+            Ok(release_at) => release_at,
+            Err(e) => {
+                eprintln!("❌ Nie sprawdzono więzienia dla {user}: {e:?}");
+                None
+            }
+        }
+    }
+}
+
 #[async_trait]
 impl EventHandler for Handler {
     async fn ready(&self, ctx: Context, ready: Ready) {
         println!("{} jest online!", ready.user.name);
 
-        let mut commands: Vec<CreateCommand> = Vec::new();
-        commands.push(slut::register());
-
-        {
-            let mut c = builder::CreateCommand::new("work");
-            work::register(&mut c);
-            commands.push(c);
-        }
-        {
-            let c = crime::register();
-            commands.push(c);
-        }
-        {
-            let mut c = builder::CreateCommand::new("daily");
-            daily::register(&mut c);
-            commands.push(c);
-        }
-        {
-            let mut c = builder::CreateCommand::new("rob");
-            rob::register(&mut c);
-            commands.push(c);
-        }
-        {
-            let mut c = builder::CreateCommand::new("balance");
-            balance::register(&mut c);
-            commands.push(c);
-        }
-        {
-            let mut c = builder::CreateCommand::new("pay");
-            pay::register(&mut c);
-            commands.push(c);
-        }
-        {
-            let mut c = builder::CreateCommand::new("admcontrol");
-            admcontrol::register(&mut c);
-            commands.push(c);
-        }
-        {
-            let mut c = builder::CreateCommand::new("shop");
-            shop_ui::register(&mut c);
-            commands.push(c);
-        }
-        {
-            let mut c = builder::CreateCommand::new("subskrypcje");
-            subscribers::register(&mut c);
-            commands.push(c);
-        }
+        let commands = self.registry.definitions();
 
         if let Err(err) = Command::set_global_commands(&ctx.http, commands).await {
             eprintln!("❌ Nie udało się ustawić globalnych komend: {err:?}");
@@ -199,22 +173,25 @@ impl EventHandler for Handler {
                 let id = ic.data.custom_id.as_str();
                 eprintln!("[component] id={}", id);
 
-                if id.starts_with("shop|") || id.starts_with("shopgift|") {
-                    let _ = shop_ui::handle_component(&ctx, &ic, &self.db).await;
-                    return;
-                }
-                if id.starts_with("work:") {
-                    let _ = work::handle_component(&ctx, &ic, &self.db).await;
-                    return;
-                }
-                if id.starts_with("slut:") {
-                    let _ = slut::handle_component(&ctx, &ic, &self.db).await;
-                    return;
-                }
-                if id.starts_with("crime:") {
-                    let _ = crime::handle_component(&ctx, &ic, &self.db).await;
+                if let Some(c) = self.registry.by_component(id) {
+                    if c.economy() && ic.guild_id.is_none() {
+                        let _ = ic
+                            .create_response(&ctx.http, guild_only_response())
+                            .await;
+                        return;
+                    }
+                    if c.earning_component(id) {
+                        if let Some(release_at) = self.jailed_until(ic.guild_id, ic.user.id.get()).await {
+                            let _ = ic.create_response(&ctx.http, jailed_response(release_at)).await;
+                            return;
+                        }
+                    }
+                    if let Err(e) = c.handle_component(&ctx, &ic, &self.db).await {
+                        eprintln!("❌ Błąd komponentu /{} ({}): {:?}", c.name(), id, e);
+                    }
                     return;
                 }
+                eprintln!("⚠️ Nieobsłużony komponent: {}", id);
 
                 let _ = ic
                     .create_response(
@@ -231,14 +208,13 @@ impl EventHandler for Handler {
             Interaction::Modal(mi) => {
                 let id = mi.data.custom_id.as_str();
 
-                if id.starts_with("shop") {
-                    let _ = shop_ui::handle_modal(&ctx, &mi, &self.db).await;
-                    return;
-                }
-                if id.starts_with("crime:") {
-                    let _ = crime::handle_modal(&ctx, &mi, &self.db).await;
+                if let Some(c) = self.registry.by_modal(id) {
+                    if let Err(e) = c.handle_modal(&ctx, &mi, &self.db).await {
+                        eprintln!("❌ Błąd modala /{} ({}): {:?}", c.name(), id, e);
+                    }
                     return;
                 }
+                eprintln!("⚠️ Nieobsłużony modal: {}", id);
 
                 let _ = mi
                     .create_response(
@@ -252,7 +228,23 @@ impl EventHandler for Handler {
 
             Interaction::Command(cmd) => {
                 let user_id = cmd.user.id.get();
-                let name = cmd.data.name.as_str();
+                let Some(command) = self.registry.by_name(cmd.data.name.as_str()) else {
+                    eprintln!("⚠️ Nieznana komenda: /{}", cmd.data.name);
+                    return;
+                };
+                let name = command.name();
+
+                if command.economy() && cmd.guild_id.is_none() {
+                    let _ = cmd.create_response(&ctx.http, guild_only_response()).await;
+                    return;
+                }
+                let sub = cmd.data.options.first().map(|o| o.name.as_str());
+                if command.earning(sub) {
+                    if let Some(release_at) = self.jailed_until(cmd.guild_id, user_id).await {
+                        let _ = cmd.create_response(&ctx.http, jailed_response(release_at)).await;
+                        return;
+                    }
+                }
 
                 let key = (user_id, name.to_owned());
                 use dashmap::mapref::entry::Entry;
@@ -298,19 +290,7 @@ impl EventHandler for Handler {
                 };
 
                 let start_total = std::time::Instant::now();
-                let result = match name {
-                    "work" => work::run(&ctx, &cmd, &self.db).await,
-                    "crime" => crime::run(&ctx, &cmd, &self.db).await,
-                    "slut" => slut::run(&ctx, &cmd, &self.db).await,
-                    "daily" => daily::run(&ctx, &cmd, &self.db).await,
-                    "rob" => rob::run(&ctx, &cmd, &self.db).await,
-                    "balance" => balance::run(&ctx, &cmd, &self.db).await,
-                    "pay" => pay::run(&ctx, &cmd, &self.db).await,
-                    "admcontrol" => admcontrol::run(&ctx, &cmd, &self.db).await,
-                    "shop" | "tigrisshop" => shop_ui::run(&ctx, &cmd, &self.db).await,
-                    "subskrypcje" => subscribers::run(&ctx, &cmd, &self.db).await,
-                    _ => Ok(()),
-                };
+                let result = command.run(&ctx, &cmd, &self.db).await;
 
                 drop(guard);
 
@@ -465,6 +445,27 @@ async fn find_role_remover(
     None
 }
 
+// odpowiedź dla komend ekonomii wywołanych w DM
+fn guild_only_response() -> CreateInteractionResponse {
+    CreateInteractionResponse::Message(
+        CreateInteractionResponseMessage::new()
+            .ephemeral(true)
+            .content("❌ Ta komenda działa tylko na serwerze — każdy serwer ma własną ekonomię."),
+    )
+}
+
+// odpowiedź dla komend zarabiających, gdy gracz siedzi w więzieniu
+fn jailed_response(release_at: chrono::DateTime<Utc>) -> CreateInteractionResponse {
+    CreateInteractionResponse::Message(
+        CreateInteractionResponseMessage::new()
+            .ephemeral(true)
+            .content(format!(
+                "🔒 Siedzisz w więzieniu do <t:{0}:T> (<t:{0}:R>). Kaucja albo ucieczka: `/wiezienie`.",
+                release_at.timestamp()
+            )),
+    )
+}
+
 // guard usuwający wpis z inflight
 struct InFlightGuard {
     key: (u64, String),
@@ -478,6 +479,7 @@ impl Drop for InFlightGuard {
 
 // ===== Helpers: metryki =====
 
+#[allow(clippy::too_many_arguments)]
 async fn log_command_metric_http(
     http: Arc<Http>,
     channel_id: ChannelId,
@@ -516,10 +518,3 @@ async fn wipe_all_guild_commands(ctx: &Context) {
         let _ = gid.set_commands(&ctx.http, Vec::<builder::CreateCommand>::new()).await;
     }
 }
-
-async fn admin_display(http: &Http, uid: UserId) -> String {
-    match http.get_user(uid).await {
-        Ok(u) => format!("{} (`{}`)", u.tag(), uid.get()), // np. Tigris#1234 (`9876543210`)
-        Err(_) => format!("`{}`", uid.get()),
-    }
-}
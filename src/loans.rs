@@ -0,0 +1,446 @@
+//! Pożyczki — z banku (`sys:loans`) albo od innego gracza (`/pozyczka`). Pożyczka od gracza
+//! wypłaca się przelewem `/pay`, więc wlicza się w jego dzienny limit i płaci opłatę.
+//!
+//! Odsetki są jednorazowe: przy wypłacie do długu (`owed`) dolicza się `interest`. Spłata idzie
+//! z portfela dłużnika do wierzyciela — ręcznie (`/pozyczka splac`) albo potrąceniem części
+//! każdego zarobku z /work, /slut i /daily ([`garnish`], w transakcji komendy). Po terminie
+//! zadanie `loans.default` dolicza karę i przestawia pożyczkę na `defaulted`; taki dłużnik nie
+//! może przelewać (`/pay`) ani kupować w sklepach, a potrącenia rosną do `default_garnish_pct`.
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use sqlx::{PgConnection, PgExecutor, PgPool};
+
+use crate::clock;
+use crate::economy::Economy;
+use crate::engine::config;
+use crate::ledger::{self, Account, Entry, Overdraft};
+
+/// Konto systemowe banku pożyczkowego.
+const LOAN_ACCOUNT: &str = "loans";
+
+const COLUMNS: &str = "id, borrower, lender, principal, interest, penalty, owed, status, due_at, created_at";
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct Loan {
+    pub id: i64,
+    pub borrower: i64,
+    /// `None` = bank.
+    pub lender: Option<i64>,
+    pub principal: i64,
+    pub interest: i64,
+    pub penalty: i64,
+    /// Zostało do spłaty.
+    pub owed: i64,
+    pub status: String,
+    pub due_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Loan {
+    pub fn defaulted(&self) -> bool {
+        self.status == "defaulted"
+    }
+
+    /// Dokąd idą spłaty.
+    fn creditor(&self) -> Account {
+        match self.lender {
+            Some(id) => Account::User(id as u64),
+            None => Account::System(LOAN_ACCOUNT),
+        }
+    }
+}
+
+/// Odsetki od kapitału przy stopie `rate` (zaokrąglone w górę).
+pub fn interest_for(principal: i64, rate: f64) -> i64 {
+    (principal as f64 * rate).ceil() as i64
+}
+
+/// Niespłacona pożyczka gracza (w terminie albo po nim).
+pub async fn open(db: impl PgExecutor<'_>, eco: Economy, borrower: u64) -> Result<Option<Loan>> {
+    let sql = format!("SELECT {COLUMNS} FROM loans WHERE guild_id = $1 AND borrower = $2 AND status <> 'repaid'");
+    Ok(sqlx::query_as(&sql).bind(eco.id()).bind(borrower as i64).fetch_optional(db).await?)
+}
+
+async fn open_locked(conn: &mut PgConnection, eco: Economy, borrower: u64) -> Result<Option<Loan>> {
+    let sql = format!(
+        "SELECT {COLUMNS} FROM loans WHERE guild_id = $1 AND borrower = $2 AND status <> 'repaid' FOR UPDATE"
+    );
+    Ok(sqlx::query_as(&sql).bind(eco.id()).bind(borrower as i64).fetch_optional(&mut *conn).await?)
+}
+
+/// Niespłacone pożyczki, których gracz udzielił innym.
+pub async fn lent(db: impl PgExecutor<'_>, eco: Economy, lender: u64) -> Result<Vec<Loan>> {
+    let sql = format!(
+        "SELECT {COLUMNS} FROM loans WHERE guild_id = $1 AND lender = $2 AND status <> 'repaid' ORDER BY due_at"
+    );
+    Ok(sqlx::query_as(&sql).bind(eco.id()).bind(lender as i64).fetch_all(db).await?)
+}
+
+/// Dług po terminie (blokuje /pay i zakupy) — `Some(owed)`.
+pub async fn in_default(db: impl PgExecutor<'_>, eco: Economy, user: u64) -> Result<Option<i64>> {
+    Ok(sqlx::query_scalar("SELECT owed FROM loans WHERE guild_id = $1 AND borrower = $2 AND status = 'defaulted'")
+        .bind(eco.id())
+        .bind(user as i64)
+        .fetch_optional(db)
+        .await?)
+}
+
+/// Pożyczka z banku.
+pub enum Borrow {
+    /// Kwota poza widełkami banku.
+    OutOfRange { min: i64, max: i64 },
+    /// Dłużnik ma już niespłaconą pożyczkę.
+    AlreadyOwing { owed: i64 },
+    Done { loan: Loan, wallet: i64 },
+}
+
+/// Pożyczka od gracza — widełki banku jej nie dotyczą.
+pub enum Lend {
+    /// Dłużnik ma już niespłaconą pożyczkę.
+    AlreadyOwing { owed: i64 },
+    Done { loan: Loan },
+}
+
+/// Blokuje wiersz dłużnika (równoległe pożyczki tej samej osoby idą po kolei) i zwraca
+/// niespłacony dług, jeśli już jakiś ma.
+async fn lock_borrower(conn: &mut PgConnection, eco: Economy, borrower: u64) -> Result<Option<i64>> {
+    ledger::ensure_user(&mut *conn, eco, borrower).await?;
+    sqlx::query("SELECT 1 FROM users WHERE guild_id = $1 AND id = $2 FOR UPDATE")
+        .bind(eco.id())
+        .bind(borrower as i64)
+        .execute(&mut *conn)
+        .await?;
+    Ok(open_locked(conn, eco, borrower).await?.map(|loan| loan.owed))
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn insert(
+    conn: &mut PgConnection,
+    eco: Economy,
+    borrower: u64,
+    lender: Option<u64>,
+    amount: i64,
+    interest: i64,
+    days: i64,
+    interaction_id: Option<u64>,
+) -> Result<Loan> {
+    let sql = format!(
+        r#"INSERT INTO loans (guild_id, borrower, lender, principal, interest, owed, due_at, interaction_id)
+           VALUES ($1, $2, $3, $4, $5, $4 + $5, $6, $7)
+        RETURNING {COLUMNS}"#
+    );
+    Ok(sqlx::query_as(&sql)
+        .bind(eco.id())
+        .bind(borrower as i64)
+        .bind(lender.map(|id| id as i64))
+        .bind(amount)
+        .bind(interest)
+        .bind(clock::now() + Duration::days(days))
+        .bind(interaction_id.map(|id| id as i64))
+        .fetch_one(&mut *conn)
+        .await?)
+}
+
+/// Pożyczka z banku: `amount` TK z odsetkami `interest`, płatna za `days` dni.
+pub async fn borrow(
+    db: &PgPool,
+    eco: Economy,
+    borrower: u64,
+    amount: i64,
+    interest: i64,
+    days: i64,
+    interaction_id: Option<u64>,
+) -> Result<Borrow> {
+    let l = &config::get().loans;
+    if !(l.bank_min..=l.bank_max).contains(&amount) {
+        return Ok(Borrow::OutOfRange { min: l.bank_min, max: l.bank_max });
+    }
+
+    let mut tx = db.begin().await?;
+    if let Some(owed) = lock_borrower(&mut tx, eco, borrower).await? {
+        return Ok(Borrow::AlreadyOwing { owed });
+    }
+    let entry = Entry { reason: "wypłata pożyczki", command: "pozyczka", interaction_id };
+    let posted = ledger::transfer(
+        &mut tx,
+        eco,
+        Account::System(LOAN_ACCOUNT),
+        Account::User(borrower),
+        amount,
+        &entry,
+        Overdraft::Forbid,
+    )
+    .await?;
+    let loan = insert(&mut tx, eco, borrower, None, amount, interest, days, interaction_id).await?;
+    tx.commit().await?;
+
+    Ok(Borrow::Done { loan, wallet: posted.sink_balance.unwrap_or_default() })
+}
+
+/// Pożyczka od gracza w transakcji wywołującego. Kapitał wypłaca wcześniej wołający zwykłym
+/// przelewem `/pay` (limit dzienny, opłata, blokada dłużnika), więc odmowa tutaj cofa go razem
+/// z transakcją.
+#[allow(clippy::too_many_arguments)]
+pub async fn lend_in(
+    conn: &mut PgConnection,
+    eco: Economy,
+    lender: u64,
+    borrower: u64,
+    amount: i64,
+    interest: i64,
+    days: i64,
+    interaction_id: Option<u64>,
+) -> Result<Lend> {
+    if let Some(owed) = lock_borrower(conn, eco, borrower).await? {
+        return Ok(Lend::AlreadyOwing { owed });
+    }
+    let loan = insert(conn, eco, borrower, Some(lender), amount, interest, days, interaction_id).await?;
+    Ok(Lend::Done { loan })
+}
+
+// =======================
+// Oferty od graczy
+// =======================
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct Offer {
+    pub id: i64,
+    pub lender: i64,
+    pub borrower: i64,
+    pub amount: i64,
+    pub interest: i64,
+    pub days: i32,
+    pub status: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+const OFFER_COLUMNS: &str = "id, lender, borrower, amount, interest, days, status, expires_at";
+
+/// Nowa oferta ważna `config::LoanTables::offer_mins`.
+#[allow(clippy::too_many_arguments)]
+pub async fn create_offer(
+    db: &PgPool,
+    eco: Economy,
+    lender: u64,
+    borrower: u64,
+    amount: i64,
+    interest: i64,
+    days: i64,
+    interaction_id: u64,
+) -> Result<Offer> {
+    let sql = format!(
+        r#"INSERT INTO loan_offers (guild_id, lender, borrower, amount, interest, days, expires_at, interaction_id)
+           VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        RETURNING {OFFER_COLUMNS}"#
+    );
+    Ok(sqlx::query_as(&sql)
+        .bind(eco.id())
+        .bind(lender as i64)
+        .bind(borrower as i64)
+        .bind(amount)
+        .bind(interest)
+        .bind(days as i32)
+        .bind(clock::now() + Duration::minutes(config::get().loans.offer_mins))
+        .bind(interaction_id as i64)
+        .fetch_one(db)
+        .await?)
+}
+
+/// Oferta zablokowana `FOR UPDATE` — drugi klik czeka na pierwszy i widzi już nowy status.
+pub async fn lock_offer(conn: &mut PgConnection, eco: Economy, id: i64) -> Result<Option<Offer>> {
+    let sql = format!("SELECT {OFFER_COLUMNS} FROM loan_offers WHERE guild_id = $1 AND id = $2 FOR UPDATE");
+    Ok(sqlx::query_as(&sql).bind(eco.id()).bind(id).fetch_optional(&mut *conn).await?)
+}
+
+/// Zamyka ofertę; `loan` — pożyczka, która z niej powstała.
+pub async fn close_offer(conn: &mut PgConnection, id: i64, status: &str, loan: Option<i64>) -> Result<()> {
+    sqlx::query("UPDATE loan_offers SET status = $2, loan_id = $3, resolved_at = $4 WHERE id = $1")
+        .bind(id)
+        .bind(status)
+        .bind(loan)
+        .bind(clock::now())
+        .execute(&mut *conn)
+        .await?;
+    Ok(())
+}
+
+/// Spłata `amount` z portfela dłużnika do wierzyciela; dług spada do zera = pożyczka zamknięta.
+async fn pay_down(conn: &mut PgConnection, eco: Economy, loan: &Loan, amount: i64, entry: &Entry<'_>) -> Result<(Loan, i64)> {
+    let posted =
+        ledger::transfer(conn, eco, Account::User(loan.borrower as u64), loan.creditor(), amount, entry, Overdraft::Forbid)
+            .await?;
+    let sql = format!(
+        r#"UPDATE loans
+              SET owed = owed - $2,
+                  status = CASE WHEN owed - $2 = 0 THEN 'repaid' ELSE status END,
+                  closed_at = CASE WHEN owed - $2 = 0 THEN $3 ELSE closed_at END
+            WHERE id = $1
+        RETURNING {COLUMNS}"#
+    );
+    let loan: Loan = sqlx::query_as(&sql)
+        .bind(loan.id)
+        .bind(amount)
+        .bind(clock::now())
+        .fetch_one(&mut *conn)
+        .await?;
+    Ok((loan, posted.source_balance.unwrap_or_default()))
+}
+
+pub enum Repay {
+    NoLoan,
+    /// Pusty portfel.
+    Nothing,
+    TooPoor { wallet: i64 },
+    Done { amount: i64, loan: Loan, wallet: i64 },
+}
+
+/// Ręczna spłata z portfela; `amount = None` spłaca tyle, ile się da (nadwyżka ponad dług jest ucinana).
+pub async fn repay(db: &PgPool, eco: Economy, user: u64, amount: Option<i64>, interaction_id: Option<u64>) -> Result<Repay> {
+    let mut tx = db.begin().await?;
+    let Some(loan) = open_locked(&mut tx, eco, user).await? else {
+        return Ok(Repay::NoLoan);
+    };
+    let wallet = ledger::balance_of(&mut tx, eco, user).await?;
+    let amount = match amount {
+        Some(a) if a.min(loan.owed) > wallet => return Ok(Repay::TooPoor { wallet }),
+        Some(a) => a.min(loan.owed),
+        None if wallet <= 0 => return Ok(Repay::Nothing),
+        None => wallet.min(loan.owed),
+    };
+
+    let entry = Entry { reason: "spłata pożyczki", command: "pozyczka", interaction_id };
+    let (loan, wallet) = pay_down(&mut tx, eco, &loan, amount, &entry).await?;
+    tx.commit().await?;
+    Ok(Repay::Done { amount, loan, wallet })
+}
+
+/// Potrącenie z zarobku.
+#[derive(Debug, Clone, Copy)]
+pub struct Garnished {
+    pub amount: i64,
+    /// Zostało do spłaty (0 = pożyczka zamknięta).
+    pub owed: i64,
+    /// Portfel po potrąceniu.
+    pub wallet: i64,
+}
+
+/// Potrąca część zarobku `income` na spłatę pożyczki — w transakcji komendy, zaraz po wypłacie
+/// (`wallet` = portfel po wypłacie). `None`, jeśli nie ma długu albo nie ma z czego potrącić.
+pub async fn garnish(
+    conn: &mut PgConnection,
+    eco: Economy,
+    user: u64,
+    income: i64,
+    wallet: i64,
+    entry: &Entry<'_>,
+) -> Result<Option<Garnished>> {
+    if income <= 0 || wallet <= 0 {
+        return Ok(None);
+    }
+    let Some(loan) = open_locked(conn, eco, user).await? else {
+        return Ok(None);
+    };
+    let cut = garnish_cut(income, wallet, loan.owed, loan.defaulted());
+    if cut <= 0 {
+        return Ok(None);
+    }
+
+    let (loan, wallet) = pay_down(conn, eco, &loan, cut, entry).await?;
+    Ok(Some(Garnished { amount: cut, owed: loan.owed, wallet }))
+}
+
+/// Ile potrącić z zarobku: `garnish_pct` (po terminie `default_garnish_pct`) w górę,
+/// ale nie więcej niż dług i portfel. 0 = nic.
+pub fn garnish_cut(income: i64, wallet: i64, owed: i64, defaulted: bool) -> i64 {
+    let l = &config::get().loans;
+    let pct = if defaulted { l.default_garnish_pct } else { l.garnish_pct };
+    ((income.max(0) as f64 * pct).ceil() as i64).min(owed).min(wallet).max(0)
+}
+
+/// Kara za przekroczenie terminu: `rate` od pozostałego długu, w górę.
+pub fn default_penalty(owed: i64, rate: f64) -> i64 {
+    (owed.max(0) as f64 * rate).ceil() as i64
+}
+
+/// Pożyczka, która właśnie przekroczyła termin.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct Defaulted {
+    pub guild_id: i64,
+    pub borrower: i64,
+    pub lender: Option<i64>,
+    pub penalty: i64,
+    pub owed: i64,
+}
+
+/// Przeterminowane pożyczki → `defaulted` z karą [`default_penalty`] od pozostałego długu.
+pub async fn default_overdue(db: &PgPool) -> Result<Vec<Defaulted>> {
+    let rate = config::get().loans.default_penalty;
+    let mut tx = db.begin().await?;
+    let overdue: Vec<(i64, i64)> = sqlx::query_as(
+        "SELECT id, owed FROM loans WHERE status = 'active' AND due_at <= $1 ORDER BY id FOR UPDATE",
+    )
+    .bind(clock::now())
+    .fetch_all(&mut *tx)
+    .await?;
+
+    let mut out = Vec::with_capacity(overdue.len());
+    for (id, owed) in overdue {
+        let penalty = default_penalty(owed, rate);
+        let row: Defaulted = sqlx::query_as(
+            r#"UPDATE loans
+                  SET status = 'defaulted', penalty = $2, owed = owed + $2
+                WHERE id = $1
+            RETURNING guild_id, borrower, lender, penalty, owed"#,
+        )
+        .bind(id)
+        .bind(penalty)
+        .fetch_one(&mut *tx)
+        .await?;
+        out.push(row);
+    }
+    tx.commit().await?;
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn garnish_takes_share_of_income_rounded_up() {
+        let l = &config::get().loans;
+        let share = (1_001.0 * l.garnish_pct).ceil() as i64;
+        assert_eq!(garnish_cut(1_001, 10_000, 10_000, false), share);
+        let share = (1_001.0 * l.default_garnish_pct).ceil() as i64;
+        assert_eq!(garnish_cut(1_001, 10_000, 10_000, true), share, "po terminie potrącenie rośnie");
+    }
+
+    #[test]
+    fn garnish_stops_at_remaining_debt() {
+        // dług mniejszy niż potrącana część — bierzemy tylko resztę długu
+        assert_eq!(garnish_cut(1_000, 10_000, 7, false), 7);
+        assert_eq!(garnish_cut(1_000, 10_000, 0, true), 0);
+    }
+
+    #[test]
+    fn garnish_never_exceeds_wallet() {
+        assert_eq!(garnish_cut(1_000, 3, 10_000, false), 3);
+        assert_eq!(garnish_cut(1_000, 0, 10_000, false), 0);
+    }
+
+    #[test]
+    fn zero_payout_garnishes_nothing() {
+        assert_eq!(garnish_cut(0, 10_000, 10_000, false), 0);
+        assert_eq!(garnish_cut(0, 10_000, 10_000, true), 0);
+        assert_eq!(garnish_cut(-50, 10_000, 10_000, false), 0);
+    }
+
+    #[test]
+    fn default_penalty_rounds_up() {
+        assert_eq!(default_penalty(1_000, 0.2), 200);
+        assert_eq!(default_penalty(1, 0.2), 1);
+        assert_eq!(default_penalty(0, 0.2), 0);
+        assert_eq!(default_penalty(1_000, 0.0), 0);
+    }
+}
@@ -0,0 +1,179 @@
+//! Wersjonowane migracje schematu.
+//!
+//! Jedyne miejsce, które tworzy/zmienia tabele. Migracje są numerowane,
+//! każda ma sumę kontrolną (SHA-256 treści SQL) zapisaną w `schema_migrations`.
+//! Uruchamiane raz przy starcie (przed `Client::builder`); jeśli zastosowana
+//! migracja różni się od tej w kodzie albo baza zna wersję, której kod nie zna —
+//! start kończy się błędem zamiast cicho jechać na rozjechanym schemacie.
+
+use std::time::Instant;
+
+use anyhow::{bail, Context as _, Result};
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+
+/// Klucz `pg_advisory_lock` — serializuje migracje między procesami (dev/prod na jednej bazie).
+const MIGRATION_LOCK_KEY: i64 = 0x5449_4752_4953_0001; // "TIGRIS" + 1
+
+pub struct Migration {
+    pub version: i64,
+    pub name: &'static str,
+    pub sql: &'static str,
+}
+
+impl Migration {
+    pub fn checksum(&self) -> String {
+        hex::encode(Sha256::digest(self.sql.as_bytes()))
+    }
+}
+
+macro_rules! migration {
+    ($version:expr, $name:literal) => {
+        Migration {
+            version: $version,
+            name: $name,
+            sql: include_str!(concat!("../migrations/", $name, ".sql")),
+        }
+    };
+}
+
+/// Lista migracji — tylko dopisujemy na końcu, nigdy nie edytujemy już wdrożonych.
+pub const MIGRATIONS: &[Migration] = &[
+    migration!(1, "0001_baseline"),
+    migration!(2, "0002_ledger"),
+    migration!(3, "0003_guild_economies"),
+    migration!(4, "0004_cooldowns"),
+    migration!(5, "0005_scheduled_jobs"),
+    migration!(6, "0006_reminders"),
+    migration!(7, "0007_crime_sessions"),
+    migration!(8, "0008_rolls"),
+    migration!(9, "0009_heat_decay"),
+    migration!(10, "0010_crime_minigame"),
+    migration!(11, "0011_crime_crews"),
+    migration!(12, "0012_jail"),
+    migration!(13, "0013_crime_inventory"),
+    migration!(14, "0014_crime_targets"),
+    migration!(15, "0015_crime_perks"),
+    migration!(16, "0016_crime_heists"),
+    migration!(17, "0017_bank"),
+    migration!(18, "0018_wealth"),
+    migration!(19, "0019_loans"),
+    migration!(20, "0020_pay_requests"),
+    migration!(21, "0021_pay_confirmations"),
+    migration!(22, "0022_item_offers"),
+    migration!(23, "0023_loan_offers"),
+];
+
+#[derive(sqlx::FromRow)]
+struct AppliedRow {
+    version: i64,
+    name: String,
+    checksum: String,
+}
+
+/// Uruchamia wszystkie brakujące migracje. Błąd = rozjazd schematu, bot nie startuje.
+pub async fn run(db: &PgPool) -> Result<()> {
+    validate_list()?;
+
+    // advisory lock trzymamy na jednym połączeniu przez cały przebieg
+    let mut conn = db.acquire().await?;
+    sqlx::query("SELECT pg_advisory_lock($1)")
+        .bind(MIGRATION_LOCK_KEY)
+        .execute(&mut *conn)
+        .await?;
+
+    let result = apply_pending(&mut conn).await;
+
+    let _ = sqlx::query("SELECT pg_advisory_unlock($1)")
+        .bind(MIGRATION_LOCK_KEY)
+        .execute(&mut *conn)
+        .await;
+
+    result
+}
+
+async fn apply_pending(conn: &mut sqlx::PgConnection) -> Result<()> {
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS schema_migrations (
+            version      BIGINT PRIMARY KEY,
+            name         TEXT   NOT NULL,
+            checksum     TEXT   NOT NULL,
+            execution_ms BIGINT NOT NULL,
+            applied_at   TIMESTAMPTZ NOT NULL DEFAULT now()
+        )
+        "#,
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    let applied: Vec<AppliedRow> = sqlx::query_as(
+        "SELECT version, name, checksum FROM schema_migrations ORDER BY version",
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+
+    // 1) drift: wszystko, co już jest w bazie, musi zgadzać się z kodem
+    for row in &applied {
+        let Some(m) = MIGRATIONS.iter().find(|m| m.version == row.version) else {
+            bail!(
+                "Baza ma migrację {} ({}), której ta wersja bota nie zna — downgrade albo obcy build?",
+                row.version,
+                row.name
+            );
+        };
+        let expected = m.checksum();
+        if row.checksum != expected {
+            bail!(
+                "Migracja {} ({}) została zmieniona po wdrożeniu: checksum w bazie {} ≠ w kodzie {}",
+                m.version,
+                m.name,
+                row.checksum,
+                expected
+            );
+        }
+    }
+
+    // 2) brakujące — każda w osobnej transakcji
+    for m in MIGRATIONS.iter().filter(|m| !applied.iter().any(|a| a.version == m.version)) {
+        let started = Instant::now();
+        let mut tx = sqlx::Connection::begin(&mut *conn).await?;
+
+        sqlx::raw_sql(m.sql)
+            .execute(&mut *tx)
+            .await
+            .with_context(|| format!("Migracja {} ({}) nie powiodła się", m.version, m.name))?;
+
+        sqlx::query(
+            "INSERT INTO schema_migrations (version, name, checksum, execution_ms) VALUES ($1, $2, $3, $4)",
+        )
+        .bind(m.version)
+        .bind(m.name)
+        .bind(m.checksum())
+        .bind(started.elapsed().as_millis() as i64)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        tracing::info!(version = m.version, name = m.name, "migracja zastosowana");
+    }
+
+    Ok(())
+}
+
+/// Lista w kodzie musi być ściśle rosnąca i bez dziur (1, 2, 3, …).
+fn validate_list() -> Result<()> {
+    for (i, m) in MIGRATIONS.iter().enumerate() {
+        let expected = i as i64 + 1;
+        if m.version != expected {
+            bail!(
+                "Lista migracji jest niespójna: na pozycji {} jest wersja {} ({}), oczekiwano {}",
+                i,
+                m.version,
+                m.name,
+                expected
+            );
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,269 @@
+//! Przypomnienia „cooldown zakończony”.
+//!
+//! Komenda po starcie cooldownu zapisuje przypomnienie (`schedule`), a zadanie
+//! harmonogramu (`jobs::reminders`) doręcza zaległe — DM-em albo pingiem na kanale,
+//! w zależności od `reminder_prefs` gracza. Stan siedzi w bazie, więc restart bota
+//! niczego nie gubi (w przeciwieństwie do tokenów interakcji, ważnych 15 minut).
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use serenity::all::{ChannelId, CreateEmbed, CreateEmbedFooter, CreateMessage, GuildId, Http, Mentionable, UserId};
+use sqlx::{PgExecutor, PgPool};
+
+use crate::clock;
+
+/// Ile razy próbujemy doręczyć, zanim przypomnienie przepadnie.
+const MAX_ATTEMPTS: i32 = 3;
+/// Ile przypomnień na jedno uruchomienie zadania.
+const BATCH: i64 = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    Dm,
+    Channel,
+    Off,
+}
+
+impl Mode {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Mode::Dm => "dm",
+            Mode::Channel => "channel",
+            Mode::Off => "off",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "dm" => Some(Mode::Dm),
+            "channel" => Some(Mode::Channel),
+            "off" => Some(Mode::Off),
+            _ => None,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Mode::Dm => "📩 wiadomość prywatna",
+            Mode::Channel => "📢 ping na kanale",
+            Mode::Off => "🔕 wyłączone",
+        }
+    }
+}
+
+/// Tryb gracza (domyślnie DM).
+pub async fn mode_of(db: impl PgExecutor<'_>, user: u64) -> Result<Mode> {
+    let mode: Option<String> = sqlx::query_scalar("SELECT mode FROM reminder_prefs WHERE user_id = $1")
+        .bind(user as i64)
+        .fetch_optional(db)
+        .await?;
+    Ok(mode.as_deref().and_then(Mode::parse).unwrap_or(Mode::Dm))
+}
+
+/// Zmienia tryb; `off` kasuje też oczekujące przypomnienia.
+pub async fn set_mode(db: &PgPool, user: u64, mode: Mode) -> Result<()> {
+    let mut tx = db.begin().await?;
+    sqlx::query(
+        r#"
+        INSERT INTO reminder_prefs (user_id, mode) VALUES ($1, $2)
+        ON CONFLICT (user_id) DO UPDATE SET mode = EXCLUDED.mode, updated_at = now()
+        "#,
+    )
+    .bind(user as i64)
+    .bind(mode.as_str())
+    .execute(&mut *tx)
+    .await?;
+
+    if mode == Mode::Off {
+        sqlx::query("DELETE FROM reminders WHERE user_id = $1")
+            .bind(user as i64)
+            .execute(&mut *tx)
+            .await?;
+    }
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Zapisuje (albo przesuwa) przypomnienie o końcu cooldownu `key`.
+/// Nic nie robi, gdy gracz ma przypomnienia wyłączone.
+pub async fn schedule(
+    db: &PgPool,
+    guild: GuildId,
+    channel: ChannelId,
+    user: u64,
+    key: &str,
+    command: &str,
+    due_at: DateTime<Utc>,
+) -> Result<()> {
+    if mode_of(db, user).await? == Mode::Off {
+        return Ok(());
+    }
+
+    sqlx::query(
+        r#"
+        INSERT INTO reminders (guild_id, user_id, key, channel_id, command, due_at)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        ON CONFLICT (guild_id, user_id, key) DO UPDATE
+        SET channel_id = EXCLUDED.channel_id, command = EXCLUDED.command,
+            due_at = EXCLUDED.due_at, attempts = 0, last_error = NULL
+        "#,
+    )
+    .bind(guild.get() as i64)
+    .bind(user as i64)
+    .bind(key)
+    .bind(channel.get() as i64)
+    .bind(command)
+    .bind(due_at)
+    .execute(db)
+    .await?;
+    Ok(())
+}
+
+/// Oczekujące przypomnienia gracza: (komenda, termin).
+pub async fn pending(db: &PgPool, user: u64) -> Result<Vec<(String, DateTime<Utc>)>> {
+    let rows = sqlx::query_as("SELECT command, due_at FROM reminders WHERE user_id = $1 ORDER BY due_at")
+        .bind(user as i64)
+        .fetch_all(db)
+        .await?;
+    Ok(rows)
+}
+
+/// Kasuje oczekujące przypomnienia gracza. Zwraca ich liczbę.
+pub async fn clear(db: &PgPool, user: u64) -> Result<u64> {
+    let res = sqlx::query("DELETE FROM reminders WHERE user_id = $1")
+        .bind(user as i64)
+        .execute(db)
+        .await?;
+    Ok(res.rows_affected())
+}
+
+/// Kasuje przypomnienia o podanych cooldownach (np. po ich resecie przez admina).
+pub async fn cancel(db: &PgPool, user: u64, keys: &[String]) -> Result<u64> {
+    let res = sqlx::query("DELETE FROM reminders WHERE user_id = $1 AND key = ANY($2)")
+        .bind(user as i64)
+        .bind(keys)
+        .execute(db)
+        .await?;
+    Ok(res.rows_affected())
+}
+
+#[derive(sqlx::FromRow)]
+struct DueRow {
+    guild_id: i64,
+    user_id: i64,
+    key: String,
+    channel_id: i64,
+    command: String,
+    due_at: DateTime<Utc>,
+    attempts: i32,
+    mode: Option<String>,
+}
+
+/// Doręcza zaległe przypomnienia. Zwraca (doręczone, nieudane).
+pub async fn deliver_due(http: &Http, db: &PgPool) -> Result<(usize, usize)> {
+    let rows: Vec<DueRow> = sqlx::query_as(
+        r#"
+        SELECT r.guild_id, r.user_id, r.key, r.channel_id, r.command, r.due_at, r.attempts, p.mode
+          FROM reminders r
+          LEFT JOIN reminder_prefs p ON p.user_id = r.user_id
+         WHERE r.due_at <= $1
+         ORDER BY r.due_at
+         LIMIT $2
+        "#,
+    )
+    .bind(clock::now())
+    .bind(BATCH)
+    .fetch_all(db)
+    .await?;
+
+    let (mut sent, mut failed) = (0, 0);
+    for r in rows {
+        let mode = r.mode.as_deref().and_then(Mode::parse).unwrap_or(Mode::Dm);
+        let res = match mode {
+            Mode::Off => Ok(()),
+            Mode::Dm => send_dm(http, &r).await,
+            Mode::Channel => send_channel(http, &r).await,
+        };
+
+        match res {
+            Ok(()) => {
+                if mode != Mode::Off {
+                    sent += 1;
+                }
+                // warunek na due_at: nowy cooldown w międzyczasie przesunął termin — zostaje
+                sqlx::query("DELETE FROM reminders WHERE guild_id = $1 AND user_id = $2 AND key = $3 AND due_at = $4")
+                    .bind(r.guild_id)
+                    .bind(r.user_id)
+                    .bind(&r.key)
+                    .bind(r.due_at)
+                    .execute(db)
+                    .await?;
+            }
+            Err(e) => {
+                failed += 1;
+                if r.attempts + 1 >= MAX_ATTEMPTS {
+                    sqlx::query("DELETE FROM reminders WHERE guild_id = $1 AND user_id = $2 AND key = $3 AND due_at = $4")
+                        .bind(r.guild_id)
+                        .bind(r.user_id)
+                        .bind(&r.key)
+                        .bind(r.due_at)
+                        .execute(db)
+                        .await?;
+                } else {
+                    sqlx::query(
+                        r#"
+                        UPDATE reminders
+                           SET attempts = attempts + 1, last_error = $5, due_at = $6
+                         WHERE guild_id = $1 AND user_id = $2 AND key = $3 AND due_at = $4
+                        "#,
+                    )
+                    .bind(r.guild_id)
+                    .bind(r.user_id)
+                    .bind(&r.key)
+                    .bind(r.due_at)
+                    .bind(format!("{e:#}"))
+                    .bind(clock::now() + Duration::minutes(1))
+                    .execute(db)
+                    .await?;
+                }
+            }
+        }
+    }
+
+    Ok((sent, failed))
+}
+
+fn ready_embed(command: &str) -> CreateEmbed {
+    CreateEmbed::new()
+        .color(0x2ECC71)
+        .title("✅ Cooldown zakończony")
+        .description(format!("Możesz znowu użyć `{command}`."))
+        .timestamp(Utc::now())
+}
+
+async fn send_dm(http: &Http, r: &DueRow) -> Result<()> {
+    let dm = UserId::new(r.user_id as u64).create_dm_channel(http).await?;
+    dm.id
+        .send_message(
+            http,
+            CreateMessage::new().embed(
+                ready_embed(&r.command)
+                    .footer(CreateEmbedFooter::new("Zmień lub wyłącz: /przypomnienia")),
+            ),
+        )
+        .await?;
+    Ok(())
+}
+
+async fn send_channel(http: &Http, r: &DueRow) -> Result<()> {
+    let user = UserId::new(r.user_id as u64);
+    ChannelId::new(r.channel_id as u64)
+        .send_message(
+            http,
+            CreateMessage::new()
+                .content(user.mention().to_string())
+                .embed(ready_embed(&r.command)),
+        )
+        .await?;
+    Ok(())
+}
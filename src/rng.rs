@@ -0,0 +1,188 @@
+//! Losowość gry: każde losowanie idzie z jawnego ziarna.
+//!
+//! Komenda bierze `(seed, rng)` z [`seeded`], losuje czystą funkcją `roll(inputs, rng)`
+//! i zapisuje ziarno + wejście + wynik ([`record`]) w tej samej transakcji co skutek.
+//! Sporny wynik odtwarzamy potem 1:1 przez [`replay`] (`/admcontrol rzut`).
+//! Produkcja bierze ziarna z systemu ([`OsSeeds`]); symulacje instalują [`SequenceSeeds`].
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use anyhow::{Context as _, Result};
+use chrono::{DateTime, Utc};
+use once_cell::sync::OnceCell;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use sqlx::{PgExecutor, PgPool};
+
+use crate::economy::Economy;
+
+/// Generator używany przez logikę gry — deterministyczny dla danego ziarna.
+pub type GameRng = StdRng;
+
+pub trait SeedSource: Send + Sync {
+    fn next_seed(&self) -> u64;
+}
+
+pub struct OsSeeds;
+
+impl SeedSource for OsSeeds {
+    fn next_seed(&self) -> u64 {
+        rand::random()
+    }
+}
+
+/// Kolejne ziarna od zadanego startu — powtarzalne przebiegi symulacji.
+pub struct SequenceSeeds {
+    next: AtomicU64,
+}
+
+impl SequenceSeeds {
+    pub fn new(start: u64) -> Self {
+        Self { next: AtomicU64::new(start) }
+    }
+}
+
+impl SeedSource for SequenceSeeds {
+    fn next_seed(&self) -> u64 {
+        self.next.fetch_add(1, Ordering::Relaxed)
+    }
+}
+
+static SEEDS: OnceCell<Arc<dyn SeedSource>> = OnceCell::new();
+
+/// Podmienia źródło ziaren — tylko przed pierwszym losowaniem. `false` = już ustawione.
+pub fn install(source: Arc<dyn SeedSource>) -> bool {
+    SEEDS.set(source).is_ok()
+}
+
+/// Nowe ziarno i generator z niego.
+pub fn seeded() -> (u64, GameRng) {
+    let seed = SEEDS.get_or_init(|| Arc::new(OsSeeds)).next_seed();
+    (seed, from_seed(seed))
+}
+
+pub fn from_seed(seed: u64) -> GameRng {
+    StdRng::seed_from_u64(seed)
+}
+
+/// Czym było losowanie: rodzaj (`rob`, `crime.simon`…), gracz, interakcja, ziarno.
+pub struct Roll {
+    pub command: &'static str,
+    pub user: u64,
+    pub interaction_id: Option<u64>,
+    pub seed: u64,
+}
+
+/// Zapisuje losowanie — wołać na połączeniu/transakcji, która zapisuje jego skutek.
+pub async fn record(
+    db: impl PgExecutor<'_>,
+    eco: Economy,
+    roll: &Roll,
+    inputs: &impl Serialize,
+    outcome: &impl Serialize,
+) -> Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO rolls (guild_id, user_id, command, interaction_id, seed, inputs, outcome)
+        VALUES ($1, $2, $3, $4, $5, $6::jsonb, $7::jsonb)
+        "#,
+    )
+    .bind(eco.id())
+    .bind(roll.user as i64)
+    .bind(roll.command)
+    .bind(roll.interaction_id.map(|id| id as i64))
+    .bind(roll.seed as i64)
+    .bind(serde_json::to_string(inputs)?)
+    .bind(serde_json::to_string(outcome)?)
+    .execute(db)
+    .await?;
+    Ok(())
+}
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct RollRecord {
+    pub id: i64,
+    pub guild_id: i64,
+    pub user_id: i64,
+    pub command: String,
+    pub interaction_id: Option<i64>,
+    pub seed: i64,
+    pub inputs: String,
+    pub outcome: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl RollRecord {
+    pub fn seed(&self) -> u64 {
+        self.seed as u64
+    }
+}
+
+const RECORD_COLUMNS: &str =
+    "id, guild_id, user_id, command, interaction_id, seed, inputs::text AS inputs, outcome::text AS outcome, created_at";
+
+/// Losowania jednej interakcji (zwykle jedno; /crime może mieć kilka).
+pub async fn by_interaction(db: &PgPool, interaction_id: u64) -> Result<Vec<RollRecord>> {
+    let rows = sqlx::query_as(&format!(
+        "SELECT {RECORD_COLUMNS} FROM rolls WHERE interaction_id = $1 ORDER BY id"
+    ))
+    .bind(interaction_id as i64)
+    .fetch_all(db)
+    .await?;
+    Ok(rows)
+}
+
+/// Ostatnie losowania gracza w ekonomii.
+pub async fn recent(db: &PgPool, eco: Economy, user: u64, limit: i64) -> Result<Vec<RollRecord>> {
+    let rows = sqlx::query_as(&format!(
+        "SELECT {RECORD_COLUMNS} FROM rolls WHERE guild_id = $1 AND user_id = $2 ORDER BY id DESC LIMIT $3"
+    ))
+    .bind(eco.id())
+    .bind(user as i64)
+    .bind(limit)
+    .fetch_all(db)
+    .await?;
+    Ok(rows)
+}
+
+/// Powtarza losowanie z zapisanego ziarna i wejścia; wynik jako JSON do porównania z zapisanym.
+pub fn replay<I, O>(seed: u64, inputs: &str, roll: impl FnOnce(&I, &mut GameRng) -> O) -> Result<serde_json::Value>
+where
+    I: DeserializeOwned,
+    O: Serialize,
+{
+    let inputs: I = serde_json::from_str(inputs).context("Nieczytelne wejście losowania")?;
+    let outcome = roll(&inputs, &mut from_seed(seed));
+    Ok(serde_json::to_value(outcome)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+
+    #[test]
+    fn same_seed_same_stream() {
+        let draw = |seed| from_seed(seed).random_iter::<u32>().take(8).collect::<Vec<_>>();
+        assert_eq!(draw(42), draw(42));
+        assert_ne!(draw(42), draw(43));
+    }
+
+    #[test]
+    fn sequence_seeds_count_up() {
+        let seeds = SequenceSeeds::new(7);
+        assert_eq!([seeds.next_seed(), seeds.next_seed(), seeds.next_seed()], [7, 8, 9]);
+    }
+
+    #[test]
+    fn replay_matches_recorded_outcome() {
+        let roll = |max: &u32, rng: &mut GameRng| rng.random_range(0..=*max);
+        let seed = 1234;
+        let recorded = serde_json::to_value(roll(&100, &mut from_seed(seed))).unwrap();
+        assert_eq!(replay(seed, "100", roll).unwrap(), recorded);
+        assert!(replay(seed, "nie-json", roll).is_err());
+    }
+}
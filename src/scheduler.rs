@@ -0,0 +1,357 @@
+//! Harmonogram zadań w tle (wygasanie subskrypcji, przypomnienia, naliczenia okresowe).
+//!
+//! Zadanie implementuje [`Job`] i jest dopisane w `jobs::all()`. Stan każdego zadania
+//! (następne uruchomienie, ostatni wynik) siedzi w `scheduled_jobs`; przed uruchomieniem
+//! proces bierze dzierżawę wiersza (`locked_by`/`locked_until`), więc przy kilku
+//! procesach na jednej bazie dane uruchomienie wykonuje dokładnie jeden z nich.
+
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration as StdDuration;
+
+use anyhow::{anyhow, bail, Context as _, Result};
+use chrono::{DateTime, Datelike, Duration, TimeZone, Timelike, Utc};
+use serenity::all::Http;
+use serenity::async_trait;
+use sqlx::PgPool;
+
+use crate::clock;
+
+/// Co ile proces sprawdza, czy któreś zadanie jest do zrobienia.
+const DEFAULT_TICK_SECS: u64 = 15;
+/// Maks. długość `last_output` w bazie.
+const OUTPUT_LIMIT: usize = 500;
+
+// =====================
+// Harmonogram
+// =====================
+
+#[derive(Debug, Clone)]
+pub enum Schedule {
+    /// Co stały odstęp, liczony od końca poprzedniego uruchomienia.
+    Every(Duration),
+    /// Wyrażenie cron (UTC).
+    Cron(Cron),
+}
+
+impl Schedule {
+    pub fn every_secs(secs: i64) -> Self {
+        Schedule::Every(Duration::seconds(secs))
+    }
+
+    /// Cron z literału w kodzie — błędne wyrażenie to błąd programisty.
+    pub fn cron(expr: &str) -> Self {
+        match Cron::parse(expr) {
+            Ok(c) => Schedule::Cron(c),
+            Err(e) => panic!("niepoprawny cron `{expr}`: {e}"),
+        }
+    }
+
+    /// Pierwsze uruchomienie po rejestracji zadania.
+    fn first_run(&self, now: DateTime<Utc>) -> DateTime<Utc> {
+        match self {
+            Schedule::Every(_) => now,
+            Schedule::Cron(c) => c.next_after(now),
+        }
+    }
+
+    fn next_after(&self, now: DateTime<Utc>) -> DateTime<Utc> {
+        match self {
+            Schedule::Every(d) => now + *d,
+            Schedule::Cron(c) => c.next_after(now),
+        }
+    }
+}
+
+impl fmt::Display for Schedule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Schedule::Every(d) => write!(f, "every {}s", d.num_seconds()),
+            Schedule::Cron(c) => write!(f, "cron {}", c.expr),
+        }
+    }
+}
+
+/// Pięciopolowy cron: `minuta godzina dzień-miesiąca miesiąc dzień-tygodnia`.
+/// Pola: `*`, `*/n`, `a`, `a-b`, `a-b/n` i listy po przecinku; niedziela = 0 lub 7.
+#[derive(Debug, Clone)]
+pub struct Cron {
+    expr: String,
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    /// Czy pola dnia są ograniczone (cron: oba ograniczone = wystarczy jedno z nich).
+    days_restricted: bool,
+    weekdays_restricted: bool,
+}
+
+impl Cron {
+    pub fn parse(expr: &str) -> Result<Self> {
+        let fields: Vec<&str> = expr.split_whitespace().collect();
+        let [min, hour, dom, mon, dow] = fields[..] else {
+            bail!("oczekiwano 5 pól, jest {}", fields.len());
+        };
+
+        let mut weekdays = parse_field(dow, 0, 7).context("dzień tygodnia")?;
+        if weekdays & (1 << 7) != 0 {
+            weekdays = (weekdays | 1) & !(1 << 7);
+        }
+
+        Ok(Self {
+            expr: expr.to_string(),
+            minutes: parse_field(min, 0, 59).context("minuta")?,
+            hours: parse_field(hour, 0, 23).context("godzina")?,
+            days: parse_field(dom, 1, 31).context("dzień miesiąca")?,
+            months: parse_field(mon, 1, 12).context("miesiąc")?,
+            weekdays,
+            days_restricted: dom != "*",
+            weekdays_restricted: dow != "*",
+        })
+    }
+
+    fn day_matches(&self, t: DateTime<Utc>) -> bool {
+        let dom = self.days & (1 << t.day()) != 0;
+        let dow = self.weekdays & (1 << t.weekday().num_days_from_sunday()) != 0;
+        match (self.days_restricted, self.weekdays_restricted) {
+            (true, true) => dom || dow,
+            _ => dom && dow,
+        }
+    }
+
+    /// Pierwsza pasująca minuta ściśle po `after`.
+    pub fn next_after(&self, after: DateTime<Utc>) -> DateTime<Utc> {
+        let mut t = after.with_second(0).and_then(|t| t.with_nanosecond(0)).unwrap_or(after) + Duration::minutes(1);
+
+        // skaczemy miesiącami/dniami/godzinami, więc nawet rzadkie wyrażenia kończą się szybko
+        for _ in 0..100_000 {
+            if self.months & (1 << t.month()) == 0 {
+                let (y, m) = if t.month() == 12 { (t.year() + 1, 1) } else { (t.year(), t.month() + 1) };
+                t = Utc.with_ymd_and_hms(y, m, 1, 0, 0, 0).single().unwrap_or(t + Duration::days(1));
+                continue;
+            }
+            if !self.day_matches(t) {
+                t = Utc
+                    .with_ymd_and_hms(t.year(), t.month(), t.day(), 0, 0, 0)
+                    .single()
+                    .unwrap_or(t)
+                    + Duration::days(1);
+                continue;
+            }
+            if self.hours & (1 << t.hour()) == 0 {
+                t = t.with_minute(0).unwrap_or(t) + Duration::hours(1);
+                continue;
+            }
+            if self.minutes & (1 << t.minute()) == 0 {
+                t += Duration::minutes(1);
+                continue;
+            }
+            return t;
+        }
+
+        // wyrażenie bez realnego terminu (np. 31 lutego) — nie blokujemy pętli
+        after + Duration::days(365)
+    }
+}
+
+fn parse_field(field: &str, min: u32, max: u32) -> Result<u64> {
+    let mut mask = 0u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((r, s)) => (r, s.parse::<u32>().map_err(|_| anyhow!("zły krok `{s}`"))?),
+            None => (part, 1),
+        };
+        if step == 0 {
+            bail!("krok 0 w `{part}`");
+        }
+
+        let (lo, hi) = if range == "*" {
+            (min, max)
+        } else if let Some((a, b)) = range.split_once('-') {
+            (parse_num(a, min, max)?, parse_num(b, min, max)?)
+        } else {
+            let v = parse_num(range, min, max)?;
+            // `5/15` = od 5 do końca co 15
+            (v, if part.contains('/') { max } else { v })
+        };
+        if lo > hi {
+            bail!("odwrócony zakres `{range}`");
+        }
+
+        for v in (lo..=hi).step_by(step as usize) {
+            mask |= 1 << v;
+        }
+    }
+    Ok(mask)
+}
+
+fn parse_num(s: &str, min: u32, max: u32) -> Result<u32> {
+    let v: u32 = s.parse().map_err(|_| anyhow!("zła wartość `{s}`"))?;
+    if v < min || v > max {
+        bail!("`{v}` poza zakresem {min}-{max}");
+    }
+    Ok(v)
+}
+
+// =====================
+// Zadania
+// =====================
+
+/// Zależności dostępne dla zadań (bez `Context` — zadania nie są zdarzeniami gatewaya).
+#[derive(Clone)]
+pub struct JobCtx {
+    pub http: Arc<Http>,
+    pub db: Arc<PgPool>,
+}
+
+#[async_trait]
+pub trait Job: Send + Sync {
+    /// Unikalna nazwa — klucz w `scheduled_jobs`.
+    fn name(&self) -> &'static str;
+
+    fn schedule(&self) -> Schedule;
+
+    /// Jak długo proces trzyma zadanie; dłuższe uruchomienie jest przerywane.
+    fn lease(&self) -> Duration {
+        Duration::minutes(5)
+    }
+
+    /// Wykonuje jedno uruchomienie. Zwraca krótki opis wyniku (trafia do `last_output`).
+    async fn run(&self, ctx: &JobCtx) -> Result<String>;
+}
+
+// =====================
+// Pętla
+// =====================
+
+/// Rejestruje zadania i odpala pętlę harmonogramu w tle.
+pub async fn start(ctx: JobCtx, jobs: Vec<Arc<dyn Job>>) -> Result<()> {
+    let mut names: Vec<&'static str> = Vec::new();
+    for j in &jobs {
+        if names.contains(&j.name()) {
+            bail!("Zadanie `{}` zarejestrowane więcej niż raz", j.name());
+        }
+        names.push(j.name());
+        register(&ctx.db, j.as_ref()).await?;
+    }
+
+    let tick = std::env::var("SCHEDULER_TICK_SECS")
+        .ok()
+        .and_then(|s| s.parse::<u64>().ok())
+        .filter(|&s| s > 0)
+        .unwrap_or(DEFAULT_TICK_SECS);
+    let instance = instance_id();
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(StdDuration::from_secs(tick));
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            for job in &jobs {
+                match claim(&ctx.db, job.as_ref(), &instance).await {
+                    Ok(true) => {
+                        let (ctx, job, instance) = (ctx.clone(), job.clone(), instance.clone());
+                        tokio::spawn(async move { execute(ctx, job, instance).await });
+                    }
+                    Ok(false) => {}
+                    Err(e) => eprintln!("❌ Harmonogram: nie udało się przejąć `{}`: {e:?}", job.name()),
+                }
+            }
+        }
+    });
+
+    Ok(())
+}
+
+/// Wstawia wiersz zadania; zmiana harmonogramu w kodzie przelicza `next_run_at`.
+async fn register(db: &PgPool, job: &dyn Job) -> Result<()> {
+    let schedule = job.schedule();
+    sqlx::query(
+        r#"
+        INSERT INTO scheduled_jobs (name, schedule, next_run_at)
+        VALUES ($1, $2, $3)
+        ON CONFLICT (name) DO UPDATE
+        SET schedule = EXCLUDED.schedule,
+            next_run_at = CASE WHEN scheduled_jobs.schedule = EXCLUDED.schedule
+                               THEN scheduled_jobs.next_run_at
+                               ELSE EXCLUDED.next_run_at END
+        "#,
+    )
+    .bind(job.name())
+    .bind(schedule.to_string())
+    .bind(schedule.first_run(clock::now()))
+    .execute(db)
+    .await?;
+    Ok(())
+}
+
+/// Bierze dzierżawę, jeśli zadanie jest do zrobienia i nikt go nie trzyma.
+async fn claim(db: &PgPool, job: &dyn Job, instance: &str) -> Result<bool> {
+    let now = clock::now();
+    let claimed = sqlx::query(
+        r#"
+        UPDATE scheduled_jobs
+           SET locked_by = $2, locked_until = $4, last_started_at = $3
+         WHERE name = $1
+           AND next_run_at <= $3
+           AND (locked_until IS NULL OR locked_until < $3)
+        "#,
+    )
+    .bind(job.name())
+    .bind(instance)
+    .bind(now)
+    .bind(now + job.lease())
+    .execute(db)
+    .await?;
+    Ok(claimed.rows_affected() == 1)
+}
+
+async fn execute(ctx: JobCtx, job: Arc<dyn Job>, instance: String) {
+    let limit = job.lease().to_std().unwrap_or(StdDuration::from_secs(300));
+    let outcome = match tokio::time::timeout(limit, job.run(&ctx)).await {
+        Ok(Ok(out)) => Ok(out),
+        Ok(Err(e)) => Err(format!("{e:#}")),
+        Err(_) => Err(format!("przekroczono czas ({}s)", limit.as_secs())),
+    };
+    if let Err(e) = &outcome {
+        eprintln!("❌ Zadanie `{}`: {e}", job.name());
+    }
+
+    let finished = clock::now();
+    let (ok, output) = match outcome {
+        Ok(out) => (true, out),
+        Err(e) => (false, e),
+    };
+    let output: String = output.chars().take(OUTPUT_LIMIT).collect();
+
+    let res = sqlx::query(
+        r#"
+        UPDATE scheduled_jobs
+           SET locked_by = NULL, locked_until = NULL,
+               next_run_at = $3, last_finished_at = $4,
+               last_ok = $5, last_output = $6,
+               run_count = run_count + 1,
+               fail_count = fail_count + CASE WHEN $5 THEN 0 ELSE 1 END
+         WHERE name = $1 AND locked_by = $2
+        "#,
+    )
+    .bind(job.name())
+    .bind(&instance)
+    .bind(job.schedule().next_after(finished))
+    .bind(finished)
+    .bind(ok)
+    .bind(output)
+    .execute(&*ctx.db)
+    .await;
+
+    if let Err(e) = res {
+        eprintln!("❌ Harmonogram: nie zapisano wyniku `{}`: {e:?}", job.name());
+    }
+}
+
+/// Identyfikator procesu w `locked_by` (host + pid).
+fn instance_id() -> String {
+    let host = std::env::var("HOSTNAME").unwrap_or_else(|_| "bot".to_string());
+    format!("{host}:{}", std::process::id())
+}
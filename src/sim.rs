@@ -0,0 +1,475 @@
+//! Symulator balansu (Monte Carlo) — silnik `bin/balance_sim.rs`.
+//!
+//! Liczy na tych samych czystych funkcjach co bot (`resolve_solo`, `roll_work` + `shift_payout`,
+//! `roll_flirt` + `flirt_payout`), bez bazy i Discorda. Każdy scenariusz to `runs` graczy
+//! po `steps` kolejnych akcji: HEAT, umiejętność, streaki i reputacja płyną między akcjami,
+//! czas gry to cooldown + `play_secs` (+ odsiadka po wpadce), a HEAT stygnie jak w `heat.decay`.
+//! Wynik napadu jest pomniejszony o zużycie przedmiotów (`items::wear_cost`).
+//! Ziarno scenariusza wynika z `seed` i jego numeru — ten sam przebieg daje te same liczby.
+
+use std::fmt::Write as _;
+
+use rand::Rng;
+use serde::Serialize;
+
+use crate::commands::{crime, slut, work};
+use crate::engine::{
+    balance,
+    config,
+    core::resolve_solo,
+    items::{self, ITEM_META},
+    targets::TARGET_META,
+    types::{CrimeMode, ItemKey, MinigameKind, MinigameResult, PlayerProfile, Risk, SoloHeistConfig, TargetKey},
+};
+use crate::jail;
+use crate::rng::{self, GameRng};
+
+pub const MODES: [CrimeMode; 8] = [
+    CrimeMode::Standard,
+    CrimeMode::Szybki,
+    CrimeMode::Ostrozny,
+    CrimeMode::Shadow,
+    CrimeMode::Hardcore,
+    CrimeMode::Ryzykowny,
+    CrimeMode::Planowany,
+    CrimeMode::Szalony,
+];
+
+pub const RISKS: [Risk; 4] = [Risk::Low, Risk::Medium, Risk::High, Risk::Hardcore];
+
+/// Jak dobrze gracz gra minigierkę — rozkład wyników na napad.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SkillProfile {
+    Perfect,
+    Good,
+    Average,
+    Poor,
+    Afk,
+}
+
+impl SkillProfile {
+    pub const ALL: [SkillProfile; 5] =
+        [SkillProfile::Perfect, SkillProfile::Good, SkillProfile::Average, SkillProfile::Poor, SkillProfile::Afk];
+
+    pub fn key(self) -> &'static str {
+        match self {
+            SkillProfile::Perfect => "perfect",
+            SkillProfile::Good => "good",
+            SkillProfile::Average => "average",
+            SkillProfile::Poor => "poor",
+            SkillProfile::Afk => "afk",
+        }
+    }
+
+    /// (% sukcesu, % „prawie”) — reszta to wtopa; AFK nie gra wcale.
+    fn odds(self) -> (u32, u32) {
+        match self {
+            SkillProfile::Perfect => (100, 0),
+            SkillProfile::Good => (70, 20),
+            SkillProfile::Average => (40, 35),
+            SkillProfile::Poor => (15, 30),
+            SkillProfile::Afk => (0, 0),
+        }
+    }
+
+    fn draw(self, rng: &mut impl Rng) -> MinigameResult {
+        if self == SkillProfile::Afk {
+            return MinigameResult::NotPlayed;
+        }
+        let (win, close) = self.odds();
+        let r = rng.random_range(0..100);
+        if r < win {
+            MinigameResult::Success
+        } else if r < win + close {
+            MinigameResult::Partial(rng.random_range(50..=250))
+        } else {
+            MinigameResult::Fail
+        }
+    }
+}
+
+/// Które zestawy przedmiotów liczyć.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Loadouts {
+    /// Bez przedmiotów + każdy przedmiot osobno.
+    Single,
+    /// Wszystkie zestawy do 3 przedmiotów (jak limit w /crime).
+    All,
+}
+
+impl Loadouts {
+    pub fn build(self) -> Vec<Vec<ItemKey>> {
+        let keys: Vec<ItemKey> = ITEM_META.iter().map(|(k, _)| *k).collect();
+        let mut out = vec![Vec::new()];
+        out.extend(keys.iter().map(|k| vec![*k]));
+        if self == Loadouts::All {
+            for i in 0..keys.len() {
+                for j in i + 1..keys.len() {
+                    out.push(vec![keys[i], keys[j]]);
+                    for k in j + 1..keys.len() {
+                        out.push(vec![keys[i], keys[j], keys[k]]);
+                    }
+                }
+            }
+        }
+        out
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SimConfig {
+    /// Ilu niezależnych graczy na scenariusz.
+    pub runs: u32,
+    /// Ile kolejnych akcji każdego gracza.
+    pub steps: u32,
+    pub seed: u64,
+    pub loadouts: Loadouts,
+    /// Oprócz ulicy liczyć też każdy cel napadu (kiosk, jubiler, …).
+    pub targets: bool,
+    /// Czas samej gry na akcję (minigierka, klikanie), doliczany do cooldownu.
+    pub play_secs: i64,
+    pub crime: bool,
+    pub work: bool,
+    pub slut: bool,
+}
+
+impl Default for SimConfig {
+    fn default() -> Self {
+        Self {
+            runs: 100,
+            steps: 200,
+            seed: 1,
+            loadouts: Loadouts::Single,
+            targets: false,
+            play_secs: 20,
+            crime: true,
+            work: true,
+            slut: true,
+        }
+    }
+}
+
+/// Wiersz raportu (CSV/JSON). Pola `heat_*`, `ambush_rate`, `jail_rate` tylko dla /crime.
+#[derive(Debug, Clone, Serialize)]
+pub struct Row {
+    pub kind: &'static str,
+    pub scenario: String,
+    pub samples: u64,
+    /// Średni wynik akcji w TK (wartość oczekiwana).
+    pub ev: f64,
+    pub stddev: f64,
+    pub min: i64,
+    pub max: i64,
+    pub success_rate: f64,
+    pub tk_per_hour: f64,
+    /// Średni HEAT po 25% / 50% / 100% akcji.
+    pub heat_q1: Option<f64>,
+    pub heat_mid: Option<f64>,
+    pub heat_end: Option<f64>,
+    pub heat_max: Option<i64>,
+    pub ambush_rate: Option<f64>,
+    pub jail_rate: Option<f64>,
+}
+
+// =================== Statystyki ===================
+
+/// Średnia i wariancja online (Welford) + min/max.
+#[derive(Debug, Clone, Default)]
+struct Stats {
+    n: u64,
+    mean: f64,
+    m2: f64,
+    min: i64,
+    max: i64,
+}
+
+impl Stats {
+    fn push(&mut self, x: i64) {
+        if self.n == 0 {
+            self.min = x;
+            self.max = x;
+        }
+        self.n += 1;
+        let xf = x as f64;
+        let d = xf - self.mean;
+        self.mean += d / self.n as f64;
+        self.m2 += d * (xf - self.mean);
+        self.min = self.min.min(x);
+        self.max = self.max.max(x);
+    }
+
+    fn stddev(&self) -> f64 {
+        if self.n < 2 {
+            0.0
+        } else {
+            (self.m2 / (self.n - 1) as f64).sqrt()
+        }
+    }
+}
+
+/// Wspólne liczniki pętli: wynik akcji, sukcesy, czas gry.
+#[derive(Default)]
+struct Tally {
+    tk: Stats,
+    successes: u64,
+    total_tk: i64,
+    total_secs: i64,
+}
+
+impl Tally {
+    fn push(&mut self, amount: i64, success: bool, secs: i64) {
+        self.tk.push(amount);
+        self.total_tk += amount;
+        self.total_secs += secs;
+        if success {
+            self.successes += 1;
+        }
+    }
+
+    fn row(&self, kind: &'static str, scenario: String) -> Row {
+        let n = self.tk.n.max(1) as f64;
+        Row {
+            kind,
+            scenario,
+            samples: self.tk.n,
+            ev: self.tk.mean,
+            stddev: self.tk.stddev(),
+            min: self.tk.min,
+            max: self.tk.max,
+            success_rate: self.successes as f64 / n,
+            tk_per_hour: if self.total_secs > 0 { self.total_tk as f64 * 3600.0 / self.total_secs as f64 } else { 0.0 },
+            heat_q1: None,
+            heat_mid: None,
+            heat_end: None,
+            heat_max: None,
+            ambush_rate: None,
+            jail_rate: None,
+        }
+    }
+}
+
+// =================== Scenariusze ===================
+
+enum Scenario {
+    Crime { target: Option<TargetKey>, mode: CrimeMode, risk: Risk, items: Vec<ItemKey>, skill: SkillProfile },
+    Work(work::WorkChoice),
+    Slut(slut::Approach),
+}
+
+fn scenarios(cfg: &SimConfig) -> Vec<Scenario> {
+    let mut out = Vec::new();
+    if cfg.crime {
+        let loadouts = cfg.loadouts.build();
+        let mut targets = vec![None];
+        if cfg.targets {
+            targets.extend(TARGET_META.iter().map(|(k, _)| Some(*k)));
+        }
+        for target in targets {
+            for mode in MODES {
+                for risk in RISKS {
+                    for items in &loadouts {
+                        for skill in SkillProfile::ALL {
+                            out.push(Scenario::Crime { target, mode, risk, items: items.clone(), skill });
+                        }
+                    }
+                }
+            }
+        }
+    }
+    if cfg.work {
+        out.extend(work::WorkChoice::ALL.into_iter().map(Scenario::Work));
+    }
+    if cfg.slut {
+        out.extend(slut::Approach::ALL.into_iter().map(Scenario::Slut));
+    }
+    out
+}
+
+/// Liczy wszystkie scenariusze (równolegle na dostępnych rdzeniach), w stałej kolejności.
+pub fn run(cfg: &SimConfig) -> Vec<Row> {
+    let all = scenarios(cfg);
+    let threads = std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1).min(all.len().max(1));
+    let chunk = all.len().div_ceil(threads).max(1);
+
+    std::thread::scope(|s| {
+        let handles: Vec<_> = all
+            .chunks(chunk)
+            .enumerate()
+            .map(|(c, part)| {
+                s.spawn(move || {
+                    part.iter()
+                        .enumerate()
+                        .map(|(i, sc)| {
+                            // ziarno scenariusza zależy tylko od jego pozycji, nie od liczby wątków
+                            let idx = (c * chunk + i) as u64;
+                            let mut rng = rng::from_seed(cfg.seed ^ idx.wrapping_mul(0x9E37_79B9_7F4A_7C15));
+                            simulate(sc, cfg, &mut rng)
+                        })
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+        handles.into_iter().flat_map(|h| h.join().expect("wątek symulacji padł")).collect()
+    })
+}
+
+fn simulate(sc: &Scenario, cfg: &SimConfig, rng: &mut GameRng) -> Row {
+    match sc {
+        Scenario::Crime { target, mode, risk, items, skill } => sim_crime(*target, *mode, *risk, items, *skill, cfg, rng),
+        Scenario::Work(choice) => sim_work(*choice, cfg, rng),
+        Scenario::Slut(style) => sim_slut(*style, cfg, rng),
+    }
+}
+
+fn sim_crime(
+    target: Option<TargetKey>,
+    mode: CrimeMode,
+    risk: Risk,
+    items: &[ItemKey],
+    skill: SkillProfile,
+    cfg: &SimConfig,
+    rng: &mut GameRng,
+) -> Row {
+    let heist = SoloHeistConfig {
+        mode: Some(mode),
+        risk: Some(risk),
+        minigame: MinigameKind::Qte,
+        items: items.to_vec(),
+        target,
+        ..SoloHeistConfig::default()
+    };
+    let checkpoints = [(cfg.steps / 4).max(1), (cfg.steps / 2).max(1), cfg.steps];
+    let mut heat_at = [0f64; 3];
+    let mut heat_max = 0i64;
+    let (mut ambushes, mut jailings) = (0u64, 0u64);
+    let mut t = Tally::default();
+    // każdy napad zjada po jednym użyciu przedmiotów — liczone jako koszt akcji
+    let wear = items::wear_cost(items);
+
+    for _ in 0..cfg.runs {
+        let mut profile = PlayerProfile::default();
+        let mut cooling_secs = 0i64;
+
+        for step in 1..=cfg.steps {
+            let fx = balance::heat_effects(mode, risk, profile.heat.max(0) as u32);
+            let mg = skill.draw(rng);
+            let (after, outcome) = resolve_solo(profile, &heist, mg, rng);
+            profile = after;
+
+            let mut secs = cfg.play_secs + crime::cooldown_meta().secs + fx.extra_cooldown_secs as i64;
+            if outcome.ambushed {
+                ambushes += 1;
+            }
+            if !outcome.success {
+                if let Some(mins) = jail::sentence_mins(risk, profile.heat, outcome.ambushed) {
+                    jailings += 1;
+                    secs += mins * 60;
+                }
+            }
+            t.push(outcome.amount_final - wear, outcome.success, secs);
+
+            // HEAT stygnie co pełną godzinę (jak zadanie `heat.decay`)
+            cooling_secs += secs;
+            let hours = cooling_secs / 3600;
+            if hours > 0 {
+                profile.heat = (profile.heat - hours * config::get().heat.decay_per_hour).max(0);
+                cooling_secs %= 3600;
+            }
+
+            heat_max = heat_max.max(profile.heat);
+            for (slot, &at) in heat_at.iter_mut().zip(&checkpoints) {
+                if step == at {
+                    *slot += profile.heat as f64;
+                }
+            }
+        }
+    }
+
+    let loadout = if items.is_empty() {
+        "-".to_string()
+    } else {
+        items.iter().map(|k| crime::key_item(*k)).collect::<Vec<_>>().join("+")
+    };
+    let mut scenario = format!("{}/{}/{loadout}/{}", crime::mode_to_str(mode), crime::risk_to_str(risk), skill.key());
+    if let Some(t) = target {
+        scenario = format!("{}/{scenario}", crime::key_target(t));
+    }
+
+    let runs = cfg.runs.max(1) as f64;
+    let heists = t.tk.n.max(1) as f64;
+    Row {
+        heat_q1: Some(heat_at[0] / runs),
+        heat_mid: Some(heat_at[1] / runs),
+        heat_end: Some(heat_at[2] / runs),
+        heat_max: Some(heat_max),
+        ambush_rate: Some(ambushes as f64 / heists),
+        jail_rate: Some(jailings as f64 / heists),
+        ..t.row("crime", scenario)
+    }
+}
+
+fn sim_work(choice: work::WorkChoice, cfg: &SimConfig, rng: &mut GameRng) -> Row {
+    let mut t = Tally::default();
+    for _ in 0..cfg.runs {
+        // gracz pracuje bez przerw > 2h, więc streak trwa do pierwszej porażki
+        let mut streak = 0;
+        for _ in 0..cfg.steps {
+            let roll = work::roll_work(&choice, rng);
+            streak = if roll.base_amount == 0 { 0 } else { streak + 1 };
+            let (_, amount) = work::shift_payout(roll.base_amount, streak);
+            t.push(amount, roll.base_amount > 0, cfg.play_secs + work::cooldown_meta().secs);
+        }
+    }
+    t.row("work", format!("{choice:?}").to_lowercase())
+}
+
+fn sim_slut(style: slut::Approach, cfg: &SimConfig, rng: &mut GameRng) -> Row {
+    let mut t = Tally::default();
+    for _ in 0..cfg.runs {
+        let (mut rep, mut streak, mut fails) = (0, 0, 0);
+        for _ in 0..cfg.steps {
+            let roll = slut::roll_flirt(&slut::FlirtInput { style, rep, fails }, rng);
+            streak = if roll.success { streak + 1 } else { 0 };
+            let pay = slut::flirt_payout(style, &roll, streak);
+            rep = slut::clamp_rep(rep + roll.rep_delta());
+            fails = if roll.success { 0 } else { fails + 1 };
+            t.push(pay.work_part + pay.flat_bonus, roll.success, cfg.play_secs + slut::cooldown_meta().secs);
+        }
+    }
+    t.row("slut", format!("{style:?}").to_lowercase())
+}
+
+// =================== Wyjście ===================
+
+pub fn to_json(rows: &[Row]) -> anyhow::Result<String> {
+    Ok(serde_json::to_string_pretty(rows)?)
+}
+
+pub fn to_csv(rows: &[Row]) -> String {
+    let opt = |v: Option<f64>| v.map(|x| format!("{x:.4}")).unwrap_or_default();
+    let mut out = String::from(
+        "kind,scenario,samples,ev,stddev,min,max,success_rate,tk_per_hour,heat_q1,heat_mid,heat_end,heat_max,ambush_rate,jail_rate\n",
+    );
+    for r in rows {
+        let _ = writeln!(
+            out,
+            "{},{},{},{:.4},{:.4},{},{},{:.4},{:.2},{},{},{},{},{},{}",
+            r.kind,
+            r.scenario,
+            r.samples,
+            r.ev,
+            r.stddev,
+            r.min,
+            r.max,
+            r.success_rate,
+            r.tk_per_hour,
+            opt(r.heat_q1),
+            opt(r.heat_mid),
+            opt(r.heat_end),
+            r.heat_max.map(|h| h.to_string()).unwrap_or_default(),
+            opt(r.ambush_rate),
+            opt(r.jail_rate),
+        );
+    }
+    out
+}
@@ -0,0 +1,172 @@
+//! Drzewko złodzieja — umiejętności `/crime` kupowane za PP.
+//!
+//! `profiles.pp` to licznik zdobytych PP i dalej odblokowuje przedmioty i cele; drzewko
+//! wydaje tylko „wolne” PP (zdobyte − wydane). Koszt poziomu, limity i efekty to tabele
+//! balansu (`config::PerkTables`), wymagania gałęzi — `perks::PERK_META`. Reset oddaje
+//! wszystkie wydane PP za opłatą w TK do `sys:skills`.
+
+use anyhow::Result;
+use sqlx::{PgConnection, PgExecutor, PgPool};
+
+use crate::commands::skill_tree::{from_key_perk, key_perk};
+use crate::economy::Economy;
+use crate::engine::config;
+use crate::engine::perks::{self, PERK_META};
+use crate::engine::types::{PerkKey, PerkRanks};
+use crate::ledger::{self, Entry, Overdraft};
+
+/// Konto systemowe, do którego idą opłaty za reset.
+const SKILLS_ACCOUNT: &str = "skills";
+
+/// Stan drzewka gracza.
+#[derive(Debug, Clone, Default)]
+pub struct Tree {
+    pub ranks: PerkRanks,
+    /// Zdobyte PP (licznik z profilu).
+    pub pp: u32,
+    /// PP wydane na umiejętności.
+    pub spent: u32,
+}
+
+impl Tree {
+    pub fn free(&self) -> u32 {
+        self.pp.saturating_sub(self.spent)
+    }
+}
+
+/// Same poziomy umiejętności (do napadu).
+pub async fn ranks(db: impl PgExecutor<'_>, eco: Economy, user: u64) -> Result<PerkRanks> {
+    let rows: Vec<(String, i32)> = sqlx::query_as("SELECT perk, rank FROM crime_perks WHERE guild_id = $1 AND user_id = $2")
+        .bind(eco.id())
+        .bind(user as i64)
+        .fetch_all(db)
+        .await?;
+    Ok(rows
+        .into_iter()
+        .filter_map(|(k, rank)| from_key_perk(&k).map(|perk| (perk, rank.clamp(0, u8::MAX as i32) as u8)))
+        .collect())
+}
+
+/// Drzewko z PP z profilu; `lock` = blokada wierszy do końca transakcji.
+async fn read(conn: &mut PgConnection, eco: Economy, user: u64, lock: bool) -> Result<Tree> {
+    let sql = if lock {
+        "SELECT pp FROM profiles WHERE guild_id = $1 AND user_id = $2 FOR UPDATE"
+    } else {
+        "SELECT pp FROM profiles WHERE guild_id = $1 AND user_id = $2"
+    };
+    let pp: i32 = sqlx::query_scalar(sql).bind(eco.id()).bind(user as i64).fetch_optional(&mut *conn).await?.unwrap_or(0);
+
+    let sql = if lock {
+        "SELECT perk, rank, spent FROM crime_perks WHERE guild_id = $1 AND user_id = $2 FOR UPDATE"
+    } else {
+        "SELECT perk, rank, spent FROM crime_perks WHERE guild_id = $1 AND user_id = $2"
+    };
+    let rows: Vec<(String, i32, i32)> = sqlx::query_as(sql).bind(eco.id()).bind(user as i64).fetch_all(&mut *conn).await?;
+
+    let mut tree = Tree { pp: pp.max(0) as u32, ..Tree::default() };
+    for (k, rank, spent) in rows {
+        tree.spent += spent.max(0) as u32;
+        if let Some(perk) = from_key_perk(&k) {
+            tree.ranks.insert(perk, rank.clamp(0, u8::MAX as i32) as u8);
+        }
+    }
+    Ok(tree)
+}
+
+pub async fn load(db: &PgPool, eco: Economy, user: u64) -> Result<Tree> {
+    let mut conn = db.acquire().await?;
+    read(&mut conn, eco, user, false).await
+}
+
+pub enum Learn {
+    Maxed { max: u8 },
+    /// Najpierw trzeba wziąć poprzednią umiejętność gałęzi.
+    Requires(PerkKey),
+    NotEnoughPp { free: u32, cost: u32 },
+    Learned { rank: u8, free: u32 },
+}
+
+/// Kolejny poziom umiejętności za wolne PP.
+pub async fn learn(db: &PgPool, eco: Economy, user: u64, perk: PerkKey) -> Result<Learn> {
+    let t = *config::get().perks.get(perk);
+
+    let mut tx = db.begin().await?;
+    let tree = read(&mut tx, eco, user, true).await?;
+    let rank = perks::rank(&tree.ranks, perk);
+    if rank >= t.max_rank {
+        return Ok(Learn::Maxed { max: t.max_rank });
+    }
+    if let Some(req) = perks::meta(perk).requires {
+        if perks::rank(&tree.ranks, req) == 0 {
+            return Ok(Learn::Requires(req));
+        }
+    }
+    if tree.free() < t.cost_pp {
+        return Ok(Learn::NotEnoughPp { free: tree.free(), cost: t.cost_pp });
+    }
+
+    sqlx::query(
+        r#"INSERT INTO crime_perks (guild_id, user_id, perk, rank, spent, updated_at)
+           VALUES ($1, $2, $3, 1, $4, now())
+           ON CONFLICT (guild_id, user_id, perk) DO UPDATE
+           SET rank = crime_perks.rank + 1,
+               spent = crime_perks.spent + EXCLUDED.spent,
+               updated_at = now()"#,
+    )
+    .bind(eco.id())
+    .bind(user as i64)
+    .bind(key_perk(perk))
+    .bind(t.cost_pp as i32)
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
+
+    Ok(Learn::Learned { rank: rank + 1, free: tree.free() - t.cost_pp })
+}
+
+/// Opłata za reset drzewka z `spent` wydanymi PP.
+pub fn respec_cost(spent: u32) -> i64 {
+    let p = &config::get().perks;
+    p.respec_base + p.respec_per_pp * i64::from(spent)
+}
+
+pub enum Respec {
+    Empty,
+    TooPoor { balance: i64, cost: i64 },
+    Done { refunded: u32, cost: i64, balance: i64 },
+}
+
+/// Reset: wszystkie umiejętności znikają, wydane PP wracają do puli.
+pub async fn respec(db: &PgPool, eco: Economy, user: u64, interaction_id: Option<u64>) -> Result<Respec> {
+    let mut tx = db.begin().await?;
+    let tree = read(&mut tx, eco, user, true).await?;
+    if tree.spent == 0 {
+        return Ok(Respec::Empty);
+    }
+
+    let cost = respec_cost(tree.spent);
+    let entry = Entry { reason: "reset drzewka", command: "crime", interaction_id };
+    let balance = match ledger::settle(&mut tx, eco, user, SKILLS_ACCOUNT, -cost, &entry, Overdraft::Forbid).await {
+        Ok(b) => b,
+        Err(e) => {
+            return match ledger::insufficient(&e) {
+                Some(balance) => Ok(Respec::TooPoor { balance, cost }),
+                None => Err(e),
+            }
+        }
+    };
+
+    sqlx::query("DELETE FROM crime_perks WHERE guild_id = $1 AND user_id = $2")
+        .bind(eco.id())
+        .bind(user as i64)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+
+    Ok(Respec::Done { refunded: tree.spent, cost, balance })
+}
+
+/// Umiejętności w kolejności drzewka.
+pub fn ordered(ranks: &PerkRanks) -> Vec<(PerkKey, u8)> {
+    PERK_META.iter().map(|(k, _)| (*k, perks::rank(ranks, *k))).filter(|(_, r)| *r > 0).collect()
+}
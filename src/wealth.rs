@@ -0,0 +1,207 @@
+//! Odsetki od banku i progresywny podatek od majątku — naliczane raz dziennie (`jobs::wealth`).
+//!
+//! Odsetki to emisja z `sys:interest` na konto bankowe (do `interest_cap` salda; co nie
+//! zmieści się w pojemności konta, trafia do portfela). Podatek liczy się od portfela + banku
+//! progami z `config::TaxTables`, schodzi najpierw z banku, potem z portfela, i trafia do
+//! skarbca ekonomii (`treasury`). Każdy ruch to zwykły wpis w księdze (`command = "wealth"`).
+
+use anyhow::Result;
+use chrono::NaiveDate;
+use sqlx::PgPool;
+
+use crate::bank;
+use crate::economy::Economy;
+use crate::engine::config::{self, TaxBracket};
+use crate::ledger::{self, Account, Entry, Overdraft};
+
+/// Konto systemowe, z którego płyną odsetki.
+const INTEREST_ACCOUNT: &str = "interest";
+
+/// Podsumowanie naliczenia w jednej ekonomii.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Summary {
+    pub eco: u64,
+    pub interest: i64,
+    pub interest_to: i32,
+    pub tax: i64,
+    pub taxed: i32,
+    /// Saldo skarbca po naliczeniu.
+    pub treasury: i64,
+}
+
+/// Odsetki od salda banku (liczone do `interest_cap`).
+pub fn interest(bank: i64) -> i64 {
+    let b = &config::get().bank;
+    (bank.clamp(0, b.interest_cap) as f64 * b.interest_rate).floor() as i64
+}
+
+/// Podatek od majątku: każda stawka dotyczy części ponad swój próg, do progu następnego.
+pub fn wealth_tax(wealth: i64, brackets: &[TaxBracket]) -> i64 {
+    let mut tax = 0.0;
+    for (i, br) in brackets.iter().enumerate() {
+        if wealth <= br.from {
+            break;
+        }
+        let upper = brackets.get(i + 1).map_or(wealth, |next| next.from.min(wealth));
+        tax += (upper - br.from) as f64 * br.rate;
+    }
+    tax.floor() as i64
+}
+
+/// Czy przy bieżących tabelach jest cokolwiek do naliczenia.
+pub fn enabled() -> bool {
+    let cfg = config::get();
+    (cfg.bank.interest_rate > 0.0 && cfg.bank.interest_cap > 0) || cfg.tax.brackets.iter().any(|b| b.rate > 0.0)
+}
+
+/// Ekonomie, w których ktoś ma coś w banku albo majątek ponad najniższy próg podatku.
+pub async fn economies(db: &PgPool) -> Result<Vec<Economy>> {
+    let min_from = config::get().tax.brackets.first().map_or(i64::MAX, |b| b.from);
+    let ids: Vec<i64> =
+        sqlx::query_scalar("SELECT DISTINCT guild_id FROM users WHERE bank > 0 OR GREATEST(balance, 0) + bank > $1")
+            .bind(min_from)
+            .fetch_all(db)
+            .await?;
+    Ok(ids.into_iter().map(|id| Economy(id as u64)).collect())
+}
+
+/// Naliczenie za `day` w jednej ekonomii — całe w jednej transakcji.
+/// `None`, jeśli ten dzień był już naliczony.
+pub async fn run(db: &PgPool, eco: Economy, day: NaiveDate) -> Result<Option<Summary>> {
+    let cfg = config::get();
+    let mut tx = db.begin().await?;
+
+    let fresh = sqlx::query("INSERT INTO wealth_runs (guild_id, day) VALUES ($1, $2) ON CONFLICT DO NOTHING")
+        .bind(eco.id())
+        .bind(day)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected()
+        > 0;
+    if !fresh {
+        return Ok(None);
+    }
+
+    let min_from = cfg.tax.brackets.first().map_or(i64::MAX, |b| b.from);
+    let players: Vec<(i64, i64, i64, i16)> = sqlx::query_as(
+        r#"SELECT id, balance, bank, bank_level FROM users
+            WHERE guild_id = $1 AND (bank > 0 OR GREATEST(balance, 0) + bank > $2)
+            ORDER BY id
+              FOR UPDATE"#,
+    )
+    .bind(eco.id())
+    .bind(min_from)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    let mut sum = Summary { eco: eco.0, ..Summary::default() };
+    for (id, wallet, bank_bal, level) in players {
+        let user = id as u64;
+        let mut wallet = wallet;
+        let mut bank_bal = bank_bal;
+
+        let paid = interest(bank_bal);
+        if paid > 0 {
+            let room = (bank::capacity(level.clamp(0, u8::MAX as i16) as u8) - bank_bal).max(0);
+            let entry = Entry { reason: "odsetki", command: "wealth", interaction_id: None };
+            let (to_bank, to_wallet) = (paid.min(room), paid - paid.min(room));
+            if to_bank > 0 {
+                let posted = ledger::transfer(
+                    &mut tx,
+                    eco,
+                    Account::System(INTEREST_ACCOUNT),
+                    Account::Bank(user),
+                    to_bank,
+                    &entry,
+                    Overdraft::Forbid,
+                )
+                .await?;
+                bank_bal = posted.sink_balance.unwrap_or(bank_bal + to_bank);
+            }
+            if to_wallet > 0 {
+                wallet = ledger::settle(&mut tx, eco, user, INTEREST_ACCOUNT, to_wallet, &entry, Overdraft::Forbid).await?;
+            }
+            sum.interest += paid;
+            sum.interest_to += 1;
+        }
+
+        let due = wealth_tax(wallet.max(0) + bank_bal, &cfg.tax.brackets);
+        if due > 0 {
+            let entry = Entry { reason: "podatek od majątku", command: "wealth", interaction_id: None };
+            let from_bank = due.min(bank_bal);
+            let from_wallet = due - from_bank;
+            for (from, amount) in [(Account::Bank(user), from_bank), (Account::User(user), from_wallet)] {
+                if amount > 0 {
+                    ledger::transfer(&mut tx, eco, from, Account::Treasury, amount, &entry, Overdraft::Forbid).await?;
+                }
+            }
+            sum.tax += due;
+            sum.taxed += 1;
+        }
+    }
+
+    sqlx::query(
+        "UPDATE wealth_runs SET interest = $3, interest_to = $4, tax = $5, taxed = $6 WHERE guild_id = $1 AND day = $2",
+    )
+    .bind(eco.id())
+    .bind(day)
+    .bind(sum.interest)
+    .bind(sum.interest_to)
+    .bind(sum.tax)
+    .bind(sum.taxed)
+    .execute(&mut *tx)
+    .await?;
+    sum.treasury = ledger::treasury_of(&mut tx, eco).await?;
+    tx.commit().await?;
+
+    Ok(Some(sum))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BRACKETS: [TaxBracket; 3] = [
+        TaxBracket { from: 1_000, rate: 0.125 },
+        TaxBracket { from: 2_000, rate: 0.25 },
+        TaxBracket { from: 5_000, rate: 0.5 },
+    ];
+
+    #[test]
+    fn below_first_bracket_is_free() {
+        assert_eq!(wealth_tax(0, &BRACKETS), 0);
+        assert_eq!(wealth_tax(999, &BRACKETS), 0);
+        assert_eq!(wealth_tax(-500, &BRACKETS), 0);
+    }
+
+    #[test]
+    fn bracket_boundary_taxes_only_the_part_below_it() {
+        assert_eq!(wealth_tax(1_000, &BRACKETS), 0);
+        assert_eq!(wealth_tax(2_000, &BRACKETS), 125);
+        assert_eq!(wealth_tax(2_001, &BRACKETS), 125);
+        assert_eq!(wealth_tax(5_000, &BRACKETS), 125 + 750);
+    }
+
+    #[test]
+    fn wealth_spanning_several_brackets() {
+        // 1000×12,5% + 3000×25% + 1000×50%
+        assert_eq!(wealth_tax(6_000, &BRACKETS), 125 + 750 + 500);
+        // część ułamkowa w dół
+        assert_eq!(wealth_tax(1_007, &BRACKETS), 0);
+        assert_eq!(wealth_tax(1_008, &BRACKETS), 1);
+    }
+
+    #[test]
+    fn no_brackets_no_tax() {
+        assert_eq!(wealth_tax(1_000_000, &[]), 0);
+    }
+
+    #[test]
+    fn interest_is_capped_and_never_negative() {
+        let b = &config::get().bank;
+        assert_eq!(interest(-1_000), 0);
+        assert_eq!(interest(0), 0);
+        assert_eq!(interest(b.interest_cap * 10), interest(b.interest_cap));
+        assert_eq!(interest(b.interest_cap), (b.interest_cap as f64 * b.interest_rate).floor() as i64);
+    }
+}